@@ -37,10 +37,33 @@ struct LspStatus {
 
 struct PendingWork<'a> {
     language_server_id: LanguageServerId,
+    language_server_name: &'a str,
     progress_token: &'a str,
     progress: &'a LanguageServerProgress,
 }
 
+impl PendingWork<'_> {
+    /// Describes the work like "rust-analyzer: Indexing (40%): 3/120 (serde)".
+    fn description(&self) -> String {
+        let mut description = format!(
+            "{}: {}",
+            self.language_server_name,
+            self.progress
+                .title
+                .as_deref()
+                .unwrap_or(self.progress_token)
+        );
+        if let Some(percentage) = self.progress.percentage {
+            write!(&mut description, " ({}%)", percentage).unwrap();
+        }
+        if let Some(progress_message) = self.progress.message.as_ref() {
+            description.push_str(": ");
+            description.push_str(progress_message);
+        }
+        description
+    }
+}
+
 #[derive(Default)]
 struct Content {
     icon: Option<gpui::AnyElement>,
@@ -163,6 +186,7 @@ impl ActivityIndicator {
                         .iter()
                         .map(|(token, progress)| PendingWork {
                             language_server_id: server_id,
+                            language_server_name: status.name.as_str(),
                             progress_token: token.as_str(),
                             progress,
                         })
@@ -177,27 +201,8 @@ impl ActivityIndicator {
     fn content_to_render(&mut self, cx: &mut ViewContext<Self>) -> Content {
         // Show any language server has pending activity.
         let mut pending_work = self.pending_language_server_work(cx);
-        if let Some(PendingWork {
-            progress_token,
-            progress,
-            ..
-        }) = pending_work.next()
-        {
-            let mut message = progress
-                .title
-                .as_deref()
-                .unwrap_or(progress_token)
-                .to_string();
-
-            if let Some(percentage) = progress.percentage {
-                write!(&mut message, " ({}%)", percentage).unwrap();
-            }
-
-            if let Some(progress_message) = progress.message.as_ref() {
-                message.push_str(": ");
-                message.push_str(progress_message);
-            }
-
+        if let Some(work) = pending_work.next() {
+            let mut message = work.description();
             let additional_work_count = pending_work.count();
             if additional_work_count > 0 {
                 write!(&mut message, " + {} more", additional_work_count).unwrap();
@@ -384,13 +389,7 @@ impl ActivityIndicator {
                 has_work = true;
 
                 let this = this.clone();
-                let title = SharedString::from(
-                    work.progress
-                        .title
-                        .as_deref()
-                        .unwrap_or(work.progress_token)
-                        .to_string(),
-                );
+                let title = SharedString::from(work.description());
                 if work.progress.is_cancellable {
                     let language_server_id = work.language_server_id;
                     let token = work.progress_token.to_string();
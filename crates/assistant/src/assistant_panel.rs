@@ -1681,9 +1681,10 @@ impl ContextEditor {
                                 }
                             });
                             let placeholder = FoldPlaceholder {
-                                render: Arc::new(move |_, _, _| Empty.into_any()),
+                                render: Arc::new(move |_, _, _, _| Empty.into_any()),
                                 constrain_width: false,
                                 merge_adjacent: false,
+                                summary: None,
                             };
                             let render_toggle = {
                                 let confirm_command = confirm_command.clone();
@@ -1821,7 +1822,7 @@ impl ContextEditor {
                             let editor = cx.view().downgrade();
                             let icon = section.icon;
                             let label = section.label.clone();
-                            move |fold_id, fold_range, _cx| {
+                            move |fold_id, fold_range, _, _cx| {
                                 let editor = editor.clone();
                                 ButtonLike::new(fold_id)
                                     .style(ButtonStyle::Filled)
@@ -1844,6 +1845,7 @@ impl ContextEditor {
                         }),
                         constrain_width: false,
                         merge_adjacent: false,
+                        summary: None,
                     },
                     render_slash_command_output_toggle,
                     |_, _, _| Empty.into_any_element(),
@@ -2657,8 +2659,9 @@ impl FollowableItem for ContextEditor {
                                 selections: editor_state.selections,
                                 pending_selection: editor_state.pending_selection,
                                 scroll_top_anchor: editor_state.scroll_top_anchor,
-                                scroll_x: editor_state.scroll_y,
+                                scroll_x: editor_state.scroll_x,
                                 scroll_y: editor_state.scroll_y,
+                                text_scale: editor_state.text_scale,
                                 ..Default::default()
                             }),
                             cx,
@@ -1,12 +1,12 @@
-use editor::Editor;
+use editor::{scroll::Autoscroll, Anchor, Editor, ToOffset};
 use gpui::{
-    Element, EventEmitter, IntoElement, ParentElement, Render, StyledText, Subscription,
-    ViewContext,
+    AnchorCorner, AnyElement, AppContext, Element, EventEmitter, IntoElement, ParentElement,
+    Render, StyledText, Subscription, ViewContext, WeakView,
 };
 use itertools::Itertools;
 use std::cmp;
 use theme::ActiveTheme;
-use ui::{prelude::*, ButtonLike, ButtonStyle, Label, Tooltip};
+use ui::{prelude::*, ButtonLike, ButtonStyle, ContextMenu, Label, PopoverMenu, Tooltip};
 use workspace::{
     item::{BreadcrumbText, ItemEvent, ItemHandle},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
@@ -37,10 +37,17 @@ impl Render for Breadcrumbs {
         let Some(active_item) = self.active_item.as_ref() else {
             return element;
         };
-        let Some(mut segments) = active_item.breadcrumbs(cx.theme(), cx) else {
+        let Some(segments) = active_item.breadcrumbs(cx.theme(), cx) else {
             return element;
         };
 
+        // Keep track of each segment's position, so that the symbols collapsed into the
+        // ellipsis don't shift which symbol the remaining segments stand for.
+        let mut segments = segments
+            .into_iter()
+            .enumerate()
+            .map(|(ix, segment)| (Some(ix), segment))
+            .collect::<Vec<_>>();
         let prefix_end_ix = cmp::min(segments.len(), MAX_SEGMENTS / 2);
         let suffix_start_ix = cmp::max(
             prefix_end_ix,
@@ -49,15 +56,26 @@ impl Render for Breadcrumbs {
         if suffix_start_ix > prefix_end_ix {
             segments.splice(
                 prefix_end_ix..suffix_start_ix,
-                Some(BreadcrumbText {
-                    text: "⋯".into(),
-                    highlights: None,
-                    font: None,
-                }),
+                Some((
+                    None,
+                    BreadcrumbText {
+                        text: "⋯".into(),
+                        highlights: None,
+                        font: None,
+                    },
+                )),
             );
         }
 
-        let highlighted_segments = segments.into_iter().map(|segment| {
+        let editor = active_item
+            .downcast::<Editor>()
+            .map(|editor| editor.downgrade());
+        let symbols_navigable = editor
+            .as_ref()
+            .and_then(|editor| editor.upgrade())
+            .is_some_and(|editor| editor.read(cx).buffer().read(cx).is_singleton());
+
+        let highlighted_segments = segments.into_iter().map(|(ix, segment)| {
             let mut text_style = cx.text_style();
             if let Some(font) = segment.font {
                 text_style.font_family = font.family;
@@ -67,22 +85,13 @@ impl Render for Breadcrumbs {
             }
             text_style.color = Color::Muted.color(cx);
 
-            StyledText::new(segment.text.replace('\n', "␤"))
+            let text = StyledText::new(segment.text.replace('\n', "␤"))
                 .with_highlights(&text_style, segment.highlights.unwrap_or_default())
-                .into_any()
-        });
-        let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
-            Label::new("›").color(Color::Placeholder).into_any_element()
-        });
+                .into_any();
 
-        let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
-        match active_item
-            .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
-            Some(editor) => element.child(
-                ButtonLike::new("toggle outline view")
-                    .child(breadcrumbs_stack)
+            match (ix, editor.clone()) {
+                (Some(0), Some(editor)) => ButtonLike::new("toggle outline view")
+                    .child(text)
                     .style(ButtonStyle::Transparent)
                     .on_click(move |_, cx| {
                         if let Some(editor) = editor.upgrade() {
@@ -95,16 +104,96 @@ impl Render for Breadcrumbs {
                             &editor::actions::ToggleOutline,
                             cx,
                         )
-                    }),
-            ),
-            None => element
-                // Match the height of the `ButtonLike` in the other arm.
-                .h(rems_from_px(22.))
-                .child(breadcrumbs_stack),
-        }
+                    })
+                    .into_any_element(),
+                (Some(ix), Some(editor)) if symbols_navigable => {
+                    render_sibling_symbols_menu(ix - 1, text, editor).into_any_element()
+                }
+                _ => text,
+            }
+        });
+        let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
+            Label::new("›").color(Color::Placeholder).into_any_element()
+        });
+
+        element
+            // Match the height of the `ButtonLike` segments of editors.
+            .h(rems_from_px(22.))
+            .child(h_flex().gap_1().children(breadcrumbs))
     }
 }
 
+/// Renders a symbol segment that opens a menu of the symbols next to it, to jump to one of them.
+fn render_sibling_symbols_menu(
+    symbol_ix: usize,
+    text: AnyElement,
+    editor: WeakView<Editor>,
+) -> impl IntoElement {
+    PopoverMenu::new(("breadcrumb-symbol-menu", symbol_ix))
+        .menu(move |cx| {
+            let editor = editor.upgrade()?;
+            let siblings = sibling_symbols(editor.read(cx), symbol_ix, cx)?;
+            Some(ContextMenu::build(cx, |mut menu, _| {
+                for (label, position, is_current) in siblings {
+                    let editor = editor.downgrade();
+                    menu = menu.toggleable_entry(label, is_current, None, move |cx| {
+                        editor
+                            .update(cx, |editor, cx| {
+                                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                    s.select_ranges([position..position])
+                                });
+                                editor.focus(cx);
+                            })
+                            .ok();
+                    });
+                }
+                menu
+            }))
+        })
+        .trigger(
+            ButtonLike::new(("breadcrumb-symbol", symbol_ix))
+                .child(text)
+                .style(ButtonStyle::Transparent),
+        )
+        .attach(AnchorCorner::BottomLeft)
+}
+
+/// Returns the outline items at the same depth and within the same parent as the
+/// `symbol_ix`th symbol containing the cursor, and whether each of them is that symbol.
+fn sibling_symbols(
+    editor: &Editor,
+    symbol_ix: usize,
+    cx: &AppContext,
+) -> Option<Vec<(String, Anchor, bool)>> {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let cursor = editor.selections.newest_anchor().head();
+    let (_, symbols) = snapshot.symbols_containing(cursor, None)?;
+    let symbol = symbols.get(symbol_ix)?;
+    let symbol_start = symbol.range.start.to_offset(&snapshot);
+    let parent_range = symbol_ix
+        .checked_sub(1)
+        .and_then(|parent_ix| symbols.get(parent_ix))
+        .map(|parent| {
+            parent.range.start.to_offset(&snapshot)..parent.range.end.to_offset(&snapshot)
+        });
+
+    let outline = snapshot.outline(None)?;
+    let siblings = outline
+        .items
+        .into_iter()
+        .filter_map(|item| {
+            let start = item.range.start.to_offset(&snapshot);
+            let end = item.range.end.to_offset(&snapshot);
+            let within_parent = parent_range
+                .as_ref()
+                .map_or(true, |parent| parent.start <= start && end <= parent.end);
+            (item.depth == symbol.depth && within_parent)
+                .then(|| (item.text, item.range.start, start == symbol_start))
+        })
+        .collect();
+    Some(siblings)
+}
+
 impl ToolbarItemView for Breadcrumbs {
     fn set_active_pane_item(
         &mut self,
@@ -0,0 +1,748 @@
+mod call_hierarchy_panel_settings;
+
+use std::sync::Arc;
+
+use call_hierarchy_panel_settings::CallHierarchyPanelSettings;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, prelude::FluentBuilder, px, Action, AppContext, AsyncWindowContext, EventEmitter,
+    FocusHandle, FocusableView, InteractiveElement, IntoElement, KeyContext, Model, ParentElement,
+    Pixels, Render, SharedString, StatefulInteractiveElement, Styled, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
+};
+use language::{Location, OffsetRangeExt};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{CallHierarchyCall, CallHierarchyDirection, CallHierarchyItem, Fs, Project};
+use settings::Settings;
+use ui::{
+    h_flex, v_flex, ActiveTheme, Button, Clickable, Color, IconName, Label, LabelCommon, LabelSize,
+    ListItem, Selectable,
+};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPanelState, DockPosition, Panel, PanelEvent},
+    item::ItemHandle,
+    Workspace,
+};
+
+actions!(
+    call_hierarchy_panel,
+    [
+        ToggleFocus,
+        ShowIncomingCalls,
+        ShowOutgoingCalls,
+        ExpandSelectedEntry,
+        CollapseSelectedEntry,
+    ]
+);
+
+const CALL_HIERARCHY_PANEL_KEY: &str = "CallHierarchyPanel";
+
+pub fn init(cx: &mut AppContext) {
+    CallHierarchyPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<CallHierarchyPanel>(cx);
+        });
+        workspace.register_action(|workspace, _: &ShowIncomingCalls, cx| {
+            CallHierarchyPanel::show_call_hierarchy(workspace, CallHierarchyDirection::Incoming, cx)
+        });
+        workspace.register_action(|workspace, _: &ShowOutgoingCalls, cx| {
+            CallHierarchyPanel::show_call_hierarchy(workspace, CallHierarchyDirection::Outgoing, cx)
+        });
+    })
+    .detach();
+}
+
+/// Shows the calls to or from the function under the cursor as a tree, whose nodes request
+/// their own calls from the language server when they're first expanded.
+pub struct CallHierarchyPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    dock_state: DockPanelState,
+    direction: CallHierarchyDirection,
+    /// The items the tree was prepared for, so that its direction can be switched.
+    prepared_items: Vec<CallHierarchyItem>,
+    roots: Vec<CallNode>,
+    /// The path of child indices from the roots to the selected node.
+    selected: Option<Vec<usize>>,
+    /// Bumped whenever the tree is replaced, so that stale call requests are discarded.
+    generation: usize,
+    prepare_task: Task<()>,
+}
+
+struct CallNode {
+    item: CallHierarchyItem,
+    /// Where the calls are made, empty for the roots of the tree.
+    call_sites: Vec<Location>,
+    /// The node's own calls, once they've been requested.
+    children: Option<Vec<CallNode>>,
+    expanded: bool,
+    _load_task: Option<Task<()>>,
+}
+
+impl CallNode {
+    fn new(item: CallHierarchyItem, call_sites: Vec<Location>) -> Self {
+        Self {
+            item,
+            call_sites,
+            children: None,
+            expanded: false,
+            _load_task: None,
+        }
+    }
+}
+
+impl CallHierarchyPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let dock_state =
+            DockPanelState::load(CALL_HIERARCHY_PANEL_KEY, cx.background_executor().clone()).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            Self::new(workspace, dock_state, cx)
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        dock_state: DockPanelState,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let workspace_handle = cx.view().downgrade();
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| Self {
+            workspace: workspace_handle,
+            project,
+            fs,
+            focus_handle: cx.focus_handle(),
+            dock_state,
+            direction: CallHierarchyDirection::default(),
+            prepared_items: Vec::new(),
+            roots: Vec::new(),
+            selected: None,
+            generation: 0,
+            prepare_task: Task::ready(()),
+        })
+    }
+
+    /// Prepares the call hierarchy of the function under the cursor of the active editor and
+    /// shows it in the panel.
+    fn show_call_hierarchy(
+        workspace: &mut Workspace,
+        direction: CallHierarchyDirection,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some(panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        }) else {
+            return;
+        };
+
+        workspace.open_panel::<Self>(cx);
+        panel.update(cx, |panel, cx| {
+            let items = panel.project.update(cx, |project, cx| {
+                project.prepare_call_hierarchy(&buffer, position, cx)
+            });
+            panel.prepare_task = cx.spawn(|panel, mut cx| async move {
+                let Some(items) = items.await.log_err() else {
+                    return;
+                };
+                panel
+                    .update(&mut cx, |panel, cx| {
+                        panel.prepared_items = items;
+                        panel.set_direction(direction, cx);
+                    })
+                    .ok();
+            });
+        });
+    }
+
+    /// Rebuilds the tree from the prepared items, listing their calls in the given direction.
+    fn set_direction(&mut self, direction: CallHierarchyDirection, cx: &mut ViewContext<Self>) {
+        self.direction = direction;
+        self.generation += 1;
+        self.roots = self
+            .prepared_items
+            .iter()
+            .map(|item| CallNode::new(item.clone(), Vec::new()))
+            .collect();
+        self.selected = None;
+        if !self.roots.is_empty() {
+            self.selected = Some(vec![0]);
+            self.set_expanded(vec![0], true, cx);
+        }
+        cx.notify();
+    }
+
+    fn node(&self, path: &[usize]) -> Option<&CallNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get(*first)?;
+        for ix in rest {
+            node = node.children.as_ref()?.get(*ix)?;
+        }
+        Some(node)
+    }
+
+    fn node_mut(&mut self, path: &[usize]) -> Option<&mut CallNode> {
+        let (first, rest) = path.split_first()?;
+        let mut node = self.roots.get_mut(*first)?;
+        for ix in rest {
+            node = node.children.as_mut()?.get_mut(*ix)?;
+        }
+        Some(node)
+    }
+
+    /// Expands or collapses the node at the given path, requesting its calls the first time it
+    /// is expanded.
+    fn set_expanded(&mut self, path: Vec<usize>, expanded: bool, cx: &mut ViewContext<Self>) {
+        let direction = self.direction;
+        let generation = self.generation;
+        let project = self.project.clone();
+        let Some(node) = self.node_mut(&path) else {
+            return;
+        };
+        node.expanded = expanded;
+        if expanded && node.children.is_none() && node._load_task.is_none() {
+            let calls = project.update(cx, |project, cx| {
+                project.call_hierarchy_calls(&node.item, direction, cx)
+            });
+            node._load_task = Some(cx.spawn(|panel, mut cx| async move {
+                let calls = calls.await.log_err().unwrap_or_default();
+                panel
+                    .update(&mut cx, |panel, cx| {
+                        if panel.generation == generation {
+                            panel.set_calls(&path, calls, cx);
+                        }
+                    })
+                    .ok();
+            }));
+        }
+        cx.notify();
+    }
+
+    fn set_calls(
+        &mut self,
+        path: &[usize],
+        calls: Vec<CallHierarchyCall>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(node) = self.node_mut(path) {
+            node.children = Some(
+                calls
+                    .into_iter()
+                    .map(|call| CallNode::new(call.item, call.call_sites))
+                    .collect(),
+            );
+            node._load_task = None;
+            cx.notify();
+        }
+    }
+
+    /// The paths of the visible nodes in order, with their depths.
+    fn visible_entries(&self) -> Vec<(Vec<usize>, usize)> {
+        fn push_entries(
+            nodes: &[CallNode],
+            path: &mut Vec<usize>,
+            entries: &mut Vec<(Vec<usize>, usize)>,
+        ) {
+            for (ix, node) in nodes.iter().enumerate() {
+                path.push(ix);
+                entries.push((path.clone(), path.len() - 1));
+                if node.expanded {
+                    if let Some(children) = &node.children {
+                        push_entries(children, path, entries);
+                    }
+                }
+                path.pop();
+            }
+        }
+
+        let mut entries = Vec::new();
+        push_entries(&self.roots, &mut Vec::new(), &mut entries);
+        entries
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let entries = self.visible_entries();
+        let ix = match self.selected_entry_ix(&entries) {
+            Some(ix) => (ix + 1).min(entries.len().saturating_sub(1)),
+            None => 0,
+        };
+        self.selected = entries.get(ix).map(|(path, _)| path.clone());
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let entries = self.visible_entries();
+        let ix = match self.selected_entry_ix(&entries) {
+            Some(ix) => ix.saturating_sub(1),
+            None => entries.len().saturating_sub(1),
+        };
+        self.selected = entries.get(ix).map(|(path, _)| path.clone());
+        cx.notify();
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.selected = self.visible_entries().first().map(|(path, _)| path.clone());
+        cx.notify();
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        self.selected = self.visible_entries().last().map(|(path, _)| path.clone());
+        cx.notify();
+    }
+
+    fn selected_entry_ix(&self, entries: &[(Vec<usize>, usize)]) -> Option<usize> {
+        let selected = self.selected.as_ref()?;
+        entries.iter().position(|(path, _)| path == selected)
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selected.clone() {
+            self.set_expanded(path, true, cx);
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        let Some(mut path) = self.selected.clone() else {
+            return;
+        };
+        // Collapsing a collapsed node selects its parent, as in the project panel.
+        if self.node(&path).map_or(false, |node| node.expanded) {
+            self.set_expanded(path, false, cx);
+        } else if path.len() > 1 {
+            path.pop();
+            self.selected = Some(path);
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(path) = self.selected.clone() {
+            self.open_entry(path, false, cx);
+        }
+    }
+
+    /// Opens the first call site of the node at the given path, or the definition of the node's
+    /// item for the roots of the tree.
+    fn open_entry(&mut self, path: Vec<usize>, focus_editor: bool, cx: &mut ViewContext<Self>) {
+        let Some(node) = self.node(&path) else {
+            return;
+        };
+        let location = node
+            .call_sites
+            .first()
+            .unwrap_or(&node.item.location)
+            .clone();
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        self.selected = Some(path);
+        let range = location.range.to_offset(location.buffer.read(cx));
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor = workspace.open_project_item::<Editor>(
+                pane,
+                location.buffer,
+                true,
+                focus_editor,
+                cx,
+            );
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([range])
+                });
+            });
+        });
+        cx.notify();
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("CallHierarchyPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let direction = self.direction;
+        h_flex()
+            .gap_1()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Button::new("incoming-calls", "Incoming Calls")
+                    .label_size(LabelSize::Small)
+                    .selected(direction == CallHierarchyDirection::Incoming)
+                    .on_click(cx.listener(|panel, _, cx| {
+                        panel.set_direction(CallHierarchyDirection::Incoming, cx)
+                    })),
+            )
+            .child(
+                Button::new("outgoing-calls", "Outgoing Calls")
+                    .label_size(LabelSize::Small)
+                    .selected(direction == CallHierarchyDirection::Outgoing)
+                    .on_click(cx.listener(|panel, _, cx| {
+                        panel.set_direction(CallHierarchyDirection::Outgoing, cx)
+                    })),
+            )
+    }
+
+    fn render_entry(
+        &self,
+        path: Vec<usize>,
+        depth: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<impl IntoElement> {
+        let node = self.node(&path)?;
+        let id = SharedString::from(format!(
+            "call-{}",
+            path.iter()
+                .map(|ix| ix.to_string())
+                .collect::<Vec<_>>()
+                .join("-")
+        ));
+        // Nodes whose calls have been loaded and turned out empty can't be expanded.
+        let toggle = match &node.children {
+            Some(children) if children.is_empty() => None,
+            _ => Some(node.expanded),
+        };
+        let loading = node.expanded && node.children.is_none();
+
+        Some(
+            ListItem::new(id)
+                .indent_level(depth)
+                .indent_step_size(px(12.))
+                .selected(self.selected.as_ref() == Some(&path))
+                .toggle(toggle)
+                .on_toggle(cx.listener({
+                    let path = path.clone();
+                    move |panel, _, cx| {
+                        let expanded = panel.node(&path).map_or(false, |node| node.expanded);
+                        panel.set_expanded(path.clone(), !expanded, cx)
+                    }
+                }))
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(node.item.name.clone()))
+                        .when_some(node.item.detail.clone(), |this, detail| {
+                            this.child(
+                                Label::new(detail)
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        })
+                        .when(loading, |this| {
+                            this.child(
+                                Label::new("Loading…")
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                        }),
+                )
+                .on_click(
+                    cx.listener(move |panel, _, cx| panel.open_entry(path.clone(), true, cx)),
+                ),
+        )
+    }
+}
+
+impl Panel for CallHierarchyPanel {
+    fn persistent_name() -> &'static str {
+        "Call Hierarchy Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        CallHierarchyPanelSettings::get_global(cx).dock.into()
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<CallHierarchyPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.dock_state.size(
+            self.position(cx),
+            CallHierarchyPanelSettings::get_global(cx),
+        )
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        let position = self.position(cx);
+        self.dock_state.set_size(position, size, cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        CallHierarchyPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Code)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Call Hierarchy Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.dock_state.active()
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.dock_state.set_active(active, cx);
+    }
+}
+
+impl FocusableView for CallHierarchyPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for CallHierarchyPanel {}
+
+impl Render for CallHierarchyPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.roots.is_empty() {
+            let keystroke = cx.keystroke_text_for(&ShowIncomingCalls);
+            v_flex()
+                .id("call-hierarchy-empty")
+                .flex_1()
+                .justify_center()
+                .items_center()
+                .child(
+                    Label::new(format!(
+                        "Show the calls of the function under the cursor with {keystroke}"
+                    ))
+                    .color(Color::Muted),
+                )
+        } else {
+            v_flex()
+                .id("call-hierarchy")
+                .flex_1()
+                .overflow_y_scroll()
+                .py_1()
+                .children(
+                    self.visible_entries()
+                        .into_iter()
+                        .filter_map(|(path, depth)| self.render_entry(path, depth, cx)),
+                )
+        };
+
+        v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_call_hierarchy_panel(cx: &mut TestAppContext) {
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.update(|cx| init(cx));
+        let panel = cx.update_workspace(|workspace, cx| {
+            let panel = CallHierarchyPanel::new(workspace, DockPanelState::new("test"), cx);
+            workspace.add_panel(panel.clone(), cx);
+            panel
+        });
+
+        cx.set_state(indoc! {"
+            fn a() {
+                b();
+            }
+            fn ˇb() {
+                c();
+            }
+            fn c() {}
+        "});
+        let url = cx.buffer_lsp_url.clone();
+        let lsp_item = |name: &str, row: u32| lsp::CallHierarchyItem {
+            name: name.to_string(),
+            kind: lsp::SymbolKind::FUNCTION,
+            tags: None,
+            detail: None,
+            uri: url.clone(),
+            range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row + 1, 0)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(row, 3),
+                lsp::Position::new(row, 4),
+            ),
+            data: None,
+        };
+        let (a, b, c) = (lsp_item("a", 0), lsp_item("b", 3), lsp_item("c", 6));
+        let _prepare_requests =
+            cx.handle_request::<lsp::request::CallHierarchyPrepare, _, _>(move |_, _, _| {
+                let b = b.clone();
+                async move { Ok(Some(vec![b])) }
+            });
+        let _incoming_requests = cx
+            .handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+                move |_, params, _| {
+                    let a = a.clone();
+                    async move {
+                        // Only `b` is called, by `a`.
+                        let calls = if params.item.name == "b" {
+                            vec![lsp::CallHierarchyIncomingCall {
+                                from: a,
+                                from_ranges: vec![lsp::Range::new(
+                                    lsp::Position::new(1, 4),
+                                    lsp::Position::new(1, 5),
+                                )],
+                            }]
+                        } else {
+                            Vec::new()
+                        };
+                        Ok(Some(calls))
+                    }
+                },
+            );
+        let _outgoing_requests = cx
+            .handle_request::<lsp::request::CallHierarchyOutgoingCalls, _, _>(
+                move |_, params, _| {
+                    let c = c.clone();
+                    async move {
+                        assert_eq!(params.item.name, "b");
+                        Ok(Some(vec![lsp::CallHierarchyOutgoingCall {
+                            to: c,
+                            from_ranges: vec![lsp::Range::new(
+                                lsp::Position::new(4, 4),
+                                lsp::Position::new(4, 5),
+                            )],
+                        }]))
+                    }
+                },
+            );
+
+        // The prepared item is expanded to list its incoming calls.
+        cx.update_workspace(|workspace, cx| {
+            CallHierarchyPanel::show_call_hierarchy(workspace, CallHierarchyDirection::Incoming, cx)
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(
+                visible_entries(panel.read(cx)),
+                [("b".to_string(), 0), ("a".to_string(), 1)]
+            );
+        });
+
+        // Expanding a caller requests its own calls, and it can't be expanded once they
+        // turn out to be empty.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.select_next(&SelectNext, cx);
+                panel.expand_selected_entry(&ExpandSelectedEntry, cx);
+                assert!(panel.node(&[0, 0]).unwrap().children.is_none());
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            let panel = panel.read(cx);
+            let caller = panel.node(&[0, 0]).unwrap();
+            assert!(caller.expanded);
+            assert_eq!(caller.children.as_ref().map(Vec::len), Some(0));
+        });
+
+        // Switching the direction lists the outgoing calls of the prepared item instead.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.set_direction(CallHierarchyDirection::Outgoing, cx)
+            })
+        });
+        cx.run_until_parked();
+        cx.update(|cx| {
+            assert_eq!(
+                visible_entries(panel.read(cx)),
+                [("b".to_string(), 0), ("c".to_string(), 1)]
+            );
+        });
+
+        // Confirming a call opens where it is made.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.select_next(&SelectNext, cx);
+                panel.confirm(&Confirm, cx);
+            })
+        });
+        cx.assert_editor_state(indoc! {"
+            fn a() {
+                b();
+            }
+            fn b() {
+                «cˇ»();
+            }
+            fn c() {}
+        "});
+
+        // Collapsing a collapsed node selects its parent, and then collapses it.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.collapse_selected_entry(&CollapseSelectedEntry, cx);
+                assert_eq!(panel.selected, Some(vec![0]));
+                panel.collapse_selected_entry(&CollapseSelectedEntry, cx);
+                assert_eq!(visible_entries(panel), [("b".to_string(), 0)]);
+            })
+        });
+    }
+
+    fn visible_entries(panel: &CallHierarchyPanel) -> Vec<(String, usize)> {
+        panel
+            .visible_entries()
+            .into_iter()
+            .map(|(path, depth)| (panel.node(&path).unwrap().item.name.clone(), depth))
+            .collect()
+    }
+}
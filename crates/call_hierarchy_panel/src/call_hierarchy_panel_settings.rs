@@ -0,0 +1,30 @@
+use anyhow;
+use serde::Deserialize;
+use settings::{Settings, SettingsSources};
+use std::ops::Deref;
+use workspace::dock::{DockPanelSettings, DockPanelSettingsContent};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+pub struct CallHierarchyPanelSettings(pub DockPanelSettings);
+
+impl Deref for CallHierarchyPanelSettings {
+    type Target = DockPanelSettings;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Settings for CallHierarchyPanelSettings {
+    const KEY: Option<&'static str> = Some("call_hierarchy_panel");
+
+    type FileContent = DockPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
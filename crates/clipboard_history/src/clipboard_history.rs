@@ -0,0 +1,377 @@
+mod clipboard_history_settings;
+mod persistence;
+
+use std::{collections::VecDeque, path::PathBuf, sync::Arc, time::Duration};
+
+pub use clipboard_history_settings::ClipboardHistorySettings;
+use editor::{Editor, EditorEvent};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, ClipboardItem, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    Global, ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use persistence::CLIPBOARD_HISTORY_DB;
+use picker::{Picker, PickerDelegate};
+use settings::Settings;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::{truncate_and_trailoff, ResultExt};
+use workspace::{ModalView, Workspace};
+
+actions!(clipboard_history, [PasteFromHistory, ClearHistory]);
+
+/// How many characters of each entry are shown in the picker.
+const MAX_PREVIEW_LEN: usize = 200;
+/// How long the history waits for more copies before saving them.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+pub fn init(cx: &mut AppContext) {
+    ClipboardHistorySettings::register(cx);
+
+    let mut history = ClipboardHistory::default();
+    if ClipboardHistorySettings::get_global(cx).persist {
+        if let Some(entries) = CLIPBOARD_HISTORY_DB.entries().log_err() {
+            history.entries = entries
+                .into_iter()
+                .map(|(text, source_path)| ClipboardEntry {
+                    text,
+                    source_path: source_path.map(PathBuf::from),
+                })
+                .collect();
+        }
+    }
+    cx.set_global(history);
+
+    cx.observe_new_views(|_, cx: &mut ViewContext<Editor>| {
+        let editor = cx.view().clone();
+        cx.subscribe(&editor, record_copy).detach();
+    })
+    .detach();
+    cx.observe_new_views(ClipboardHistoryModal::register)
+        .detach();
+}
+
+fn record_copy(
+    editor: &mut Editor,
+    _: View<Editor>,
+    event: &EditorEvent,
+    cx: &mut ViewContext<Editor>,
+) {
+    if let EditorEvent::Copied { text } = event {
+        let source_path = editor
+            .active_excerpt(cx)
+            .and_then(|(_, buffer, _)| Some(buffer.read(cx).file()?.path().to_path_buf()));
+        ClipboardHistory::push(
+            ClipboardEntry {
+                text: text.clone(),
+                source_path,
+            },
+            cx,
+        );
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClipboardEntry {
+    pub text: String,
+    /// The path of the file the text was copied from, relative to its worktree.
+    pub source_path: Option<PathBuf>,
+}
+
+/// The text recently copied or cut in editors, newest first.
+#[derive(Default)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardEntry>,
+    pending_save: Option<Task<()>>,
+}
+
+impl Global for ClipboardHistory {}
+
+impl ClipboardHistory {
+    pub fn entries(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.entries.iter()
+    }
+
+    /// Adds an entry to the front of the history, replacing any older entry with the same text.
+    pub fn push(entry: ClipboardEntry, cx: &mut AppContext) {
+        let max_entries = ClipboardHistorySettings::get_global(cx).max_entries;
+        cx.update_global::<Self, _>(|history, _| {
+            history
+                .entries
+                .retain(|existing| existing.text != entry.text);
+            history.entries.push_front(entry);
+            history.entries.truncate(max_entries);
+        });
+        Self::save(cx);
+    }
+
+    pub fn clear(cx: &mut AppContext) {
+        cx.update_global::<Self, _>(|history, _| history.entries.clear());
+        Self::save(cx);
+    }
+
+    fn save(cx: &mut AppContext) {
+        if !ClipboardHistorySettings::get_global(cx).persist {
+            return;
+        }
+
+        // Replacing a pending save restarts the delay, so that copying repeatedly only saves
+        // the history once.
+        let task = cx.spawn(|cx| async move {
+            cx.background_executor().timer(SAVE_DEBOUNCE).await;
+            let Some(entries) = cx
+                .update(|cx| {
+                    cx.global::<Self>()
+                        .entries
+                        .iter()
+                        .map(|entry| {
+                            let source_path = entry
+                                .source_path
+                                .as_ref()
+                                .map(|path| path.to_string_lossy().into_owned());
+                            (entry.text.clone(), source_path)
+                        })
+                        .collect()
+                })
+                .log_err()
+            else {
+                return;
+            };
+            CLIPBOARD_HISTORY_DB.save_entries(entries).await.log_err();
+        });
+        cx.global_mut::<Self>().pending_save = Some(task);
+    }
+}
+
+pub struct ClipboardHistoryModal {
+    picker: View<Picker<ClipboardHistoryDelegate>>,
+}
+
+impl ClipboardHistoryModal {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(|workspace, _: &PasteFromHistory, cx| {
+            Self::toggle(workspace, cx);
+        });
+        workspace.register_action(|_, _: &ClearHistory, cx| {
+            ClipboardHistory::clear(cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
+        let editor = workspace.active_item(cx)?.act_as::<Editor>(cx)?;
+        workspace.toggle_modal(cx, move |cx| {
+            ClipboardHistoryModal::new(editor.downgrade(), cx)
+        });
+        Some(())
+    }
+
+    fn new(editor: WeakView<Editor>, cx: &mut ViewContext<Self>) -> Self {
+        let entries = cx.global::<ClipboardHistory>().entries().cloned().collect();
+        let delegate = ClipboardHistoryDelegate::new(cx.view().downgrade(), editor, entries);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for ClipboardHistoryModal {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for ClipboardHistoryModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for ClipboardHistoryModal {}
+impl ModalView for ClipboardHistoryModal {}
+
+pub struct ClipboardHistoryDelegate {
+    modal: WeakView<ClipboardHistoryModal>,
+    editor: WeakView<Editor>,
+    entries: Vec<ClipboardEntry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl ClipboardHistoryDelegate {
+    fn new(
+        modal: WeakView<ClipboardHistoryModal>,
+        editor: WeakView<Editor>,
+        entries: Vec<ClipboardEntry>,
+    ) -> Self {
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, entry)| {
+                // Show each entry on a single line.
+                let preview = entry.text.split_whitespace().collect::<Vec<_>>().join(" ");
+                StringMatchCandidate::new(
+                    candidate_id,
+                    truncate_and_trailoff(&preview, MAX_PREVIEW_LEN),
+                )
+            })
+            .collect();
+
+        Self {
+            modal,
+            editor,
+            entries,
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ClipboardHistoryDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Paste from clipboard history...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = self.entries[mat.candidate_id].clone();
+            cx.write_to_clipboard(ClipboardItem::new(entry.text.clone()));
+            self.editor
+                .update(cx, |editor, cx| {
+                    editor.do_paste(&entry.text, None, true, cx)
+                })
+                .log_err();
+            ClipboardHistory::push(entry, cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let entry = &self.entries[mat.candidate_id];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(entry.source_path.as_ref().map(|path| {
+                    Label::new(path.to_string_lossy().into_owned())
+                        .color(Color::Muted)
+                        .size(LabelSize::Small)
+                })),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    fn test_clipboard_history(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            ClipboardHistorySettings::register(cx);
+            cx.set_global(ClipboardHistory::default());
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<ClipboardHistorySettings>(cx, |settings| {
+                    settings.max_entries = Some(2);
+                });
+            });
+        });
+
+        cx.update(|cx| {
+            for text in ["one", "two", "one", "three", "two"] {
+                ClipboardHistory::push(
+                    ClipboardEntry {
+                        text: text.into(),
+                        source_path: None,
+                    },
+                    cx,
+                );
+            }
+
+            // Copying text that's already in the history moves it to the front, and only the
+            // newest entries are kept.
+            assert_eq!(
+                cx.global::<ClipboardHistory>()
+                    .entries()
+                    .map(|entry| entry.text.as_str())
+                    .collect::<Vec<_>>(),
+                ["two", "three"]
+            );
+
+            ClipboardHistory::clear(cx);
+            assert_eq!(cx.global::<ClipboardHistory>().entries().count(), 0);
+        });
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct ClipboardHistorySettings {
+    pub max_entries: usize,
+    pub persist: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct ClipboardHistorySettingsContent {
+    /// How many of the most recent copies to keep in the clipboard history.
+    ///
+    /// Default: 20
+    pub max_entries: Option<usize>,
+    /// Whether to keep the clipboard history across restarts.
+    ///
+    /// Default: false
+    pub persist: Option<bool>,
+}
+
+impl Settings for ClipboardHistorySettings {
+    const KEY: Option<&'static str> = Some("clipboard_history");
+
+    type FileContent = ClipboardHistorySettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+
+define_connection!(pub static ref CLIPBOARD_HISTORY_DB: ClipboardHistoryDb<()> =
+    &[sql!(
+        CREATE TABLE IF NOT EXISTS clipboard_history(
+            position INTEGER PRIMARY KEY,
+            text TEXT NOT NULL,
+            source_path TEXT
+        ) STRICT;
+    )];
+);
+
+impl ClipboardHistoryDb {
+    query! {
+        pub fn entries() -> Result<Vec<(String, Option<String>)>> {
+            SELECT text, source_path
+            FROM clipboard_history
+            ORDER BY position
+        }
+    }
+
+    /// Replaces the saved history with the given entries, newest first.
+    pub async fn save_entries(&self, entries: Vec<(String, Option<String>)>) -> Result<()> {
+        self.write(move |conn| {
+            conn.with_savepoint("save_clipboard_history", || {
+                conn.exec(sql!(DELETE FROM clipboard_history))?()?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO clipboard_history(position, text, source_path)
+                    VALUES (?, ?, ?)
+                ))?;
+                for (position, (text, source_path)) in entries.into_iter().enumerate() {
+                    insert((position as i64, text, source_path))?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
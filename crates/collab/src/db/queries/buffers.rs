@@ -447,7 +447,9 @@ impl Database {
             let mut requires_write_permission = false;
             for op in operations.iter() {
                 match op.variant {
-                    None | Some(proto::operation::Variant::UpdateSelections(_)) => {}
+                    None
+                    | Some(proto::operation::Variant::UpdateSelections(_))
+                    | Some(proto::operation::Variant::UpdateViewport(_)) => {}
                     Some(_) => requires_write_permission = true,
                 }
             }
@@ -504,6 +504,12 @@ impl Server {
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::InlayHints>,
             ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetFoldingRanges>,
+            ))
+            .add_request_handler(user_handler(
+                forward_read_only_project_request::<proto::GetDocumentColors>,
+            ))
             .add_request_handler(user_handler(
                 forward_read_only_project_request::<proto::OpenBufferByPath>,
             ))
@@ -3109,7 +3115,7 @@ async fn create_buffer_for_peer(
 }
 
 /// Notify other participants that a buffer has been updated. This is
-/// allowed for guests as long as the update is limited to selections.
+/// allowed for guests as long as the update is limited to selections and viewports.
 async fn update_buffer(
     request: proto::UpdateBuffer,
     response: Response<proto::UpdateBuffer>,
@@ -3120,7 +3126,9 @@ async fn update_buffer(
 
     for op in request.operations.iter() {
         match op.variant {
-            None | Some(proto::operation::Variant::UpdateSelections(_)) => {}
+            None
+            | Some(proto::operation::Variant::UpdateSelections(_))
+            | Some(proto::operation::Variant::UpdateViewport(_)) => {}
             Some(_) => capability = Capability::ReadWrite,
         }
     }
@@ -3171,9 +3179,9 @@ async fn update_context(message: proto::UpdateContext, session: Session) -> Resu
         Some(proto::context_operation::Variant::BufferOperation(buffer_op)) => {
             if let Some(buffer_op) = buffer_op.operation.as_ref() {
                 match buffer_op.variant {
-                    None | Some(proto::operation::Variant::UpdateSelections(_)) => {
-                        Capability::ReadOnly
-                    }
+                    None
+                    | Some(proto::operation::Variant::UpdateSelections(_))
+                    | Some(proto::operation::Variant::UpdateViewport(_)) => Capability::ReadOnly,
                     _ => Capability::ReadWrite,
                 }
             } else {
@@ -1522,6 +1522,7 @@ async fn test_mutual_editor_inlay_hint_cache_update(
                     show_type_hints: true,
                     show_parameter_hints: false,
                     show_other_hints: true,
+                    syntax_parameter_hints: false,
                 })
             });
         });
@@ -1536,6 +1537,7 @@ async fn test_mutual_editor_inlay_hint_cache_update(
                     show_type_hints: true,
                     show_parameter_hints: false,
                     show_other_hints: true,
+                    syntax_parameter_hints: false,
                 })
             });
         });
@@ -1784,6 +1786,7 @@ async fn test_inlay_hint_refresh_is_forwarded(
                     show_type_hints: false,
                     show_parameter_hints: false,
                     show_other_hints: false,
+                    syntax_parameter_hints: false,
                 })
             });
         });
@@ -1798,6 +1801,7 @@ async fn test_inlay_hint_refresh_is_forwarded(
                     show_type_hints: true,
                     show_parameter_hints: true,
                     show_other_hints: true,
+                    syntax_parameter_hints: false,
                 })
             });
         });
@@ -2263,6 +2267,7 @@ async fn test_git_blame_is_forwarded(cx_a: &mut TestAppContext, cx_b: &mut TestA
         enabled: false,
         delay_ms: None,
         min_column: None,
+        show_commit_summary: true,
     });
     cx_a.update(|cx| {
         SettingsStore::update_global(cx, |store, cx| {
@@ -542,6 +542,7 @@ impl FollowableItem for ChannelView {
                                 scroll_top_anchor: state.scroll_top_anchor,
                                 scroll_x: state.scroll_x,
                                 scroll_y: state.scroll_y,
+                                text_scale: state.text_scale,
                                 ..Default::default()
                             }),
                             cx,
@@ -162,10 +162,12 @@ impl ProjectDiagnosticsEditor {
                 project_handle.read(cx).capability(),
             )
         });
+        let include_warnings = ProjectDiagnosticsSettings::get_global(cx).include_warnings;
         let editor = cx.new_view(|cx| {
             let mut editor =
                 Editor::for_multibuffer(excerpts.clone(), Some(project_handle.clone()), false, cx);
             editor.set_vertical_scroll_margin(5, cx);
+            editor.set_scroll_position_key(Some(scroll_position_key(include_warnings)), cx);
             editor
         });
         cx.subscribe(&editor, |this, _editor, event: &EditorEvent, cx| {
@@ -195,7 +197,7 @@ impl ProjectDiagnosticsEditor {
             editor,
             path_states: Default::default(),
             paths_to_update: Default::default(),
-            include_warnings: ProjectDiagnosticsSettings::get_global(cx).include_warnings,
+            include_warnings,
             update_paths_tx: update_excerpts_tx,
             _update_excerpts_task: cx.spawn(move |this, mut cx| async move {
                 while let Some((path, language_server_id)) = update_excerpts_rx.next().await {
@@ -244,6 +246,10 @@ impl ProjectDiagnosticsEditor {
 
     fn toggle_warnings(&mut self, _: &ToggleWarnings, cx: &mut ViewContext<Self>) {
         self.include_warnings = !self.include_warnings;
+        let key = scroll_position_key(self.include_warnings);
+        self.editor.update(cx, |editor, cx| {
+            editor.set_scroll_position_key(Some(key), cx)
+        });
         self.enqueue_update_all_excerpts(cx);
         cx.notify();
     }
@@ -775,6 +781,12 @@ impl Item for ProjectDiagnosticsEditor {
 
 const DIAGNOSTIC_HEADER: &'static str = "diagnostic header";
 
+/// The key the scroll position is remembered under in the workspace, so that reopening
+/// the diagnostics restores it.
+fn scroll_position_key(include_warnings: bool) -> SharedString {
+    format!("diagnostics:{include_warnings}").into()
+}
+
 fn diagnostic_header_renderer(diagnostic: Diagnostic) -> RenderBlock {
     let (message, code_ranges) = highlight_diagnostic_message(&diagnostic, None);
     let message: SharedString = message;
@@ -813,6 +813,82 @@ async fn test_random_diagnostics(cx: &mut TestAppContext, mut rng: StdRng) {
     assert_eq!(mutated_excerpts, reference_excerpts);
 }
 
+#[gpui::test]
+async fn test_diagnostics_scroll_position_is_restored(cx: &mut TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    let text = (0..40)
+        .map(|row| format!("let x{row} = y;\n"))
+        .collect::<String>();
+    fs.insert_tree("/test", json!({ "main.rs": text })).await;
+
+    let project = Project::test(fs.clone(), ["/test".as_ref()], cx).await;
+    let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+    let cx = &mut VisualTestContext::from_window(*window, cx);
+    let workspace = window.root(cx).unwrap();
+    let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+    workspace.update(cx, |workspace, _| workspace.set_database_id(workspace_id));
+
+    project.update(cx, |project, cx| {
+        project
+            .update_diagnostic_entries(
+                LanguageServerId(0),
+                PathBuf::from("/test/main.rs"),
+                None,
+                (0..40)
+                    .step_by(10)
+                    .map(|row| DiagnosticEntry {
+                        range: Unclipped(PointUtf16::new(row, 12))
+                            ..Unclipped(PointUtf16::new(row, 13)),
+                        diagnostic: Diagnostic {
+                            message: format!("unknown value {row}"),
+                            severity: DiagnosticSeverity::ERROR,
+                            is_primary: true,
+                            group_id: row as usize,
+                            ..Default::default()
+                        },
+                    })
+                    .collect(),
+                cx,
+            )
+            .unwrap();
+    });
+
+    let open_diagnostics = |cx: &mut VisualTestContext| {
+        let view = window.build_view(cx, |cx| {
+            ProjectDiagnosticsEditor::new_with_context(
+                1,
+                project.clone(),
+                workspace.downgrade(),
+                cx,
+            )
+        });
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(view.clone()), None, true, cx)
+        });
+        view
+    };
+
+    let view = open_diagnostics(cx);
+    view.next_notification(cx).await;
+    let editor = view.update(cx, |view, _| view.editor.clone());
+    editor.update(cx, |editor, cx| {
+        editor.set_scroll_position(gpui::point(0., 6.5), cx);
+    });
+    cx.run_until_parked();
+
+    // Reopening the diagnostics scrolls back to where they were left.
+    let view = open_diagnostics(cx);
+    view.next_notification(cx).await;
+    cx.run_until_parked();
+    let editor = view.update(cx, |view, _| view.editor.clone());
+    assert_eq!(
+        editor.update(cx, |editor, cx| editor.scroll_position(cx)),
+        gpui::point(0., 6.5)
+    );
+}
+
 fn init_test(cx: &mut TestAppContext) {
     cx.update(|cx| {
         let settings = SettingsStore::test(cx);
@@ -0,0 +1,422 @@
+mod documentation_panel_settings;
+
+use std::{sync::Arc, time::Duration};
+
+use documentation_panel_settings::DocumentationPanelSettings;
+use editor::{Anchor, Editor, EditorEvent};
+use gpui::{
+    actions, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, ParentElement, Pixels, Point, Render, ScrollHandle,
+    StatefulInteractiveElement, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use markdown::Markdown;
+use project::Fs;
+use settings::Settings;
+use ui::{
+    h_flex, v_flex, ActiveTheme, Color, IconButton, IconName, IconSize, Label, LabelCommon,
+    LabelSize, Selectable, Tooltip,
+};
+use workspace::{
+    dock::{DockPanelState, DockPosition, Panel, PanelEvent},
+    item::ItemHandle,
+    Workspace,
+};
+
+actions!(documentation_panel, [ToggleFocus, PinHover, ToggleLock]);
+
+const DOCUMENTATION_PANEL_KEY: &str = "DocumentationPanel";
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+pub fn init(cx: &mut AppContext) {
+    DocumentationPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<DocumentationPanel>(cx);
+        });
+        workspace.register_action(DocumentationPanel::pin_hover);
+    })
+    .detach();
+}
+
+/// Shows the hover documentation of the symbol under the cursor of the active editor,
+/// updating as the cursor moves unless the panel is locked.
+pub struct DocumentationPanel {
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    dock_state: DockPanelState,
+    locked: bool,
+    followed_editor: Option<FollowedEditor>,
+    documentation: Vec<View<Markdown>>,
+    update_task: Task<()>,
+    _workspace_subscription: Subscription,
+}
+
+struct FollowedEditor {
+    editor: WeakView<Editor>,
+    _subscription: Subscription,
+}
+
+impl DocumentationPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let dock_state =
+            DockPanelState::load(DOCUMENTATION_PANEL_KEY, cx.background_executor().clone()).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            Self::new(workspace, dock_state, cx)
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        dock_state: DockPanelState,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let workspace_handle = cx.view().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| {
+            let workspace_subscription =
+                cx.subscribe(&workspace_handle, |panel: &mut Self, _, event, cx| {
+                    if let workspace::Event::ActiveItemChanged = event {
+                        if panel.dock_state.active() && !panel.locked {
+                            panel.follow_active_editor(cx);
+                        }
+                    }
+                });
+
+            Self {
+                workspace: workspace_handle.downgrade(),
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: ScrollHandle::new(),
+                dock_state,
+                locked: false,
+                followed_editor: None,
+                documentation: Vec::new(),
+                update_task: Task::ready(()),
+                _workspace_subscription: workspace_subscription,
+            }
+        })
+    }
+
+    /// Moves the contents of the visible hover popover (or the documentation of the symbol under
+    /// the cursor, if there is no hover) into the panel, and unlocks the panel so that it follows
+    /// the cursor from there.
+    fn pin_hover(workspace: &mut Workspace, _: &PinHover, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some(panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+
+        let anchor = editor.update(cx, |editor, cx| {
+            editor
+                .dismiss_hover(cx)
+                .unwrap_or_else(|| editor.selections.newest_anchor().head())
+        });
+        workspace.open_panel::<Self>(cx);
+        panel.update(cx, |panel, cx| {
+            panel.locked = false;
+            panel.documentation.clear();
+            panel.follow_editor(editor.clone(), cx);
+            panel.update_documentation(&editor, anchor, None, cx);
+        });
+    }
+
+    fn toggle_lock(&mut self, _: &ToggleLock, cx: &mut ViewContext<Self>) {
+        self.locked = !self.locked;
+        if !self.locked {
+            self.follow_active_editor(cx);
+        }
+        cx.notify();
+    }
+
+    fn follow_active_editor(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(editor) = self
+            .workspace
+            .upgrade()
+            .and_then(|workspace| workspace.read(cx).active_item(cx))
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+
+        let anchor = editor.read(cx).selections.newest_anchor().head();
+        self.follow_editor(editor.clone(), cx);
+        self.update_documentation(&editor, anchor, None, cx);
+    }
+
+    fn follow_editor(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        if self.followed_editor.as_ref().map_or(false, |followed| {
+            followed.editor.entity_id() == editor.entity_id()
+        }) {
+            return;
+        }
+
+        let subscription = cx.subscribe(&editor, |panel, editor, event, cx| {
+            if let EditorEvent::SelectionsChanged { local: true } = event {
+                if panel.dock_state.active() && !panel.locked {
+                    let anchor = editor.read(cx).selections.newest_anchor().head();
+                    panel.update_documentation(&editor, anchor, Some(UPDATE_DEBOUNCE), cx);
+                }
+            }
+        });
+        self.followed_editor = Some(FollowedEditor {
+            editor: editor.downgrade(),
+            _subscription: subscription,
+        });
+    }
+
+    fn update_documentation(
+        &mut self,
+        editor: &View<Editor>,
+        anchor: Anchor,
+        debounce: Option<Duration>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let editor = editor.downgrade();
+        self.update_task = cx.spawn(|panel, mut cx| async move {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+
+            let Some(documentation) = editor
+                .update(&mut cx, |editor, cx| editor.hover_documentation(anchor, cx))
+                .ok()
+            else {
+                return;
+            };
+            let documentation = documentation.await;
+            // Keep showing the last documentation while the cursor is between symbols.
+            if documentation.is_empty() {
+                return;
+            }
+
+            panel
+                .update(&mut cx, |panel, cx| {
+                    panel.documentation = documentation;
+                    panel.scroll_handle.set_offset(Point::default());
+                    cx.notify();
+                })
+                .ok();
+        });
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let locked = self.locked;
+        h_flex()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new("Documentation")
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+            )
+            .child(
+                IconButton::new("toggle-documentation-lock", IconName::FileLock)
+                    .icon_size(IconSize::Small)
+                    .selected(locked)
+                    .tooltip(move |cx| {
+                        let label = if locked {
+                            "Unlock Documentation"
+                        } else {
+                            "Lock Documentation"
+                        };
+                        Tooltip::for_action(label, &ToggleLock, cx)
+                    })
+                    .on_click(cx.listener(|panel, _, cx| panel.toggle_lock(&ToggleLock, cx))),
+            )
+    }
+}
+
+impl Panel for DocumentationPanel {
+    fn persistent_name() -> &'static str {
+        "Documentation Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        DocumentationPanelSettings::get_global(cx).dock.into()
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<DocumentationPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.dock_state.size(
+            self.position(cx),
+            DocumentationPanelSettings::get_global(cx),
+        )
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        let position = self.position(cx);
+        self.dock_state.set_size(position, size, cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        DocumentationPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::Book)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("Documentation Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.dock_state.active()
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        let was_active = self.dock_state.active();
+        self.dock_state.set_active(active, cx);
+        if active && !was_active && !self.locked {
+            self.follow_active_editor(cx);
+        }
+    }
+}
+
+impl FocusableView for DocumentationPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DocumentationPanel {}
+
+impl Render for DocumentationPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.documentation.is_empty() {
+            let keystroke = cx.keystroke_text_for(&PinHover);
+            v_flex()
+                .id("documentation-empty")
+                .flex_1()
+                .justify_center()
+                .items_center()
+                .child(
+                    Label::new(format!(
+                        "Pin a hover with {keystroke} to read its documentation here"
+                    ))
+                    .color(Color::Muted),
+                )
+        } else {
+            v_flex()
+                .id("documentation")
+                .flex_1()
+                .overflow_y_scroll()
+                .track_scroll(&self.scroll_handle)
+                .p_2()
+                .gap_2()
+                .children(self.documentation.iter().cloned())
+        };
+
+        v_flex()
+            .key_context("DocumentationPanel")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_lock))
+            .size_full()
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_documentation_follows_cursor(cx: &mut TestAppContext) {
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.update(|cx| init(cx));
+        let _hover_requests =
+            cx.handle_request::<lsp::request::HoverRequest, _, _>(move |_, params, _| async move {
+                let row = params.text_document_position_params.position.line;
+                Ok(Some(lsp::Hover {
+                    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                        kind: lsp::MarkupKind::Markdown,
+                        value: format!("docs for row {row}"),
+                    }),
+                    range: None,
+                }))
+            });
+        cx.set_state(indoc! {"
+            fn ˇa() {}
+            fn b() {}
+        "});
+
+        // Opening the panel shows the documentation at the cursor.
+        let panel = cx.update_workspace(|workspace, cx| {
+            let panel = DocumentationPanel::new(workspace, DockPanelState::new("test"), cx);
+            workspace.add_panel(panel.clone(), cx);
+            panel
+        });
+        cx.update(|cx| panel.update(cx, |panel, cx| panel.set_active(true, cx)));
+        cx.run_until_parked();
+        cx.update(|cx| assert_eq!(documentation(panel.read(cx), cx), ["docs for row 0"]));
+
+        // Moving the cursor updates it once the cursor settles.
+        cx.set_selections_state(indoc! {"
+            fn a() {}
+            fn ˇb() {}
+        "});
+        cx.run_until_parked();
+        cx.update(|cx| assert_eq!(documentation(panel.read(cx), cx), ["docs for row 0"]));
+        cx.background_executor.advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+        cx.update(|cx| assert_eq!(documentation(panel.read(cx), cx), ["docs for row 1"]));
+
+        // A locked panel keeps its documentation.
+        cx.update(|cx| panel.update(cx, |panel, cx| panel.toggle_lock(&ToggleLock, cx)));
+        cx.set_selections_state(indoc! {"
+            fn ˇa() {}
+            fn b() {}
+        "});
+        cx.background_executor.advance_clock(UPDATE_DEBOUNCE);
+        cx.run_until_parked();
+        cx.update(|cx| assert_eq!(documentation(panel.read(cx), cx), ["docs for row 1"]));
+    }
+
+    fn documentation(panel: &DocumentationPanel, cx: &AppContext) -> Vec<String> {
+        panel
+            .documentation
+            .iter()
+            .map(|markdown| markdown.read(cx).source().to_string())
+            .collect()
+    }
+}
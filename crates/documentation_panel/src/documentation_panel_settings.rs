@@ -0,0 +1,30 @@
+use anyhow;
+use serde::Deserialize;
+use settings::{Settings, SettingsSources};
+use std::ops::Deref;
+use workspace::dock::{DockPanelSettings, DockPanelSettingsContent};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+pub struct DocumentationPanelSettings(pub DockPanelSettings);
+
+impl Deref for DocumentationPanelSettings {
+    type Target = DockPanelSettings;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Settings for DocumentationPanelSettings {
+    const KEY: Option<&'static str> = Some("documentation_panel");
+
+    type FileContent = DockPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
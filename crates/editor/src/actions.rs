@@ -81,6 +81,11 @@ pub struct FoldAt {
     pub buffer_row: MultiBufferRow,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct FoldAtLevel {
+    pub level: u32,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct UnfoldAt {
     pub buffer_row: MultiBufferRow,
@@ -109,6 +114,40 @@ pub struct SelectDownByLines {
     pub(super) lines: u32,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ScrollUpByLines {
+    #[serde(default = "default_scroll_lines")]
+    pub(super) lines: u32,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ScrollDownByLines {
+    #[serde(default = "default_scroll_lines")]
+    pub(super) lines: u32,
+}
+
+fn default_scroll_lines() -> u32 {
+    1
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ScrollCursorToViewportPercent {
+    #[serde(default)]
+    pub(super) percent: u32,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ScrollCursorTopWithContext {
+    #[serde(default)]
+    pub(super) lines: u32,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct ScrollCursorBottomWithContext {
+    #[serde(default)]
+    pub(super) lines: u32,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct ExpandExcerpts {
     #[serde(default)]
@@ -132,21 +171,52 @@ pub struct ShowCompletions {
     pub(super) trigger: Option<String>,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct Increment {
+    /// Whether to add one more for each subsequent cursor, numbering them 1, 2, 3...
+    #[serde(default)]
+    pub(super) step: bool,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct Decrement {
+    /// Whether to subtract one more for each subsequent cursor.
+    #[serde(default)]
+    pub(super) step: bool,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct SurroundSelections {
+    pub start: String,
+    /// Defaults to the end of the language's bracket pair starting with `start`, or to `start`
+    /// itself.
+    #[serde(default)]
+    pub end: Option<String>,
+}
+
 impl_actions!(
     editor,
     [
         ConfirmCodeAction,
         ConfirmCompletion,
+        Decrement,
         ExpandExcerpts,
         ExpandExcerptsUp,
         ExpandExcerptsDown,
         FoldAt,
+        FoldAtLevel,
+        Increment,
         MoveDownByLines,
         MovePageDown,
         MovePageUp,
         MoveToBeginningOfLine,
         MoveToEndOfLine,
         MoveUpByLines,
+        ScrollCursorBottomWithContext,
+        ScrollCursorToViewportPercent,
+        ScrollCursorTopWithContext,
+        ScrollDownByLines,
+        ScrollUpByLines,
         SelectDownByLines,
         SelectNext,
         SelectPrevious,
@@ -154,6 +224,7 @@ impl_actions!(
         SelectToEndOfLine,
         SelectUpByLines,
         ShowCompletions,
+        SurroundSelections,
         ToggleCodeActions,
         ToggleComments,
         UnfoldAt,
@@ -166,12 +237,15 @@ gpui::actions!(
         AcceptPartialCopilotSuggestion,
         AcceptInlineCompletion,
         AcceptPartialInlineCompletion,
+        AddCursorAbove,
+        AddCursorBelow,
         AddSelectionAbove,
         AddSelectionBelow,
         Backspace,
         Cancel,
         CancelLanguageServerWork,
         ConfirmRename,
+        ConfirmRenamePreview,
         ContextMenuFirst,
         ContextMenuLast,
         ContextMenuNext,
@@ -179,11 +253,13 @@ gpui::actions!(
         ConvertToKebabCase,
         ConvertToLowerCamelCase,
         ConvertToLowerCase,
+        ConvertToNextCase,
         ConvertToOppositeCase,
         ConvertToSnakeCase,
         ConvertToTitleCase,
         ConvertToUpperCamelCase,
         ConvertToUpperCase,
+        ConvertToUpperSnakeCase,
         Copy,
         CopyHighlightJson,
         CopyPath,
@@ -193,6 +269,7 @@ gpui::actions!(
         CutToEndOfLine,
         Delete,
         DeleteLine,
+        DeleteSurroundingPair,
         DeleteToBeginningOfLine,
         DeleteToEndOfLine,
         DeleteToNextSubwordEnd,
@@ -202,6 +279,7 @@ gpui::actions!(
         DisplayCursorNames,
         DuplicateLineDown,
         DuplicateLineUp,
+        ExcludeRenameEdit,
         ExpandAllHunkDiffs,
         ExpandMacroRecursively,
         FindAllReferences,
@@ -211,13 +289,16 @@ gpui::actions!(
         GoToDefinition,
         GoToDefinitionSplit,
         GoToDiagnostic,
+        GoToDocumentHighlight,
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
         GoToPrevDiagnostic,
+        GoToPrevDocumentHighlight,
         GoToPrevHunk,
         GoToTypeDefinition,
         GoToTypeDefinitionSplit,
+        GrowPeekView,
         HalfPageDown,
         HalfPageUp,
         Hover,
@@ -249,10 +330,16 @@ gpui::actions!(
         OpenExcerptsSplit,
         OpenPermalinkToLine,
         OpenUrl,
+        OrganizeImports,
+        OrganizeImportsInDirtyBuffers,
         Outdent,
         PageDown,
         PageUp,
         Paste,
+        PasteAsCursors,
+        PeekDefinition,
+        PeekNextDefinition,
+        PeekPreviousDefinition,
         PreviousInlineCompletion,
         Redo,
         RedoSelection,
@@ -264,8 +351,14 @@ gpui::actions!(
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorTop,
+        ScrollHistoryBack,
+        ScrollHistoryForward,
         SelectAll,
         SelectAllMatches,
+        SelectColumnDown,
+        SelectColumnLeft,
+        SelectColumnRight,
+        SelectColumnUp,
         SelectDown,
         SelectLargerSyntaxNode,
         SelectEnclosingSymbol,
@@ -287,10 +380,14 @@ gpui::actions!(
         ShowCharacterPalette,
         ShowInlineCompletion,
         ShowSignatureHelp,
+        ShrinkPeekView,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
+        SortLinesDescending,
+        SortLinesNumerically,
         SplitSelectionIntoLines,
+        StopLanguageServer,
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
@@ -300,13 +397,21 @@ gpui::actions!(
         ToggleHunkDiff,
         ToggleInlayHints,
         ToggleLineNumbers,
+        ToggleOvertype,
+        ToggleScrollLock,
+        ToggleFold,
         ToggleIndentGuides,
         ToggleSoftWrap,
         ToggleTabBar,
+        ToggleTypewriterScrolling,
         Transpose,
+        TransposeWords,
         Undo,
+        UndoAddCursor,
         UndoSelection,
+        UnfoldAll,
         UnfoldLines,
+        UniqueAdjacentLines,
         UniqueLinesCaseInsensitive,
         UniqueLinesCaseSensitive,
     ]
@@ -0,0 +1,266 @@
+use std::{any::TypeId, ops::Range};
+
+use gpui::{HighlightStyle, Task, ViewContext};
+use multi_buffer::{Anchor, ExcerptId, MultiBufferSnapshot};
+use settings::Settings;
+use text::{Bias, Point};
+use theme::ActiveTheme;
+
+use crate::{Editor, EditorMode, EditorSettings};
+
+/// How many nesting levels get a distinct color before the colors repeat.
+const BRACKET_COLOR_COUNT: usize = 6;
+
+enum BracketColorHighlight<const DEPTH: usize> {}
+
+fn bracket_color_highlight_key(depth: usize) -> TypeId {
+    [
+        TypeId::of::<BracketColorHighlight<0>>(),
+        TypeId::of::<BracketColorHighlight<1>>(),
+        TypeId::of::<BracketColorHighlight<2>>(),
+        TypeId::of::<BracketColorHighlight<3>>(),
+        TypeId::of::<BracketColorHighlight<4>>(),
+        TypeId::of::<BracketColorHighlight<5>>(),
+    ][depth % BRACKET_COLOR_COUNT]
+}
+
+/// The state of an editor's bracket colors.
+#[derive(Default)]
+pub(crate) struct BracketColors {
+    /// What the current colors were computed for, if they are shown.
+    colored: Option<ColoredRange>,
+    task: Option<Task<()>>,
+}
+
+#[derive(Clone, PartialEq)]
+struct ColoredRange {
+    edit_count: usize,
+    non_text_state_update_count: usize,
+    visible_range: Range<Point>,
+}
+
+impl BracketColors {
+    /// Makes the next refresh recompute the colors, such as when the theme changes.
+    pub(crate) fn invalidate(&mut self) {
+        if self.colored.is_some() {
+            self.colored = None;
+            self.task = None;
+        }
+    }
+}
+
+/// Colors the brackets in the visible part of the editor by their nesting depth.
+///
+/// Bracket pairs come from each language's brackets query, so brackets inside of
+/// strings and comments are left alone. The colors are computed in the background,
+/// and only when the visible range, the text or the syntax trees have changed.
+pub fn refresh_bracket_colors(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let multi_buffer = editor.buffer().read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let visible_range = EditorSettings::get_global(cx)
+        .colorize_brackets
+        .then(|| editor.visible_line_count())
+        .flatten()
+        .map(|visible_line_count| {
+            let visible_start = editor
+                .scroll_manager
+                .anchor()
+                .anchor
+                .to_point(&multi_buffer_snapshot);
+            let visible_end = multi_buffer_snapshot.clip_point(
+                visible_start + Point::new(visible_line_count.ceil() as u32, 0),
+                Bias::Left,
+            );
+            visible_start..visible_end
+        });
+    let colored = visible_range.map(|visible_range| ColoredRange {
+        edit_count: multi_buffer_snapshot.edit_count(),
+        non_text_state_update_count: multi_buffer_snapshot.non_text_state_update_count(),
+        visible_range,
+    });
+    if editor.bracket_colors.colored == colored {
+        return;
+    }
+
+    let Some(visible_range) = colored.clone().map(|colored| colored.visible_range) else {
+        editor.bracket_colors = BracketColors::default();
+        highlight_bracket_colors(editor, vec![Vec::new(); BRACKET_COLOR_COUNT], cx);
+        return;
+    };
+    let visible_excerpts = multi_buffer
+        .range_to_buffer_ranges(visible_range, cx)
+        .into_iter()
+        .map(|(_, visible_range, excerpt_id)| (excerpt_id, visible_range))
+        .collect::<Vec<_>>();
+
+    let ranges_by_color = cx
+        .background_executor()
+        .spawn(async move { bracket_ranges_by_color(&multi_buffer_snapshot, &visible_excerpts) });
+    editor.bracket_colors = BracketColors {
+        colored,
+        task: Some(cx.spawn(|editor, mut cx| async move {
+            let ranges_by_color = ranges_by_color.await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    highlight_bracket_colors(editor, ranges_by_color, cx)
+                })
+                .ok();
+        })),
+    };
+}
+
+/// Groups the brackets in the visible ranges of the excerpts by the color of their depth.
+fn bracket_ranges_by_color(
+    multi_buffer_snapshot: &MultiBufferSnapshot,
+    visible_excerpts: &[(ExcerptId, Range<usize>)],
+) -> Vec<Vec<Range<Anchor>>> {
+    let mut ranges_by_color = vec![Vec::new(); BRACKET_COLOR_COUNT];
+    for (excerpt_id, visible_range) in visible_excerpts {
+        let Some(buffer_snapshot) = multi_buffer_snapshot.buffer_for_excerpt(*excerpt_id) else {
+            continue;
+        };
+
+        // This includes the pairs that enclose the visible range, so that the depth
+        // of the visible brackets can be counted.
+        let mut bracket_pairs = buffer_snapshot
+            .bracket_ranges(visible_range.clone())
+            .collect::<Vec<_>>();
+        bracket_pairs.sort_by_key(|(open, close)| (open.start, std::cmp::Reverse(close.end)));
+        bracket_pairs.dedup();
+
+        // Pairs are sorted with the enclosing ones first, so the pairs still open at each
+        // pair are the ones enclosing it.
+        let mut enclosing_pair_ends = Vec::new();
+        for (open, close) in &bracket_pairs {
+            while enclosing_pair_ends
+                .last()
+                .is_some_and(|enclosing_end| *enclosing_end <= open.start)
+            {
+                enclosing_pair_ends.pop();
+            }
+            let depth = enclosing_pair_ends.len();
+            enclosing_pair_ends.push(close.end);
+
+            for range in [open, close] {
+                let start = multi_buffer_snapshot
+                    .anchor_in_excerpt(*excerpt_id, buffer_snapshot.anchor_after(range.start));
+                let end = multi_buffer_snapshot
+                    .anchor_in_excerpt(*excerpt_id, buffer_snapshot.anchor_before(range.end));
+                if let Some((start, end)) = start.zip(end) {
+                    ranges_by_color[depth % BRACKET_COLOR_COUNT].push(start..end);
+                }
+            }
+        }
+    }
+    ranges_by_color
+}
+
+fn highlight_bracket_colors(
+    editor: &mut Editor,
+    ranges_by_color: Vec<Vec<Range<Anchor>>>,
+    cx: &mut ViewContext<Editor>,
+) {
+    let accents = cx.theme().accents().clone();
+    editor.display_map.update(cx, |display_map, _| {
+        for (depth, ranges) in ranges_by_color.into_iter().enumerate() {
+            let key = bracket_color_highlight_key(depth);
+            if ranges.is_empty() {
+                display_map.clear_highlights(key);
+            } else {
+                let style = HighlightStyle {
+                    color: Some(accents.color_for_index(depth as u32)),
+                    ..Default::default()
+                };
+                display_map.highlight_text(key, ranges, style);
+            }
+        }
+    });
+    cx.notify();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext};
+    use indoc::indoc;
+    use language::{Language, LanguageConfig, LanguageMatcher};
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_bracket_colors(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    matcher: LanguageMatcher {
+                        path_suffixes: vec!["rs".to_string()],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::language()),
+            )
+            .with_brackets_query(indoc! {r#"
+                ("(" @open ")" @close)
+                ("{" @open "}" @close)
+            "#})
+            .unwrap(),
+            Default::default(),
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {r#"
+            fn a(b: u32) {
+                c("{(");ˇ
+                d(e());
+            }
+        "#});
+        cx.update_editor(|editor, cx| {
+            editor.set_visible_line_count(10., cx);
+            refresh_bracket_colors(editor, cx);
+        });
+        cx.run_until_parked();
+
+        // Brackets in the string literal aren't colored.
+        cx.assert_editor_text_highlights::<BracketColorHighlight<0>>(indoc! {r#"
+            fn a«(»b: u32«)» «{»
+                c("{(");
+                d(e());
+            «}»
+        "#});
+        cx.assert_editor_text_highlights::<BracketColorHighlight<1>>(indoc! {r#"
+            fn a(b: u32) {
+                c«(»"{("«)»;
+                d«(»e()«)»;
+            }
+        "#});
+        cx.assert_editor_text_highlights::<BracketColorHighlight<2>>(indoc! {r#"
+            fn a(b: u32) {
+                c("{(");
+                d(e«(»«)»);
+            }
+        "#});
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.colorize_brackets = Some(false);
+                });
+            });
+        });
+        cx.update_editor(|editor, cx| refresh_bracket_colors(editor, cx));
+        cx.assert_editor_text_highlights::<BracketColorHighlight<0>>(indoc! {r#"
+            fn a(b: u32) {
+                c("{(");
+                d(e());
+            }
+        "#});
+    }
+}
@@ -0,0 +1,715 @@
+use std::{any::TypeId, cell::RefCell, ops::Range, rc::Rc};
+
+use collections::HashMap;
+use gpui::{AnyElement, ClickEvent, HighlightStyle, Hsla, Model, Point, Rgba, Task, WeakView};
+use language::{Bias, BufferId, Language, OffsetRangeExt as _};
+use lazy_static::lazy_static;
+use multi_buffer::{Anchor, ExcerptId, MultiBufferSnapshot, ToOffset as _};
+use project::{DocumentColor, Project};
+use regex::Regex;
+use settings::Settings;
+use ui::{prelude::*, ContextMenu, NumericStepper};
+use util::{post_inc, ResultExt};
+
+use crate::{
+    display_map::{Inlay, InlayOffset},
+    hover_links::InlayHighlight,
+    mouse_context_menu::MouseContextMenu,
+    viewport_refresh::VisibleBuffers,
+    Editor, EditorSettings, InlayId, PointForPosition,
+};
+
+/// The text of the inlay shown in front of every color, the square being colored in.
+pub(crate) const COLOR_SWATCH_TEXT: &str = "■ ";
+const COLOR_SWATCH_GLYPH_LEN: usize = "■".len();
+
+enum ColorSwatchHighlight {}
+
+/// The languages that colors are found in by their notation, when language servers don't
+/// report them. In other languages, such as in comments referencing `#123`, that notation is
+/// rarely a color.
+const COLOR_LANGUAGES: &[&str] = &[
+    "CSS", "HTML", "JSON", "JSONC", "Less", "Sass", "SCSS", "Svelte", "Vue.js",
+];
+
+lazy_static! {
+    static ref COLOR_REGEX: Regex =
+        Regex::new(r"\B#[0-9a-fA-F]{3,8}\b|\b(?:rgba?|hsla?)\([^()\n]*\)").unwrap();
+}
+
+/// How a color is written in the buffer, so that an edited color can be written back the same way.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ColorFormat {
+    Hex { short: bool, alpha: bool },
+    Rgb { alpha: bool },
+    Hsl { alpha: bool },
+}
+
+pub(crate) struct ColorSwatch {
+    inlay_id: InlayId,
+    range: Range<Anchor>,
+    color: Hsla,
+    /// `None` for colors reported by a language server in a notation that can't be written back.
+    format: Option<ColorFormat>,
+}
+
+struct FoundColor {
+    range: Range<Anchor>,
+    color: Hsla,
+    format: Option<ColorFormat>,
+}
+
+/// Shows a swatch in front of every visible color in the editor, using the colors reported by the
+/// language servers and falling back to hex, `rgb()` and `hsl()` colors found in the text of
+/// languages using them. The colors of visible buffers that weren't edited since the last
+/// refresh are not requested again.
+pub(super) fn refresh_color_swatches(
+    this: &mut Editor,
+    project: &Model<Project>,
+    visible: &VisibleBuffers,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Option<()>> {
+    if !EditorSettings::get_global(cx).color_swatches {
+        this.document_colors.clear();
+        set_color_swatches(this, Vec::new(), cx);
+        return Task::ready(None);
+    }
+
+    let tasks = project.update(cx, |project, cx| {
+        visible
+            .buffers
+            .iter()
+            .filter(|(buffer_id, _)| {
+                visible.edited.contains(buffer_id) || !this.document_colors.contains_key(buffer_id)
+            })
+            .map(|(buffer_id, (buffer, _))| (*buffer_id, project.document_colors(buffer, cx)))
+            .collect::<Vec<_>>()
+    });
+    let snapshot = visible.snapshot.clone();
+    let visible_ranges = visible.excerpts.clone();
+    let visible_buffer_ids = visible.buffers.keys().copied().collect::<Vec<_>>();
+
+    cx.spawn(|this, mut cx| async move {
+        let mut document_colors = HashMap::default();
+        for (buffer_id, task) in tasks {
+            document_colors.insert(buffer_id, task.await.log_err().unwrap_or_default());
+        }
+        let lsp_colors = this
+            .update(&mut cx, |this, _| {
+                this.document_colors
+                    .retain(|buffer_id, _| visible_buffer_ids.contains(buffer_id));
+                this.document_colors.extend(document_colors);
+                this.document_colors.clone()
+            })
+            .ok()?;
+
+        let colors = cx
+            .background_executor()
+            .spawn(async move { find_colors(&snapshot, &visible_ranges, &lsp_colors) })
+            .await;
+        this.update(&mut cx, |this, cx| set_color_swatches(this, colors, cx))
+            .ok()
+    })
+}
+
+fn is_color_language(language: &Language) -> bool {
+    COLOR_LANGUAGES.contains(&language.name().as_ref())
+}
+
+/// Finds the colors within the given visible ranges of excerpts, in buffer offsets.
+fn find_colors(
+    snapshot: &MultiBufferSnapshot,
+    visible_ranges: &[(ExcerptId, Range<usize>)],
+    lsp_colors: &HashMap<BufferId, Vec<DocumentColor>>,
+) -> Vec<FoundColor> {
+    let mut colors = Vec::new();
+    for (excerpt_id, context) in visible_ranges {
+        let (excerpt_id, context) = (*excerpt_id, context.clone());
+        let Some(buffer) = snapshot.buffer_for_excerpt(excerpt_id) else {
+            continue;
+        };
+        let mut excerpt_colors = Vec::new();
+
+        for document_color in lsp_colors.get(&buffer.remote_id()).into_iter().flatten() {
+            let range = document_color.range.to_offset(buffer);
+            if range.is_empty() || range.start < context.start || range.end > context.end {
+                continue;
+            }
+            let text = buffer.text_for_range(range.clone()).collect::<String>();
+            let format = parse_color(&text).map(|(_, format)| format);
+            let color = Rgba {
+                r: document_color.color.red,
+                g: document_color.color.green,
+                b: document_color.color.blue,
+                a: document_color.color.alpha,
+            };
+            excerpt_colors.push((range, color.into(), format));
+        }
+
+        let text = buffer.text_for_range(context.clone()).collect::<String>();
+        for found in COLOR_REGEX.find_iter(&text) {
+            let range = context.start + found.start()..context.start + found.end();
+            if !buffer
+                .language_at(range.start)
+                .is_some_and(|language| is_color_language(language))
+            {
+                continue;
+            }
+            let overlaps_lsp_color = excerpt_colors.iter().any(|(lsp_range, _, _)| {
+                lsp_range.start < range.end && range.start < lsp_range.end
+            });
+            if overlaps_lsp_color {
+                continue;
+            }
+            if let Some((color, format)) = parse_color(found.as_str()) {
+                excerpt_colors.push((range, color, Some(format)));
+            }
+        }
+
+        excerpt_colors.sort_by_key(|(range, _, _)| range.start);
+        for (range, color, format) in excerpt_colors {
+            let start = snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_before(range.start));
+            let end = snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_after(range.end));
+            if let Some((start, end)) = start.zip(end) {
+                colors.push(FoundColor {
+                    range: start..end,
+                    color,
+                    format,
+                });
+            }
+        }
+    }
+    colors
+}
+
+/// Shows the swatches of the given colors in place of the ones shown before. The inlays of
+/// swatches whose color is still found at the same place are kept, so that only the swatches of
+/// new or changed colors are inserted.
+fn set_color_swatches(this: &mut Editor, colors: Vec<FoundColor>, cx: &mut ViewContext<Editor>) {
+    let snapshot = this.buffer.read(cx).snapshot(cx);
+    let offset_range =
+        |range: &Range<Anchor>| range.start.to_offset(&snapshot)..range.end.to_offset(&snapshot);
+    let mut to_remove = Vec::new();
+    let mut old_swatches = HashMap::default();
+    for swatch in this.color_swatches.drain(..) {
+        if let Some(replaced) = old_swatches.insert(offset_range(&swatch.range), swatch) {
+            to_remove.push(replaced.inlay_id);
+        }
+    }
+
+    let mut to_insert = Vec::new();
+    for found in colors {
+        let (inlay_id, range) = match old_swatches.remove(&offset_range(&found.range)) {
+            Some(old_swatch) if old_swatch.color == found.color => {
+                (old_swatch.inlay_id, old_swatch.range)
+            }
+            old_swatch => {
+                to_remove.extend(old_swatch.map(|old_swatch| old_swatch.inlay_id));
+                let inlay = Inlay::color(post_inc(&mut this.next_inlay_id), found.range.start);
+                let inlay_id = inlay.id;
+                to_insert.push(inlay);
+                (inlay_id, found.range)
+            }
+        };
+        this.color_swatches.push(ColorSwatch {
+            inlay_id,
+            range,
+            color: found.color,
+            format: found.format,
+        });
+    }
+    to_remove.extend(old_swatches.into_values().map(|swatch| swatch.inlay_id));
+    if to_remove.is_empty() && to_insert.is_empty() {
+        return;
+    }
+
+    this.display_map.update(cx, |display_map, cx| {
+        display_map.clear_highlights(TypeId::of::<ColorSwatchHighlight>());
+        display_map.splice_inlays(to_remove, to_insert, cx);
+        for swatch in &this.color_swatches {
+            display_map.highlight_inlays(
+                TypeId::of::<ColorSwatchHighlight>(),
+                vec![InlayHighlight {
+                    inlay: swatch.inlay_id,
+                    inlay_position: swatch.range.start,
+                    range: 0..COLOR_SWATCH_GLYPH_LEN,
+                }],
+                HighlightStyle {
+                    color: Some(swatch.color),
+                    ..Default::default()
+                },
+            );
+        }
+    });
+    cx.notify();
+}
+
+/// The color being edited in an open color picker.
+struct PickedColor {
+    range: Range<Anchor>,
+    color: Hsla,
+    format: ColorFormat,
+}
+
+#[derive(Clone, Copy)]
+enum ColorChannel {
+    Hue,
+    Saturation,
+    Lightness,
+    Alpha,
+}
+
+impl ColorChannel {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::Hue => "Hue",
+            Self::Saturation => "Saturation",
+            Self::Lightness => "Lightness",
+            Self::Alpha => "Alpha",
+        }
+    }
+
+    fn display_value(&self, color: Hsla) -> String {
+        match self {
+            Self::Hue => format!("{}°", (color.h * 360.).round()),
+            Self::Saturation => format!("{}%", (color.s * 100.).round()),
+            Self::Lightness => format!("{}%", (color.l * 100.).round()),
+            Self::Alpha => format!("{}%", (color.a * 100.).round()),
+        }
+    }
+
+    /// Moves the channel by a single step, up when `steps` is positive and down otherwise.
+    fn step(&self, mut color: Hsla, steps: f32) -> Hsla {
+        match self {
+            Self::Hue => color.h = (color.h + steps * 5. / 360.).rem_euclid(1.),
+            Self::Saturation => color.s = (color.s + steps * 0.05).clamp(0., 1.),
+            Self::Lightness => color.l = (color.l + steps * 0.05).clamp(0., 1.),
+            Self::Alpha => color.a = (color.a + steps * 0.05).clamp(0., 1.),
+        }
+        color
+    }
+}
+
+impl Editor {
+    /// Opens a color picker when the given position is on a color swatch, writing every change
+    /// made in the picker back to the buffer. Returns whether a picker was opened.
+    pub(crate) fn deploy_color_picker(
+        &mut self,
+        point_for_position: &PointForPosition,
+        position: Point<Pixels>,
+        cx: &mut ViewContext<Self>,
+    ) -> bool {
+        if self.color_swatches.is_empty()
+            || self.read_only(cx)
+            || point_for_position.as_valid().is_some()
+            || point_for_position.column_overshoot_after_line_end > 0
+        {
+            return false;
+        }
+
+        let snapshot = self.snapshot(cx);
+        let hovered_offset =
+            snapshot.display_point_to_inlay_offset(point_for_position.exact_unclipped, Bias::Left);
+        let Some(swatch) = self.color_swatches.iter().find(|swatch| {
+            let start = snapshot.anchor_to_inlay_offset(swatch.range.start);
+            start <= hovered_offset && hovered_offset < start + InlayOffset(COLOR_SWATCH_TEXT.len())
+        }) else {
+            return false;
+        };
+        let Some(format) = swatch.format else {
+            return false;
+        };
+
+        let source = swatch.range.start;
+        let picked = Rc::new(RefCell::new(PickedColor {
+            range: swatch.range.clone(),
+            color: swatch.color,
+            format,
+        }));
+        let editor = cx.view().downgrade();
+        let context_menu = ContextMenu::build(cx, |menu, cx| {
+            let menu_handle = cx.view().downgrade();
+            let preview = {
+                let picked = picked.clone();
+                move |cx: &mut WindowContext| {
+                    let picked = picked.borrow();
+                    h_flex()
+                        .gap_2()
+                        .child(
+                            div()
+                                .size_4()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(cx.theme().colors().border)
+                                .bg(picked.color),
+                        )
+                        .child(Label::new(format_color(picked.color, picked.format)))
+                        .into_any_element()
+                }
+            };
+
+            let mut menu = menu.header("Color").custom_row(preview);
+            for channel in [
+                ColorChannel::Hue,
+                ColorChannel::Saturation,
+                ColorChannel::Lightness,
+                ColorChannel::Alpha,
+            ] {
+                menu = menu.custom_row(render_channel_stepper(
+                    channel,
+                    picked.clone(),
+                    editor.clone(),
+                    menu_handle.clone(),
+                ));
+            }
+            menu
+        });
+
+        self.mouse_context_menu =
+            MouseContextMenu::pinned_to_editor(self, source, position, context_menu, cx);
+        cx.notify();
+        self.mouse_context_menu.is_some()
+    }
+}
+
+fn render_channel_stepper(
+    channel: ColorChannel,
+    picked: Rc<RefCell<PickedColor>>,
+    editor: WeakView<Editor>,
+    menu: WeakView<ContextMenu>,
+) -> impl Fn(&mut WindowContext) -> AnyElement {
+    move |_| {
+        let step = |steps: f32| {
+            let picked = picked.clone();
+            let editor = editor.clone();
+            let menu = menu.clone();
+            move |_: &ClickEvent, cx: &mut WindowContext| {
+                let color = channel.step(picked.borrow().color, steps);
+                editor
+                    .update(cx, |editor, cx| {
+                        write_picked_color(editor, &picked, color, cx)
+                    })
+                    .log_err();
+                menu.update(cx, |_, cx| cx.notify()).ok();
+            }
+        };
+
+        h_flex()
+            .gap_4()
+            .justify_between()
+            .child(Label::new(channel.label()))
+            .child(NumericStepper::new(
+                channel.label(),
+                channel.display_value(picked.borrow().color),
+                step(-1.),
+                step(1.),
+            ))
+            .into_any_element()
+    }
+}
+
+fn write_picked_color(
+    editor: &mut Editor,
+    picked: &RefCell<PickedColor>,
+    color: Hsla,
+    cx: &mut ViewContext<Editor>,
+) {
+    let mut picked = picked.borrow_mut();
+    let text = format_color(color, picked.format);
+    let start = picked
+        .range
+        .start
+        .to_offset(&editor.buffer.read(cx).snapshot(cx));
+    editor.buffer.update(cx, |buffer, cx| {
+        buffer.edit([(picked.range.clone(), text.as_str())], None, cx)
+    });
+
+    let snapshot = editor.buffer.read(cx).snapshot(cx);
+    picked.range = snapshot.anchor_before(start)..snapshot.anchor_after(start + text.len());
+    picked.color = color;
+}
+
+/// Parses a color written as `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`,
+/// `hsl()` or `hsla()`, returning it along with the notation to write it back with.
+pub(crate) fn parse_color(text: &str) -> Option<(Hsla, ColorFormat)> {
+    if let Some(hex) = text.strip_prefix('#') {
+        let digits = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|digit| digit as f32))
+            .collect::<Option<Vec<_>>>()?;
+        let channels = match digits.len() {
+            3 | 4 => digits.iter().map(|digit| digit * 17. / 255.).collect(),
+            6 | 8 => digits
+                .chunks(2)
+                .map(|pair| (pair[0] * 16. + pair[1]) / 255.)
+                .collect::<Vec<_>>(),
+            _ => return None,
+        };
+        let color = Rgba {
+            r: channels[0],
+            g: channels[1],
+            b: channels[2],
+            a: channels.get(3).copied().unwrap_or(1.),
+        };
+        let format = ColorFormat::Hex {
+            short: digits.len() <= 4,
+            alpha: channels.len() == 4,
+        };
+        return Some((color.into(), format));
+    }
+
+    let (function, arguments) = text.strip_suffix(')')?.split_once('(')?;
+    let function = function.to_ascii_lowercase();
+    let arguments = arguments.split(',').map(str::trim).collect::<Vec<_>>();
+    if arguments.len() != 3 && arguments.len() != 4 {
+        return None;
+    }
+    let alpha = match arguments.get(3) {
+        Some(alpha) => parse_alpha(alpha)?,
+        None => 1.,
+    };
+    let has_alpha = arguments.len() == 4 || function.ends_with('a');
+
+    match function.as_str() {
+        "rgb" | "rgba" => {
+            let channel = |argument: &str| Some(argument.parse::<u8>().ok()? as f32 / 255.);
+            let color = Rgba {
+                r: channel(arguments[0])?,
+                g: channel(arguments[1])?,
+                b: channel(arguments[2])?,
+                a: alpha,
+            };
+            Some((color.into(), ColorFormat::Rgb { alpha: has_alpha }))
+        }
+        "hsl" | "hsla" => {
+            let hue = arguments[0].strip_suffix("deg").unwrap_or(arguments[0]);
+            let percentage = |argument: &str| {
+                let value = argument.strip_suffix('%')?.parse::<f32>().ok()?;
+                (0. ..=100.).contains(&value).then_some(value / 100.)
+            };
+            let color = Hsla {
+                h: (hue.parse::<f32>().ok()? / 360.).rem_euclid(1.),
+                s: percentage(arguments[1])?,
+                l: percentage(arguments[2])?,
+                a: alpha,
+            };
+            Some((color, ColorFormat::Hsl { alpha: has_alpha }))
+        }
+        _ => None,
+    }
+}
+
+fn parse_alpha(text: &str) -> Option<f32> {
+    let alpha = match text.strip_suffix('%') {
+        Some(percentage) => percentage.parse::<f32>().ok()? / 100.,
+        None => text.parse::<f32>().ok()?,
+    };
+    (0. ..=1.).contains(&alpha).then_some(alpha)
+}
+
+/// Writes a color in the given notation, adding an alpha channel when it's needed.
+pub(crate) fn format_color(color: Hsla, format: ColorFormat) -> String {
+    let alpha = (color.a * 100.).round() / 100.;
+    match format {
+        ColorFormat::Hex {
+            short,
+            alpha: has_alpha,
+        } => {
+            let rgba = Rgba::from(color);
+            let mut channels = vec![rgba.r, rgba.g, rgba.b];
+            if has_alpha || rgba.a < 1. {
+                channels.push(rgba.a);
+            }
+            let channels = channels
+                .into_iter()
+                .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8)
+                .collect::<Vec<_>>();
+            if short && channels.iter().all(|channel| channel % 17 == 0) {
+                channels.iter().fold("#".to_string(), |text, channel| {
+                    format!("{text}{:x}", channel / 17)
+                })
+            } else {
+                channels.iter().fold("#".to_string(), |text, channel| {
+                    format!("{text}{channel:02x}")
+                })
+            }
+        }
+        ColorFormat::Rgb { alpha: has_alpha } => {
+            let rgba = Rgba::from(color);
+            let [r, g, b] = [rgba.r, rgba.g, rgba.b]
+                .map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8);
+            if has_alpha || alpha < 1. {
+                format!("rgba({r}, {g}, {b}, {alpha})")
+            } else {
+                format!("rgb({r}, {g}, {b})")
+            }
+        }
+        ColorFormat::Hsl { alpha: has_alpha } => {
+            let h = (color.h * 360.).round();
+            let s = (color.s * 100.).round();
+            let l = (color.l * 100.).round();
+            if has_alpha || alpha < 1. {
+                format!("hsla({h}, {s}%, {l}%, {alpha})")
+            } else {
+                format!("hsl({h}, {s}%, {l}%)")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test,
+        test::editor_test_context::EditorTestContext,
+        viewport_refresh::{refresh_viewport, ViewportRefreshReason},
+    };
+    use gpui::AppContext;
+    use indoc::indoc;
+    use language::LanguageConfig;
+    use settings::SettingsStore;
+    use std::sync::Arc;
+
+    fn language(name: &str) -> Arc<Language> {
+        Arc::new(Language::new(
+            LanguageConfig {
+                name: name.into(),
+                ..Default::default()
+            },
+            None,
+        ))
+    }
+
+    fn swatch_texts(editor: &Editor, cx: &AppContext) -> Vec<String> {
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        editor
+            .color_swatches
+            .iter()
+            .map(|swatch| {
+                snapshot
+                    .text_for_range(swatch.range.clone())
+                    .collect::<String>()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_format_color() {
+        for text in [
+            "#f00",
+            "#f008",
+            "#1a2b3c",
+            "#1a2b3c80",
+            "rgb(26, 43, 60)",
+            "rgba(26, 43, 60, 0.5)",
+            "hsl(210, 40%, 20%)",
+            "hsla(210, 40%, 20%, 0.25)",
+        ] {
+            let (color, format) = parse_color(text).unwrap();
+            assert_eq!(format_color(color, format), text);
+        }
+
+        let (red, format) = parse_color("#ff0000").unwrap();
+        assert_eq!(
+            format,
+            ColorFormat::Hex {
+                short: false,
+                alpha: false
+            }
+        );
+        assert_eq!(
+            format_color(
+                red,
+                ColorFormat::Hex {
+                    short: true,
+                    alpha: false
+                }
+            ),
+            "#f00"
+        );
+        assert_eq!(
+            format_color(red, ColorFormat::Rgb { alpha: false }),
+            "rgb(255, 0, 0)"
+        );
+        assert_eq!(
+            format_color(red, ColorFormat::Hsl { alpha: false }),
+            "hsl(0, 100%, 50%)"
+        );
+        assert_eq!(
+            format_color(ColorChannel::Alpha.step(red, -1.), format),
+            "#ff0000f2"
+        );
+
+        assert_eq!(parse_color("#ff00f"), None);
+        assert_eq!(parse_color("rgb(256, 0, 0)"), None);
+        assert_eq!(parse_color("rgb(0, 0)"), None);
+        assert_eq!(parse_color("hsl(0, 100, 50%)"), None);
+    }
+
+    #[gpui::test]
+    async fn test_color_swatches(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language("CSS")), cx));
+        cx.set_state(indoc! {"
+            .a { color: #f00; border: 1px solid rgba(0, 0, 255, 0.5); }ˇ
+            .b { background: hsl(120, 100%, 25%); content: \"#nothex\"; }
+        "});
+        cx.update_editor(|editor, cx| {
+            refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                swatch_texts(editor, cx),
+                ["#f00", "rgba(0, 0, 255, 0.5)", "hsl(120, 100%, 25%)"]
+            );
+            assert!(editor.text(cx).len() < editor.display_text(cx).len());
+        });
+
+        // Colors are not looked for in the text of other languages, where `#123` is more likely
+        // to reference an issue.
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language("Rust")), cx));
+        cx.set_state("// Fixes #123 and #f00.ˇ");
+        cx.update_editor(|editor, cx| {
+            refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| assert!(swatch_texts(editor, cx).is_empty()));
+
+        cx.update_buffer(|buffer, cx| buffer.set_language(Some(language("CSS")), cx));
+        cx.set_state(".a { color: #f00; }ˇ");
+        cx.update_editor(|editor, cx| {
+            refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| assert_eq!(swatch_texts(editor, cx), ["#f00"]));
+
+        // The swatches of colors found again keep their inlays.
+        let inlay_id = cx.update_editor(|editor, _| editor.color_swatches[0].inlay_id);
+        cx.update_editor(|editor, cx| editor.handle_input(" .b { color: #0f0; }", cx));
+        cx.update_editor(|editor, cx| {
+            refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            assert_eq!(swatch_texts(editor, cx), ["#f00", "#0f0"]);
+            assert_eq!(editor.color_swatches[0].inlay_id, inlay_id);
+        });
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.color_swatches = Some(false);
+                });
+            });
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            assert!(editor.color_swatches.is_empty());
+            assert_eq!(editor.display_text(cx), editor.text(cx));
+        });
+    }
+}
@@ -37,14 +37,15 @@ pub use crease_map::*;
 pub use fold_map::{Fold, FoldId, FoldPlaceholder, FoldPoint};
 use fold_map::{FoldMap, FoldSnapshot};
 use gpui::{
-    AnyElement, Font, HighlightStyle, LineLayout, Model, ModelContext, Pixels, UnderlineStyle,
+    AnyElement, Font, HighlightStyle, LineLayout, Model, ModelContext, Pixels, SharedString,
+    UnderlineStyle,
 };
 pub(crate) use inlay_map::Inlay;
 use inlay_map::{InlayMap, InlaySnapshot};
 pub use inlay_map::{InlayOffset, InlayPoint};
 use language::{
     language_settings::language_settings, ChunkRenderer, OffsetUtf16, Point,
-    Subscription as BufferSubscription,
+    Subscription as BufferSubscription, SyntaxFold,
 };
 use lsp::DiagnosticSeverity;
 use multi_buffer::{
@@ -62,7 +63,7 @@ use std::{
 };
 use sum_tree::{Bias, TreeMap};
 use tab_map::{TabMap, TabSnapshot};
-use text::LineIndent;
+use text::{BufferId, LineIndent, OffsetRangeExt};
 use ui::WindowContext;
 use wrap_map::{WrapMap, WrapSnapshot};
 
@@ -82,6 +83,9 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<Option<TypeId>, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type LspFoldRanges = TreeMap<BufferId, Arc<[Range<text::Anchor>]>>;
+/// Non-overlapping highlights sorted by their position in the buffer.
+type SemanticHighlights = Arc<[(Range<Anchor>, HighlightStyle)]>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -105,8 +109,13 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Highlights derived from the semantic tokens reported by language servers, layered over
+    /// syntax highlighting.
+    semantic_highlights: SemanticHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
+    /// Foldable ranges reported by language servers, which supersede syntax based fold range suggestions.
+    lsp_fold_ranges: LspFoldRanges,
     fold_placeholder: FoldPlaceholder,
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
@@ -153,9 +162,11 @@ impl DisplayMap {
             wrap_map,
             block_map,
             crease_map,
+            lsp_fold_ranges: Default::default(),
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_highlights: Arc::new([]),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -181,8 +192,10 @@ impl DisplayMap {
             wrap_snapshot,
             block_snapshot,
             crease_snapshot: self.crease_map.snapshot(),
+            lsp_fold_ranges: self.lsp_fold_ranges.clone(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_highlights: self.semantic_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -268,6 +281,25 @@ impl DisplayMap {
         self.crease_map.remove(crease_ids, &snapshot)
     }
 
+    pub fn set_lsp_fold_ranges(
+        &mut self,
+        buffer_id: BufferId,
+        mut ranges: Vec<Range<text::Anchor>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if ranges.is_empty() {
+            self.lsp_fold_ranges.remove(&buffer_id);
+            return;
+        }
+
+        let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+            return;
+        };
+        let buffer = buffer.read(cx);
+        ranges.sort_by(|a, b| a.start.cmp(&b.start, buffer));
+        self.lsp_fold_ranges.insert(buffer_id, ranges.into());
+    }
+
     pub fn insert_blocks(
         &mut self,
         blocks: impl IntoIterator<Item = BlockProperties<Anchor>>,
@@ -351,6 +383,13 @@ impl DisplayMap {
             .insert(Some(type_id), Arc::new((style, ranges)));
     }
 
+    pub(crate) fn set_semantic_highlights(
+        &mut self,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        self.semantic_highlights = highlights.into();
+    }
+
     pub(crate) fn highlight_inlays(
         &mut self,
         type_id: TypeId,
@@ -445,6 +484,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_highlights: Option<&'a [(Range<Anchor>, HighlightStyle)]>,
     pub styles: HighlightStyles,
 }
 
@@ -466,12 +506,14 @@ pub struct DisplaySnapshot {
     pub buffer_snapshot: MultiBufferSnapshot,
     pub fold_snapshot: FoldSnapshot,
     pub crease_snapshot: CreaseSnapshot,
+    lsp_fold_ranges: LspFoldRanges,
     inlay_snapshot: InlaySnapshot,
     tab_snapshot: TabSnapshot,
     wrap_snapshot: WrapSnapshot,
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_highlights: SemanticHighlights,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -656,6 +698,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_highlights: Some(&self.semantic_highlights),
                 styles: highlight_styles,
             },
         )
@@ -951,9 +994,142 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Returns whether a fold can start at the given row, whether based on
+    /// language servers, syntax or indentation.
+    pub fn is_foldable(&self, buffer_row: MultiBufferRow) -> bool {
+        self.language_fold_range(buffer_row, Self::line_syntax_folds)
+            .is_some()
+            || self.starts_indent(buffer_row)
+    }
+
+    /// Returns the range folded when folding at the given row, as reported by
+    /// the buffer's language servers or, failing that, by its syntax tree,
+    /// along with the summary the language's folds query captured for it.
+    ///
+    /// When the range ends on a line holding nothing but its closing delimiter,
+    /// that line stays visible like it does for indentation based folds.
+    fn language_fold_range(
+        &self,
+        buffer_row: MultiBufferRow,
+        syntax_folds: impl FnOnce(&language::BufferSnapshot, u32) -> Vec<SyntaxFold>,
+    ) -> Option<(Range<Point>, Option<SharedString>)> {
+        let line_start = self
+            .buffer_snapshot
+            .point_to_offset(Point::new(buffer_row.0, 0));
+        let excerpt = self
+            .buffer_snapshot
+            .excerpt_containing(line_start..line_start)?;
+        let buffer = excerpt.buffer();
+        let row = buffer
+            .offset_to_point(excerpt.map_offset_to_buffer(line_start))
+            .row;
+
+        let syntax_folds = syntax_folds(buffer, row);
+
+        let end_row = match self.lsp_fold_ranges.get(&buffer.remote_id()) {
+            Some(ranges) => Self::lsp_fold_end_row(ranges, buffer, row),
+            None => syntax_folds
+                .iter()
+                .filter_map(|fold| Self::syntax_fold_end_row(buffer, fold))
+                .filter(|end_row| *end_row > row)
+                .max(),
+        }?;
+        let end_offset = buffer.point_to_offset(Point::new(end_row, buffer.line_len(end_row)));
+        let end = self
+            .buffer_snapshot
+            .offset_to_point(excerpt.map_offset_from_buffer(end_offset));
+
+        let start = Point::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
+        if end.row <= start.row {
+            return None;
+        }
+
+        let summary = syntax_folds.iter().find_map(|fold| {
+            let summary_range = fold.summary_range.clone()?;
+            fold_summary(&buffer.text_for_range(summary_range).collect::<String>())
+        });
+        Some((start..end, summary))
+    }
+
+    /// Returns the syntax folds starting at the given row of the buffer.
+    fn line_syntax_folds(buffer: &language::BufferSnapshot, row: u32) -> Vec<SyntaxFold> {
+        let line = Point::new(row, 0)..Point::new(row, buffer.line_len(row));
+        buffer
+            .syntax_folds(line)
+            .filter(|fold| buffer.offset_to_point(fold.range.start).row == row)
+            .collect()
+    }
+
+    fn lsp_fold_end_row(
+        ranges: &[Range<text::Anchor>],
+        buffer: &language::BufferSnapshot,
+        row: u32,
+    ) -> Option<u32> {
+        let start_ix = ranges
+            .partition_point(|range| buffer.summary_for_anchor::<Point>(&range.start).row < row);
+        ranges[start_ix..]
+            .iter()
+            .take_while(|range| buffer.summary_for_anchor::<Point>(&range.start).row == row)
+            .map(|range| buffer.summary_for_anchor::<Point>(&range.end).row)
+            .filter(|end_row| *end_row > row)
+            .max()
+    }
+
+    fn syntax_fold_end_row(buffer: &language::BufferSnapshot, fold: &SyntaxFold) -> Option<u32> {
+        let end = buffer.offset_to_point(fold.range.end);
+        let ends_with_closing_line = buffer
+            .text_for_range(Point::new(end.row, 0)..end)
+            .flat_map(str::chars)
+            .all(|c| c.is_whitespace() || c.is_ascii_punctuation());
+        if ends_with_closing_line {
+            end.row.checked_sub(1)
+        } else {
+            Some(end.row)
+        }
+    }
+
     pub fn foldable_range(
         &self,
         buffer_row: MultiBufferRow,
+    ) -> Option<(Range<Point>, FoldPlaceholder)> {
+        self.foldable_range_with_syntax_folds(buffer_row, Self::line_syntax_folds)
+    }
+
+    /// Returns the ranges folded when folding at each of the foldable rows, in order.
+    ///
+    /// The folds query runs once per excerpt, rather than once per row as it would
+    /// when calling [`Self::foldable_range`] for every row.
+    pub fn foldable_ranges(&self) -> Vec<(Range<Point>, FoldPlaceholder)> {
+        let mut syntax_folds = HashMap::<(BufferId, u32), Vec<SyntaxFold>>::default();
+        for (_, buffer, range) in self.buffer_snapshot.excerpts() {
+            let context = range.context.to_offset(buffer);
+            for fold in buffer.syntax_folds(context.clone()) {
+                if context.contains(&fold.range.start) {
+                    let row = buffer.offset_to_point(fold.range.start).row;
+                    syntax_folds
+                        .entry((buffer.remote_id(), row))
+                        .or_default()
+                        .push(fold);
+                }
+            }
+        }
+
+        (0..=self.buffer_snapshot.max_buffer_row().0)
+            .filter_map(|row| {
+                self.foldable_range_with_syntax_folds(MultiBufferRow(row), |buffer, row| {
+                    syntax_folds
+                        .get(&(buffer.remote_id(), row))
+                        .cloned()
+                        .unwrap_or_default()
+                })
+            })
+            .collect()
+    }
+
+    fn foldable_range_with_syntax_folds(
+        &self,
+        buffer_row: MultiBufferRow,
+        syntax_folds: impl FnOnce(&language::BufferSnapshot, u32) -> Vec<SyntaxFold>,
     ) -> Option<(Range<Point>, FoldPlaceholder)> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -964,9 +1140,15 @@ impl DisplaySnapshot {
                 crease.range.to_point(&self.buffer_snapshot),
                 crease.placeholder.clone(),
             ))
-        } else if self.starts_indent(MultiBufferRow(start.row))
-            && !self.is_line_folded(MultiBufferRow(start.row))
-        {
+        } else if self.is_line_folded(buffer_row) {
+            None
+        } else if let Some((range, summary)) = self.language_fold_range(buffer_row, syntax_folds) {
+            let placeholder = match summary {
+                Some(summary) => self.fold_placeholder.with_summary(summary),
+                None => self.fold_placeholder.clone(),
+            };
+            Some((range, placeholder))
+        } else if self.starts_indent(MultiBufferRow(start.row)) {
             let start_line_indent = self.line_indent_for_buffer_row(buffer_row);
             let max_point = self.buffer_snapshot.max_point();
             let mut end = None;
@@ -1036,6 +1218,25 @@ impl DisplaySnapshot {
     }
 }
 
+/// Condenses the text a folds query captured as a fold's summary into its first sentence,
+/// without the quotes or comment markers surrounding it.
+fn fold_summary(text: &str) -> Option<SharedString> {
+    const MAX_SUMMARY_LEN: usize = 80;
+
+    let line = text
+        .lines()
+        .map(|line| {
+            line.trim_start_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+                .trim_end_matches(|c: char| c.is_whitespace() || "\"'*/".contains(c))
+        })
+        .find(|line| !line.is_empty())?;
+    let sentence = match line.find(". ") {
+        Some(ix) => &line[..=ix],
+        None => line,
+    };
+    Some(util::truncate_and_trailoff(sentence, MAX_SUMMARY_LEN).into())
+}
+
 #[derive(Copy, Clone, Default, Eq, Ord, PartialOrd, PartialEq)]
 pub struct DisplayPoint(BlockPoint);
 
@@ -1904,6 +2105,59 @@ pub mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_chunks_with_semantic_highlights(cx: &mut gpui::AppContext) {
+        init_test(cx, |_| {});
+
+        let buffer = MultiBuffer::build_simple("ab c", cx);
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let map = cx.new_model(|cx| {
+            DisplayMap::new(
+                buffer,
+                font("Courier"),
+                px(16.0),
+                None,
+                true,
+                1,
+                1,
+                1,
+                FoldPlaceholder::test(),
+                cx,
+            )
+        });
+
+        let style = |color| HighlightStyle {
+            color: Some(color),
+            ..Default::default()
+        };
+        let range = |range: Range<usize>| {
+            buffer_snapshot.anchor_after(range.start)..buffer_snapshot.anchor_before(range.end)
+        };
+        enum MyType {}
+        map.update(cx, |map, _| {
+            map.set_semantic_highlights(vec![
+                (range(0..1), style(Hsla::red())),
+                (range(1..2), style(Hsla::green())),
+                (range(3..4), style(Hsla::green())),
+            ]);
+            let blue = style(Hsla::blue());
+            map.highlight_text(TypeId::of::<MyType>(), vec![range(3..4)], blue);
+        });
+
+        // Adjacent semantic highlights keep their own styles, and text highlights are applied
+        // over them.
+        let theme = SyntaxTheme::default();
+        assert_eq!(
+            chunks(DisplayRow(0)..DisplayRow(1), &map, &theme, cx),
+            [
+                ("a".to_string(), None, Some(Hsla::red())),
+                ("b".to_string(), None, Some(Hsla::green())),
+                (" ".to_string(), None, None),
+                ("c".to_string(), None, Some(Hsla::blue())),
+            ]
+        );
+    }
+
     #[gpui::test]
     fn test_clip_point(cx: &mut gpui::AppContext) {
         init_test(cx, |_| {});
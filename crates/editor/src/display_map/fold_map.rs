@@ -2,7 +2,7 @@ use super::{
     inlay_map::{InlayBufferRows, InlayChunks, InlayEdit, InlayOffset, InlayPoint, InlaySnapshot},
     Highlights,
 };
-use gpui::{AnyElement, ElementId, WindowContext};
+use gpui::{AnyElement, ElementId, SharedString, WindowContext};
 use language::{Chunk, ChunkRenderer, Edit, Point, TextSummary};
 use multi_buffer::{Anchor, AnchorRangeExt, MultiBufferRow, MultiBufferSnapshot, ToOffset};
 use std::{
@@ -16,12 +16,18 @@ use util::post_inc;
 
 #[derive(Clone)]
 pub struct FoldPlaceholder {
-    /// Creates an element to represent this fold's placeholder.
-    pub render: Arc<dyn Send + Sync + Fn(FoldId, Range<Anchor>, &mut WindowContext) -> AnyElement>,
+    /// Creates an element to represent this fold's placeholder, given its summary.
+    pub render: Arc<
+        dyn Send
+            + Sync
+            + Fn(FoldId, Range<Anchor>, Option<SharedString>, &mut WindowContext) -> AnyElement,
+    >,
     /// If true, the element is constrained to the shaped width of an ellipsis.
     pub constrain_width: bool,
     /// If true, merges the fold with an adjacent one.
     pub merge_adjacent: bool,
+    /// A short description of the folded text, such as the first sentence of a docstring.
+    pub summary: Option<SharedString>,
 }
 
 impl FoldPlaceholder {
@@ -30,9 +36,18 @@ impl FoldPlaceholder {
         use gpui::IntoElement;
 
         Self {
-            render: Arc::new(|_id, _range, _cx| gpui::Empty.into_any_element()),
+            render: Arc::new(|_id, _range, _summary, _cx| gpui::Empty.into_any_element()),
             constrain_width: true,
             merge_adjacent: true,
+            summary: None,
+        }
+    }
+
+    /// Returns a copy of this placeholder that describes the folded text with the given summary.
+    pub fn with_summary(&self, summary: impl Into<SharedString>) -> Self {
+        Self {
+            summary: Some(summary.into()),
+            ..self.clone()
         }
     }
 }
@@ -41,6 +56,7 @@ impl fmt::Debug for FoldPlaceholder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FoldPlaceholder")
             .field("constrain_width", &self.constrain_width)
+            .field("summary", &self.summary)
             .finish()
     }
 }
@@ -49,7 +65,9 @@ impl Eq for FoldPlaceholder {}
 
 impl PartialEq for FoldPlaceholder {
     fn eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self.render, &other.render) && self.constrain_width == other.constrain_width
+        Arc::ptr_eq(&self.render, &other.render)
+            && self.constrain_width == other.constrain_width
+            && self.summary == other.summary
     }
 }
 
@@ -437,6 +455,7 @@ impl FoldMap {
                                             (fold.placeholder.render)(
                                                 fold_id,
                                                 fold.range.0.clone(),
+                                                fold.placeholder.summary.clone(),
                                                 cx,
                                             )
                                         }),
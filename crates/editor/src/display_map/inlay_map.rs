@@ -1,4 +1,4 @@
-use crate::{HighlightStyles, InlayId};
+use crate::{color_swatches::COLOR_SWATCH_TEXT, HighlightStyles, InlayId};
 use collections::{BTreeMap, BTreeSet};
 use gpui::HighlightStyle;
 use language::{Chunk, Edit, Point, TextSummary};
@@ -69,6 +69,22 @@ impl Inlay {
             text: text.into(),
         }
     }
+
+    pub fn color(id: usize, position: Anchor) -> Self {
+        Self {
+            id: InlayId::Color(id),
+            position,
+            text: COLOR_SWATCH_TEXT.into(),
+        }
+    }
+
+    pub fn replacement<T: Into<Rope>>(id: usize, position: Anchor, text: T) -> Self {
+        Self {
+            id: InlayId::Replacement(id),
+            position,
+            text: text.into(),
+        }
+    }
 }
 
 impl sum_tree::Item for Transform {
@@ -191,10 +207,19 @@ pub struct InlayBufferRows<'a> {
 struct HighlightEndpoint {
     offset: InlayOffset,
     is_start: bool,
-    tag: Option<TypeId>,
+    tag: HighlightTag,
     style: HighlightStyle,
 }
 
+/// Identifies the highlight an endpoint belongs to. Semantic highlights order first, so that text
+/// highlights are applied over them.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightTag {
+    /// The index of a semantic highlight, as adjacent tokens can share a style.
+    Semantic(usize),
+    Text(Option<TypeId>),
+}
+
 impl PartialOrd for HighlightEndpoint {
     fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
         Some(self.cmp(other))
@@ -219,7 +244,7 @@ pub struct InlayChunks<'a> {
     max_output_offset: InlayOffset,
     highlight_styles: HighlightStyles,
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<Option<TypeId>, HighlightStyle>,
+    active_highlights: BTreeMap<HighlightTag, HighlightStyle>,
     highlights: Highlights<'a>,
     snapshot: &'a InlaySnapshot,
 }
@@ -312,6 +337,8 @@ impl<'a> Iterator for InlayChunks<'a> {
                 let mut highlight_style = match inlay.id {
                     InlayId::Suggestion(_) => self.highlight_styles.suggestion,
                     InlayId::Hint(_) => self.highlight_styles.inlay_hint,
+                    InlayId::Color(_) => None,
+                    InlayId::Replacement(_) => None,
                 };
                 let next_inlay_highlight_endpoint;
                 let offset_in_inlay = self.output_offset - self.transforms.start().0;
@@ -1040,6 +1067,9 @@ impl InlaySnapshot {
                 cursor.seek(&range.start, Bias::Right, &());
             }
         }
+        if let Some(semantic_highlights) = highlights.semantic_highlights {
+            self.apply_semantic_highlights(&range, semantic_highlights, &mut highlight_endpoints);
+        }
         highlight_endpoints.sort();
         let buffer_range = self.to_buffer_offset(range.start)..self.to_buffer_offset(range.end);
         let buffer_chunks = self.buffer.chunks(buffer_range, language_aware);
@@ -1102,13 +1132,13 @@ impl InlaySnapshot {
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.start.to_offset(&self.buffer)),
                         is_start: true,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                     highlight_endpoints.push(HighlightEndpoint {
                         offset: self.to_inlay_offset(range.end.to_offset(&self.buffer)),
                         is_start: false,
-                        tag: *tag,
+                        tag: HighlightTag::Text(*tag),
                         style,
                     });
                 }
@@ -1118,6 +1148,40 @@ impl InlaySnapshot {
         }
     }
 
+    fn apply_semantic_highlights(
+        &self,
+        range: &Range<InlayOffset>,
+        semantic_highlights: &[(Range<Anchor>, HighlightStyle)],
+        highlight_endpoints: &mut Vec<HighlightEndpoint>,
+    ) {
+        let buffer_start = self.to_buffer_offset(range.start);
+        let buffer_end = self.to_buffer_offset(range.end);
+        let start_ix = semantic_highlights.partition_point(|(highlight_range, _)| {
+            highlight_range.end.to_offset(&self.buffer) <= buffer_start
+        });
+        for (ix, (highlight_range, style)) in semantic_highlights.iter().enumerate().skip(start_ix)
+        {
+            let highlight_range = highlight_range.start.to_offset(&self.buffer)
+                ..highlight_range.end.to_offset(&self.buffer);
+            if highlight_range.start >= buffer_end {
+                break;
+            }
+
+            highlight_endpoints.push(HighlightEndpoint {
+                offset: self.to_inlay_offset(highlight_range.start),
+                is_start: true,
+                tag: HighlightTag::Semantic(ix),
+                style: *style,
+            });
+            highlight_endpoints.push(HighlightEndpoint {
+                offset: self.to_inlay_offset(highlight_range.end),
+                is_start: false,
+                tag: HighlightTag::Semantic(ix),
+                style: *style,
+            });
+        }
+    }
+
     #[cfg(test)]
     pub fn text(&self) -> String {
         self.chunks(Default::default()..self.len(), false, Highlights::default())
@@ -15,11 +15,14 @@
 pub mod actions;
 mod blame_entry_tooltip;
 mod blink_manager;
+mod bracket_colorization;
+mod color_swatches;
 mod debounced_delay;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -32,11 +35,18 @@ pub mod items;
 mod linked_editing_ranges;
 mod mouse_context_menu;
 pub mod movement;
+mod peek_view;
 mod persistence;
+mod pull_diagnostics;
+mod rename_preview;
+mod replacement_previews;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
+mod viewport_refresh;
+mod virtual_space;
 
 #[cfg(test)]
 mod editor_tests;
@@ -50,9 +60,11 @@ pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
+use bracket_colorization::refresh_bracket_colors;
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
+use color_swatches::ColorSwatch;
 use convert_case::{Case, Casing};
 use debounced_delay::DebouncedDelay;
 use display_map::*;
@@ -89,13 +101,18 @@ use itertools::Itertools;
 use language::{
     char_kind,
     language_settings::{self, all_language_settings, InlayHintSettings},
-    markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CharKind, CodeLabel,
-    CursorShape, Diagnostic, Documentation, IndentKind, IndentSize, Language, OffsetRangeExt,
-    Point, Selection, SelectionGoal, TransactionId,
+    markdown, point_from_lsp, AutoindentMode, BlockCommentContinuation, BracketPair, Buffer,
+    Capability, CharKind, CodeLabel, CursorShape, Diagnostic, Documentation, IndentKind,
+    IndentSize, Language, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
 };
 use language::{point_to_lsp, BufferRow, Runnable, RunnableRange};
 use linked_editing_ranges::refresh_linked_ranges;
+use peek_view::PeekView;
+use rename_preview::RenamePreview;
+pub use replacement_previews::ReplacementPreview;
 use task::{ResolvedTask, TaskTemplate, TaskVariables};
+use viewport_refresh::{refresh_viewport, ViewportRefreshReason};
+use virtual_space::VirtualSpace;
 
 use hover_links::{HoverLink, HoveredLinkState, InlayHighlight};
 pub use lsp::CompletionContext;
@@ -114,12 +131,14 @@ use ordered_float::OrderedFloat;
 use parking_lot::{Mutex, RwLock};
 use project::project_settings::{GitGutterSetting, ProjectSettings};
 use project::{
-    CodeAction, Completion, FormatTrigger, Item, Location, Project, ProjectPath,
-    ProjectTransaction, TaskSourceKind, WorktreeId,
+    BufferSemanticTokens, CodeAction, Completion, DocumentColor, FormatTrigger, Item, Location,
+    Project, ProjectPath, ProjectTransaction, TaskSourceKind, WorktreeId,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
-use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
+use scroll::{
+    Autoscroll, OngoingScroll, ScrollAnchor, ScrollLock, ScrollManager, ScrollbarAutoHide,
+};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsStore};
@@ -149,12 +168,10 @@ use ui::{
     h_flex, prelude::*, ButtonSize, ButtonStyle, Disclosure, IconButton, IconName, IconSize,
     ListItem, Popover, Tooltip,
 };
-use util::{defer, maybe, post_inc, RangeExt, ResultExt, TryFutureExt};
+use util::{defer, maybe, paths::PathWithPosition, post_inc, RangeExt, ResultExt, TryFutureExt};
 use workspace::item::{ItemHandle, PreviewTabsSettings};
 use workspace::notifications::{DetachAndPromptErr, NotificationId};
-use workspace::{
-    searchable::SearchEvent, ItemNavHistory, SplitDirection, ViewId, Workspace, WorkspaceId,
-};
+use workspace::{searchable::SearchEvent, ItemNavHistory, ViewId, Workspace, WorkspaceId};
 use workspace::{OpenInTerminal, OpenTerminal, TabBarSettings, Toast};
 
 use crate::hover_links::find_url;
@@ -173,6 +190,8 @@ pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 pub const CODE_ACTIONS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
 #[doc(hidden)]
 pub const DOCUMENT_HIGHLIGHTS_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+const SELECTION_OCCURRENCES_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
+const ADDITIONAL_EDIT_HIGHLIGHT_DURATION: Duration = Duration::from_millis(500);
 
 pub(crate) const FORMAT_TIMEOUT: Duration = Duration::from_secs(2);
 
@@ -243,6 +262,8 @@ pub fn render_parsed_markdown(
 pub(crate) enum InlayId {
     Suggestion(usize),
     Hint(usize),
+    Color(usize),
+    Replacement(usize),
 }
 
 impl InlayId {
@@ -250,6 +271,8 @@ impl InlayId {
         match self {
             Self::Suggestion(id) => *id,
             Self::Hint(id) => *id,
+            Self::Color(id) => *id,
+            Self::Replacement(id) => *id,
         }
     }
 }
@@ -257,6 +280,9 @@ impl InlayId {
 enum DiffRowHighlight {}
 enum DocumentHighlightRead {}
 enum DocumentHighlightWrite {}
+enum SelectionOccurrenceHighlight {}
+enum FoldToggleHighlight {}
+enum AdditionalEditHighlight {}
 enum InputComposition {}
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -280,6 +306,7 @@ pub fn init(cx: &mut AppContext) {
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(Editor::new_file);
             workspace.register_action(Editor::new_file_in_direction);
+            workspace.register_action(Editor::organize_imports_in_dirty_buffers);
         },
     )
     .detach();
@@ -320,6 +347,7 @@ pub enum SelectPhase {
     BeginColumnar {
         position: DisplayPoint,
         reset: bool,
+        add: bool,
         goal_column: u32,
     },
     Extend {
@@ -460,7 +488,15 @@ pub struct Editor {
     /// typing enters text into each of them, even the ones that aren't focused.
     pub(crate) show_cursor_when_unfocused: bool,
     columnar_selection_tail: Option<Anchor>,
+    /// The selections that were there before a columnar selection added to them.
+    columnar_kept_selections: Vec<Selection<Anchor>>,
     add_selections_state: Option<AddSelectionsState>,
+    /// The ids of the cursors added by each `AddCursorAbove`/`AddCursorBelow`, most recent last.
+    added_cursors_stack: Vec<Vec<usize>>,
+    keyboard_columnar_selection: Option<KeyboardColumnarSelection>,
+    virtual_space: VirtualSpace,
+    rename_preview: Option<RenamePreview>,
+    peek_view: Option<PeekView>,
     select_next_state: Option<SelectNextState>,
     select_prev_state: Option<SelectNextState>,
     selection_history: SelectionHistory,
@@ -505,11 +541,21 @@ pub struct Editor {
     available_code_actions: Option<(Location, Arc<[CodeAction]>)>,
     code_actions_task: Option<Task<()>>,
     document_highlights_task: Option<Task<()>>,
+    selection_occurrences_task: Option<Task<()>>,
     linked_editing_range_task: Option<Task<Option<()>>>,
+    viewport_refresh_task: Option<Task<Option<()>>>,
+    viewport_buffer_versions: HashMap<BufferId, clock::Global>,
+    color_swatches: Vec<ColorSwatch>,
+    document_colors: HashMap<BufferId, Vec<DocumentColor>>,
+    replacement_previews: Vec<(ReplacementPreview, Option<InlayId>)>,
+    semantic_tokens: HashMap<BufferId, BufferSemanticTokens>,
     linked_edit_ranges: linked_editing_ranges::LinkedEditingRanges,
+    bracket_colors: bracket_colorization::BracketColors,
     pending_rename: Option<RenameState>,
     searchable: bool,
     cursor_shape: CursorShape,
+    /// While overtype mode is on, the cursor shape to restore when it's turned off.
+    overtype: Option<CursorShape>,
     current_line_highlight: Option<CurrentLineHighlight>,
     collapse_matches: bool,
     autoindent_mode: Option<AutoindentMode>,
@@ -519,9 +565,13 @@ pub struct Editor {
     use_modal_editing: bool,
     read_only: bool,
     leader_peer_id: Option<PeerId>,
+    /// The text scale of the collaborator being followed, which replaces the
+    /// local buffer font size adjustment while following.
+    leader_text_scale: Option<f32>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
     gutter_hovered: bool,
+    hovered_fold_toggle: Option<MultiBufferRow>,
     hovered_link_state: Option<HoveredLinkState>,
     inline_completion_provider: Option<RegisteredInlineCompletionProvider>,
     active_inline_completion: Option<(Inlay, Option<Range<Anchor>>)>,
@@ -561,6 +611,7 @@ pub struct Editor {
     file_header_size: u32,
     breadcrumb_header: Option<String>,
     focused_block: Option<FocusedBlock>,
+    scroll_locks: Vec<ScrollLock>,
 }
 
 #[derive(Clone)]
@@ -576,6 +627,7 @@ pub struct EditorSnapshot {
     pub placeholder_text: Option<Arc<str>>,
     is_focused: bool,
     scroll_anchor: ScrollAnchor,
+    overscroll: f32,
     ongoing_scroll: OngoingScroll,
     current_line_highlight: CurrentLineHighlight,
     gutter_hovered: bool,
@@ -617,6 +669,14 @@ pub struct RemoteSelection {
     pub user_name: Option<SharedString>,
 }
 
+#[derive(Debug)]
+pub struct RemoteViewport {
+    pub replica_id: ReplicaId,
+    pub range: Range<Anchor>,
+    pub peer_id: PeerId,
+    pub participant_index: Option<ParticipantIndex>,
+}
+
 #[derive(Clone, Debug)]
 struct SelectionHistoryEntry {
     selections: Arc<[Selection<Anchor>]>,
@@ -732,6 +792,15 @@ struct AddSelectionsState {
     stack: Vec<usize>,
 }
 
+/// A columnar selection being extended with the `SelectColumn*` actions.
+#[derive(Clone, Copy, Debug)]
+struct KeyboardColumnarSelection {
+    tail: Anchor,
+    /// The head is kept in display coordinates so that its column can lie past the end of
+    /// shorter lines.
+    head: DisplayPoint,
+}
+
 #[derive(Clone)]
 struct SelectNextState {
     query: AhoCorasick,
@@ -758,6 +827,7 @@ struct AutocloseRegion {
 #[derive(Debug)]
 struct SnippetState {
     ranges: Vec<Vec<Range<Anchor>>>,
+    choices: Vec<Option<Vec<String>>>,
     active_index: usize,
 }
 
@@ -1036,7 +1106,24 @@ impl CompletionsMenu {
 
         let multiline_docs = if show_completion_documentation {
             let mat = &self.matches[selected_item];
-            let multiline_docs = match &self.completions.read()[mat.candidate_id].documentation {
+            let completions = self.completions.read();
+            let completion = &completions[mat.candidate_id];
+            // Show the signature of the selected item above its documentation, unless its label
+            // already includes it.
+            let signature = completion
+                .lsp_completion
+                .detail
+                .as_ref()
+                .filter(|detail| {
+                    !detail.trim().is_empty() && !completion.label.text.contains(detail.as_str())
+                })
+                .map(|detail| {
+                    div()
+                        .font_family(style.text.font().family)
+                        .text_color(cx.theme().colors().text_muted)
+                        .child(SharedString::from(detail.clone()))
+                });
+            let documentation = match &completion.documentation {
                 Some(Documentation::MultiLinePlainText(text)) => {
                     Some(div().child(SharedString::from(text.clone())))
                 }
@@ -1051,6 +1138,11 @@ impl CompletionsMenu {
                 }
                 _ => None,
             };
+            let multiline_docs = if signature.is_some() || documentation.is_some() {
+                Some(v_flex().gap_1().children(signature).children(documentation))
+            } else {
+                None
+            };
             multiline_docs.map(|div| {
                 div.id("multiline_docs")
                     .max_h(max_height)
@@ -1664,35 +1756,57 @@ impl Editor {
         let font_size = style.font_size.to_pixels(cx.rem_size());
         let editor = cx.view().downgrade();
         let fold_placeholder = FoldPlaceholder {
-            constrain_width: true,
-            render: Arc::new(move |fold_id, fold_range, cx| {
-                let editor = editor.clone();
-                div()
-                    .id(fold_id)
-                    .bg(cx.theme().colors().ghost_element_background)
-                    .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
-                    .active(|style| style.bg(cx.theme().colors().ghost_element_active))
-                    .rounded_sm()
-                    .size_full()
-                    .cursor_pointer()
-                    .child("⋯")
-                    .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
-                    .on_click(move |_, cx| {
-                        editor
-                            .update(cx, |editor, cx| {
-                                editor.unfold_ranges(
-                                    [fold_range.start..fold_range.end],
-                                    true,
-                                    false,
-                                    cx,
-                                );
-                                cx.stop_propagation();
-                            })
-                            .ok();
-                    })
-                    .into_any()
+            constrain_width: false,
+            render: Arc::new({
+                let buffer = buffer.clone();
+                move |fold_id, fold_range, summary, cx| {
+                    let editor = editor.clone();
+                    let summary = summary.or_else(|| {
+                        let buffer = buffer.read(cx).read(cx);
+                        let folded_rows = fold_range.end.to_point(&buffer).row
+                            - fold_range.start.to_point(&buffer).row;
+                        match folded_rows {
+                            0 => None,
+                            1 => Some("1 line".into()),
+                            rows => Some(format!("{rows} lines").into()),
+                        }
+                    });
+                    div()
+                        .id(fold_id)
+                        .bg(cx.theme().colors().ghost_element_background)
+                        .hover(|style| style.bg(cx.theme().colors().ghost_element_hover))
+                        .active(|style| style.bg(cx.theme().colors().ghost_element_active))
+                        .rounded_sm()
+                        .h_full()
+                        .flex()
+                        .items_center()
+                        .gap_1()
+                        .cursor_pointer()
+                        .child("⋯")
+                        .when_some(summary, |this, summary: SharedString| {
+                            this.pr_1()
+                                .text_color(cx.theme().colors().text_muted)
+                                .child(summary)
+                        })
+                        .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                        .on_click(move |_, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    editor.unfold_ranges(
+                                        [fold_range.start..fold_range.end],
+                                        true,
+                                        false,
+                                        cx,
+                                    );
+                                    cx.stop_propagation();
+                                })
+                                .ok();
+                        })
+                        .into_any()
+                }
             }),
             merge_adjacent: true,
+            summary: None,
         };
         let file_header_size = if show_excerpt_controls { 3 } else { 2 };
         let display_map = cx.new_model(|cx| {
@@ -1728,6 +1842,8 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::LanguageServerAdded(_) = event {
+                        refresh_viewport(editor, ViewportRefreshReason::LanguageServersChanged, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1779,7 +1895,13 @@ impl Editor {
             selections,
             scroll_manager: ScrollManager::new(cx),
             columnar_selection_tail: None,
+            columnar_kept_selections: Vec::new(),
             add_selections_state: None,
+            added_cursors_stack: Vec::new(),
+            keyboard_columnar_selection: None,
+            virtual_space: Default::default(),
+            rename_preview: None,
+            peek_view: None,
             select_next_state: None,
             select_prev_state: None,
             selection_history: Default::default(),
@@ -1823,10 +1945,18 @@ impl Editor {
             available_code_actions: Default::default(),
             code_actions_task: Default::default(),
             document_highlights_task: Default::default(),
+            selection_occurrences_task: Default::default(),
             linked_editing_range_task: Default::default(),
+            viewport_refresh_task: None,
+            viewport_buffer_versions: HashMap::default(),
+            color_swatches: Vec::new(),
+            document_colors: HashMap::default(),
+            replacement_previews: Vec::new(),
+            semantic_tokens: HashMap::default(),
             pending_rename: Default::default(),
             searchable: true,
             cursor_shape: Default::default(),
+            overtype: None,
             current_line_highlight: None,
             autoindent_mode: Some(AutoindentMode::EachLine),
             collapse_matches: false,
@@ -1839,6 +1969,7 @@ impl Editor {
             use_auto_surround: true,
             auto_replace_emoji_shortcode: false,
             leader_peer_id: None,
+            leader_text_scale: None,
             remote_id: None,
             hover_state: Default::default(),
             hovered_link_state: Default::default(),
@@ -1847,6 +1978,7 @@ impl Editor {
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             expanded_hunks: ExpandedHunks::default(),
             gutter_hovered: false,
+            hovered_fold_toggle: None,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
             expect_bounds_change: None,
@@ -1877,7 +2009,10 @@ impl Editor {
                 cx.observe(&display_map, Self::on_display_map_changed),
                 cx.observe(&blink_manager, |_, _, cx| cx.notify()),
                 cx.observe_global::<SettingsStore>(Self::settings_changed),
-                observe_buffer_font_size_adjustment(cx, |_, cx| cx.notify()),
+                observe_buffer_font_size_adjustment(cx, |_, cx| {
+                    cx.emit(EditorEvent::TextScaleChanged);
+                    cx.notify();
+                }),
                 cx.observe_window_activation(|editor, cx| {
                     let active = cx.is_window_active();
                     editor.blink_manager.update(cx, |blink_manager, cx| {
@@ -1891,11 +2026,14 @@ impl Editor {
             ],
             tasks_update_task: None,
             linked_edit_ranges: Default::default(),
+            bracket_colors: Default::default(),
             previous_search_ranges: None,
             breadcrumb_header: None,
             focused_block: None,
+            scroll_locks: Vec::new(),
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
+        refresh_viewport(&mut this, ViewportRefreshReason::EditorOpened, cx);
         this._subscriptions.extend(project_subscriptions);
 
         this.end_selection(cx);
@@ -1938,6 +2076,12 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.rename_preview.is_some() {
+            key_context.add("rename_preview");
+        }
+        if self.peek_view.is_some() {
+            key_context.add("peeking");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
@@ -2048,6 +2192,23 @@ impl Editor {
         self.leader_peer_id
     }
 
+    /// Returns the buffer font size relative to the configured one, as changed by
+    /// zooming in or out, or the leader's text scale while following.
+    pub fn text_scale(&self, cx: &AppContext) -> f32 {
+        if let Some(scale) = self.leader_text_scale {
+            return scale;
+        }
+        let settings = ThemeSettings::get_global(cx);
+        settings.buffer_font_size(cx) / settings.buffer_font_size
+    }
+
+    pub(crate) fn set_leader_text_scale(&mut self, scale: Option<f32>, cx: &mut ViewContext<Self>) {
+        if self.leader_text_scale != scale {
+            self.leader_text_scale = scale;
+            cx.notify();
+        }
+    }
+
     pub fn buffer(&self) -> &Model<MultiBuffer> {
         &self.buffer
     }
@@ -2071,6 +2232,7 @@ impl Editor {
             render_git_blame_gutter: self.render_git_blame_gutter(cx),
             display_snapshot: self.display_map.update(cx, |map, cx| map.snapshot(cx)),
             scroll_anchor: self.scroll_manager.anchor(),
+            overscroll: self.scroll_manager.overscroll(),
             ongoing_scroll: self.scroll_manager.ongoing_scroll(),
             placeholder_text: self.placeholder_text.clone(),
             is_focused: self.focus_handle.is_focused(cx),
@@ -2299,12 +2461,16 @@ impl Editor {
             .update(cx, |display_map, cx| display_map.snapshot(cx));
         let buffer = &display_map.buffer_snapshot;
         self.add_selections_state = None;
+        self.added_cursors_stack.clear();
+        self.keyboard_columnar_selection = None;
         self.select_next_state = None;
         self.select_prev_state = None;
         self.select_larger_syntax_node_stack.clear();
         self.invalidate_autoclose_regions(&self.selections.disjoint_anchors(), buffer);
         self.snippet_stack
             .invalidate(&self.selections.disjoint_anchors(), buffer);
+        self.virtual_space
+            .invalidate(&self.selections.disjoint_anchors(), buffer);
         self.take_rename(false, cx);
 
         let new_cursor_position = self.selections.newest_anchor().head();
@@ -2379,6 +2545,7 @@ impl Editor {
             }
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
+            self.refresh_selection_occurrences(cx);
             refresh_matching_bracket_highlights(self, cx);
             self.discard_inline_completion(false, cx);
             linked_editing_ranges::refresh_linked_ranges(self, cx);
@@ -2503,7 +2670,8 @@ impl Editor {
                 position,
                 goal_column,
                 reset,
-            } => self.begin_columnar_selection(position, goal_column, reset, cx),
+                add,
+            } => self.begin_columnar_selection(position, goal_column, reset, add, cx),
             SelectPhase::Extend {
                 position,
                 click_count,
@@ -2658,6 +2826,7 @@ impl Editor {
         position: DisplayPoint,
         goal_column: u32,
         reset: bool,
+        add: bool,
         cx: &mut ViewContext<Self>,
     ) {
         if !self.focus_handle.is_focused(cx) {
@@ -2666,6 +2835,11 @@ impl Editor {
         }
 
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        self.columnar_kept_selections = if add && !reset {
+            self.selections.disjoint_anchors().to_vec()
+        } else {
+            Vec::new()
+        };
 
         if reset {
             let pointer_position = display_map
@@ -2794,6 +2968,7 @@ impl Editor {
 
     fn end_selection(&mut self, cx: &mut ViewContext<Self>) {
         self.columnar_selection_tail.take();
+        self.columnar_kept_selections.clear();
         if self.selections.pending_anchor().is_some() {
             let selections = self.selections.all::<usize>(cx);
             self.change_selections(None, cx, |s| {
@@ -2816,11 +2991,31 @@ impl Editor {
         let start_column = cmp::min(tail.column(), goal_column);
         let end_column = cmp::max(tail.column(), goal_column);
         let reversed = start_column < tail.column();
+        let virtual_space = EditorSettings::get_global(cx).virtual_space;
+        let mut virtual_cursors = Vec::new();
 
         let selection_ranges = (start_row.0..=end_row.0)
             .map(DisplayRow)
             .filter_map(|row| {
-                if start_column <= display_map.line_len(row) && !display_map.is_block_line(row) {
+                if virtual_space
+                    && start_column > display_map.line_len(row)
+                    && !display_map.is_block_line(row)
+                {
+                    // Place a cursor past the end of the line, in the block's first column,
+                    // unless the line is soft-wrapped there.
+                    let line_end =
+                        DisplayPoint::new(row, display_map.line_len(row)).to_point(display_map);
+                    let buffer_line_len = display_map
+                        .buffer_snapshot
+                        .line_len(MultiBufferRow(line_end.row));
+                    if line_end.column != buffer_line_len {
+                        return None;
+                    }
+                    virtual_cursors.push((line_end, start_column - display_map.line_len(row)));
+                    Some(line_end..line_end)
+                } else if start_column <= display_map.line_len(row)
+                    && !display_map.is_block_line(row)
+                {
                     let start = display_map
                         .clip_point(DisplayPoint::new(row, start_column), Bias::Left)
                         .to_point(display_map);
@@ -2837,10 +3032,26 @@ impl Editor {
                 }
             })
             .collect::<Vec<_>>();
+        let kept_ranges = self
+            .columnar_kept_selections
+            .iter()
+            .map(|selection| {
+                let start = selection.start.to_point(&display_map.buffer_snapshot);
+                let end = selection.end.to_point(&display_map.buffer_snapshot);
+                if selection.reversed {
+                    end..start
+                } else {
+                    start..end
+                }
+            })
+            .collect::<Vec<_>>();
 
         self.change_selections(None, cx, |s| {
-            s.select_ranges(selection_ranges);
+            s.select_ranges(kept_ranges.into_iter().chain(selection_ranges));
         });
+        if !virtual_cursors.is_empty() {
+            self.place_cursors_in_virtual_space(virtual_cursors, cx);
+        }
         cx.notify();
     }
 
@@ -2854,11 +3065,18 @@ impl Editor {
             || (self.columnar_selection_tail.is_some() && self.selections.disjoint.len() > 1)
     }
 
+    pub(crate) fn has_columnar_selection(&self) -> bool {
+        self.columnar_selection_tail.is_some()
+    }
+
     pub fn has_pending_selection(&self) -> bool {
         self.selections.pending_anchor().is_some() || self.columnar_selection_tail.is_some()
     }
 
     pub fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        if self.stop_middle_click_autoscroll(cx) {
+            return;
+        }
         if self.clear_clicked_diff_hunks(cx) {
             cx.notify();
             return;
@@ -2885,6 +3103,10 @@ impl Editor {
             return true;
         }
 
+        if self.close_peek_view(cx) {
+            return true;
+        }
+
         if hide_hover(self, cx) {
             return true;
         }
@@ -2971,6 +3193,13 @@ impl Editor {
         if self.read_only(cx) {
             return;
         }
+        if !self.virtual_space.is_empty() {
+            self.transact(cx, |this, cx| {
+                this.fill_virtual_space(cx);
+                this.handle_input(&text, cx);
+            });
+            return;
+        }
 
         let selections = self.selections.all_adjusted(cx);
         let mut bracket_inserted = false;
@@ -2983,6 +3212,18 @@ impl Editor {
         for (selection, autoclose_region) in
             self.selections_with_autoclose_regions(selections, &snapshot)
         {
+            // In overtype mode, the typed text replaces the text after the cursor, without any
+            // brackets being auto-closed.
+            if self.overtype.is_some() && selection.is_empty() {
+                let end = overtype_range(&snapshot, selection.start.to_offset(&snapshot), &text)
+                    .end
+                    .to_point(&snapshot);
+                let anchor = snapshot.anchor_after(end);
+                new_selections.push((selection.map(|_| anchor), 0));
+                edits.push((selection.start..end, text.clone()));
+                continue;
+            }
+
             if let Some(scope) = snapshot.language_scope_at(selection.head()) {
                 // Determine if the inserted text matches the opening or closing
                 // bracket of any of this language's bracket pairs.
@@ -3343,21 +3584,19 @@ impl Editor {
                         let end = selection.end;
                         let selection_is_empty = start == end;
                         let language_scope = buffer.language_scope_at(start);
+                        let leading_whitespace_len = buffer
+                            .reversed_chars_at(start)
+                            .take_while(|c| c.is_whitespace() && *c != '\n')
+                            .map(|c| c.len_utf8())
+                            .sum::<usize>();
+                        let trailing_whitespace_len = buffer
+                            .chars_at(end)
+                            .take_while(|c| c.is_whitespace() && *c != '\n')
+                            .map(|c| c.len_utf8())
+                            .sum::<usize>();
                         let (comment_delimiter, insert_extra_newline) = if let Some(language) =
                             &language_scope
                         {
-                            let leading_whitespace_len = buffer
-                                .reversed_chars_at(start)
-                                .take_while(|c| c.is_whitespace() && *c != '\n')
-                                .map(|c| c.len_utf8())
-                                .sum::<usize>();
-
-                            let trailing_whitespace_len = buffer
-                                .chars_at(end)
-                                .take_while(|c| c.is_whitespace() && *c != '\n')
-                                .map(|c| c.len_utf8())
-                                .sum::<usize>();
-
                             let insert_extra_newline =
                                 language.brackets().any(|(pair, enabled)| {
                                     let pair_start = pair.start.trim_end();
@@ -3421,6 +3660,37 @@ impl Editor {
                             (None, false)
                         };
 
+                        let continued_block_comment = language_scope
+                            .as_ref()
+                            .and_then(|language| language.block_comment_continuation())
+                            .filter(|_| {
+                                comment_delimiter.is_none()
+                                    && selection_is_empty
+                                    && multi_buffer.settings_at(0, cx).extend_comment_on_newline
+                            })
+                            .and_then(|continuation| {
+                                continued_block_comment(&buffer, continuation, start_point)
+                            });
+                        let comment_delimiter = comment_delimiter.or_else(|| {
+                            Some(continued_block_comment.as_ref()?.prefix.as_str().into())
+                        });
+                        let comment_end_padding =
+                            continued_block_comment.and_then(|continued| continued.end_padding);
+
+                        // When splitting a comment, move the text after the cursor right
+                        // after the new line's comment delimiter, which ends with a space.
+                        let replace_whitespace = comment_delimiter
+                            .as_ref()
+                            .map_or(false, |delimiter| delimiter.ends_with(char::is_whitespace));
+                        let (start, end) = if replace_whitespace {
+                            (
+                                start - leading_whitespace_len,
+                                end + trailing_whitespace_len,
+                            )
+                        } else {
+                            (start, end)
+                        };
+
                         let capacity_for_delimiter = comment_delimiter
                             .as_deref()
                             .map(str::len)
@@ -3434,13 +3704,21 @@ impl Editor {
                         }
                         if insert_extra_newline {
                             new_text = new_text.repeat(2);
+                        } else if let Some(padding) = &comment_end_padding {
+                            // Put the end of the comment on its own line, after the cursor's.
+                            new_text.push('\n');
+                            new_text.extend(indent.chars());
+                            new_text.push_str(padding);
                         }
 
                         let anchor = buffer.anchor_after(end);
                         let new_selection = selection.map(|_| anchor);
                         (
                             (start..end, new_text),
-                            (insert_extra_newline, new_selection),
+                            (
+                                insert_extra_newline || comment_end_padding.is_some(),
+                                new_selection,
+                            ),
                         )
                     })
                     .unzip()
@@ -4224,6 +4502,7 @@ impl Editor {
         if newest_selection.start.buffer_id != Some(buffer_handle.read(cx).remote_id()) {
             return None;
         }
+        let excerpt_id = newest_selection.start.excerpt_id;
 
         let lookbehind = newest_selection
             .start
@@ -4295,7 +4574,7 @@ impl Editor {
             text: text.into(),
         });
 
-        self.transact(cx, |this, cx| {
+        let completion_transaction_id = self.transact(cx, |this, cx| {
             if let Some(mut snippet) = snippet {
                 snippet.text = text.to_string();
                 for tabstop in snippet.tabstops.iter_mut().flatten() {
@@ -4332,6 +4611,12 @@ impl Editor {
 
             this.refresh_inline_completion(true, cx);
         });
+        let completion_transaction_id = completion_transaction_id.and_then(|_| {
+            buffer_handle
+                .read(cx)
+                .peek_undo_stack()
+                .map(|entry| entry.transaction_id())
+        });
 
         if let Some(confirm) = completion.confirm.as_ref() {
             (confirm)(cx);
@@ -4343,7 +4628,7 @@ impl Editor {
 
         let provider = self.completion_provider.as_ref()?;
         let apply_edits = provider.apply_additional_edits_for_completion(
-            buffer_handle,
+            buffer_handle.clone(),
             completion.clone(),
             true,
             cx,
@@ -4356,12 +4641,62 @@ impl Editor {
             self.show_signature_help(&ShowSignatureHelp, cx);
         }
 
-        Some(cx.foreground_executor().spawn(async move {
-            apply_edits.await?;
+        Some(cx.spawn(|editor, mut cx| async move {
+            if let Some(transaction) = apply_edits.await? {
+                editor.update(&mut cx, |editor, cx| {
+                    // Undo the additional edits, such as auto-imports, together with the
+                    // completion that produced them.
+                    if let Some(completion_transaction_id) = completion_transaction_id {
+                        buffer_handle.update(cx, |buffer, _| {
+                            buffer.merge_transactions(transaction.id, completion_transaction_id)
+                        });
+                    }
+                    editor.highlight_additional_edits(&buffer_handle, excerpt_id, &transaction, cx);
+                })?;
+            }
             Ok(())
         }))
     }
 
+    fn highlight_additional_edits(
+        &mut self,
+        buffer: &Model<Buffer>,
+        excerpt_id: ExcerptId,
+        transaction: &language::Transaction,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let buffer = buffer.read(cx);
+        let edited_ranges = buffer
+            .edited_ranges_for_transaction::<usize>(transaction)
+            .filter_map(|range| {
+                let start = buffer.anchor_after(range.start);
+                let end = buffer.anchor_before(range.end);
+                Some(
+                    snapshot.anchor_in_excerpt(excerpt_id, start)?
+                        ..=snapshot.anchor_in_excerpt(excerpt_id, end)?,
+                )
+            })
+            .collect::<Vec<_>>();
+        let color = cx.theme().colors().editor_highlighted_line_background;
+        for range in edited_ranges {
+            self.highlight_rows::<AdditionalEditHighlight>(range, Some(color), false, cx);
+        }
+
+        cx.spawn(|editor, mut cx| async move {
+            cx.background_executor()
+                .timer(ADDITIONAL_EDIT_HIGHLIGHT_DURATION)
+                .await;
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.clear_row_highlights::<AdditionalEditHighlight>();
+                    cx.notify();
+                })
+                .ok();
+        })
+        .detach();
+    }
+
     pub fn toggle_code_actions(&mut self, action: &ToggleCodeActions, cx: &mut ViewContext<Self>) {
         let mut context_menu = self.context_menu.write();
         if let Some(ContextMenu::CodeActions(code_actions)) = context_menu.as_ref() {
@@ -4830,6 +5165,66 @@ impl Editor {
         None
     }
 
+    /// Highlights the other occurrences of the selected text, when there's a single selection
+    /// within one line.
+    fn refresh_selection_occurrences(&mut self, cx: &mut ViewContext<Self>) {
+        self.selection_occurrences_task = None;
+        let selection = self.selections.newest::<usize>(cx);
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let query = buffer.text_for_range(selection.range()).collect::<String>();
+        if self.mode != EditorMode::Full
+            || !EditorSettings::get_global(cx).selection_highlight
+            || self.selections.count() > 1
+            || query.trim().is_empty()
+            || query.contains('\n')
+        {
+            self.clear_background_highlights::<SelectionOccurrenceHighlight>(cx);
+            return;
+        }
+        let Ok(query) = AhoCorasick::new(&[query]) else {
+            return;
+        };
+
+        self.selection_occurrences_task = Some(cx.spawn(|this, mut cx| async move {
+            cx.background_executor()
+                .timer(SELECTION_OCCURRENCES_DEBOUNCE_TIMEOUT)
+                .await;
+
+            let ranges = cx
+                .background_executor()
+                .spawn(async move {
+                    query
+                        .stream_find_iter(buffer.bytes_in_range(0..buffer.len()))
+                        .filter_map(|query_match| {
+                            let query_match = query_match.ok()?;
+                            Some(
+                                buffer.anchor_after(query_match.start())
+                                    ..buffer.anchor_before(query_match.end()),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.highlight_background::<SelectionOccurrenceHighlight>(
+                    &ranges,
+                    |theme| theme.editor_document_highlight_read_background,
+                    cx,
+                );
+            })
+            .log_err();
+        }));
+    }
+
+    /// The number of occurrences of the selected text that are highlighted, including the
+    /// selection itself.
+    pub fn selection_occurrence_count(&self) -> usize {
+        self.background_highlights
+            .get(&TypeId::of::<SelectionOccurrenceHighlight>())
+            .map_or(0, |(_, ranges)| ranges.len())
+    }
+
     fn refresh_inline_completion(
         &mut self,
         debounce: bool,
@@ -5255,6 +5650,9 @@ impl Editor {
             self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select_ranges(tabstop.ranges.iter().cloned());
             });
+            if let Some(Some(choices)) = snippet.choices.first() {
+                self.show_snippet_choices(choices, cx);
+            }
 
             // If we're already at the last tabstop and it's at the end of the snippet,
             // we're done, we don't need to keep the state around.
@@ -5266,6 +5664,7 @@ impl Editor {
                 self.snippet_stack.push(SnippetState {
                     active_index: 0,
                     ranges,
+                    choices: snippet.choices,
                 });
             }
 
@@ -5308,6 +5707,62 @@ impl Editor {
         Ok(())
     }
 
+    /// Offers the choices of a snippet's tabstop in the completions menu, replacing the newest
+    /// selection and the selections mirroring it.
+    fn show_snippet_choices(&mut self, choices: &[String], cx: &mut ViewContext<Self>) {
+        let range = self.selections.newest_anchor().range();
+        let buffer = self.buffer.read(cx);
+        let Some((buffer_handle, start)) = buffer.text_anchor_for_position(range.start, cx) else {
+            return;
+        };
+        let Some((_, end)) = buffer.text_anchor_for_position(range.end, cx) else {
+            return;
+        };
+
+        let completions = choices
+            .iter()
+            .map(|choice| Completion {
+                old_range: start..end,
+                new_text: choice.clone(),
+                label: CodeLabel::plain(choice.clone(), None),
+                server_id: LanguageServerId(usize::MAX),
+                documentation: None,
+                lsp_completion: Default::default(),
+                confirm: None,
+                show_new_completions_on_confirm: false,
+            })
+            .collect::<Vec<_>>();
+        let menu = CompletionsMenu {
+            id: post_inc(&mut self.next_completion_id),
+            initial_position: range.start,
+            buffer: buffer_handle,
+            completions: Arc::new(RwLock::new(completions.into())),
+            match_candidates: choices
+                .iter()
+                .enumerate()
+                .map(|(id, choice)| StringMatchCandidate::new(id, choice.clone()))
+                .collect(),
+            matches: choices
+                .iter()
+                .enumerate()
+                .map(|(candidate_id, choice)| StringMatch {
+                    candidate_id,
+                    score: 0.,
+                    positions: Vec::new(),
+                    string: choice.clone(),
+                })
+                .collect(),
+            selected_item: 0,
+            scroll_handle: UniformListScrollHandle::new(),
+            selected_completion_documentation_resolve_debounce: Arc::new(Mutex::new(
+                DebouncedDelay::new(),
+            )),
+        };
+        *self.context_menu.write() = Some(ContextMenu::Completions(menu));
+        self.discard_inline_completion(false, cx);
+        cx.notify();
+    }
+
     pub fn move_to_next_snippet_tabstop(&mut self, cx: &mut ViewContext<Self>) -> bool {
         self.move_to_snippet_tabstop(Bias::Right, cx)
     }
@@ -5340,6 +5795,9 @@ impl Editor {
                 self.change_selections(Some(Autoscroll::fit()), cx, |s| {
                     s.select_anchor_ranges(current_ranges.iter().cloned())
                 });
+                if let Some(Some(choices)) = snippet.choices.get(snippet.active_index) {
+                    self.show_snippet_choices(choices, cx);
+                }
                 // If snippet state is not at the last tabstop, push it back on the stack
                 if snippet.active_index + 1 < snippet.ranges.len() {
                     self.snippet_stack.push(snippet);
@@ -5382,12 +5840,28 @@ impl Editor {
                 }
             }
 
+            let hungry_backspace = EditorSettings::get_global(cx).hungry_backspace;
             let mut selections = this.selections.all::<MultiBufferPoint>(cx);
             if !this.selections.line_mode {
                 let display_map = this.display_map.update(cx, |map, cx| map.snapshot(cx));
                 for selection in &mut selections {
                     if selection.is_empty() {
                         let old_head = selection.head();
+                        if hungry_backspace {
+                            let buffer = &display_map.buffer_snapshot;
+                            let whitespace_len = buffer
+                                .reversed_chars_at(old_head)
+                                .take_while(|c| c.is_whitespace())
+                                .map(char::len_utf8)
+                                .sum::<usize>();
+                            if whitespace_len > 0 {
+                                let new_head =
+                                    (old_head.to_offset(buffer) - whitespace_len).to_point(buffer);
+                                selection.set_head(new_head, SelectionGoal::None);
+                                continue;
+                            }
+                        }
+
                         let mut new_head =
                             movement::left(&display_map, old_head.to_display_point(&display_map))
                                 .to_point(&display_map);
@@ -5851,9 +6325,37 @@ impl Editor {
                     let end_of_line = Point::new(row.0, snapshot.line_len(row));
                     let next_line_row = row.next_row();
                     let indent = snapshot.indent_size_for_line(next_line_row);
-                    let start_of_next_line = Point::new(next_line_row.0, indent.len);
+                    let mut start_of_next_line = Point::new(next_line_row.0, indent.len);
+
+                    // When joining two line comments, drop the comment leader of the second one.
+                    if let Some(scope) = snapshot.language_scope_at(start_of_next_line) {
+                        let line_indent = snapshot.indent_size_for_line(row);
+                        let line = snapshot
+                            .text_for_range(Point::new(row.0, line_indent.len)..end_of_line)
+                            .collect::<String>();
+                        let next_line = snapshot
+                            .text_for_range(
+                                start_of_next_line
+                                    ..Point::new(next_line_row.0, snapshot.line_len(next_line_row)),
+                            )
+                            .collect::<String>();
+                        let mut comment_prefixes = scope
+                            .line_comment_prefixes()
+                            .iter()
+                            .map(|prefix| prefix.trim_end())
+                            .filter(|prefix| !prefix.is_empty())
+                            .collect::<Vec<_>>();
+                        comment_prefixes.sort_by_key(|prefix| Reverse(prefix.len()));
+                        if let Some(prefix) = comment_prefixes.into_iter().find(|prefix| {
+                            line.starts_with(prefix) && next_line.starts_with(prefix)
+                        }) {
+                            let rest = &next_line[prefix.len()..];
+                            let leader_len = next_line.len() - rest.trim_start().len();
+                            start_of_next_line.column += leader_len as u32;
+                        }
+                    }
 
-                    let replace = if snapshot.line_len(next_line_row) > indent.len {
+                    let replace = if snapshot.line_len(next_line_row) > start_of_next_line.column {
                         " "
                     } else {
                         ""
@@ -5887,6 +6389,23 @@ impl Editor {
         self.manipulate_lines(cx, |lines| lines.sort_by_key(|line| line.to_lowercase()))
     }
 
+    pub fn sort_lines_descending(&mut self, _: &SortLinesDescending, cx: &mut ViewContext<Self>) {
+        self.manipulate_lines(cx, |lines| lines.sort_by(|a, b| b.cmp(a)))
+    }
+
+    /// Sorts lines by the number they start with. Lines that don't start with a number
+    /// keep their order and go first.
+    pub fn sort_lines_numerically(&mut self, _: &SortLinesNumerically, cx: &mut ViewContext<Self>) {
+        self.manipulate_lines(cx, |lines| {
+            lines.sort_by(|a, b| match (leading_number(a), leading_number(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (Some(_), None) => Ordering::Greater,
+                (None, Some(_)) => Ordering::Less,
+                (None, None) => Ordering::Equal,
+            })
+        })
+    }
+
     pub fn unique_lines_case_insensitive(
         &mut self,
         _: &UniqueLinesCaseInsensitive,
@@ -5909,6 +6428,10 @@ impl Editor {
         })
     }
 
+    pub fn unique_adjacent_lines(&mut self, _: &UniqueAdjacentLines, cx: &mut ViewContext<Self>) {
+        self.manipulate_lines(cx, |lines| lines.dedup())
+    }
+
     pub fn revert_selected_hunks(&mut self, _: &RevertSelectedHunks, cx: &mut ViewContext<Self>) {
         let revert_changes = self.gather_revert_changes(&self.selections.disjoint_anchors(), cx);
         if !revert_changes.is_empty() {
@@ -6095,6 +6618,14 @@ impl Editor {
         self.manipulate_text(cx, |text| text.to_case(Case::Snake))
     }
 
+    pub fn convert_to_upper_snake_case(
+        &mut self,
+        _: &ConvertToUpperSnakeCase,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.manipulate_text(cx, |text| text.to_case(Case::UpperSnake))
+    }
+
     pub fn convert_to_kebab_case(&mut self, _: &ConvertToKebabCase, cx: &mut ViewContext<Self>) {
         self.manipulate_text(cx, |text| text.to_case(Case::Kebab))
     }
@@ -6139,6 +6670,16 @@ impl Editor {
         })
     }
 
+    /// Converts each line of the selections to the case following its current one, cycling
+    /// through snake_case, SCREAMING_SNAKE_CASE, kebab-case, camelCase, PascalCase and Title Case.
+    pub fn convert_to_next_case(&mut self, _: &ConvertToNextCase, cx: &mut ViewContext<Self>) {
+        self.manipulate_text(cx, |text| {
+            text.split('\n')
+                .map(|line| line.to_case(next_case(line)))
+                .join("\n")
+        })
+    }
+
     fn manipulate_text<Fn>(&mut self, cx: &mut ViewContext<Self>, mut callback: Fn)
     where
         Fn: FnMut(&str) -> String,
@@ -6252,6 +6793,7 @@ impl Editor {
     }
 
     pub fn move_line_up(&mut self, _: &MoveLineUp, cx: &mut ViewContext<Self>) {
+        let reindent = EditorSettings::get_global(cx).reindent_moved_lines;
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
@@ -6341,21 +6883,39 @@ impl Editor {
             new_selections.append(&mut contiguous_row_selections);
         }
 
+        let mut reindented_selections = Vec::new();
         self.transact(cx, |this, cx| {
             this.unfold_ranges(unfold_ranges, true, true, cx);
             this.buffer.update(cx, |buffer, cx| {
                 for (range, text) in edits {
-                    buffer.edit([(range, text)], None, cx);
+                    // Only the inserted lines are reindented, the deletions are left as is.
+                    let autoindent_mode = if reindent && !text.is_empty() {
+                        Some(AutoindentMode::Block {
+                            original_indent_columns: Vec::new(),
+                        })
+                    } else {
+                        None
+                    };
+                    buffer.edit([(range, text)], autoindent_mode, cx);
                 }
             });
+            if reindent {
+                let buffer = this.buffer.read(cx).snapshot(cx);
+                reindented_selections = anchor_reindented_selections(&new_selections, &buffer);
+            }
             this.fold_ranges(refold_ranges, true, cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
                 s.select(new_selections);
             })
         });
+        // The moved lines are only reindented once the transaction ends.
+        if reindent {
+            self.change_selections(None, cx, |s| s.select_anchors(reindented_selections));
+        }
     }
 
     pub fn move_line_down(&mut self, _: &MoveLineDown, cx: &mut ViewContext<Self>) {
+        let reindent = EditorSettings::get_global(cx).reindent_moved_lines;
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
 
@@ -6435,16 +6995,33 @@ impl Editor {
             new_selections.append(&mut contiguous_row_selections);
         }
 
+        let mut reindented_selections = Vec::new();
         self.transact(cx, |this, cx| {
             this.unfold_ranges(unfold_ranges, true, true, cx);
             this.buffer.update(cx, |buffer, cx| {
                 for (range, text) in edits {
-                    buffer.edit([(range, text)], None, cx);
+                    // Only the inserted lines are reindented, the deletions are left as is.
+                    let autoindent_mode = if reindent && !text.is_empty() {
+                        Some(AutoindentMode::Block {
+                            original_indent_columns: Vec::new(),
+                        })
+                    } else {
+                        None
+                    };
+                    buffer.edit([(range, text)], autoindent_mode, cx);
                 }
             });
+            if reindent {
+                let buffer = this.buffer.read(cx).snapshot(cx);
+                reindented_selections = anchor_reindented_selections(&new_selections, &buffer);
+            }
             this.fold_ranges(refold_ranges, true, cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
         });
+        // The moved lines are only reindented once the transaction ends.
+        if reindent {
+            self.change_selections(None, cx, |s| s.select_anchors(reindented_selections));
+        }
     }
 
     pub fn transpose(&mut self, _: &Transpose, cx: &mut ViewContext<Self>) {
@@ -6505,25 +7082,233 @@ impl Editor {
         });
     }
 
-    pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
-        let mut text = String::new();
+    /// Swaps the word under each cursor (or the next word, between words) with the word after
+    /// it, leaving the cursor after both of them.
+    pub fn transpose_words(&mut self, _: &TransposeWords, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
         let buffer = self.buffer.read(cx).snapshot(cx);
-        let mut selections = self.selections.all::<Point>(cx);
-        let mut clipboard_selections = Vec::with_capacity(selections.len());
-        {
-            let max_point = buffer.max_point();
-            let mut is_first = true;
-            for selection in &mut selections {
-                let is_entire_line = selection.is_empty() || self.selections.line_mode;
-                if is_entire_line {
-                    selection.start = Point::new(selection.start.row, 0);
-                    selection.end = cmp::min(max_point, Point::new(selection.end.row + 1, 0));
-                    selection.goal = SelectionGoal::None;
-                }
-                if is_first {
-                    is_first = false;
-                } else {
-                    text += "\n";
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut last_edit_end = 0;
+        for selection in self.selections.all::<usize>(cx) {
+            let head = selection.head();
+            let (word_range, kind) = buffer.surrounding_word(head);
+            let first_word = if kind == Some(CharKind::Word) {
+                Some(word_range)
+            } else {
+                next_word_range(&buffer, head)
+            };
+            let words = first_word.and_then(|first_word| {
+                let second_word = next_word_range(&buffer, first_word.end)?;
+                Some((first_word, second_word))
+            });
+
+            match words {
+                Some((first_word, second_word)) if first_word.start >= last_edit_end => {
+                    let first_text = buffer
+                        .text_for_range(first_word.clone())
+                        .collect::<String>();
+                    let second_text = buffer
+                        .text_for_range(second_word.clone())
+                        .collect::<String>();
+                    edits.push((first_word, second_text));
+                    edits.push((second_word.clone(), first_text));
+                    last_edit_end = second_word.end;
+                    // Swapping the words doesn't change the length of the text they span.
+                    new_selections.push(second_word.end..second_word.end);
+                }
+                _ => new_selections.push(head..head),
+            }
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_selections);
+            });
+        });
+    }
+
+    /// Wraps each selection in the given pair of strings, keeping the wrapped text selected.
+    pub fn surround_selections(&mut self, action: &SurroundSelections, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) || action.start.is_empty() {
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let end = action.end.clone().unwrap_or_else(|| {
+                buffer
+                    .language_scope_at(selection.start)
+                    .and_then(|scope| {
+                        scope
+                            .brackets()
+                            .find(|(pair, _)| pair.start == action.start)
+                            .map(|(pair, _)| pair.end.clone())
+                    })
+                    .unwrap_or_else(|| action.start.clone())
+            });
+            edits.push((selection.start..selection.start, action.start.clone()));
+            edits.push((selection.end..selection.end, end));
+            new_selections.push(Selection {
+                id: selection.id,
+                start: buffer.anchor_after(selection.start),
+                end: buffer.anchor_before(selection.end),
+                reversed: selection.reversed,
+                goal: selection.goal,
+            });
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchors(new_selections);
+            });
+        });
+    }
+
+    /// Deletes the innermost pair of brackets or quotes enclosing each selection.
+    pub fn delete_surrounding_pair(
+        &mut self,
+        _: &DeleteSurroundingPair,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut pairs = Vec::new();
+        for selection in self.selections.all::<usize>(cx) {
+            let range = selection.range();
+            // Only consider pairs that are strictly outside of the selection.
+            let is_outside_selection = |open: Range<usize>, close: Range<usize>| {
+                open.end <= range.start && close.start >= range.end
+            };
+            if let Some(pair) = buffer
+                .innermost_enclosing_bracket_ranges(range.clone(), Some(&is_outside_selection))
+            {
+                pairs.push(pair);
+            }
+        }
+        // Several selections can share the same enclosing pair.
+        pairs.sort_by_key(|(open, _)| open.start);
+        pairs.dedup();
+        if pairs.is_empty() {
+            return;
+        }
+
+        let edits = pairs
+            .into_iter()
+            .flat_map(|(open, close)| [(open, ""), (close, "")])
+            .collect::<Vec<_>>();
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        });
+    }
+
+    pub fn increment(&mut self, action: &Increment, cx: &mut ViewContext<Self>) {
+        let step = if action.step { 1 } else { 0 };
+        self.increment_numbers(1, step, cx);
+    }
+
+    pub fn decrement(&mut self, action: &Decrement, cx: &mut ViewContext<Self>) {
+        let step = if action.step { -1 } else { 0 };
+        self.increment_numbers(-1, step, cx);
+    }
+
+    /// Adds `delta` to the number at or after each selection, and `step` more for each
+    /// subsequent selection that has one.
+    fn increment_numbers(&mut self, mut delta: i64, step: i64, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut last_edit_end = Point::zero();
+        for selection in self.selections.all::<Point>(cx) {
+            let row = selection.start.row;
+            let line = buffer
+                .text_for_range(
+                    Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row))),
+                )
+                .collect::<String>();
+            let number =
+                number_at_or_after(&line, selection.start.column as usize).and_then(|range| {
+                    let new_text = incremented_number(&line[range.clone()], delta)?;
+                    Some((
+                        Point::new(row, range.start as u32)..Point::new(row, range.end as u32),
+                        new_text,
+                    ))
+                });
+
+            match number {
+                Some((range, new_text)) if range.start >= last_edit_end => {
+                    let end = buffer.anchor_after(range.end);
+                    let start = if selection.is_empty() {
+                        end
+                    } else {
+                        buffer.anchor_before(range.start)
+                    };
+                    new_selections.push(Selection {
+                        id: selection.id,
+                        start,
+                        end,
+                        reversed: false,
+                        goal: SelectionGoal::None,
+                    });
+                    last_edit_end = range.end;
+                    edits.push((range, new_text));
+                    delta += step;
+                }
+                _ => new_selections.push(selection.map(|point| buffer.anchor_before(point))),
+            }
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchors(new_selections);
+            });
+        });
+    }
+
+    pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
+        let mut text = String::new();
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut selections = self.selections.all::<Point>(cx);
+        let mut clipboard_selections = Vec::with_capacity(selections.len());
+        {
+            let max_point = buffer.max_point();
+            let mut is_first = true;
+            for selection in &mut selections {
+                let is_entire_line = selection.is_empty() || self.selections.line_mode;
+                if is_entire_line {
+                    selection.start = Point::new(selection.start.row, 0);
+                    selection.end = cmp::min(max_point, Point::new(selection.end.row + 1, 0));
+                    selection.goal = SelectionGoal::None;
+                }
+                if is_first {
+                    is_first = false;
+                } else {
+                    text += "\n";
                 }
                 let mut len = 0;
                 for chunk in buffer.text_for_range(selection.start..selection.end) {
@@ -6545,7 +7330,10 @@ impl Editor {
                 s.select(selections);
             });
             this.insert("", cx);
-            cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+            Self::write_to_clipboard(
+                ClipboardItem::new(text).with_metadata(clipboard_selections),
+                cx,
+            );
         });
     }
 
@@ -6584,7 +7372,17 @@ impl Editor {
             }
         }
 
-        cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+        Self::write_to_clipboard(
+            ClipboardItem::new(text).with_metadata(clipboard_selections),
+            cx,
+        );
+    }
+
+    /// Writes text copied from the editor to the clipboard, emitting [`EditorEvent::Copied`].
+    pub fn write_to_clipboard(item: ClipboardItem, cx: &mut ViewContext<Self>) {
+        let text = item.text().clone();
+        cx.write_to_clipboard(item);
+        cx.emit(EditorEvent::Copied { text });
     }
 
     pub fn do_paste(
@@ -6597,9 +7395,29 @@ impl Editor {
         if self.read_only(cx) {
             return;
         }
+        if !self.virtual_space.is_empty() {
+            self.transact(cx, |this, cx| {
+                this.fill_virtual_space(cx);
+                this.do_paste(text, clipboard_selections, handle_entire_lines, cx);
+            });
+            return;
+        }
 
         let clipboard_text = Cow::Borrowed(text);
 
+        // When the clipboard wasn't written with one slice per cursor, but it has one line per
+        // cursor, each cursor pastes one line.
+        let selection_count = self.selections.count();
+        let clipboard_selections = match clipboard_selections {
+            Some(clipboard_selections) if clipboard_selections.len() == selection_count => {
+                Some(clipboard_selections)
+            }
+            clipboard_selections => {
+                clipboard_selections_by_line(text, selection_count).or(clipboard_selections)
+            }
+        };
+
+        let overtype = self.overtype.is_some();
         self.transact(cx, |this, cx| {
             if let Some(mut clipboard_selections) = clipboard_selections {
                 let old_selections = this.selections.all::<usize>(cx);
@@ -6640,6 +7458,8 @@ impl Editor {
                             let column = selection.start.to_point(&snapshot).column as usize;
                             let line_start = selection.start - column;
                             line_start..line_start
+                        } else if selection.is_empty() && overtype {
+                            overtype_range(&snapshot, selection.start, to_insert)
                         } else {
                             selection.range()
                         };
@@ -6661,6 +7481,17 @@ impl Editor {
                 let selections = this.selections.all::<usize>(cx);
                 this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
             } else {
+                if overtype {
+                    let snapshot = this.buffer.read(cx).snapshot(cx);
+                    let mut selections = this.selections.all::<usize>(cx);
+                    for selection in &mut selections {
+                        if selection.is_empty() {
+                            selection.end =
+                                overtype_range(&snapshot, selection.start, &clipboard_text).end;
+                        }
+                    }
+                    this.change_selections(None, cx, |s| s.select(selections));
+                }
                 this.insert(&clipboard_text, cx);
             }
         });
@@ -6677,6 +7508,37 @@ impl Editor {
         };
     }
 
+    /// Pastes the clipboard text at each cursor, leaving a cursor at the end of every pasted line.
+    pub fn paste_as_cursors(&mut self, _: &PasteAsCursors, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let Some(item) = cx.read_from_clipboard() else {
+            return;
+        };
+
+        let text = item.text().as_str();
+        let text = text.strip_suffix('\n').unwrap_or(text);
+        let line_count = text.split('\n').count() as u32;
+        self.transact(cx, |this, cx| {
+            this.insert(text, cx);
+
+            let buffer = this.buffer.read(cx).snapshot(cx);
+            let mut new_selections = Vec::new();
+            for selection in this.selections.all::<Point>(cx) {
+                let head = selection.head();
+                for row in head.row + 1 - line_count..head.row {
+                    let line_end = Point::new(row, buffer.line_len(MultiBufferRow(row)));
+                    new_selections.push(line_end..line_end);
+                }
+                new_selections.push(head..head);
+            }
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges(new_selections)
+            });
+        });
+    }
+
     pub fn undo(&mut self, _: &Undo, cx: &mut ViewContext<Self>) {
         if self.read_only(cx) {
             return;
@@ -7685,6 +8547,169 @@ impl Editor {
         }
     }
 
+    pub fn add_cursor_above(&mut self, _: &AddCursorAbove, cx: &mut ViewContext<Self>) {
+        self.add_cursors(Direction::Prev, cx);
+    }
+
+    pub fn add_cursor_below(&mut self, _: &AddCursorBelow, cx: &mut ViewContext<Self>) {
+        self.add_cursors(Direction::Next, cx);
+    }
+
+    /// Adds a cursor on the display row above or below the head of every selection.
+    ///
+    /// The new cursors remember the column they were added at, so that adding more of
+    /// them after passing a short line returns to that column.
+    fn add_cursors(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let text_layout_details = self.text_layout_details(cx);
+        let selections = self.selections.all::<Point>(cx);
+        let max_row = display_map.max_point().row();
+
+        let mut new_cursors = Vec::new();
+        for selection in &selections {
+            let head = selection.head().to_display_point(&display_map);
+            let row = match direction {
+                Direction::Prev if head.row().0 > 0 => DisplayRow(head.row().0 - 1),
+                Direction::Next if head.row() < max_row => DisplayRow(head.row().0 + 1),
+                _ => continue,
+            };
+            let x = match selection.goal {
+                SelectionGoal::HorizontalPosition(x) => x.into(),
+                _ => display_map.x_for_display_point(head, &text_layout_details),
+            };
+            let column = display_map.display_column_for_x(row, x, &text_layout_details);
+            let position = DisplayPoint::new(row, column).to_point(&display_map);
+
+            // Don't stack a cursor onto a position that is already selected.
+            let is_selected = selections
+                .iter()
+                .any(|selection| selection.start <= position && position <= selection.end);
+            let is_added = new_cursors
+                .iter()
+                .any(|(added_position, _)| *added_position == position);
+            if !is_selected && !is_added {
+                new_cursors.push((position, x));
+            }
+        }
+        if new_cursors.is_empty() {
+            return;
+        }
+
+        let mut added_cursors_stack = mem::take(&mut self.added_cursors_stack);
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            let mut added_ids = Vec::with_capacity(new_cursors.len());
+            let mut new_selections = selections;
+            for (position, x) in new_cursors {
+                let id = s.new_selection_id();
+                added_ids.push(id);
+                new_selections.push(Selection {
+                    id,
+                    start: position,
+                    end: position,
+                    reversed: false,
+                    goal: SelectionGoal::HorizontalPosition(x.into()),
+                });
+            }
+            s.select(new_selections);
+            added_cursors_stack.push(added_ids);
+        });
+        self.added_cursors_stack = added_cursors_stack;
+    }
+
+    /// Removes the cursors that were added by the most recent `AddCursorAbove` or `AddCursorBelow`.
+    pub fn undo_add_cursor(&mut self, _: &UndoAddCursor, cx: &mut ViewContext<Self>) {
+        let mut added_cursors_stack = mem::take(&mut self.added_cursors_stack);
+        let Some(added_ids) = added_cursors_stack.pop() else {
+            return;
+        };
+
+        let mut selections = self.selections.all::<Point>(cx);
+        selections.retain(|selection| !added_ids.contains(&selection.id));
+        if !selections.is_empty() {
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(selections));
+        }
+        self.added_cursors_stack = added_cursors_stack;
+    }
+
+    pub fn select_column_up(&mut self, _: &SelectColumnUp, cx: &mut ViewContext<Self>) {
+        self.extend_columnar_selection(-1, 0, cx);
+    }
+
+    pub fn select_column_down(&mut self, _: &SelectColumnDown, cx: &mut ViewContext<Self>) {
+        self.extend_columnar_selection(1, 0, cx);
+    }
+
+    pub fn select_column_left(&mut self, _: &SelectColumnLeft, cx: &mut ViewContext<Self>) {
+        self.extend_columnar_selection(0, -1, cx);
+    }
+
+    pub fn select_column_right(&mut self, _: &SelectColumnRight, cx: &mut ViewContext<Self>) {
+        self.extend_columnar_selection(0, 1, cx);
+    }
+
+    /// Moves the head of the columnar selection, starting one from the newest selection if
+    /// there isn't one yet, and selects the block between the tail and the head with one
+    /// selection per display row.
+    fn extend_columnar_selection(
+        &mut self,
+        row_delta: i32,
+        column_delta: i32,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let mut state = self.keyboard_columnar_selection.take().unwrap_or_else(|| {
+            let newest_selection = self.selections.newest::<Point>(cx);
+            KeyboardColumnarSelection {
+                tail: display_map
+                    .buffer_snapshot
+                    .anchor_before(newest_selection.tail()),
+                head: newest_selection.head().to_display_point(&display_map),
+            }
+        });
+        let tail = state.tail.to_display_point(&display_map);
+
+        let max_row = display_map.max_point().row();
+        let head_row = DisplayRow(
+            state
+                .head
+                .row()
+                .0
+                .saturating_add_signed(row_delta)
+                .min(max_row.0),
+        );
+
+        // Step over whole characters (e.g. tabs) within a line, and over single columns past its end.
+        let line_len = display_map.line_len(head_row);
+        let mut head_column = state.head.column();
+        if column_delta < 0 && head_column > 0 {
+            head_column = if head_column > line_len {
+                head_column - 1
+            } else {
+                display_map
+                    .clip_point(DisplayPoint::new(head_row, head_column - 1), Bias::Left)
+                    .column()
+            };
+        } else if column_delta > 0 {
+            let (start_row, end_row) = (tail.row().min(head_row), tail.row().max(head_row));
+            let max_column = (start_row.0..=end_row.0)
+                .map(|row| display_map.line_len(DisplayRow(row)))
+                .max()
+                .unwrap_or(line_len);
+            if head_column < line_len {
+                head_column = display_map
+                    .clip_point(DisplayPoint::new(head_row, head_column + 1), Bias::Right)
+                    .column();
+            } else if head_column < max_column {
+                head_column += 1;
+            }
+        }
+
+        state.head = DisplayPoint::new(head_row, head_column);
+        self.select_columns(tail, state.head, head_column, &display_map, cx);
+        self.request_autoscroll(Autoscroll::newest(), cx);
+        self.keyboard_columnar_selection = Some(state);
+    }
+
     pub fn select_next_match_internal(
         &mut self,
         display_map: &DisplaySnapshot,
@@ -8857,6 +9882,53 @@ impl Editor {
         }
     }
 
+    fn go_to_document_highlight(&mut self, _: &GoToDocumentHighlight, cx: &mut ViewContext<Self>) {
+        self.go_to_document_highlight_impl(Direction::Next, cx)
+    }
+
+    fn go_to_prev_document_highlight(
+        &mut self,
+        _: &GoToPrevDocumentHighlight,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.go_to_document_highlight_impl(Direction::Prev, cx)
+    }
+
+    /// Moves the cursor to the start of the next or previous occurrence of the symbol under
+    /// the cursor, wrapping around at the ends of the buffer.
+    fn go_to_document_highlight_impl(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut ranges = [
+            TypeId::of::<DocumentHighlightRead>(),
+            TypeId::of::<DocumentHighlightWrite>(),
+        ]
+        .iter()
+        .filter_map(|type_id| self.background_highlights.get(type_id))
+        .flat_map(|(_, ranges)| ranges.iter())
+        .map(|range| range.to_offset(&buffer))
+        .collect::<Vec<_>>();
+        ranges.sort_unstable_by_key(|range| range.start);
+
+        let cursor = self.selections.newest::<usize>(cx).head();
+        let target = match direction {
+            Direction::Next => ranges
+                .iter()
+                .find(|range| range.start > cursor)
+                .or(ranges.first()),
+            Direction::Prev => ranges
+                .iter()
+                .rev()
+                .find(|range| range.end < cursor)
+                .or(ranges.last()),
+        };
+        if let Some(target) = target {
+            let position = target.start;
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_ranges([position..position])
+            });
+        }
+    }
+
     fn go_to_hunk(&mut self, _: &GoToHunk, cx: &mut ViewContext<Self>) {
         let snapshot = self
             .display_map
@@ -9073,6 +10145,7 @@ impl Editor {
                     cx.open_url(&url);
                     Task::ready(Ok(None))
                 }
+                HoverLink::File(path) => return self.navigate_to_file(path, split, cx),
             };
             cx.spawn(|editor, mut cx| async move {
                 let target = target_task.await.context("target resolution task")?;
@@ -9171,7 +10244,7 @@ impl Editor {
                                     )
                                 }),
                                 HoverLink::InlayHint(_, _) => None,
-                                HoverLink::Url(_) => None,
+                                HoverLink::Url(_) | HoverLink::File(_) => None,
                             })
                             .unwrap_or(tab_kind.to_string());
                         let location_tasks = definitions
@@ -9181,7 +10254,7 @@ impl Editor {
                                 HoverLink::InlayHint(lsp_location, server_id) => {
                                     editor.compute_target_location(lsp_location, server_id, cx)
                                 }
-                                HoverLink::Url(_) => Task::ready(Ok(None)),
+                                HoverLink::Url(_) | HoverLink::File(_) => Task::ready(Ok(None)),
                             })
                             .collect::<Vec<_>>();
                         (title, location_tasks, editor.workspace().clone())
@@ -9213,6 +10286,45 @@ impl Editor {
         }
     }
 
+    fn navigate_to_file(
+        &mut self,
+        path: PathWithPosition,
+        split: bool,
+        cx: &mut ViewContext<Editor>,
+    ) -> Task<Result<bool>> {
+        let Some(workspace) = self.workspace() else {
+            return Task::ready(Ok(false));
+        };
+
+        cx.spawn(|_, mut cx| async move {
+            let item = workspace
+                .update(&mut cx, |workspace, cx| {
+                    if split {
+                        workspace.split_abs_path(path.path.clone(), false, cx)
+                    } else {
+                        workspace.open_abs_path(path.path.clone(), false, cx)
+                    }
+                })?
+                .await?;
+            if let Some((row, editor)) = path.row.zip(item.downcast::<Editor>()) {
+                editor.update(&mut cx, |editor, cx| {
+                    let buffer = editor.buffer.read(cx).snapshot(cx);
+                    let point = buffer.clip_point(
+                        Point::new(
+                            row.saturating_sub(1),
+                            path.column.unwrap_or(0).saturating_sub(1),
+                        ),
+                        Bias::Left,
+                    );
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([point..point])
+                    });
+                })?;
+            }
+            Ok(true)
+        })
+    }
+
     fn compute_target_location(
         &self,
         lsp_location: lsp::Location,
@@ -9412,7 +10524,8 @@ impl Editor {
         let item_id = item.item_id();
 
         if split {
-            workspace.split_item(SplitDirection::Right, item.clone(), cx);
+            let pane = workspace.adjacent_pane(cx);
+            workspace.add_item(pane, item.clone(), None, true, true, cx);
         } else {
             let destination_index = workspace.active_pane().update(cx, |pane, cx| {
                 if PreviewTabsSettings::get_global(cx).enable_preview_from_code_navigation {
@@ -9645,17 +10758,30 @@ impl Editor {
                 project.perform_rename(buffer.clone(), range.start, new_name.clone(), true, cx)
             });
         let workspace = workspace.downgrade();
+        let preview = EditorSettings::get_global(cx).preview_renames;
 
         Some(cx.spawn(|editor, mut cx| async move {
             let project_transaction = rename.await?;
-            Self::open_project_transaction(
-                &editor,
-                workspace,
-                project_transaction,
-                format!("Rename: {} → {}", old_name, new_name),
-                cx.clone(),
-            )
-            .await?;
+            if preview {
+                Self::open_rename_preview(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    old_name.to_string(),
+                    new_name,
+                    cx.clone(),
+                )
+                .await?;
+            } else {
+                Self::open_project_transaction(
+                    &editor,
+                    workspace,
+                    project_transaction,
+                    format!("Rename: {} → {}", old_name, new_name),
+                    cx.clone(),
+                )
+                .await?;
+            }
 
             editor.update(&mut cx, |editor, cx| {
                 editor.refresh_document_highlights(cx);
@@ -9758,6 +10884,58 @@ impl Editor {
         })
     }
 
+    fn organize_imports(
+        &mut self,
+        _: &OrganizeImports,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        let project = self.project.clone()?;
+        let buffer = self.buffer().clone();
+        let buffers = buffer.read(cx).all_buffers();
+        let organize_imports = project.update(cx, |project, cx| {
+            project.apply_code_actions_of_kind(
+                buffers,
+                lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                true,
+                cx,
+            )
+        });
+
+        Some(cx.spawn(|_, mut cx| async move {
+            let transaction = organize_imports.await?;
+            buffer.update(&mut cx, |buffer, cx| {
+                if !buffer.is_singleton() {
+                    buffer.push_transaction(&transaction.0, cx);
+                }
+                cx.notify();
+            })?;
+            Ok(())
+        }))
+    }
+
+    fn organize_imports_in_dirty_buffers(
+        workspace: &mut Workspace,
+        _: &OrganizeImportsInDirtyBuffers,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        workspace
+            .project()
+            .update(cx, |project, cx| {
+                let buffers = project
+                    .opened_buffers(cx)
+                    .into_iter()
+                    .filter(|buffer| buffer.read(cx).is_dirty())
+                    .collect();
+                project.apply_code_actions_of_kind(
+                    buffers,
+                    lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                    true,
+                    cx,
+                )
+            })
+            .detach_and_log_err(cx);
+    }
+
     fn restart_language_server(&mut self, _: &RestartLanguageServer, cx: &mut ViewContext<Self>) {
         if let Some(project) = self.project.clone() {
             self.buffer.update(cx, |multi_buffer, cx| {
@@ -9768,6 +10946,16 @@ impl Editor {
         }
     }
 
+    fn stop_language_server(&mut self, _: &StopLanguageServer, cx: &mut ViewContext<Self>) {
+        if let Some(project) = self.project.clone() {
+            self.buffer.update(cx, |multi_buffer, cx| {
+                project.update(cx, |project, cx| {
+                    project.stop_language_servers_for_buffers(multi_buffer.all_buffers(), cx);
+                });
+            })
+        }
+    }
+
     fn cancel_language_server_work(
         &mut self,
         _: &CancelLanguageServerWork,
@@ -10041,6 +11229,52 @@ impl Editor {
         self.unfold_ranges(std::iter::once(intersection_range), true, autoscroll, cx)
     }
 
+    pub fn toggle_fold(&mut self, _: &actions::ToggleFold, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let selections = self.selections.all::<Point>(cx);
+        let any_folded = selections.iter().any(|selection| {
+            let range = selection.range().sorted();
+            (range.start.row..=range.end.row)
+                .any(|row| display_map.is_line_folded(MultiBufferRow(row)))
+        });
+
+        if any_folded {
+            self.unfold_lines(&UnfoldLines, cx);
+        } else {
+            self.fold(&actions::Fold, cx);
+        }
+    }
+
+    pub fn fold_at_level(&mut self, fold_at: &actions::FoldAtLevel, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+
+        let mut fold_ranges = Vec::new();
+        // The end rows of the foldable ranges enclosing the current one, innermost last.
+        let mut enclosing_end_rows = Vec::<u32>::new();
+        for (range, placeholder) in display_map.foldable_ranges() {
+            let row = range.start.row;
+            while enclosing_end_rows
+                .last()
+                .is_some_and(|end_row| *end_row < row)
+            {
+                enclosing_end_rows.pop();
+            }
+
+            let end_row = range.end.row;
+            if enclosing_end_rows.len() + 1 == fold_at.level as usize {
+                fold_ranges.push((range, placeholder));
+            }
+            enclosing_end_rows.push(end_row);
+        }
+
+        self.fold_ranges(fold_ranges, true, cx);
+    }
+
+    pub fn unfold_all(&mut self, _: &actions::UnfoldAll, cx: &mut ViewContext<Self>) {
+        let len = self.buffer.read(cx).len(cx);
+        self.unfold_ranges([0..len], true, true, cx);
+    }
+
     pub fn fold_selected_ranges(&mut self, _: &FoldSelectedRanges, cx: &mut ViewContext<Self>) {
         let selections = self.selections.all::<Point>(cx);
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
@@ -10148,10 +11382,46 @@ impl Editor {
     pub fn set_gutter_hovered(&mut self, hovered: bool, cx: &mut ViewContext<Self>) {
         if hovered != self.gutter_hovered {
             self.gutter_hovered = hovered;
+            if !hovered {
+                if let Some(buffer_row) = self.hovered_fold_toggle {
+                    self.set_fold_toggle_hovered(buffer_row, false, cx);
+                }
+            }
             cx.notify();
         }
     }
 
+    /// Highlights the rows that clicking the fold toggle at the given row will fold.
+    fn set_fold_toggle_hovered(
+        &mut self,
+        buffer_row: MultiBufferRow,
+        hovered: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !hovered && self.hovered_fold_toggle != Some(buffer_row) {
+            return;
+        }
+
+        self.clear_row_highlights::<FoldToggleHighlight>();
+        self.hovered_fold_toggle = None;
+        if hovered {
+            let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+            if let Some((range, _)) = display_map.foldable_range(buffer_row) {
+                let buffer = &display_map.buffer_snapshot;
+                let start = buffer.anchor_before(Point::new(range.start.row, 0));
+                let end = buffer.anchor_after(range.end);
+                self.highlight_rows::<FoldToggleHighlight>(
+                    start..=end,
+                    Some(cx.theme().colors().editor_highlighted_line_background),
+                    false,
+                    cx,
+                );
+                self.hovered_fold_toggle = Some(buffer_row);
+            }
+        }
+        cx.notify();
+    }
+
     pub fn insert_blocks(
         &mut self,
         blocks: impl IntoIterator<Item = BlockProperties<Anchor>>,
@@ -10394,12 +11664,36 @@ impl Editor {
         self.show_indent_guides
     }
 
+    /// Whether typed and pasted text replaces the text after the cursor instead of being inserted.
+    pub fn overtype(&self) -> bool {
+        self.overtype.is_some()
+    }
+
+    pub fn toggle_overtype(&mut self, _: &ToggleOvertype, cx: &mut ViewContext<Self>) {
+        if let Some(cursor_shape) = self.overtype.take() {
+            self.set_cursor_shape(cursor_shape, cx);
+        } else {
+            self.overtype = Some(self.cursor_shape);
+            self.set_cursor_shape(CursorShape::Block, cx);
+        }
+    }
+
     pub fn toggle_line_numbers(&mut self, _: &ToggleLineNumbers, cx: &mut ViewContext<Self>) {
         let mut editor_settings = EditorSettings::get_global(cx).clone();
         editor_settings.gutter.line_numbers = !editor_settings.gutter.line_numbers;
         EditorSettings::override_global(editor_settings, cx);
     }
 
+    pub fn toggle_typewriter_scrolling(
+        &mut self,
+        _: &ToggleTypewriterScrolling,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let mut editor_settings = EditorSettings::get_global(cx).clone();
+        editor_settings.typewriter_scrolling = !editor_settings.typewriter_scrolling;
+        EditorSettings::override_global(editor_settings, cx);
+    }
+
     pub fn set_show_gutter(&mut self, show_gutter: bool, cx: &mut ViewContext<Self>) {
         self.show_gutter = show_gutter;
         cx.notify();
@@ -11239,6 +12533,7 @@ impl Editor {
                     }
                 }
 
+                refresh_viewport(self, ViewportRefreshReason::ViewportChanged, cx);
                 let Some(project) = &self.project else { return };
                 let telemetry = project.read(cx).client().telemetry().clone();
                 refresh_linked_ranges(self, cx);
@@ -11250,12 +12545,15 @@ impl Editor {
                 excerpts,
             } => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                self.restore_pending_scroll_position(cx);
+                refresh_viewport(self, ViewportRefreshReason::ViewportChanged, cx);
                 cx.emit(EditorEvent::ExcerptsAdded {
                     buffer: buffer.clone(),
                     predecessor: *predecessor,
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_bracket_colors(self, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
@@ -11269,11 +12567,13 @@ impl Editor {
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                refresh_bracket_colors(self, cx);
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                refresh_viewport(self, ViewportRefreshReason::LanguageServersChanged, cx);
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -11289,6 +12589,7 @@ impl Editor {
             }
             multi_buffer::Event::DiffUpdated { buffer } => {
                 self.sync_expanded_diff_hunks(buffer.clone(), cx);
+                self.scrollbar_marker_state.dirty = true;
                 cx.notify();
             }
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
@@ -11302,6 +12603,8 @@ impl Editor {
     }
 
     fn on_display_map_changed(&mut self, _: Model<DisplayMap>, cx: &mut ViewContext<Self>) {
+        // Soft wraps shift the display rows that scrollbar markers are mapped to.
+        self.scrollbar_marker_state.dirty = true;
         cx.notify();
     }
 
@@ -11318,7 +12621,12 @@ impl Editor {
         );
         let editor_settings = EditorSettings::get_global(cx);
         self.scroll_manager.vertical_scroll_margin = editor_settings.vertical_scroll_margin;
+        self.scroll_manager.horizontal_scroll_margin = editor_settings.horizontal_scroll_margin;
         self.show_breadcrumbs = editor_settings.toolbar.breadcrumbs;
+        self.scrollbar_marker_state.dirty = true;
+        self.bracket_colors.invalidate();
+        refresh_bracket_colors(self, cx);
+        refresh_viewport(self, ViewportRefreshReason::SettingsChanged, cx);
 
         let project_settings = ProjectSettings::get_global(cx);
         self.serialize_dirty_buffers = project_settings.session.restore_unsaved_buffers;
@@ -11661,15 +12969,21 @@ impl Editor {
         let mut supports = false;
         self.buffer().read(cx).for_each_buffer(|buffer| {
             if !supports {
-                supports = project
-                    .language_servers_for_buffer(buffer.read(cx), cx)
-                    .any(
-                        |(_, server)| match server.capabilities().inlay_hint_provider {
-                            Some(lsp::OneOf::Left(enabled)) => enabled,
-                            Some(lsp::OneOf::Right(_)) => true,
-                            None => false,
-                        },
-                    )
+                let buffer = buffer.read(cx);
+                let syntax_parameter_hints =
+                    language_settings::language_settings(buffer.language(), buffer.file(), cx)
+                        .inlay_hints
+                        .syntax_parameter_hints;
+                supports = syntax_parameter_hints
+                    || project
+                        .language_servers_for_buffer(buffer, cx)
+                        .any(
+                            |(_, server)| match server.capabilities().inlay_hint_provider {
+                                Some(lsp::OneOf::Left(enabled)) => enabled,
+                                Some(lsp::OneOf::Right(_)) => true,
+                                None => false,
+                            },
+                        )
             }
         });
         supports
@@ -11710,6 +13024,7 @@ impl Editor {
                     );
                 }
             });
+            self.broadcast_viewport(cx);
         }
     }
 
@@ -11725,8 +13040,10 @@ impl Editor {
 
     pub fn handle_blur(&mut self, cx: &mut ViewContext<Self>) {
         self.blink_manager.update(cx, BlinkManager::disable);
-        self.buffer
-            .update(cx, |buffer, cx| buffer.remove_active_selections(cx));
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.remove_active_selections(cx);
+            buffer.set_active_viewport(None, cx);
+        });
 
         if let Some(blame) = self.blame.as_ref() {
             blame.update(cx, GitBlame::blur)
@@ -12138,6 +13455,280 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Returns the byte range of the first integer, hexadecimal or decimal number in the line that
+/// ends at or after the given column.
+fn number_at_or_after(line: &str, column: usize) -> Option<Range<usize>> {
+    let bytes = line.as_bytes();
+    let digits_from = |ix: usize, radix: u32| {
+        ix + bytes[ix..]
+            .iter()
+            .take_while(|byte| char::from(**byte).is_digit(radix))
+            .count()
+    };
+
+    let mut ix = 0;
+    while ix < bytes.len() {
+        if !bytes[ix].is_ascii_digit() {
+            ix += 1;
+            continue;
+        }
+
+        let mut start = ix;
+        let is_hex = bytes[ix] == b'0'
+            && matches!(bytes.get(ix + 1), Some(b'x' | b'X'))
+            && bytes.get(ix + 2).map_or(false, u8::is_ascii_hexdigit);
+        let end = if is_hex {
+            digits_from(ix + 2, 16)
+        } else {
+            let mut end = digits_from(ix, 10);
+            if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).map_or(false, u8::is_ascii_digit)
+            {
+                end = digits_from(end + 1, 10);
+            }
+            // Include a minus sign, unless it follows a word as in `a-1`.
+            let is_word_byte = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+            if start > 0
+                && bytes[start - 1] == b'-'
+                && (start == 1 || !is_word_byte(bytes[start - 2]))
+            {
+                start -= 1;
+            }
+            end
+        };
+
+        if end >= column {
+            return Some(start..end);
+        }
+        ix = end;
+    }
+    None
+}
+
+/// Adds `delta` to the given number, keeping its format: the case and width of hexadecimal
+/// numbers and the number of decimal places of decimal ones.
+fn incremented_number(number: &str, delta: i64) -> Option<String> {
+    if let Some(digits) = number
+        .strip_prefix("0x")
+        .or_else(|| number.strip_prefix("0X"))
+    {
+        let value = i128::from_str_radix(digits, 16)
+            .ok()?
+            .checked_add(delta.into())?;
+        if value < 0 {
+            return None;
+        }
+        let width = digits.len();
+        let new_digits = if digits.chars().any(|c| c.is_ascii_uppercase()) {
+            format!("{value:0width$X}")
+        } else {
+            format!("{value:0width$x}")
+        };
+        return Some(format!("{}{new_digits}", &number[..2]));
+    }
+
+    // Work with decimal numbers as integers scaled by their decimal places, to avoid rounding.
+    let decimals = number
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let scale = 10i128.checked_pow(decimals as u32)?;
+    let value = number
+        .replace('.', "")
+        .parse::<i128>()
+        .ok()?
+        .checked_add(i128::from(delta).checked_mul(scale)?)?;
+    if decimals == 0 {
+        return Some(value.to_string());
+    }
+    let sign = if value < 0 { "-" } else { "" };
+    let value = value.unsigned_abs();
+    let scale = scale as u128;
+    Some(format!(
+        "{sign}{}.{:0decimals$}",
+        value / scale,
+        value % scale
+    ))
+}
+
+struct ContinuedBlockComment {
+    /// The text to insert at the start of the new line, after its indentation.
+    prefix: String,
+    /// If the end of the comment follows the cursor, the padding to insert before it once it's
+    /// moved to its own line.
+    end_padding: Option<String>,
+}
+
+/// Returns how to continue the block comment containing the given position on a new line, if
+/// the position follows the comment's start or the prefix of one of its continued lines.
+fn continued_block_comment(
+    buffer: &MultiBufferSnapshot,
+    continuation: &BlockCommentContinuation,
+    position: Point,
+) -> Option<ContinuedBlockComment> {
+    let line_text = |row: u32| {
+        buffer
+            .text_for_range(
+                Point::new(row, 0)..Point::new(row, buffer.line_len(MultiBufferRow(row))),
+            )
+            .collect::<String>()
+    };
+    let line = line_text(position.row);
+    let column = (position.column as usize).min(line.len());
+    let indent_len = line.len() - line.trim_start().len();
+    let (before_cursor, after_cursor) = line.split_at(column);
+    let trimmed_line = line.trim_start();
+    let prefix_marker = continuation.prefix.trim_end();
+
+    let padding = if trimmed_line.starts_with(continuation.start.as_ref()) {
+        let comment_start = indent_len + continuation.start.len();
+        if column < comment_start
+            || before_cursor[comment_start..].contains(continuation.end.as_ref())
+        {
+            return None;
+        }
+        " ".repeat(continuation.indent as usize)
+    } else if !prefix_marker.is_empty()
+        && trimmed_line.starts_with(prefix_marker)
+        && !trimmed_line.starts_with(continuation.end.as_ref())
+    {
+        if column < indent_len + prefix_marker.len()
+            || before_cursor.contains(continuation.end.as_ref())
+        {
+            return None;
+        }
+        // Only lines following the comment's start are continued, so that e.g. a line starting
+        // with a `*` operator isn't mistaken for a comment.
+        let mut rows = (0..position.row).rev();
+        loop {
+            let line = line_text(rows.next()?);
+            let trimmed_line = line.trim_start();
+            if line.contains(continuation.end.as_ref()) {
+                return None;
+            } else if trimmed_line.starts_with(continuation.start.as_ref()) {
+                break;
+            } else if !trimmed_line.starts_with(prefix_marker) {
+                return None;
+            }
+        }
+        String::new()
+    } else {
+        return None;
+    };
+
+    let end_padding = (after_cursor.trim() == continuation.end.trim()).then(|| padding.clone());
+    Some(ContinuedBlockComment {
+        prefix: format!("{padding}{}", continuation.prefix),
+        end_padding,
+    })
+}
+
+/// Returns the range of the first word that starts at or after the given offset.
+fn next_word_range(buffer: &MultiBufferSnapshot, offset: usize) -> Option<Range<usize>> {
+    let scope = buffer.language_scope_at(offset);
+    let mut start = offset;
+    let mut chars = buffer.chars_at(offset).peekable();
+    while let Some(ch) = chars.next_if(|ch| char_kind(&scope, *ch) != CharKind::Word) {
+        start += ch.len_utf8();
+    }
+
+    let mut end = start;
+    while let Some(ch) = chars.next_if(|ch| char_kind(&scope, *ch) == CharKind::Word) {
+        end += ch.len_utf8();
+    }
+    (start < end).then_some(start..end)
+}
+
+/// The range that typing or pasting the given text at the given position replaces in overtype
+/// mode: as many characters as are in the text's first line, up to the end of the line.
+fn overtype_range(buffer: &MultiBufferSnapshot, position: usize, text: &str) -> Range<usize> {
+    let len = buffer
+        .chars_at(position)
+        .take_while(|c| *c != '\n')
+        .zip(text.chars().take_while(|c| *c != '\n'))
+        .map(|(c, _)| c.len_utf8())
+        .sum::<usize>();
+    position..position + len
+}
+
+/// Splits the clipboard text into one slice per line, when it has exactly as many lines as
+/// there are cursors.
+fn clipboard_selections_by_line(
+    text: &str,
+    selection_count: usize,
+) -> Option<Vec<ClipboardSelection>> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    if selection_count < 2 || text.split('\n').count() != selection_count {
+        return None;
+    }
+
+    Some(
+        text.split('\n')
+            .map(|line| ClipboardSelection {
+                len: line.len(),
+                is_entire_line: false,
+                first_line_indent: (line.len() - line.trim_start().len()) as u32,
+            })
+            .collect(),
+    )
+}
+
+const CASE_CYCLE: [Case; 6] = [
+    Case::Snake,
+    Case::UpperSnake,
+    Case::Kebab,
+    Case::Camel,
+    Case::Pascal,
+    Case::Title,
+];
+
+/// Returns the case after the one the text is written in, or snake case when the text
+/// doesn't match any of the cases in the cycle.
+fn next_case(text: &str) -> Case {
+    CASE_CYCLE
+        .iter()
+        .position(|case| text.is_case(*case))
+        .map_or(Case::Snake, |ix| CASE_CYCLE[(ix + 1) % CASE_CYCLE.len()])
+}
+
+/// Parses the number at the start of a line, ignoring leading whitespace.
+fn leading_number(line: &str) -> Option<f64> {
+    let line = line.trim_start();
+    let mut end = 0;
+    for (ix, ch) in line.char_indices() {
+        let is_sign = ix == 0 && (ch == '-' || ch == '+');
+        if !(is_sign || ch.is_ascii_digit() || ch == '.') {
+            break;
+        }
+        end = ix + ch.len_utf8();
+    }
+    // Trailing dots (as in "1. item") aren't part of the number.
+    line[..end].trim_end_matches('.').parse().ok()
+}
+
+/// Anchors the selections so that they stay on the same characters when the lines they are on
+/// get reindented, except for the ones at the start of a line, which stay there.
+fn anchor_reindented_selections(
+    selections: &[Selection<Point>],
+    buffer: &MultiBufferSnapshot,
+) -> Vec<Selection<Anchor>> {
+    let anchor = |point: Point| {
+        if point.column == 0 {
+            buffer.anchor_before(point)
+        } else {
+            buffer.anchor_after(point)
+        }
+    };
+    selections
+        .iter()
+        .map(|selection| Selection {
+            id: selection.id,
+            start: anchor(selection.start),
+            end: anchor(selection.end),
+            reversed: selection.reversed,
+            goal: selection.goal,
+        })
+        .collect()
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -12199,6 +13790,30 @@ impl EditorSnapshot {
             })
     }
 
+    pub fn remote_viewports<'a>(
+        &'a self,
+        collaboration_hub: &dyn CollaborationHub,
+        cx: &'a AppContext,
+    ) -> impl 'a + Iterator<Item = RemoteViewport> {
+        let participant_indices = collaboration_hub.user_participant_indices(cx);
+        let collaborators_by_replica_id = collaboration_hub
+            .collaborators(cx)
+            .iter()
+            .map(|(_, collaborator)| (collaborator.replica_id, collaborator))
+            .collect::<HashMap<_, _>>();
+        self.buffer_snapshot
+            .remote_viewports()
+            .filter_map(move |(replica_id, range)| {
+                let collaborator = collaborators_by_replica_id.get(&replica_id)?;
+                Some(RemoteViewport {
+                    replica_id,
+                    range,
+                    peer_id: collaborator.peer_id,
+                    participant_index: participant_indices.get(&collaborator.user_id).copied(),
+                })
+            })
+    }
+
     pub fn language_at<T: ToOffset>(&self, position: T) -> Option<&Arc<Language>> {
         self.display_snapshot.buffer_snapshot.language_at(position)
     }
@@ -12211,8 +13826,10 @@ impl EditorSnapshot {
         self.placeholder_text.as_ref()
     }
 
+    /// The scroll position the editor is rendered at, which includes any elastic
+    /// overscroll past the start or end of the buffer.
     pub fn scroll_position(&self) -> gpui::Point<f32> {
-        self.scroll_anchor.scroll_position(&self.display_snapshot)
+        self.scroll_anchor.scroll_position(&self.display_snapshot) + point(0., self.overscroll)
     }
 
     fn gutter_dimensions(
@@ -12318,18 +13935,26 @@ impl EditorSnapshot {
                 cx,
             ))
         } else if folded
-            || (self.starts_indent(buffer_row) && (row_contains_cursor || self.gutter_hovered))
+            || (self.is_foldable(buffer_row) && (row_contains_cursor || self.gutter_hovered))
         {
             Some(
-                Disclosure::new(("indent-fold-indicator", buffer_row.0), !folded)
-                    .selected(folded)
-                    .on_click(cx.listener_for(&editor, move |this, _e, cx| {
-                        if folded {
-                            this.unfold_at(&UnfoldAt { buffer_row }, cx);
-                        } else {
-                            this.fold_at(&FoldAt { buffer_row }, cx);
-                        }
+                div()
+                    .id(("indent-fold-indicator-hover", buffer_row.0))
+                    .on_hover(cx.listener_for(&editor, move |this, hovered, cx| {
+                        this.set_fold_toggle_hovered(buffer_row, *hovered && !folded, cx);
                     }))
+                    .child(
+                        Disclosure::new(("indent-fold-indicator", buffer_row.0), !folded)
+                            .selected(folded)
+                            .on_click(cx.listener_for(&editor, move |this, _e, cx| {
+                                this.set_fold_toggle_hovered(buffer_row, false, cx);
+                                if folded {
+                                    this.unfold_at(&UnfoldAt { buffer_row }, cx);
+                                } else {
+                                    this.fold_at(&FoldAt { buffer_row }, cx);
+                                }
+                            })),
+                    )
                     .into_any_element(),
             )
         } else {
@@ -12400,6 +14025,7 @@ pub enum EditorEvent {
         local: bool,
         autoscroll: bool,
     },
+    TextScaleChanged,
     Closed,
     TransactionUndone {
         transaction_id: clock::Lamport,
@@ -12407,6 +14033,9 @@ pub enum EditorEvent {
     TransactionBegun {
         transaction_id: clock::Lamport,
     },
+    Copied {
+        text: String,
+    },
 }
 
 impl EventEmitter<EditorEvent> for Editor {}
@@ -12437,7 +14066,10 @@ impl Render for Editor {
                 font_family: settings.buffer_font.family.clone(),
                 font_features: settings.buffer_font.features.clone(),
                 font_fallbacks: settings.buffer_font.fallbacks.clone(),
-                font_size: settings.buffer_font_size(cx).into(),
+                font_size: match self.leader_text_scale {
+                    Some(scale) => (settings.buffer_font_size * scale).into(),
+                    None => settings.buffer_font_size(cx).into(),
+                },
                 font_weight: settings.buffer_font.weight,
                 line_height: relative(settings.buffer_line_height.value()),
                 ..Default::default()
@@ -1,4 +1,5 @@
 use gpui::AppContext;
+use lsp::DiagnosticSeverity;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsSources};
@@ -7,6 +8,10 @@ use settings::{Settings, SettingsSources};
 pub struct EditorSettings {
     pub cursor_blink: bool,
     pub current_line_highlight: CurrentLineHighlight,
+    pub colorize_brackets: bool,
+    pub color_swatches: bool,
+    pub semantic_tokens: bool,
+    pub selection_highlight: bool,
     pub hover_popover_enabled: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
@@ -14,11 +19,24 @@ pub struct EditorSettings {
     pub use_on_type_format: bool,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
+    pub inline_diagnostics: InlineDiagnostics,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
+    pub horizontal_scroll_margin: f32,
+    pub typewriter_scrolling: bool,
     pub scroll_sensitivity: f32,
-    pub relative_line_numbers: bool,
+    pub mouse_wheel_scroll_sensitivity: f32,
+    pub trackpad_scroll_sensitivity: f32,
+    pub fast_scroll_modifier: FastScrollModifier,
+    pub fast_scroll_sensitivity: f32,
+    pub scroll_axis_lock: ScrollAxisLock,
+    pub elastic_overscroll: bool,
+    pub middle_click_autoscroll: bool,
+    pub drag_autoscroll: DragAutoscroll,
+    pub relative_line_numbers: RelativeLineNumbers,
     pub seed_search_query_from_cursor: SeedQuerySetting,
     pub multi_cursor_modifier: MultiCursorModifier,
     pub redact_private_values: bool,
@@ -28,6 +46,10 @@ pub struct EditorSettings {
     pub search_wrap: bool,
     pub auto_signature_help: bool,
     pub show_signature_help_after_edits: bool,
+    pub reindent_moved_lines: bool,
+    pub hungry_backspace: bool,
+    pub virtual_space: bool,
+    pub preview_renames: bool,
     pub jupyter: Jupyter,
 }
 
@@ -44,6 +66,36 @@ pub enum CurrentLineHighlight {
     All,
 }
 
+/// Whether the line numbers on editors gutter are relative to the cursor.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(untagged)]
+pub enum RelativeLineNumbers {
+    /// `true` is the same as `"hybrid"` and `false` is the same as `"disabled"`.
+    Enabled(bool),
+    Mode(RelativeLineNumbersMode),
+}
+
+impl RelativeLineNumbers {
+    pub fn mode(&self) -> RelativeLineNumbersMode {
+        match self {
+            Self::Enabled(true) => RelativeLineNumbersMode::Hybrid,
+            Self::Enabled(false) => RelativeLineNumbersMode::Disabled,
+            Self::Mode(mode) => *mode,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RelativeLineNumbersMode {
+    /// Number each line by its row in the buffer.
+    Disabled,
+    /// Number each line by its distance from the cursor line, which is numbered 0.
+    Relative,
+    /// Number each line by its distance from the cursor line, which keeps its row number.
+    Hybrid,
+}
+
 /// When to populate a new search's query based on the text under the cursor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -100,6 +152,65 @@ pub struct Scrollbar {
     pub search_results: bool,
     pub diagnostics: bool,
     pub cursors: bool,
+    pub horizontal: bool,
+    pub preview: bool,
+    pub collaborators: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_lines: usize,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnostics {
+    pub enabled: bool,
+    pub min_severity: DiagnosticSeverityThreshold,
+}
+
+/// The least severe diagnostics to show.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverityThreshold {
+    /// Only show errors.
+    Error,
+    /// Show errors and warnings.
+    Warning,
+    /// Show errors, warnings and information.
+    Info,
+    /// Show all diagnostics.
+    Hint,
+}
+
+impl DiagnosticSeverityThreshold {
+    pub fn includes(&self, severity: DiagnosticSeverity) -> bool {
+        let min_severity = match self {
+            Self::Error => DiagnosticSeverity::ERROR,
+            Self::Warning => DiagnosticSeverity::WARNING,
+            Self::Info => DiagnosticSeverity::INFORMATION,
+            Self::Hint => DiagnosticSeverity::HINT,
+        };
+        severity <= min_severity
+    }
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ScrollAxisLock {
+    pub enabled: bool,
+    pub unlock_ratio: f32,
+    pub unlock_threshold: f32,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct DragAutoscroll {
+    pub speed: f32,
+    pub ramp: DragAutoscrollRamp,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
@@ -127,6 +238,20 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Show the minimap if the buffer doesn't fit in the viewport.
+    Auto,
+    /// Always show the minimap.
+    Always,
+    /// Never show the minimap.
+    Never,
+}
+
 /// The key to use for adding multiple cursors
 ///
 /// Default: alt
@@ -138,6 +263,44 @@ pub enum MultiCursorModifier {
     CmdOrCtrl,
 }
 
+/// The key to hold for scrolling faster
+///
+/// Default: alt
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FastScrollModifier {
+    Alt,
+    Shift,
+    #[serde(alias = "cmd", alias = "ctrl")]
+    CmdOrCtrl,
+}
+
+/// How the autoscroll speed grows while drag-selecting past the edge of the editor.
+///
+/// Default: quadratic
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DragAutoscrollRamp {
+    /// The speed grows in proportion to the distance past the edge.
+    Linear,
+    /// The speed grows with the square of the distance past the edge.
+    Quadratic,
+    /// The speed grows with the cube of the distance past the edge.
+    Cubic,
+}
+
+impl DragAutoscrollRamp {
+    /// Scales the autoscroll speed for a pointer `lines_past_edge` lines past the edge.
+    pub fn apply(&self, lines_past_edge: f32) -> f32 {
+        let distance = 1. + lines_past_edge.max(0.);
+        match self {
+            DragAutoscrollRamp::Linear => distance,
+            DragAutoscrollRamp::Quadratic => distance.powi(2),
+            DragAutoscrollRamp::Cubic => distance.powi(3),
+        }
+    }
+}
+
 /// Whether the editor will scroll beyond the last line.
 ///
 /// Default: one_page
@@ -164,6 +327,25 @@ pub struct EditorSettingsContent {
     ///
     /// Default: all
     pub current_line_highlight: Option<CurrentLineHighlight>,
+    /// Whether to color brackets by how deeply they are nested.
+    /// The bracket pair around the cursor is highlighted either way.
+    ///
+    /// Default: true
+    pub colorize_brackets: Option<bool>,
+    /// Whether to show a swatch in front of colors, which opens a color picker
+    /// when clicked.
+    ///
+    /// Default: true
+    pub color_swatches: Option<bool>,
+    /// Whether to highlight the semantic tokens reported by language servers
+    /// over the syntax highlighting.
+    ///
+    /// Default: true
+    pub semantic_tokens: Option<bool>,
+    /// Whether to highlight the other occurrences of the selected text.
+    ///
+    /// Default: true
+    pub selection_highlight: Option<bool>,
     /// Whether to show the informational hover box when moving the mouse
     /// over symbols in the editor.
     ///
@@ -194,6 +376,12 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
+    /// Inline diagnostics related settings
+    pub inline_diagnostics: Option<InlineDiagnosticsContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -204,15 +392,58 @@ pub struct EditorSettingsContent {
     ///
     /// Default: 3.
     pub vertical_scroll_margin: Option<f32>,
+    /// The number of columns to keep visible to the left and right of the cursor
+    /// when scrolling horizontally.
+    ///
+    /// Default: 3.
+    pub horizontal_scroll_margin: Option<f32>,
+    /// Whether to keep the newest cursor vertically centered in the editor
+    /// when the cursor moves.
+    ///
+    /// Default: false
+    pub typewriter_scrolling: Option<bool>,
     /// Scroll sensitivity multiplier. This multiplier is applied
     /// to both the horizontal and vertical delta values while scrolling.
     ///
     /// Default: 1.0
     pub scroll_sensitivity: Option<f32>,
+    /// Scroll sensitivity multiplier for mouse wheel scrolling,
+    /// applied on top of `scroll_sensitivity`.
+    ///
+    /// Default: 1.0
+    pub mouse_wheel_scroll_sensitivity: Option<f32>,
+    /// Scroll sensitivity multiplier for trackpad scrolling,
+    /// applied on top of `scroll_sensitivity`.
+    ///
+    /// Default: 1.0
+    pub trackpad_scroll_sensitivity: Option<f32>,
+    /// The key to hold for scrolling faster
+    ///
+    /// Default: alt
+    pub fast_scroll_modifier: Option<FastScrollModifier>,
+    /// Scroll sensitivity multiplier applied while `fast_scroll_modifier` is held.
+    ///
+    /// Default: 5.0
+    pub fast_scroll_sensitivity: Option<f32>,
+    /// Trackpad scroll axis locking related settings
+    pub scroll_axis_lock: Option<ScrollAxisLockContent>,
+    /// Whether trackpad scrolling past the start or end of the buffer stretches the
+    /// editor and springs back, instead of stopping at the boundary.
+    ///
+    /// Default: false
+    pub elastic_overscroll: Option<bool>,
+    /// Whether pressing the middle mouse button enters a panning mode, where moving
+    /// the mouse away from where it was pressed scrolls the editor continuously.
+    ///
+    /// Default: false
+    pub middle_click_autoscroll: Option<bool>,
+    /// Autoscroll related settings for drag-selecting past the edge of the editor
+    pub drag_autoscroll: Option<DragAutoscrollContent>,
     /// Whether the line numbers on editors gutter are relative or not.
+    /// Either `true`, `false`, `"disabled"`, `"relative"` or `"hybrid"`.
     ///
     /// Default: false
-    pub relative_line_numbers: Option<bool>,
+    pub relative_line_numbers: Option<RelativeLineNumbers>,
     /// When to populate a new search's query based on the text under the cursor.
     ///
     /// Default: always
@@ -253,6 +484,30 @@ pub struct EditorSettingsContent {
     /// Default: true
     pub show_signature_help_after_edits: Option<bool>,
 
+    /// Whether to reindent lines moved with `editor::MoveLineUp` and `editor::MoveLineDown`
+    /// to match the block they're moved into.
+    ///
+    /// Default: false
+    pub reindent_moved_lines: Option<bool>,
+
+    /// Whether backspace deletes all the whitespace between the cursor and the previous
+    /// non-whitespace character, instead of a single character or indentation level.
+    ///
+    /// Default: false
+    pub hungry_backspace: Option<bool>,
+
+    /// Whether the cursor can be placed past the end of a line by clicking there or with
+    /// a block selection. Typing at such a cursor pads the line with spaces up to it.
+    ///
+    /// Default: false
+    pub virtual_space: Option<bool>,
+
+    /// Whether renames open their edits in a multibuffer without saving them, where edits
+    /// can be excluded before the rename is confirmed with `editor::ConfirmRenamePreview`.
+    ///
+    /// Default: false
+    pub preview_renames: Option<bool>,
+
     /// Jupyter REPL settings.
     pub jupyter: Option<JupyterContent>,
 }
@@ -302,6 +557,85 @@ pub struct ScrollbarContent {
     ///
     /// Default: true
     pub cursors: Option<bool>,
+    /// Whether to show a horizontal scrollbar when lines are wider than the editor.
+    ///
+    /// Default: true
+    pub horizontal: Option<bool>,
+    /// Whether to preview the lines under the mouse when hovering over the scrollbar.
+    ///
+    /// Default: true
+    pub preview: Option<bool>,
+    /// Whether to show the ranges collaborators are looking at in the scrollbar.
+    ///
+    /// Default: true
+    pub collaborators: Option<bool>,
+}
+
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+}
+
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the signatures of the enclosing scopes at the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of enclosing scopes to pin at once.
+    ///
+    /// Default: 5
+    pub max_lines: Option<usize>,
+}
+
+/// Inline diagnostics related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct InlineDiagnosticsContent {
+    /// Whether to show the most severe diagnostic of every line after the line's content.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The least severe diagnostics to show inline.
+    ///
+    /// Default: warning
+    pub min_severity: Option<DiagnosticSeverityThreshold>,
+}
+
+/// Trackpad scroll axis locking related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct ScrollAxisLockContent {
+    /// Whether a trackpad scroll sticks to the axis it started on. When disabled,
+    /// the editor scrolls freely in both directions.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// How many times larger the movement along the other axis needs to be than along
+    /// the locked one to unlock the scroll.
+    ///
+    /// Default: 1.9
+    pub unlock_ratio: Option<f32>,
+    /// The minimum movement, in pixels, of a single scroll event that can unlock the scroll.
+    ///
+    /// Default: 6.0
+    pub unlock_threshold: Option<f32>,
+}
+
+/// Autoscroll related settings for drag-selecting past the edge of the editor
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+pub struct DragAutoscrollContent {
+    /// The number of lines scrolled per second when the pointer is just past the edge.
+    ///
+    /// Default: 10.0
+    pub speed: Option<f32>,
+    /// How the speed grows as the pointer moves further past the edge.
+    ///
+    /// Default: quadratic
+    pub ramp: Option<DragAutoscrollRamp>,
 }
 
 /// Gutter related settings
@@ -9,7 +9,10 @@ use ui::{
     SettingsGroup,
 };
 
-use crate::EditorSettings;
+use crate::{
+    editor_settings::{RelativeLineNumbers, RelativeLineNumbersMode},
+    EditorSettings,
+};
 
 #[derive(IntoElement)]
 pub struct EditorSettingsControls {}
@@ -385,7 +388,7 @@ impl RenderOnce for LineNumbersControl {
 struct RelativeLineNumbersControl;
 
 impl EditableSettingControl for RelativeLineNumbersControl {
-    type Value = bool;
+    type Value = RelativeLineNumbersMode;
     type Settings = EditorSettings;
 
     fn name(&self) -> SharedString {
@@ -394,7 +397,7 @@ impl EditableSettingControl for RelativeLineNumbersControl {
 
     fn read(cx: &AppContext) -> Self::Value {
         let settings = EditorSettings::get_global(cx);
-        settings.relative_line_numbers
+        settings.relative_line_numbers.mode()
     }
 
     fn apply(
@@ -402,7 +405,7 @@ impl EditableSettingControl for RelativeLineNumbersControl {
         value: Self::Value,
         _cx: &AppContext,
     ) {
-        settings.relative_line_numbers = Some(value);
+        settings.relative_line_numbers = Some(RelativeLineNumbers::Mode(value));
     }
 }
 
@@ -412,15 +415,23 @@ impl RenderOnce for RelativeLineNumbersControl {
 
         DropdownMenu::new(
             "relative-line-numbers",
-            if value { "Relative" } else { "Ascending" },
+            match value {
+                RelativeLineNumbersMode::Disabled => "Ascending",
+                RelativeLineNumbersMode::Relative => "Relative",
+                RelativeLineNumbersMode::Hybrid => "Hybrid",
+            },
             ContextMenu::build(cx, |menu, _cx| {
                 menu.custom_entry(
                     |_cx| Label::new("Ascending").into_any_element(),
-                    move |cx| Self::write(false, cx),
+                    move |cx| Self::write(RelativeLineNumbersMode::Disabled, cx),
                 )
                 .custom_entry(
                     |_cx| Label::new("Relative").into_any_element(),
-                    move |cx| Self::write(true, cx),
+                    move |cx| Self::write(RelativeLineNumbersMode::Relative, cx),
+                )
+                .custom_entry(
+                    |_cx| Label::new("Hybrid").into_any_element(),
+                    move |cx| Self::write(RelativeLineNumbersMode::Hybrid, cx),
                 )
             }),
         )
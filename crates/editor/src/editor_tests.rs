@@ -1,6 +1,7 @@
 use super::*;
 use crate::{
-    scroll::scroll_amount::ScrollAmount,
+    editor_settings::{MultiCursorModifier, ScrollAxisLock},
+    scroll::{scroll_amount::ScrollAmount, Axis},
     test::{
         assert_text_with_selections, build_editor, editor_hunks,
         editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext,
@@ -10,7 +11,7 @@ use crate::{
 };
 use futures::StreamExt;
 use gpui::{
-    div, SemanticVersion, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds,
+    div, Modifiers, SemanticVersion, TestAppContext, UpdateGlobal, VisualTestContext, WindowBounds,
     WindowOptions,
 };
 use indoc::indoc;
@@ -42,7 +43,7 @@ use util::{
 };
 use workspace::{
     item::{FollowEvent, FollowableItem, Item, ItemHandle},
-    NavigationEntry, ViewId,
+    NavigationEntry, SaveIntent, SplitDirection, ViewId,
 };
 
 #[gpui::test]
@@ -1081,6 +1082,317 @@ fn test_fold_action_multiple_line_breaks(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+fn test_fold_at_level(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    fn a() {
+                        1
+                    }
+
+                    fn b() {
+                        2
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.fold_at_level(&FoldAtLevel { level: 2 }, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    fn a() {⋯
+                    }
+
+                    fn b() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        view.fold_at_level(&FoldAtLevel { level: 1 }, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {⋯
+                }
+            "
+            .unindent(),
+        );
+
+        view.unfold_all(&UnfoldAll, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+    });
+}
+
+#[gpui::test]
+fn test_toggle_fold(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    fn a() {
+                        1
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(1), 4)..DisplayPoint::new(DisplayRow(1), 4)
+            ]);
+        });
+        view.toggle_fold(&ToggleFold, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                impl Foo {
+                    fn a() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        // Folds are anchored, so they follow the text they were created for.
+        view.buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "// Foo\n")], None, cx);
+        });
+        assert_eq!(
+            view.display_text(cx),
+            "
+                // Foo
+                impl Foo {
+                    fn a() {⋯
+                    }
+                }
+            "
+            .unindent(),
+        );
+
+        view.toggle_fold(&ToggleFold, cx);
+        assert_eq!(view.display_text(cx), view.buffer.read(cx).read(cx).text());
+    });
+}
+
+#[gpui::test]
+fn test_fold_toggle_hover_highlights_foldable_range(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let view = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(
+            &"
+                impl Foo {
+                    fn a() {
+                        1
+                    }
+                }
+            "
+            .unindent(),
+            cx,
+        );
+        build_editor(buffer.clone(), cx)
+    });
+
+    _ = view.update(cx, |view, cx| {
+        view.set_gutter_hovered(true, cx);
+        view.set_fold_toggle_hovered(MultiBufferRow(1), true, cx);
+        assert_eq!(
+            view.highlighted_display_rows(cx)
+                .into_keys()
+                .collect::<Vec<_>>(),
+            vec![DisplayRow(1), DisplayRow(2)]
+        );
+
+        // Leaving a toggle that isn't the hovered one keeps the highlight.
+        view.set_fold_toggle_hovered(MultiBufferRow(0), false, cx);
+        assert_eq!(view.highlighted_display_rows(cx).len(), 2);
+
+        view.set_gutter_hovered(false, cx);
+        assert!(view.highlighted_display_rows(cx).is_empty());
+    });
+}
+
+#[gpui::test]
+async fn test_fold_syntax_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_folds_query("[(block) (array_expression)] @fold")
+        .unwrap(),
+    );
+
+    // The text isn't indented, so only the syntax tree can tell what to fold.
+    let text = "
+        fn main() {
+        let numbers = [
+        1,
+        2,
+        ];
+        }
+    "
+    .unindent();
+
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (view, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    view.condition::<crate::EditorEvent>(cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(DisplayRow(2), 0)..DisplayPoint::new(DisplayRow(2), 0)
+            ]);
+        });
+        view.fold(&Fold, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                fn main() {
+                let numbers = [⋯
+                ];
+                }
+            "
+            .unindent(),
+        );
+
+        view.fold(&Fold, cx);
+        assert_eq!(
+            view.display_text(cx),
+            "
+                fn main() {⋯
+                }
+            "
+            .unindent(),
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_lsp_folding_ranges_requested_for_new_versions(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    let request_count = Arc::new(AtomicUsize::new(0));
+    cx.handle_request::<lsp::request::FoldingRangeRequest, _, _>({
+        let request_count = request_count.clone();
+        move |_, _, _| {
+            request_count.fetch_add(1, atomic::Ordering::SeqCst);
+            async move { Ok(Some(Vec::new())) }
+        }
+    });
+    cx.set_state("fn main() {ˇ}");
+    cx.update_editor(|editor, cx| {
+        refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+    });
+    cx.run_until_parked();
+    let initial_count = request_count.load(atomic::Ordering::SeqCst);
+    assert!(initial_count > 0);
+
+    // The ranges of a buffer that wasn't edited since they were received are not requested again.
+    cx.update_editor(|editor, cx| {
+        refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+    });
+    cx.run_until_parked();
+    assert_eq!(request_count.load(atomic::Ordering::SeqCst), initial_count);
+
+    cx.update_editor(|editor, cx| {
+        editor.handle_input("a", cx);
+        refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx);
+    });
+    cx.run_until_parked();
+    assert_eq!(
+        request_count.load(atomic::Ordering::SeqCst),
+        initial_count + 1
+    );
+
+    // Everything is requested again when language servers change.
+    cx.update_editor(|editor, cx| {
+        refresh_viewport(editor, ViewportRefreshReason::LanguageServersChanged, cx)
+    });
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.run_until_parked();
+    assert_eq!(
+        request_count.load(atomic::Ordering::SeqCst),
+        initial_count + 2
+    );
+}
+
+#[gpui::test]
+async fn test_fold_summary(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_folds_query("(function_item body: (block (line_comment) @summary)) @fold")
+        .unwrap(),
+    );
+
+    let text = r#"
+        fn main() {
+            // Prints a greeting. Then exits.
+            println!("Hello");
+        }
+
+        fn other() {
+            println!("Bye");
+        }
+    "#
+    .unindent();
+
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (view, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    view.condition::<crate::EditorEvent>(cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    view.update(cx, |view, cx| {
+        let snapshot = view.snapshot(cx);
+
+        let (range, placeholder) = snapshot.foldable_range(MultiBufferRow(0)).unwrap();
+        assert_eq!(range, Point::new(0, 11)..Point::new(2, 22));
+        assert_eq!(placeholder.summary, Some("Prints a greeting.".into()));
+
+        let (range, placeholder) = snapshot.foldable_range(MultiBufferRow(5)).unwrap();
+        assert_eq!(range, Point::new(5, 12)..Point::new(6, 20));
+        assert_eq!(placeholder.summary, None);
+    });
+}
+
 #[gpui::test]
 fn test_move_cursor(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -1866,16 +2178,625 @@ async fn test_scroll_page_up_page_down(cx: &mut gpui::TestAppContext) {
             editor.snapshot(cx).scroll_position(),
             gpui::Point::new(0., 3.)
         );
+
+        editor.scroll_down_by_lines(&ScrollDownByLines { lines: 2 }, cx);
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 5.)
+        );
+        editor.scroll_up_by_lines(&ScrollUpByLines { lines: 4 }, cx);
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 1.)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor.set_vertical_scroll_margin(2, cx);
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 6. * line_height));
+
+    cx.set_state(
+        &r#"ˇone
+            two
+            three
+            four
+            five
+            six
+            seven
+            eight
+            nine
+            ten
+        "#,
+    );
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 0.0)
+        );
+    });
+
+    // Add a cursor below the visible area. Since both cursors cannot fit
+    // on screen, the editor autoscrolls to reveal the newest cursor, and
+    // allows the vertical scroll margin below that cursor.
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+            selections.select_ranges([
+                Point::new(0, 0)..Point::new(0, 0),
+                Point::new(6, 0)..Point::new(6, 0),
+            ]);
+        })
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 3.0)
+        );
+    });
+
+    // Move down. The editor cursor scrolls down to track the newest cursor.
+    cx.update_editor(|editor, cx| {
+        editor.move_down(&Default::default(), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 4.0)
+        );
+    });
+
+    // Add a cursor above the visible area. Since both cursors fit on screen,
+    // the editor scrolls to show both.
+    cx.update_editor(|editor, cx| {
+        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+            selections.select_ranges([
+                Point::new(1, 0)..Point::new(1, 0),
+                Point::new(6, 0)..Point::new(6, 0),
+            ]);
+        })
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 1.0)
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_horizontal_autoscroll_margin(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.soft_wrap = Some(language_settings::SoftWrap::None);
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(300.), 6. * line_height));
+    cx.set_state(&format!("ˇ{}", "a".repeat(300)));
+
+    let mut scroll_x_for_cursor_at = |column: u32, margin: usize| {
+        cx.update_editor(|editor, cx| {
+            editor.set_horizontal_scroll_margin(margin, cx);
+            editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
+                selections.select_ranges([Point::new(0, column)..Point::new(0, column)]);
+            })
+        });
+        cx.update_editor(|editor, cx| editor.snapshot(cx).scroll_position().x)
+    };
+
+    // Moving right past the viewport keeps the margin visible to the right of the cursor.
+    let right_without_margin = scroll_x_for_cursor_at(150, 0);
+    assert!(right_without_margin > 0.);
+    let right_with_margin = scroll_x_for_cursor_at(150, 5);
+    assert!(
+        right_with_margin > right_without_margin + 4.,
+        "{right_with_margin} should be about 5 columns past {right_without_margin}"
+    );
+
+    // Moving left past the viewport keeps the margin visible to the left of the cursor.
+    let left_without_margin = scroll_x_for_cursor_at(20, 0);
+    scroll_x_for_cursor_at(150, 0);
+    let left_with_margin = scroll_x_for_cursor_at(20, 5);
+    assert!(
+        left_with_margin < left_without_margin - 4.,
+        "{left_with_margin} should be about 5 columns before {left_without_margin}"
+    );
+
+    // At the end of the line, the margin is kept as empty columns past the last character.
+    let end_without_margin = scroll_x_for_cursor_at(300, 0);
+    let end_with_margin = scroll_x_for_cursor_at(300, 5);
+    assert!(end_with_margin > end_without_margin + 4.);
+}
+
+#[gpui::test]
+async fn test_typewriter_scrolling(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.typewriter_scrolling = Some(true);
+            });
+        });
+    });
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 7. * line_height));
+
+    cx.set_state(
+        &r#"ˇone
+            two
+            three
+            four
+            five
+            six
+            seven
+            eight
+            nine
+            ten
+        "#,
+    );
+
+    // Every cursor movement keeps the cursor centered, once it's far enough
+    // from the start of the buffer to be centered.
+    for expected_scroll_top in [0.0, 0.0, 0.0, 1.0, 2.0] {
+        cx.update_editor(|editor, cx| {
+            editor.move_down(&Default::default(), cx);
+        });
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.snapshot(cx).scroll_position(),
+                gpui::Point::new(0., expected_scroll_top)
+            );
+        });
+    }
+
+    cx.update_editor(|editor, cx| {
+        editor.move_up(&Default::default(), cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 1.0)
+        );
+    });
+}
+
+#[gpui::test]
+fn test_scroll_anchor_is_stable_across_edits_above_viewport(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let text = sample_text(30, 3, 'a');
+    let buffer = cx.new_model(|cx| language::Buffer::local(text.clone(), cx));
+    let remote_buffer = cx.new_model(|cx| {
+        language::Buffer::remote(buffer.read(cx).remote_id(), 1, ReadWrite, text.clone())
+    });
+    let remote_ops = Arc::new(Mutex::new(Vec::new()));
+    cx.update(|cx| {
+        let remote_ops = remote_ops.clone();
+        cx.subscribe(&remote_buffer, move |_, event, _| {
+            if let language::Event::Operation(operation) = event {
+                remote_ops.lock().push(operation.clone());
+            }
+        })
+        .detach();
+    });
+
+    let multibuffer = cx.new_model(|cx| MultiBuffer::singleton(buffer.clone(), cx));
+    let editor = cx.add_window(|cx| build_editor(multibuffer, cx));
+    let top_line = |editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let top_row = editor.scroll_manager.anchor().top_row(&snapshot);
+        let line = snapshot
+            .text_for_range(Point::new(top_row, 0)..Point::new(top_row, 3))
+            .collect::<String>();
+        (editor.scroll_position(cx).y, line)
+    };
+
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_scroll_position(gpui::Point::new(0., 10.), cx);
+        assert_eq!(top_line(editor, cx), (10., "kkk".to_string()));
+    });
+
+    // Lines inserted above the viewport, e.g. by a formatter.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(Point::new(2, 0)..Point::new(2, 0), "x\ny\n")], None, cx);
+    });
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(top_line(editor, cx), (12., "kkk".to_string()));
+    });
+
+    // Concurrent edits above the viewport by a collaborator, who hasn't seen the
+    // lines inserted above.
+    remote_buffer.update(cx, |buffer, cx| {
+        buffer.edit([(Point::new(0, 0)..Point::new(0, 0), "z\n")], None, cx);
+        buffer.edit([(Point::new(6, 0)..Point::new(8, 0), "")], None, cx);
+    });
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_ops(remote_ops.lock().drain(..), cx).unwrap();
+    });
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(top_line(editor, cx), (11., "kkk".to_string()));
+    });
+
+    // Lines inserted right at the start of the first visible line.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(Point::new(11, 0)..Point::new(11, 0), "w\n")], None, cx);
+    });
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(top_line(editor, cx), (12., "kkk".to_string()));
+    });
+}
+
+#[gpui::test]
+fn test_scroll_lock(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&sample_text(30, 5, 'a'), cx));
+    let left = cx.add_window(|cx| build_editor(buffer.clone(), cx));
+    let right = cx.add_window(|cx| build_editor(buffer.clone(), cx));
+    let right_view = right.root(cx).unwrap();
+
+    _ = right.update(cx, |editor, cx| {
+        editor.set_scroll_position(gpui::Point::new(0., 2.), cx);
+    });
+    _ = left.update(cx, |editor, cx| {
+        editor.lock_scroll_with(&right_view, cx);
+        assert!(editor.is_scroll_locked());
+    });
+
+    // Scrolling either editor scrolls the other one, preserving the distance between them.
+    _ = left.update(cx, |editor, cx| {
+        editor.set_scroll_position(gpui::Point::new(0., 5.), cx);
+    });
+    _ = right.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 7.));
+        editor.set_scroll_position(gpui::Point::new(0., 10.), cx);
+    });
+    _ = left.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 8.));
+        editor.unlock_scroll(cx);
+        editor.set_scroll_position(gpui::Point::new(0., 0.), cx);
+    });
+    _ = right.update(cx, |editor, cx| {
+        assert!(!editor.is_scroll_locked());
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 10.));
+    });
+}
+
+#[gpui::test]
+async fn test_scroll_lock_released_on_close(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let fs = FakeFs::new(cx.executor());
+    let project = Project::test(fs, [], cx).await;
+    let workspace = cx.add_window(|cx| Workspace::test_new(project, cx));
+    let pane = workspace
+        .update(cx, |workspace, _| workspace.active_pane().clone())
+        .unwrap();
+    let cx = &mut VisualTestContext::from_window(*workspace.deref(), cx);
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&sample_text(30, 5, 'a'), cx));
+    let left = cx.new_view(|cx| build_editor(buffer.clone(), cx));
+    let right = cx.new_view(|cx| build_editor(buffer.clone(), cx));
+    let right_id = right.entity_id();
+    left.update(cx, |editor, cx| {
+        editor.lock_scroll_with(&right, cx);
+        assert!(editor.is_scroll_locked());
+    });
+    _ = workspace.update(cx, |workspace, cx| {
+        workspace.add_item_to_active_pane(Box::new(left.clone()), None, true, cx);
+        workspace.add_item_to_active_pane(Box::new(right), None, true, cx);
+    });
+
+    // Moving one of the locked editors to a split keeps the lock.
+    _ = workspace.update(cx, |workspace, cx| {
+        workspace.split_pane_with_item(
+            pane.downgrade(),
+            SplitDirection::Right,
+            pane.downgrade(),
+            right_id,
+            cx,
+        );
+    });
+    cx.run_until_parked();
+    left.update(cx, |editor, _| assert!(editor.is_scroll_locked()));
+
+    // Closing it releases the lock of the other one.
+    let right_pane = workspace
+        .update(cx, |workspace, _| workspace.panes()[1].clone())
+        .unwrap();
+    right_pane
+        .update(cx, |pane, cx| {
+            pane.close_item_by_id(right_id, SaveIntent::Skip, cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    left.update(cx, |editor, _| assert!(!editor.is_scroll_locked()));
+}
+
+#[gpui::test]
+fn test_scroll_axis_lock(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(10, 10, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = editor.update(cx, |editor, _| {
+        let axis_lock = ScrollAxisLock {
+            enabled: true,
+            unlock_ratio: 1.9,
+            unlock_threshold: 6.,
+        };
+
+        // A new scroll locks to its dominant axis.
+        let mut delta = point(px(3.), px(10.));
+        let ongoing = editor.scroll_manager.ongoing_scroll();
+        assert_eq!(ongoing.filter(&mut delta, &axis_lock), Some(Axis::Vertical));
+        assert_eq!(delta, point(px(0.), px(10.)));
+
+        // Free scroll leaves the delta alone.
+        let mut delta = point(px(3.), px(10.));
+        let free_scroll = ScrollAxisLock {
+            enabled: false,
+            ..axis_lock
+        };
+        assert_eq!(ongoing.filter(&mut delta, &free_scroll), None);
+        assert_eq!(delta, point(px(3.), px(10.)));
+
+        // An ongoing scroll unlocks once the other axis dominates by the unlock ratio.
+        editor
+            .scroll_manager
+            .update_ongoing_scroll(Some(Axis::Vertical));
+        let ongoing = editor.scroll_manager.ongoing_scroll();
+        let mut delta = point(px(20.), px(5.));
+        assert_eq!(ongoing.filter(&mut delta, &axis_lock), None);
+        assert_eq!(delta, point(px(20.), px(5.)));
+        let mut delta = point(px(20.), px(5.));
+        let strict_lock = ScrollAxisLock {
+            unlock_ratio: 10.,
+            ..axis_lock
+        };
+        assert_eq!(
+            ongoing.filter(&mut delta, &strict_lock),
+            Some(Axis::Vertical)
+        );
+        assert_eq!(delta, point(px(0.), px(5.)));
+        let mut delta = point(px(5.), px(1.));
+        assert_eq!(ongoing.filter(&mut delta, &axis_lock), Some(Axis::Vertical));
+        assert_eq!(delta, point(px(0.), px(1.)));
+    });
+}
+
+#[gpui::test]
+fn test_middle_click_autoscroll(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(100, 100, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    let origin = point(px(100.), px(100.));
+    _ = editor.update(cx, |editor, cx| {
+        editor.start_middle_click_autoscroll(origin, px(10.), px(5.), point(50., 90.), cx);
+        assert!(editor.scroll_manager.is_middle_click_autoscrolling());
+        // Moving within the dead zone around the origin doesn't scroll.
+        editor.update_middle_click_autoscroll(point(px(104.), px(106.)));
+        assert!(!editor.scroll_manager.has_middle_click_autoscroll_moved());
+    });
+    cx.executor().advance_clock(Duration::from_millis(160));
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 0.));
+        // Moving mostly downwards scrolls vertically, with the scroll locked to that axis.
+        editor.update_middle_click_autoscroll(point(px(115.), px(208.)));
+        assert!(editor.scroll_manager.has_middle_click_autoscroll_moved());
+    });
+    cx.executor().advance_clock(Duration::from_millis(160));
+    let scroll_position = editor
+        .update(cx, |editor, cx| {
+            let scroll_position = editor.scroll_position(cx);
+            assert_eq!(scroll_position.x, 0.);
+            assert!((scroll_position.y - 8.).abs() < 0.01, "{scroll_position:?}");
+            // Moving further away scrolls faster, and upwards above the origin.
+            editor.update_middle_click_autoscroll(point(px(100.), px(-108.)));
+            scroll_position
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(32));
+    let scroll_position = editor
+        .update(cx, |editor, cx| {
+            let new_scroll_position = editor.scroll_position(cx);
+            assert!((scroll_position.y - new_scroll_position.y - 3.2).abs() < 0.01);
+            assert!(editor.stop_middle_click_autoscroll(cx));
+            new_scroll_position
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(160));
+    _ = editor.update(cx, |editor, cx| {
+        assert!(!editor.scroll_manager.is_middle_click_autoscrolling());
+        assert_eq!(editor.scroll_position(cx), scroll_position);
+
+        // Escape also leaves the panning mode.
+        editor.start_middle_click_autoscroll(origin, px(10.), px(5.), point(50., 90.), cx);
+        editor.cancel(&Cancel, cx);
+        assert!(!editor.scroll_manager.is_middle_click_autoscrolling());
+    });
+}
+
+#[gpui::test]
+fn test_drag_autoscroll(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(100, 5, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_visible_line_count(10., cx);
+        editor.begin_selection(DisplayPoint::new(DisplayRow(2), 2), false, 1, cx);
+        // Dragging just past the bottom edge scrolls slowly.
+        editor.update_drag_autoscroll(Some(0.), 2, 2, cx);
+        assert!(editor.scroll_manager.is_drag_autoscrolling());
+    });
+    cx.executor().advance_clock(Duration::from_millis(160));
+    let scroll_position = editor
+        .update(cx, |editor, cx| {
+            let scroll_position = editor.scroll_position(cx);
+            assert!(
+                (scroll_position.y - 1.6).abs() < 0.01,
+                "{scroll_position:?}"
+            );
+            // The selection follows the bottom edge while the view scrolls.
+            assert_eq!(
+                editor.selections.display_ranges(cx),
+                [DisplayPoint::new(DisplayRow(2), 2)..DisplayPoint::new(DisplayRow(10), 2)]
+            );
+            // Dragging further away accelerates the scroll.
+            editor.update_drag_autoscroll(Some(2.), 2, 2, cx);
+            scroll_position
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(160));
+    let scroll_position = editor
+        .update(cx, |editor, cx| {
+            let new_scroll_position = editor.scroll_position(cx);
+            assert!((new_scroll_position.y - scroll_position.y - 14.4).abs() < 0.01);
+            // Releasing the mouse stops the autoscroll.
+            editor.end_selection(cx);
+            editor.update_drag_autoscroll(None, 0, 0, cx);
+            assert!(!editor.scroll_manager.is_drag_autoscrolling());
+            new_scroll_position
+        })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(160));
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), scroll_position);
+    });
+}
+
+#[gpui::test]
+fn test_elastic_overscroll(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(100, 5, 'a'), cx);
+        build_editor(buffer, cx)
+    });
+    _ = editor.update(cx, |editor, cx| {
+        editor.set_visible_line_count(10., cx);
+
+        // Scrolling past the top stretches the editor, with some resistance.
+        editor.stretch_overscroll(0., -2., cx);
+        assert_eq!(editor.scroll_manager.overscroll(), -1.);
+        assert_eq!(editor.snapshot(cx).scroll_position(), point(0., -1.));
+        assert_eq!(editor.scroll_position(cx), point(0., 0.));
+
+        // The further the editor is stretched, the harder it gets to stretch it.
+        editor.stretch_overscroll(-1., -3., cx);
+        assert!((editor.scroll_manager.overscroll() + 1.6).abs() < 0.01);
+
+        // Scrolling back undoes the stretch without resistance.
+        editor.stretch_overscroll(-1.6, -0.5, cx);
+        assert_eq!(editor.scroll_manager.overscroll(), -0.5);
+    });
+
+    // The stretch springs back once the scrolling stops.
+    cx.executor().advance_clock(Duration::from_millis(100));
+    _ = editor.update(cx, |editor, _| {
+        let overscroll = editor.scroll_manager.overscroll();
+        assert!(overscroll > -0.5 && overscroll < 0., "{overscroll}");
+    });
+    cx.executor().advance_clock(Duration::from_millis(500));
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_manager.overscroll(), 0.);
+
+        // Scrolling the editor in any other way drops the stretch.
+        editor.stretch_overscroll(0., 2., cx);
+        assert_eq!(editor.scroll_manager.overscroll(), 1.);
+        editor.set_scroll_position(point(0., 5.), cx);
+        assert_eq!(editor.scroll_manager.overscroll(), 0.);
+    });
+}
+
+#[gpui::test]
+async fn test_scroll_cursor_to_viewport_position(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let line_height = cx.update_editor(|editor, cx| {
+        editor
+            .style()
+            .unwrap()
+            .text
+            .line_height_in_pixels(cx.rem_size())
+    });
+    let window = cx.window;
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+
+    let mut text = sample_text(30, 3, 'a');
+    text.insert(text.find("p").unwrap(), 'ˇ');
+    cx.set_state(&text);
+
+    cx.update_editor(|editor, cx| {
+        editor
+            .scroll_cursor_to_viewport_percent(&ScrollCursorToViewportPercent { percent: 50 }, cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 11.)
+        );
+        editor.scroll_cursor_top_with_context(&ScrollCursorTopWithContext { lines: 2 }, cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 13.)
+        );
+        editor.scroll_cursor_bottom_with_context(&ScrollCursorBottomWithContext { lines: 2 }, cx);
+    });
+    cx.update_editor(|editor, cx| {
+        assert_eq!(
+            editor.snapshot(cx).scroll_position(),
+            gpui::Point::new(0., 8.)
+        );
     });
 }
 
 #[gpui::test]
-async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
+async fn test_scroll_history(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
     let mut cx = EditorTestContext::new(cx).await;
 
     let line_height = cx.update_editor(|editor, cx| {
-        editor.set_vertical_scroll_margin(2, cx);
         editor
             .style()
             .unwrap()
@@ -1883,72 +2804,101 @@ async fn test_autoscroll(cx: &mut gpui::TestAppContext) {
             .line_height_in_pixels(cx.rem_size())
     });
     let window = cx.window;
-    cx.simulate_window_resize(window, size(px(1000.), 6. * line_height));
+    cx.simulate_window_resize(window, size(px(1000.), 10. * line_height));
+    cx.set_state(&format!("ˇ{}", sample_text(100, 3, 'a')));
 
-    cx.set_state(
-        &r#"ˇone
-            two
-            three
-            four
-            five
-            six
-            seven
-            eight
-            nine
-            ten
-        "#,
-    );
+    // Small scrolls aren't recorded in the history.
     cx.update_editor(|editor, cx| {
-        assert_eq!(
-            editor.snapshot(cx).scroll_position(),
-            gpui::Point::new(0., 0.0)
-        );
+        editor.set_scroll_position(gpui::Point::new(0., 2.), cx);
     });
-
-    // Add a cursor below the visible area. Since both cursors cannot fit
-    // on screen, the editor autoscrolls to reveal the newest cursor, and
-    // allows the vertical scroll margin below that cursor.
     cx.update_editor(|editor, cx| {
-        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
-            selections.select_ranges([
-                Point::new(0, 0)..Point::new(0, 0),
-                Point::new(6, 0)..Point::new(6, 0),
-            ]);
-        })
+        editor.scroll_history_back(&ScrollHistoryBack, cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 2.));
+
+        editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+            selections.select_ranges([Point::new(50, 0)..Point::new(50, 0)]);
+        });
     });
+    let jumped_scroll_position = cx.update_editor(|editor, cx| editor.scroll_position(cx));
+    assert!(jumped_scroll_position.y > 40.);
+
+    // Going back returns to the viewport before the jump, without moving the selections.
     cx.update_editor(|editor, cx| {
+        editor.scroll_history_back(&ScrollHistoryBack, cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 2.));
         assert_eq!(
-            editor.snapshot(cx).scroll_position(),
-            gpui::Point::new(0., 3.0)
+            editor.selections.newest::<Point>(cx).head(),
+            Point::new(50, 0)
         );
+
+        editor.scroll_history_forward(&ScrollHistoryForward, cx);
+        assert_eq!(editor.scroll_position(cx), jumped_scroll_position);
     });
 
-    // Move down. The editor cursor scrolls down to track the newest cursor.
+    // A new jump clears the forward history.
     cx.update_editor(|editor, cx| {
-        editor.move_down(&Default::default(), cx);
+        editor.scroll_history_back(&ScrollHistoryBack, cx);
+        editor.scroll_screen(&ScrollAmount::Page(1.), cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 11.));
+        editor.scroll_history_forward(&ScrollHistoryForward, cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 11.));
+        editor.scroll_history_back(&ScrollHistoryBack, cx);
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 2.));
     });
-    cx.update_editor(|editor, cx| {
-        assert_eq!(
-            editor.snapshot(cx).scroll_position(),
-            gpui::Point::new(0., 4.0)
-        );
+}
+
+#[gpui::test]
+async fn test_scroll_to_anchor_animated(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple(&sample_text(100, 5, 'a'), cx);
+        build_editor(buffer, cx)
     });
+    let task = editor
+        .update(cx, |editor, cx| {
+            let anchor = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .anchor_before(Point::new(40, 0));
+            editor.scroll_to_anchor_animated(anchor, Duration::from_millis(160), gpui::linear, cx)
+        })
+        .unwrap();
 
-    // Add a cursor above the visible area. Since both cursors fit on screen,
-    // the editor scrolls to show both.
-    cx.update_editor(|editor, cx| {
-        editor.change_selections(Some(Autoscroll::fit()), cx, |selections| {
-            selections.select_ranges([
-                Point::new(1, 0)..Point::new(1, 0),
-                Point::new(6, 0)..Point::new(6, 0),
-            ]);
+    // The editor scrolls gradually, reaching the anchor when the animation finishes.
+    cx.executor().advance_clock(Duration::from_millis(80));
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 20.));
+    });
+    cx.executor().advance_clock(Duration::from_millis(80));
+    cx.executor().run_until_parked();
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 40.));
+    });
+    task.await;
+
+    // Scrolling the editor during the animation stops it.
+    let task = editor
+        .update(cx, |editor, cx| {
+            let anchor = editor
+                .buffer()
+                .read(cx)
+                .snapshot(cx)
+                .anchor_before(Point::new(0, 0));
+            editor.scroll_to_anchor_animated(anchor, Duration::from_millis(160), gpui::linear, cx)
         })
+        .unwrap();
+    cx.executor().advance_clock(Duration::from_millis(80));
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 20.));
+        editor.set_scroll_position(gpui::Point::new(0., 30.), cx);
     });
-    cx.update_editor(|editor, cx| {
-        assert_eq!(
-            editor.snapshot(cx).scroll_position(),
-            gpui::Point::new(0., 1.0)
-        );
+    cx.executor().advance_clock(Duration::from_millis(80));
+    cx.executor().run_until_parked();
+    task.await;
+    _ = editor.update(cx, |editor, cx| {
+        assert_eq!(editor.scroll_position(cx), gpui::Point::new(0., 30.));
     });
 }
 
@@ -2371,6 +3321,62 @@ async fn test_newline_comments(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_newline_block_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into()],
+            block_comment_continuation: Some(BlockCommentContinuation {
+                start: "/*".into(),
+                end: "*/".into(),
+                prefix: "* ".into(),
+                indent: 1,
+            }),
+            ..LanguageConfig::default()
+        },
+        None,
+    ));
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    cx.set_state(indoc! {"
+        /**ˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        /**
+         * ˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        /**
+         *
+         * ˇ
+    "});
+
+    // Splitting a comment moves the text after the cursor to the new line, and the end of the
+    // comment to a line of its own.
+    cx.set_state("/** Foo ˇbar */");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/** Foo\n * ˇbar */");
+    cx.set_state("/** Foo ˇ*/");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/** Foo\n * ˇ\n */");
+    cx.set_state("// Foo ˇbar");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("// Foo\n// ˇbar");
+
+    // Closed comments, and lines starting with `*` outside of comments, aren't continued.
+    cx.set_state("/** Foo */ˇ");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("/** Foo */\nˇ");
+    cx.set_state("a;\n*b = c;ˇ");
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state("a;\n*b = c;\nˇ");
+}
+
 #[gpui::test]
 fn test_insert_with_old_selections(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2854,6 +3860,89 @@ async fn test_backspace(cx: &mut gpui::TestAppContext) {
         the lazy dogˇ"});
 }
 
+#[gpui::test]
+async fn test_hungry_backspace(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.hungry_backspace = Some(true);
+            });
+        });
+    });
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // All whitespace before the cursor is deleted, including line breaks, otherwise a single
+    // character is.
+    cx.set_state(indoc! {"
+        one  ˇtwo
+        three
+
+            ˇfour
+        fiveˇ
+    "});
+    cx.update_editor(|e, cx| e.backspace(&Backspace, cx));
+    cx.assert_editor_state(indoc! {"
+        oneˇtwo
+        threeˇfour
+        fivˇ
+    "});
+}
+
+#[gpui::test]
+async fn test_overtype(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_editor(|e, cx| e.toggle_overtype(&ToggleOvertype, cx));
+
+    // Typed text replaces the characters after the cursor, up to the end of the line, and
+    // brackets aren't auto-closed.
+    cx.set_state(indoc! {"
+        oneˇ two
+        thrˇee
+    "});
+    cx.update_editor(|e, cx| {
+        e.handle_input("(", cx);
+        e.handle_input("*", cx);
+        e.handle_input("!", cx);
+        e.handle_input("?", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        one(*!?ˇ
+        thr(*!?ˇ
+    "});
+
+    // Pasted text replaces the characters after the cursor too.
+    cx.set_state(indoc! {"
+        ˇone two
+    "});
+    cx.write_to_clipboard(ClipboardItem::new("six".into()));
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        sixˇ two
+    "});
+
+    // Selected text is replaced as usual.
+    cx.set_state(indoc! {"
+        «oneˇ» two
+    "});
+    cx.update_editor(|e, cx| e.handle_input("1", cx));
+    cx.assert_editor_state(indoc! {"
+        1ˇ two
+    "});
+
+    // Turning overtype mode off inserts text again.
+    cx.update_editor(|e, cx| {
+        e.toggle_overtype(&ToggleOvertype, cx);
+        e.handle_input("2", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        12ˇ two
+    "});
+}
+
 #[gpui::test]
 async fn test_delete(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3060,6 +4149,65 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_join_comment_lines(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into(), "/// ".into()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The comment leader of the joined line is removed along with its indentation.
+    cx.set_state(indoc! {"
+        fn a() {
+            // ˇone
+            //   two
+        }
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            // oneˇ two
+        }
+    "});
+
+    // The longest matching leader is used.
+    cx.set_state(indoc! {"
+        /// ˇone
+        /// two
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        /// oneˇ two
+    "});
+
+    // Empty comment lines are joined without a trailing space.
+    cx.set_state(indoc! {"
+        // ˇone
+        //
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        // oneˇ
+    "});
+
+    // Leaders are only removed when both lines are comments.
+    cx.set_state(indoc! {"
+        ˇone();
+        // two
+    "});
+    cx.update_editor(|editor, cx| editor.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        one();ˇ // two
+    "});
+}
+
 #[gpui::test]
 async fn test_join_lines_with_git_diff_base(
     executor: BackgroundExecutor,
@@ -3326,42 +4474,125 @@ async fn test_unique_lines_multi_selection(cx: &mut TestAppContext) {
         aaaaa
         aaaaaˇ»
 
-        aaa«aaˇ»
+        aaa«aaˇ»
+    "});
+    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
+    cx.assert_editor_state(indoc! {"
+        «aaaaa
+        bbˇ»
+
+        «aaaaaˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_unique_lines_single_selection(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        «Aaa
+        aAa
+        Aaaˇ»
+    "});
+    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
+    cx.assert_editor_state(indoc! {"
+        «Aaa
+        aAaˇ»
+    "});
+
+    cx.set_state(indoc! {"
+        «Aaa
+        aAa
+        aaAˇ»
+    "});
+    cx.update_editor(|e, cx| e.unique_lines_case_insensitive(&UniqueLinesCaseInsensitive, cx));
+    cx.assert_editor_state(indoc! {"
+        «Aaaˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_sort_lines_descending_and_numerically(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state(indoc! {"
+        «b
+        c
+        A
+        aˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_descending(&SortLinesDescending, cx));
+    cx.assert_editor_state(indoc! {"
+        «c
+        b
+        a
+        Aˇ»
+    "});
+
+    // Numbers are compared by value, and lines without one go first.
+    cx.set_state(indoc! {"
+        «10. ten
+        -2.5
+        notes
+          9 nine
+        1. one
+        todoˇ»
+    "});
+    cx.update_editor(|e, cx| e.sort_lines_numerically(&SortLinesNumerically, cx));
+    cx.assert_editor_state(indoc! {"
+        «notes
+        todo
+        -2.5
+        1. one
+          9 nine
+        10. tenˇ»
+    "});
+
+    // Each selection is sorted on its own.
+    cx.set_state(indoc! {"
+        «3
+        1ˇ»
+
+        «20
+        100ˇ»
     "});
-    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
+    cx.update_editor(|e, cx| e.sort_lines_numerically(&SortLinesNumerically, cx));
     cx.assert_editor_state(indoc! {"
-        «aaaaa
-        bbˇ»
+        «1
+        3ˇ»
 
-        «aaaaaˇ»
+        «20
+        100ˇ»
     "});
 }
 
 #[gpui::test]
-async fn test_unique_lines_single_selection(cx: &mut TestAppContext) {
+async fn test_unique_adjacent_lines(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
 
     let mut cx = EditorTestContext::new(cx).await;
 
+    // Only runs of identical lines are collapsed.
     cx.set_state(indoc! {"
-        «Aaa
-        aAa
-        Aaaˇ»
-    "});
-    cx.update_editor(|e, cx| e.unique_lines_case_sensitive(&UniqueLinesCaseSensitive, cx));
-    cx.assert_editor_state(indoc! {"
-        «Aaa
-        aAaˇ»
-    "});
-
-    cx.set_state(indoc! {"
-        «Aaa
-        aAa
-        aaAˇ»
+        «a
+        a
+        b
+        a
+        a
+        Aˇ»
+        A
     "});
-    cx.update_editor(|e, cx| e.unique_lines_case_insensitive(&UniqueLinesCaseInsensitive, cx));
+    cx.update_editor(|e, cx| e.unique_adjacent_lines(&UniqueAdjacentLines, cx));
     cx.assert_editor_state(indoc! {"
-        «Aaaˇ»
+        «a
+        b
+        a
+        Aˇ»
+        A
     "});
 }
 
@@ -3504,6 +4735,35 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
         TheLazyDogˇ»
     "});
 
+    cx.set_state(indoc! {"
+        «hello_worldˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_upper_snake_case(&ConvertToUpperSnakeCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «HELLO_WORLDˇ»
+    "});
+
+    // Test cycling through cases, with each line's case detected separately
+    cx.set_state(indoc! {"
+        «hello_world
+        helloWorldˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_next_case(&ConvertToNextCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «HELLO_WORLD
+        HelloWorldˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_next_case(&ConvertToNextCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «hello-world
+        Hello Worldˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_next_case(&ConvertToNextCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «helloWorld
+        hello_worldˇ»
+    "});
+
     // From here on out, test more complex cases of manipulate_text()
 
     // Test no selection case - should affect words cursors are in
@@ -3796,6 +5056,88 @@ fn test_move_line_up_down_with_blocks(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_move_line_up_down_with_reindent(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4);
+    });
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.reindent_moved_lines = Some(true);
+            });
+        });
+    });
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_indents_query(r#"(_ "{" "}" @end) @indent"#)
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+    cx.set_state(indoc! {"
+        fn a() {
+            bˇ();
+        }
+        c();
+    "});
+
+    cx.update_editor(|e, cx| e.move_line_down(&MoveLineDown, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+        }
+        bˇ();
+        c();
+    "});
+
+    cx.update_editor(|e, cx| e.move_line_up(&MoveLineUp, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            bˇ();
+        }
+        c();
+    "});
+
+    // Cursors at the start of a line stay there.
+    cx.set_state(indoc! {"
+        fn a() {
+        }
+        ˇc();
+    "});
+    cx.update_editor(|e, cx| e.move_line_up(&MoveLineUp, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+        ˇ    c();
+        }
+    "});
+
+    // Moved lines keep their indentation relative to each other.
+    cx.set_state(indoc! {"
+        fn a() {
+        }
+        «fn b() {
+            c();
+        }ˇ»
+    "});
+    cx.update_editor(|e, cx| e.move_line_up(&MoveLineUp, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+        «    fn b() {
+                c();
+            }ˇ»
+        }
+    "});
+}
+
 #[gpui::test]
 fn test_transpose(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3890,6 +5232,97 @@ fn test_transpose(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_transpose_words(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("oˇne two, three");
+    cx.update_editor(|e, cx| e.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("two oneˇ, three");
+
+    // Repeatedly transposing drags the word forward, skipping punctuation.
+    cx.update_editor(|e, cx| e.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("two three, oneˇ");
+
+    // There's no word after the last one.
+    cx.update_editor(|e, cx| e.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("two three, oneˇ");
+
+    // Between words, the next two words are swapped.
+    cx.set_state("ˇ  one two");
+    cx.update_editor(|e, cx| e.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state("  two oneˇ");
+
+    cx.set_state(indoc! {"
+        aˇ b c
+        dˇ e f
+    "});
+    cx.update_editor(|e, cx| e.transpose_words(&TransposeWords, cx));
+    cx.assert_editor_state(indoc! {"
+        b aˇ c
+        e dˇ f
+    "});
+}
+
+#[gpui::test]
+async fn test_increment_and_decrement(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("let a = ˇ9;");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("let a = 10ˇ;");
+
+    // The number after the cursor is changed, here crossing zero.
+    cx.set_state("ˇa - 1 + -1");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("a - 2ˇ + -1");
+    cx.set_state("a - 1ˇ + -1");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("a - 2ˇ + -1");
+    cx.set_state("a - 1 ˇ+ -1");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("a - 1 + 0ˇ");
+
+    // Hexadecimal numbers keep their case and width, and decimals their decimal places.
+    cx.set_state("ˇ0x0F 0x0f");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("0x10ˇ 0x0f");
+    cx.set_state("0x0F 0x«0fˇ»");
+    cx.update_editor(|e, cx| e.decrement(&Decrement { step: false }, cx));
+    cx.assert_editor_state("0x0F «0x0eˇ»");
+    cx.set_state("ˇ0.25");
+    cx.update_editor(|e, cx| e.decrement(&Decrement { step: false }, cx));
+    cx.assert_editor_state("-0.75ˇ");
+
+    // Cursors on lines without a number are left alone.
+    cx.set_state("ˇno numbers");
+    cx.update_editor(|e, cx| e.increment(&Increment { step: false }, cx));
+    cx.assert_editor_state("ˇno numbers");
+
+    // Stepping numbers the cursors in sequence.
+    cx.set_state(indoc! {"
+        ˇ0. a
+        ˇ0. b
+        ˇ0. c
+    "});
+    cx.update_editor(|e, cx| e.increment(&Increment { step: true }, cx));
+    cx.assert_editor_state(indoc! {"
+        1ˇ. a
+        2ˇ. b
+        3ˇ. c
+    "});
+    cx.update_editor(|e, cx| e.decrement(&Decrement { step: false }, cx));
+    cx.assert_editor_state(indoc! {"
+        0ˇ. a
+        1ˇ. b
+        2ˇ. c
+    "});
+}
+
 #[gpui::test]
 async fn test_clipboard(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -3975,6 +5408,45 @@ async fn test_clipboard(cx: &mut gpui::TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+async fn test_paste_one_line_per_cursor(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Text copied from outside the editor has one line per cursor.
+    cx.write_to_clipboard(ClipboardItem::new("one\ntwo\nthree\n".into()));
+    cx.set_state(indoc! {"
+        a ˇ
+        b ˇ
+        c ˇ"});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        a oneˇ
+        b twoˇ
+        c threeˇ"});
+
+    // A single copied selection has one line per cursor.
+    cx.set_state(indoc! {"
+        «one
+        twoˇ»"});
+    cx.update_editor(|e, cx| e.copy(&Copy, cx));
+    cx.set_state(indoc! {"
+        ˇ
+        ˇ"});
+    cx.update_editor(|e, cx| e.paste(&Paste, cx));
+    cx.assert_editor_state(indoc! {"
+        oneˇ
+        twoˇ"});
+
+    // With a single cursor, the lines are pasted together, each ending in a cursor.
+    cx.set_state("ˇ");
+    cx.update_editor(|e, cx| e.paste_as_cursors(&PasteAsCursors, cx));
+    cx.assert_editor_state(indoc! {"
+        oneˇ
+        twoˇ"});
+}
+
 #[gpui::test]
 async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4479,6 +5951,162 @@ async fn test_add_selection_above_below(cx: &mut TestAppContext) {
     ));
 }
 
+#[gpui::test]
+async fn test_select_column(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        abˇcd
+        efgh
+        ij
+        klmn
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.select_column_down(&SelectColumnDown, cx);
+        editor.select_column_right(&SelectColumnRight, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        ab«cˇ»d
+        ef«gˇ»h
+        ij
+        klmn
+    "});
+
+    // Lines that end inside the block get a cursor at their end.
+    cx.update_editor(|editor, cx| {
+        editor.select_column_down(&SelectColumnDown, cx);
+        editor.select_column_down(&SelectColumnDown, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        ab«cˇ»d
+        ef«gˇ»h
+        ijˇ
+        kl«mˇ»n
+    "});
+
+    // Typing replaces the block with one cursor per line.
+    cx.update_editor(|editor, cx| editor.handle_input("X", cx));
+    cx.assert_editor_state(indoc! {"
+        abXˇd
+        efXˇh
+        ijXˇ
+        klXˇn
+    "});
+
+    // Tabs are selected whole.
+    cx.set_state("\tˇab\n\tcd\n");
+    cx.update_editor(|editor, cx| {
+        editor.select_column_left(&SelectColumnLeft, cx);
+        editor.select_column_down(&SelectColumnDown, cx);
+    });
+    cx.assert_editor_state("«ˇ\t»ab\n«ˇ\t»cd\n");
+}
+
+#[gpui::test]
+async fn test_alt_drag_column_selection(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let drag = |cx: &mut EditorTestContext| {
+        let start = cx.pixel_position_for(DisplayPoint::new(DisplayRow(1), 1));
+        let end = cx.pixel_position_for(DisplayPoint::new(DisplayRow(2), 3));
+        let modifiers = Modifiers {
+            alt: true,
+            ..Default::default()
+        };
+        cx.simulate_mouse_down(start, MouseButton::Left, modifiers);
+        cx.simulate_mouse_move(end, MouseButton::Left, modifiers);
+        cx.simulate_mouse_up(end, MouseButton::Left, modifiers);
+    };
+
+    // Alt is the multi-cursor modifier, so the existing selections are kept.
+    cx.set_state("ˇabcd\nefgh\nijkl\nmnop");
+    drag(&mut cx);
+    cx.assert_editor_state("ˇabcd\ne«fgˇ»h\ni«jkˇ»l\nmnop");
+
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.multi_cursor_modifier = Some(MultiCursorModifier::CmdOrCtrl);
+            });
+        });
+    });
+    cx.set_state("ˇabcd\nefgh\nijkl\nmnop");
+    drag(&mut cx);
+    cx.assert_editor_state("abcd\ne«fgˇ»h\ni«jkˇ»l\nmnop");
+}
+
+#[gpui::test]
+async fn test_add_cursor_above_below(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        abcdef
+        abcˇdef
+        ab
+        abcdef
+    "});
+
+    // The cursor added on the short line is clipped to its end.
+    cx.update_editor(|editor, cx| editor.add_cursor_below(&AddCursorBelow, cx));
+    cx.assert_editor_state(indoc! {"
+        abcdef
+        abcˇdef
+        abˇ
+        abcdef
+    "});
+
+    // Cursors are added below each cursor, and the clipped one returns to its column.
+    cx.update_editor(|editor, cx| editor.add_cursor_below(&AddCursorBelow, cx));
+    cx.assert_editor_state(indoc! {"
+        abcdef
+        abcˇdef
+        abˇ
+        abcˇdef
+    "});
+
+    cx.update_editor(|editor, cx| editor.add_cursor_above(&AddCursorAbove, cx));
+    cx.assert_editor_state(indoc! {"
+        abcˇdef
+        abcˇdef
+        abˇ
+        abcˇdef
+    "});
+
+    // Undoing removes the most recently added cursors first.
+    cx.update_editor(|editor, cx| editor.undo_add_cursor(&UndoAddCursor, cx));
+    cx.assert_editor_state(indoc! {"
+        abcdef
+        abcˇdef
+        abˇ
+        abcˇdef
+    "});
+    cx.update_editor(|editor, cx| editor.undo_add_cursor(&UndoAddCursor, cx));
+    cx.update_editor(|editor, cx| editor.undo_add_cursor(&UndoAddCursor, cx));
+    cx.assert_editor_state(indoc! {"
+        abcdef
+        abcˇdef
+        ab
+        abcdef
+    "});
+
+    // Moving the cursor forgets the added cursors.
+    cx.update_editor(|editor, cx| {
+        editor.add_cursor_below(&AddCursorBelow, cx);
+        editor.move_left(&MoveLeft, cx);
+        editor.undo_add_cursor(&UndoAddCursor, cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        abcdef
+        abˇcdef
+        aˇb
+        abcdef
+    "});
+}
+
 #[gpui::test]
 async fn test_select_next(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6099,17 +7727,51 @@ async fn test_snippets(cx: &mut gpui::TestAppContext) {
             "},
         );
 
-        // As soon as the last tab stop is reached, snippet state is gone
-        editor.move_to_prev_snippet_tabstop(cx);
-        assert(
-            editor,
-            cx,
-            indoc! {"
-                a.f(one, two, three)ˇ b
-                a.f(one, two, three)ˇ b
-                a.f(one, two, three)ˇ b
-            "},
-        );
+        // As soon as the last tab stop is reached, snippet state is gone
+        editor.move_to_prev_snippet_tabstop(cx);
+        assert(
+            editor,
+            cx,
+            indoc! {"
+                a.f(one, two, three)ˇ b
+                a.f(one, two, three)ˇ b
+                a.f(one, two, three)ˇ b
+            "},
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_snippet_choices(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let (text, insertion_ranges) = marked_text_ranges(
+        indoc! {"
+            a.ˇ b
+            a.ˇ b
+        "},
+        false,
+    );
+
+    let buffer = cx.update(|cx| MultiBuffer::build_simple(&text, cx));
+    let (editor, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+
+    _ = editor.update(cx, |editor, cx| {
+        let snippet = Snippet::parse("f(${1|x,yy|})$0").unwrap();
+        editor
+            .insert_snippet(&insertion_ranges, snippet, cx)
+            .unwrap();
+
+        // The first choice is inserted, and all of them are offered.
+        assert_eq!(editor.text(cx), "a.f(x) b\na.f(x) b\n");
+        assert!(editor.context_menu_visible());
+
+        editor.confirm_completion(&ConfirmCompletion { item_ix: Some(1) }, cx);
+        assert_eq!(editor.text(cx), "a.f(yy) b\na.f(yy) b\n");
+        assert!(!editor.context_menu_visible());
+
+        assert!(editor.move_to_next_snippet_tabstop(cx));
+        assert_eq!(editor.selections.ranges::<usize>(cx), [7..7, 17..17]);
     });
 }
 
@@ -7682,6 +9344,158 @@ async fn test_completion(cx: &mut gpui::TestAppContext) {
     apply_additional_edits.await.unwrap();
 }
 
+#[gpui::test]
+async fn test_completion_documentation_resolved_lazily(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    cx.set_state("fn main() { let a = vecˇ; }");
+    let mut resolve_requests =
+        cx.handle_request::<lsp::request::ResolveCompletionItem, _, _>(|_, item, _| async move {
+            Ok(lsp::CompletionItem {
+                detail: Some("fn len(&self) -> usize".into()),
+                documentation: Some(lsp::Documentation::MarkupContent(lsp::MarkupContent {
+                    kind: lsp::MarkupKind::Markdown,
+                    value: "Returns the number of elements.\n\nRuns in constant time.".into(),
+                })),
+                ..item
+            })
+        });
+    cx.simulate_keystroke(".");
+    handle_completion_request(
+        &mut cx,
+        "fn main() { let a = vec.|<>; }",
+        vec!["len"],
+        counter.clone(),
+    )
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    resolve_requests.next().await;
+    cx.run_until_parked();
+
+    cx.update_editor(|editor, _| {
+        if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+            let completions = menu.completions.read();
+            assert_eq!(
+                completions[0].lsp_completion.detail.as_deref(),
+                Some("fn len(&self) -> usize")
+            );
+            assert!(matches!(
+                completions[0].documentation,
+                Some(Documentation::MultiLineMarkdown(_))
+            ));
+        } else {
+            panic!("expected completion menu to be open");
+        }
+    });
+}
+
+#[gpui::test]
+async fn test_completion_with_auto_import(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![":".to_string()]),
+                resolve_provider: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    cx.set_state(indoc! {"
+        fn main() {
+            HashMap:ˇ
+        }
+    "});
+    // Ensure automatic grouping doesn't occur.
+    cx.update_buffer(|buffer, _| buffer.set_group_interval(Duration::ZERO));
+    cx.simulate_keystroke(":");
+    handle_completion_request(
+        &mut cx,
+        indoc! {"
+            fn main() {
+                HashMap::|<>
+            }
+        "},
+        vec!["new"],
+        counter.clone(),
+    )
+    .await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+
+    let apply_additional_edits = cx.update_editor(|editor, cx| {
+        editor
+            .confirm_completion(&ConfirmCompletion::default(), cx)
+            .unwrap()
+    });
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            HashMap::newˇ
+        }
+    "});
+
+    // The server only computes the import when the completion is resolved.
+    handle_resolve_completion_request(
+        &mut cx,
+        Some(vec![(
+            indoc! {"
+                ˇfn main() {
+                    HashMap::new
+                }
+            "},
+            "use std::collections::HashMap;\n\n",
+        )]),
+    )
+    .await;
+    apply_additional_edits.await.unwrap();
+    cx.assert_editor_state(indoc! {"
+        use std::collections::HashMap;
+
+        fn main() {
+            HashMap::newˇ
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        assert!(!editor.highlighted_display_rows(cx).is_empty());
+    });
+
+    cx.executor()
+        .advance_clock(ADDITIONAL_EDIT_HIGHLIGHT_DURATION);
+    cx.run_until_parked();
+    cx.update_editor(|editor, cx| {
+        assert!(editor.highlighted_display_rows(cx).is_empty());
+    });
+
+    // Undoing the completion also removes the import it added.
+    cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+    cx.assert_editor_state(indoc! {"
+        fn main() {
+            HashMap::ˇ
+        }
+    "});
+}
+
 #[gpui::test]
 async fn test_completion_page_up_down_keys(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -8785,6 +10599,53 @@ async fn test_following(cx: &mut gpui::TestAppContext) {
     });
     assert_eq!(*is_still_following.borrow(), true);
 
+    // Autoscrolls aren't sent to followers, which autoscroll to the selections themselves.
+    _ = leader.update(cx, |leader, cx| {
+        let mut update = None;
+        assert!(!leader.add_event_to_update_proto(
+            &EditorEvent::ScrollPositionChanged {
+                local: true,
+                autoscroll: true,
+            },
+            &mut update,
+            cx,
+        ));
+    });
+
+    // Explicit horizontal scrolls are mirrored along with the selections.
+    _ = leader.update(cx, |leader, cx| {
+        leader.change_selections(None, cx, |s| s.select_ranges([1..1]));
+        leader.set_scroll_position(gpui::Point::new(2.5, 3.5), cx);
+    });
+    follower
+        .update(cx, |follower, cx| {
+            follower.apply_update_proto(&project, pending_update.borrow_mut().take().unwrap(), cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+    _ = follower.update(cx, |follower, cx| {
+        assert_eq!(follower.scroll_position(cx).x, 2.5);
+        assert_eq!(follower.selections.ranges(cx), vec![1..1]);
+    });
+    assert_eq!(*is_still_following.borrow(), true);
+
+    // Changing the buffer font size propagates the leader's text scale.
+    cx.update(|cx| theme::adjust_buffer_font_size(cx, |size| *size = *size * 2.));
+    follower
+        .update(cx, |follower, cx| {
+            follower.apply_update_proto(&project, pending_update.borrow_mut().take().unwrap(), cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+    _ = follower.update(cx, |follower, cx| {
+        assert_eq!(follower.leader_text_scale, Some(2.));
+        assert_eq!(follower.text_scale(cx), 2.);
+    });
+    cx.update(|cx| theme::reset_buffer_font_size(cx));
+    assert_eq!(*is_still_following.borrow(), true);
+
     // Creating a pending selection that precedes another selection
     _ = leader.update(cx, |leader, cx| {
         leader.change_selections(None, cx, |s| s.select_ranges([1..1]));
@@ -9251,6 +11112,112 @@ fn test_split_words() {
     assert_eq!(split(":do_the_thing"), &[":", "do_", "the_", "thing"]);
 }
 
+#[gpui::test]
+async fn test_selection_occurrence_highlights(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        «fooˇ» bar
+        foobar foo
+    "});
+    cx.executor()
+        .advance_clock(SELECTION_OCCURRENCES_DEBOUNCE_TIMEOUT);
+    cx.run_until_parked();
+    cx.assert_editor_background_highlights::<SelectionOccurrenceHighlight>(indoc! {"
+        «foo» bar
+        «foo»bar «foo»
+    "});
+    cx.update_editor(|editor, _| assert_eq!(editor.selection_occurrence_count(), 3));
+
+    // Selections spanning several lines don't highlight anything.
+    cx.set_state(indoc! {"
+        «foo bar
+        fooˇ»bar foo
+    "});
+    cx.assert_editor_background_highlights::<SelectionOccurrenceHighlight>(indoc! {"
+        foo bar
+        foobar foo
+    "});
+    cx.update_editor(|editor, _| assert_eq!(editor.selection_occurrence_count(), 0));
+}
+
+#[gpui::test]
+async fn test_go_to_document_highlight(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        let ˇa = 1;
+        let b = a + a;
+        a = b;
+    "});
+    cx.update_editor(|editor, cx| {
+        let buffer = editor.buffer.read(cx).snapshot(cx);
+        let anchor_range =
+            |range: Range<Point>| buffer.anchor_after(range.start)..buffer.anchor_before(range.end);
+        editor.highlight_background::<DocumentHighlightWrite>(
+            &[
+                anchor_range(Point::new(0, 4)..Point::new(0, 5)),
+                anchor_range(Point::new(2, 0)..Point::new(2, 1)),
+            ],
+            |theme| theme.editor_document_highlight_write_background,
+            cx,
+        );
+        editor.highlight_background::<DocumentHighlightRead>(
+            &[
+                anchor_range(Point::new(1, 8)..Point::new(1, 9)),
+                anchor_range(Point::new(1, 12)..Point::new(1, 13)),
+            ],
+            |theme| theme.editor_document_highlight_read_background,
+            cx,
+        );
+    });
+
+    cx.update_editor(|editor, cx| editor.go_to_document_highlight(&GoToDocumentHighlight, cx));
+    cx.assert_editor_state(indoc! {"
+        let a = 1;
+        let b = ˇa + a;
+        a = b;
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_document_highlight(&GoToDocumentHighlight, cx));
+    cx.assert_editor_state(indoc! {"
+        let a = 1;
+        let b = a + ˇa;
+        a = b;
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_document_highlight(&GoToDocumentHighlight, cx));
+    cx.assert_editor_state(indoc! {"
+        let a = 1;
+        let b = a + a;
+        ˇa = b;
+    "});
+    // Wraps around to the first occurrence.
+    cx.update_editor(|editor, cx| editor.go_to_document_highlight(&GoToDocumentHighlight, cx));
+    cx.assert_editor_state(indoc! {"
+        let ˇa = 1;
+        let b = a + a;
+        a = b;
+    "});
+
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_document_highlight(&GoToPrevDocumentHighlight, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = 1;
+        let b = a + a;
+        ˇa = b;
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.go_to_prev_document_highlight(&GoToPrevDocumentHighlight, cx)
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = 1;
+        let b = a + ˇa;
+        a = b;
+    "});
+}
+
 #[gpui::test]
 async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9301,6 +11268,64 @@ async fn test_move_to_enclosing_bracket(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_surround_selections(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_typescript(Default::default(), cx).await;
+    let mut assert = |action: SurroundSelections, before, after| {
+        let _state_context = cx.set_state(before);
+        cx.update_editor(|editor, cx| editor.surround_selections(&action, cx));
+        cx.assert_editor_state(after);
+    };
+
+    // The end defaults to the end of the language's bracket pair.
+    assert(
+        SurroundSelections {
+            start: "{".into(),
+            end: None,
+        },
+        "let a = «bˇ» + «ˇc»;",
+        "let a = {«bˇ»} + {«ˇc»};",
+    );
+    assert(
+        SurroundSelections {
+            start: "(".into(),
+            end: Some(")".into()),
+        },
+        "let a = «bˇ»;",
+        "let a = («bˇ»);",
+    );
+    // Without a matching bracket pair, the start is used on both sides.
+    assert(
+        SurroundSelections {
+            start: "*".into(),
+            end: None,
+        },
+        "let a = «bˇ»;",
+        "let a = *«bˇ»*;",
+    );
+}
+
+#[gpui::test]
+async fn test_delete_surrounding_pair(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_typescript(Default::default(), cx).await;
+    let mut assert = |before, after| {
+        let _state_context = cx.set_state(before);
+        cx.update_editor(|editor, cx| editor.delete_surrounding_pair(&DeleteSurroundingPair, cx));
+        cx.assert_editor_state(after);
+    };
+
+    assert("console.log(«varˇ»);", "console.log«varˇ»;");
+    assert("console.log(a, [1ˇ, 2]);", "console.log(a, 1ˇ, 2);");
+    // Selections inside the same pair delete it once.
+    assert("console.log(ˇa, ˇb);", "console.logˇa, ˇb;");
+    // Pairs that aren't around the whole selection are left alone.
+    assert("console.log«(varˇ»);", "console.log«(varˇ»);");
+}
+
 #[gpui::test]
 async fn test_on_type_formatting_not_triggered(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -9498,6 +11523,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             "Some other server name".into(),
             LspSettings {
                 binary: None,
+                languages: None,
                 settings: None,
                 initialization_options: Some(json!({
                     "some other init value": false
@@ -9517,6 +11543,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             language_server_name.into(),
             LspSettings {
                 binary: None,
+                languages: None,
                 settings: None,
                 initialization_options: Some(json!({
                     "anotherInitValue": false
@@ -9536,6 +11563,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             language_server_name.into(),
             LspSettings {
                 binary: None,
+                languages: None,
                 settings: None,
                 initialization_options: Some(json!({
                     "anotherInitValue": false
@@ -9555,6 +11583,7 @@ async fn test_language_server_restart_due_to_settings_change(cx: &mut gpui::Test
             language_server_name.into(),
             LspSettings {
                 binary: None,
+                languages: None,
                 settings: None,
                 initialization_options: None,
             },
@@ -13104,6 +15133,59 @@ fn test_crease_insertion_and_rendering(cx: &mut TestAppContext) {
     assert!(!snapshot.is_line_folded(MultiBufferRow(1)));
 }
 
+#[gpui::test]
+fn test_replacement_previews(cx: &mut TestAppContext) {
+    init_test(cx, |_| {});
+
+    let editor = cx.add_window(|cx| {
+        let buffer = MultiBuffer::build_simple("one two one", cx);
+        build_editor(buffer, cx)
+    });
+    _ = editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let first_match = snapshot.anchor_after(0)..snapshot.anchor_before(3);
+        let second_match = snapshot.anchor_after(8)..snapshot.anchor_before(11);
+        let previews = |first_text: &str| {
+            vec![
+                ReplacementPreview {
+                    range: first_match.clone(),
+                    new_text: first_text.to_string(),
+                },
+                ReplacementPreview {
+                    range: second_match.clone(),
+                    new_text: "three".to_string(),
+                },
+            ]
+        };
+        let inlay_ids = |editor: &Editor| {
+            editor
+                .replacement_previews
+                .iter()
+                .map(|(_, inlay_id)| *inlay_id)
+                .collect::<Vec<_>>()
+        };
+
+        editor.set_replacement_previews(previews("three"), cx);
+        assert_eq!(editor.display_text(cx), "onethree two onethree");
+        let initial_ids = inlay_ids(editor);
+
+        // Only the inlay of the changed preview is replaced.
+        editor.set_replacement_previews(previews("four"), cx);
+        assert_eq!(editor.display_text(cx), "onefour two onethree");
+        let ids = inlay_ids(editor);
+        assert_ne!(ids[0], initial_ids[0]);
+        assert_eq!(ids[1], initial_ids[1]);
+
+        editor.set_replacement_previews(previews(""), cx);
+        assert_eq!(editor.display_text(cx), "one two onethree");
+        assert_eq!(inlay_ids(editor), vec![None, initial_ids[1]]);
+
+        editor.clear_replacement_previews(cx);
+        assert_eq!(editor.display_text(cx), "one two one");
+        assert!(editor.replacement_previews.is_empty());
+    });
+}
+
 fn empty_range(row: usize, column: usize) -> Range<DisplayPoint> {
     let point = DisplayPoint::new(DisplayRow(row as u32), column as u32);
     point..point
@@ -1,11 +1,12 @@
 use crate::{
     blame_entry_tooltip::{blame_entry_relative_timestamp, BlameEntryTooltip},
+    diagnostic_style,
     display_map::{
         Block, BlockContext, BlockStyle, DisplaySnapshot, HighlightedChunk, ToDisplayPoint,
     },
     editor_settings::{
-        CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ShowScrollbar,
+        CurrentLineHighlight, DoubleClickInMultibuffer, FastScrollModifier, MultiCursorModifier,
+        RelativeLineNumbersMode, ScrollBeyondLastLine, ShowMinimap, ShowScrollbar,
     },
     git::{
         blame::{CommitDetails, GitBlame},
@@ -18,7 +19,7 @@ use crate::{
     hunk_status,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
-    scroll::scroll_amount::ScrollAmount,
+    scroll::{scroll_amount::ScrollAmount, Autoscroll},
     BlockId, CodeActionsMenu, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
     EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GutterDimensions, HalfPageDown,
@@ -34,11 +35,11 @@ use gpui::{
     anchored, deferred, div, fill, outline, point, px, quad, relative, size, svg,
     transparent_black, Action, AnchorCorner, AnyElement, AvailableSpace, Bounds, ClipboardItem,
     ContentMask, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler, Entity,
-    EntityId, FontId, GlobalElementId, Hitbox, Hsla, InteractiveElement, IntoElement, Length,
-    ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad,
-    ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine, SharedString, Size,
-    StatefulInteractiveElement, Style, Styled, TextRun, TextStyle, TextStyleRefinement, View,
-    ViewContext, WeakView, WindowContext,
+    EntityId, FontId, GlobalElementId, HighlightStyle, Hitbox, Hsla, InteractiveElement,
+    IntoElement, Length, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, PaintQuad, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine,
+    SharedString, Size, StatefulInteractiveElement, Style, Styled, StyledText, TextRun, TextStyle,
+    TextStyleRefinement, View, ViewContext, WeakView, WindowContext,
 };
 use itertools::Itertools;
 use language::language_settings::{
@@ -63,7 +64,7 @@ use std::{
     sync::Arc,
 };
 use sum_tree::Bias;
-use theme::{ActiveTheme, PlayerColor};
+use theme::{ActiveTheme, PlayerColor, ThemeSettings};
 use ui::prelude::*;
 use ui::{h_flex, ButtonLike, ButtonStyle, ContextMenu, Tooltip};
 use util::RangeExt;
@@ -189,6 +190,8 @@ impl EditorElement {
         register_action(view, cx, Editor::join_lines);
         register_action(view, cx, Editor::sort_lines_case_sensitive);
         register_action(view, cx, Editor::sort_lines_case_insensitive);
+        register_action(view, cx, Editor::sort_lines_descending);
+        register_action(view, cx, Editor::sort_lines_numerically);
         register_action(view, cx, Editor::reverse_lines);
         register_action(view, cx, Editor::shuffle_lines);
         register_action(view, cx, Editor::convert_to_upper_case);
@@ -199,6 +202,8 @@ impl EditorElement {
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
         register_action(view, cx, Editor::convert_to_opposite_case);
+        register_action(view, cx, Editor::convert_to_upper_snake_case);
+        register_action(view, cx, Editor::convert_to_next_case);
         register_action(view, cx, Editor::delete_to_previous_word_start);
         register_action(view, cx, Editor::delete_to_previous_subword_start);
         register_action(view, cx, Editor::delete_to_next_word_end);
@@ -211,9 +216,15 @@ impl EditorElement {
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
+        register_action(view, cx, Editor::transpose_words);
+        register_action(view, cx, Editor::surround_selections);
+        register_action(view, cx, Editor::delete_surrounding_pair);
+        register_action(view, cx, Editor::increment);
+        register_action(view, cx, Editor::decrement);
         register_action(view, cx, Editor::cut);
         register_action(view, cx, Editor::copy);
         register_action(view, cx, Editor::paste);
+        register_action(view, cx, Editor::paste_as_cursors);
         register_action(view, cx, Editor::undo);
         register_action(view, cx, Editor::redo);
         register_action(view, cx, Editor::move_page_up);
@@ -222,12 +233,19 @@ impl EditorElement {
         register_action(view, cx, Editor::scroll_cursor_top);
         register_action(view, cx, Editor::scroll_cursor_center);
         register_action(view, cx, Editor::scroll_cursor_bottom);
+        register_action(view, cx, Editor::scroll_cursor_to_viewport_percent);
+        register_action(view, cx, Editor::scroll_cursor_top_with_context);
+        register_action(view, cx, Editor::scroll_cursor_bottom_with_context);
+        register_action(view, cx, Editor::scroll_history_back);
+        register_action(view, cx, Editor::scroll_history_forward);
         register_action(view, cx, |editor, _: &LineDown, cx| {
             editor.scroll_screen(&ScrollAmount::Line(1.), cx)
         });
         register_action(view, cx, |editor, _: &LineUp, cx| {
             editor.scroll_screen(&ScrollAmount::Line(-1.), cx)
         });
+        register_action(view, cx, Editor::scroll_down_by_lines);
+        register_action(view, cx, Editor::scroll_up_by_lines);
         register_action(view, cx, |editor, _: &HalfPageDown, cx| {
             editor.scroll_screen(&ScrollAmount::Page(0.5), cx)
         });
@@ -254,6 +272,10 @@ impl EditorElement {
         register_action(view, cx, Editor::select_down);
         register_action(view, cx, Editor::select_left);
         register_action(view, cx, Editor::select_right);
+        register_action(view, cx, Editor::select_column_up);
+        register_action(view, cx, Editor::select_column_down);
+        register_action(view, cx, Editor::select_column_left);
+        register_action(view, cx, Editor::select_column_right);
         register_action(view, cx, Editor::select_to_previous_word_start);
         register_action(view, cx, Editor::select_to_previous_subword_start);
         register_action(view, cx, Editor::select_to_next_word_end);
@@ -270,6 +292,9 @@ impl EditorElement {
         });
         register_action(view, cx, Editor::select_line);
         register_action(view, cx, Editor::split_selection_into_lines);
+        register_action(view, cx, Editor::add_cursor_above);
+        register_action(view, cx, Editor::add_cursor_below);
+        register_action(view, cx, Editor::undo_add_cursor);
         register_action(view, cx, Editor::add_selection_above);
         register_action(view, cx, Editor::add_selection_below);
         register_action(view, cx, |editor, action, cx| {
@@ -294,6 +319,8 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::go_to_document_highlight);
+        register_action(view, cx, Editor::go_to_prev_document_highlight);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -316,12 +343,26 @@ impl EditorElement {
                 .go_to_type_definition_split(a, cx)
                 .detach_and_log_err(cx);
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.peek_definition(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
+        register_action(view, cx, Editor::peek_next_definition);
+        register_action(view, cx, Editor::peek_previous_definition);
+        register_action(view, cx, Editor::grow_peek_view);
+        register_action(view, cx, Editor::shrink_peek_view);
         register_action(view, cx, Editor::open_url);
         register_action(view, cx, Editor::fold);
         register_action(view, cx, Editor::fold_at);
         register_action(view, cx, Editor::unfold_lines);
         register_action(view, cx, Editor::unfold_at);
         register_action(view, cx, Editor::fold_selected_ranges);
+        register_action(view, cx, Editor::toggle_fold);
+        register_action(view, cx, Editor::fold_at_level);
+        register_action(view, cx, Editor::unfold_all);
         register_action(view, cx, Editor::show_completions);
         register_action(view, cx, Editor::toggle_code_actions);
         register_action(view, cx, Editor::open_excerpts);
@@ -330,6 +371,10 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_tab_bar);
         register_action(view, cx, Editor::toggle_line_numbers);
         register_action(view, cx, Editor::toggle_indent_guides);
+        register_action(view, cx, Editor::toggle_typewriter_scrolling);
+        register_action(view, cx, Editor::toggle_overtype);
+        register_action(view, cx, Editor::exclude_rename_edit);
+        register_action(view, cx, Editor::toggle_scroll_lock);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
         register_action(view, cx, Editor::reveal_in_finder);
@@ -349,7 +394,15 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.organize_imports(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, Editor::restart_language_server);
+        register_action(view, cx, Editor::stop_language_server);
         register_action(view, cx, Editor::cancel_language_server_work);
         register_action(view, cx, Editor::show_character_palette);
         register_action(view, cx, |editor, action, cx| {
@@ -380,6 +433,13 @@ impl EditorElement {
                 cx.propagate();
             }
         });
+        register_action(view, cx, |editor, action, cx| {
+            if let Some(task) = editor.confirm_rename_preview(action, cx) {
+                task.detach_and_log_err(cx);
+            } else {
+                cx.propagate();
+            }
+        });
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.find_all_references(action, cx) {
                 task.detach_and_log_err(cx);
@@ -398,6 +458,7 @@ impl EditorElement {
         register_action(view, cx, Editor::display_cursor_names);
         register_action(view, cx, Editor::unique_lines_case_insensitive);
         register_action(view, cx, Editor::unique_lines_case_sensitive);
+        register_action(view, cx, Editor::unique_adjacent_lines);
         register_action(view, cx, Editor::accept_partial_inline_completion);
         register_action(view, cx, Editor::accept_inline_completion);
         register_action(view, cx, Editor::revert_selected_hunks);
@@ -511,12 +572,20 @@ impl EditorElement {
 
         let point_for_position =
             position_map.point_for_position(text_hitbox.bounds, event.position);
+        if click_count == 1
+            && !modifiers.modified()
+            && editor.deploy_color_picker(&point_for_position, event.position, cx)
+        {
+            return;
+        }
+
         let position = point_for_position.previous_valid;
         if modifiers.shift && modifiers.alt {
             editor.select(
                 SelectPhase::BeginColumnar {
                     position,
                     reset: false,
+                    add: false,
                     goal_column: point_for_position.exact_unclipped.column(),
                 },
                 cx,
@@ -544,6 +613,15 @@ impl EditorElement {
                 },
                 cx,
             );
+            if click_count == 1
+                && point_for_position.column_overshoot_after_line_end > 0
+                && EditorSettings::get_global(cx).virtual_space
+            {
+                editor.place_newest_cursor_in_virtual_space(
+                    point_for_position.column_overshoot_after_line_end,
+                    cx,
+                );
+            }
         }
 
         cx.stop_propagation();
@@ -581,6 +659,19 @@ impl EditorElement {
             return;
         }
 
+        if EditorSettings::get_global(cx).middle_click_autoscroll && editor.mode == EditorMode::Full
+        {
+            editor.start_middle_click_autoscroll(
+                event.position,
+                position_map.line_height,
+                position_map.em_width,
+                position_map.scroll_max,
+                cx,
+            );
+            cx.stop_propagation();
+            return;
+        }
+
         let point_for_position =
             position_map.point_for_position(text_hitbox.bounds, event.position);
         let position = point_for_position.previous_valid;
@@ -589,6 +680,7 @@ impl EditorElement {
             SelectPhase::BeginColumnar {
                 position,
                 reset: true,
+                add: false,
                 goal_column: point_for_position.exact_unclipped.column(),
             },
             cx,
@@ -602,8 +694,21 @@ impl EditorElement {
         text_hitbox: &Hitbox,
         cx: &mut ViewContext<Editor>,
     ) {
+        if editor.scroll_manager.is_middle_click_autoscrolling() {
+            // Releasing the button after dragging with it held ends the autoscroll,
+            // while a plain click keeps it going until the next click.
+            if event.button == MouseButton::Middle
+                && editor.scroll_manager.has_middle_click_autoscroll_moved()
+            {
+                editor.stop_middle_click_autoscroll(cx);
+            }
+            cx.stop_propagation();
+            return;
+        }
+
         let end_selection = editor.has_pending_selection();
         let pending_nonempty_selections = editor.has_pending_nonempty_selection();
+        editor.update_drag_autoscroll(None, 0, 0, cx);
 
         if end_selection {
             editor.select(SelectPhase::End, cx);
@@ -663,12 +768,21 @@ impl EditorElement {
         let vertical_margin = position_map.line_height.min(text_bounds.size.height / 3.0);
         let top = text_bounds.origin.y + vertical_margin;
         let bottom = text_bounds.lower_left().y - vertical_margin;
-        if event.position.y < top {
-            scroll_delta.y = -scale_vertical_mouse_autoscroll_delta(top - event.position.y);
-        }
-        if event.position.y > bottom {
-            scroll_delta.y = scale_vertical_mouse_autoscroll_delta(event.position.y - bottom);
-        }
+        // Scrolling vertically continues while the pointer stays past the edge, so it
+        // is left to the drag autoscroll rather than done once per mouse move.
+        let lines_past_edge = if event.position.y < top {
+            Some(-((top - event.position.y) / position_map.line_height))
+        } else if event.position.y > bottom {
+            Some((event.position.y - bottom) / position_map.line_height)
+        } else {
+            None
+        };
+        editor.update_drag_autoscroll(
+            lines_past_edge,
+            point_for_position.exact_unclipped.column(),
+            point_for_position.exact_unclipped.column(),
+            cx,
+        );
 
         let horizontal_margin = position_map.line_height.min(text_bounds.size.width / 3.0);
         let left = text_bounds.origin.x + horizontal_margin;
@@ -680,6 +794,26 @@ impl EditorElement {
             scroll_delta.x = scale_horizontal_mouse_autoscroll_delta(event.position.x - right);
         }
 
+        // Dragging with alt held turns the selection into a columnar one. Alt-clicking without
+        // moving still adds a cursor when alt is the multi-cursor modifier, and the other
+        // selections are kept while the multi-cursor modifier is held.
+        if event.modifiers.alt && !editor.has_columnar_selection() {
+            let add = match EditorSettings::get_global(cx).multi_cursor_modifier {
+                MultiCursorModifier::Alt => true,
+                MultiCursorModifier::CmdOrCtrl => event.modifiers.secondary(),
+            };
+            editor.select(
+                SelectPhase::BeginColumnar {
+                    position: point_for_position.previous_valid,
+                    reset: false,
+                    add,
+                    goal_column: point_for_position.exact_unclipped.column(),
+                },
+                cx,
+            );
+            return;
+        }
+
         editor.select(
             SelectPhase::Update {
                 position: point_for_position.previous_valid,
@@ -959,6 +1093,48 @@ impl EditorElement {
         cursors
     }
 
+    /// Returns the display rows each collaborator is looking at, along with the lane
+    /// of the scrollbar their band is drawn in.
+    fn collect_collaborator_viewports(
+        &self,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<(usize, ColoredRange<DisplayRow>)> {
+        if !EditorSettings::get_global(cx).scrollbar.collaborators {
+            return Vec::new();
+        }
+        let editor = self.editor.read(cx);
+        let Some(collaboration_hub) = &editor.collaboration_hub else {
+            return Vec::new();
+        };
+
+        let mut lanes = HashMap::default();
+        snapshot
+            .remote_viewports(collaboration_hub.as_ref(), cx)
+            // While following someone, their viewport is the one this editor shows.
+            .filter(|viewport| Some(viewport.peer_id) != editor.leader_peer_id)
+            .map(|viewport| {
+                let lane_count = lanes.len();
+                let lane = *lanes.entry(viewport.replica_id).or_insert(lane_count);
+                let color = Self::get_participant_color(viewport.participant_index, cx);
+                let range = ColoredRange {
+                    start: viewport
+                        .range
+                        .start
+                        .to_display_point(&snapshot.display_snapshot)
+                        .row(),
+                    end: viewport
+                        .range
+                        .end
+                        .to_display_point(&snapshot.display_snapshot)
+                        .row(),
+                    color: color.cursor,
+                };
+                (lane, range)
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_visible_cursors(
         &self,
@@ -978,6 +1154,7 @@ impl EditorElement {
         let mut autoscroll_bounds = None;
         let cursor_layouts = self.editor.update(cx, |editor, cx| {
             let mut cursors = Vec::new();
+            let virtual_space_cursors = editor.virtual_space.cursors(&snapshot.display_snapshot);
             for (player_color, selections) in selections {
                 for selection in selections {
                     let cursor_position = selection.head;
@@ -991,12 +1168,20 @@ impl EditorElement {
                         [cursor_position.row().minus(visible_display_row_range.start) as usize];
                     let cursor_column = cursor_position.column() as usize;
 
-                    let cursor_character_x = cursor_row_layout.x_for_index(cursor_column);
+                    let mut cursor_character_x = cursor_row_layout.x_for_index(cursor_column);
                     let mut block_width =
                         cursor_row_layout.x_for_index(cursor_column + 1) - cursor_character_x;
                     if block_width == Pixels::ZERO {
                         block_width = em_width;
                     }
+                    if selection.is_local {
+                        if let Some((_, columns)) = virtual_space_cursors
+                            .iter()
+                            .find(|(position, _)| *position == cursor_position)
+                        {
+                            cursor_character_x += em_width * *columns as f32;
+                        }
+                    }
                     let block_text = if let CursorShape::Block = selection.cursor_shape {
                         snapshot.display_chars_at(cursor_position).next().and_then(
                             |(character, _)| {
@@ -1170,6 +1355,325 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_horizontal_scrollbar(
+        &self,
+        bounds: Bounds<Pixels>,
+        text_bounds: Bounds<Pixels>,
+        minimap_width: Pixels,
+        scrollbar_height: Pixels,
+        scroll_position: gpui::Point<f32>,
+        scroll_max_x: f32,
+        em_width: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<HorizontalScrollbarLayout> {
+        if scrollbar_height == Pixels::ZERO || scroll_max_x <= 0. {
+            return None;
+        }
+
+        let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
+
+        let visible = match scrollbar_settings.show {
+            ShowScrollbar::Auto | ShowScrollbar::System => {
+                self.editor.read(cx).scroll_manager.scrollbars_visible()
+            }
+            ShowScrollbar::Always => true,
+            ShowScrollbar::Never => return None,
+        };
+
+        // If a drag took place after we started dragging the scrollbar,
+        // cancel the scrollbar drag.
+        if cx.has_active_drag() {
+            self.editor.update(cx, |editor, cx| {
+                editor
+                    .scroll_manager
+                    .set_is_dragging_horizontal_scrollbar(false, cx);
+            });
+        }
+
+        let track_bounds = Bounds::from_corners(
+            point(text_bounds.left(), bounds.lower_left().y - scrollbar_height),
+            point(
+                self.scrollbar_left(&bounds) - minimap_width,
+                bounds.lower_left().y,
+            ),
+        );
+
+        let columns_per_page = text_bounds.size.width / em_width;
+        let total_columns = scroll_max_x + columns_per_page;
+        let width = track_bounds.size.width;
+        let px_per_column = width / total_columns;
+        let thumb_width =
+            (columns_per_page * px_per_column).max(HorizontalScrollbarLayout::MIN_THUMB_WIDTH);
+        let column_width = (width - thumb_width) / scroll_max_x;
+
+        Some(HorizontalScrollbarLayout {
+            hitbox: cx.insert_hitbox(track_bounds, false),
+            visible_column_range: scroll_position.x..scroll_position.x + columns_per_page,
+            column_width,
+            visible,
+            thumb_width,
+        })
+    }
+
+    fn layout_sticky_headers(
+        &self,
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+        gutter_hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        line_height: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<StickyHeadersLayout> {
+        let settings = EditorSettings::get_global(cx).sticky_scroll;
+        if snapshot.mode != EditorMode::Full || !settings.enabled || settings.max_lines == 0 {
+            return None;
+        }
+
+        let top_row = DisplayRow(scroll_position.y as u32);
+        let top_offset = DisplayPoint::new(top_row, 0).to_offset(snapshot, Bias::Left);
+        let (_, items) = snapshot
+            .buffer_snapshot
+            .symbols_containing(top_offset, None)?;
+
+        let mut headers = Vec::new();
+        let mut overlap = Pixels::ZERO;
+        for item in items {
+            if headers.len() >= settings.max_lines {
+                break;
+            }
+
+            // The first row of text that remains visible below the headers pinned so far.
+            let first_visible_row = top_row + DisplayRow(headers.len() as u32);
+            let start_row = item.range.start.to_display_point(snapshot).row();
+            let end_row = item.range.end.to_display_point(snapshot).row();
+            if start_row >= first_visible_row || end_row < first_visible_row {
+                break;
+            }
+
+            // When the scope ends on the row under its header, push the header up
+            // together with that row so that it scrolls out of view smoothly.
+            let offset = if end_row == first_visible_row {
+                line_height * scroll_position.y.fract()
+            } else {
+                Pixels::ZERO
+            };
+            overlap = offset;
+
+            headers.push(StickyHeader {
+                line: layout_line(start_row, snapshot, &self.style, cx),
+                target: item.range.start,
+                offset,
+            });
+        }
+
+        if headers.is_empty() {
+            return None;
+        }
+
+        let height = line_height * headers.len() as f32 - overlap;
+        let bounds = Bounds::from_corners(
+            point(gutter_hitbox.left(), text_hitbox.top()),
+            point(text_hitbox.right(), text_hitbox.top() + height),
+        );
+
+        Some(StickyHeadersLayout {
+            hitbox: cx.insert_hitbox(bounds, true),
+            headers,
+        })
+    }
+
+    /// The height reserved below the text for the horizontal scrollbar, so that it doesn't
+    /// cover the last visible line. It is reserved whenever lines can be wider than the
+    /// editor, even while they all fit, so that the text doesn't move when one stops fitting.
+    fn horizontal_scrollbar_height(&self, snapshot: &EditorSnapshot, cx: &WindowContext) -> Pixels {
+        let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
+        let lines_can_overflow = matches!(
+            self.editor.read(cx).soft_wrap_mode(cx),
+            SoftWrap::None | SoftWrap::PreferLine
+        );
+        if snapshot.mode == EditorMode::Full
+            && scrollbar_settings.horizontal
+            && scrollbar_settings.show != ShowScrollbar::Never
+            && lines_can_overflow
+        {
+            self.style.scrollbar_width
+        } else {
+            Pixels::ZERO
+        }
+    }
+
+    fn minimap_width(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        line_height: Pixels,
+        cx: &WindowContext,
+    ) -> Pixels {
+        if snapshot.mode != EditorMode::Full
+            || bounds.size.width < MinimapLayout::WIDTH * MinimapLayout::MIN_EDITOR_WIDTH_RATIO
+        {
+            return Pixels::ZERO;
+        }
+
+        let show_minimap = match EditorSettings::get_global(cx).minimap.show {
+            ShowMinimap::Auto => {
+                snapshot.max_point().row().next_row().as_f32() > bounds.size.height / line_height
+            }
+            ShowMinimap::Always => true,
+            ShowMinimap::Never => false,
+        };
+        if show_minimap {
+            MinimapLayout::WIDTH
+        } else {
+            Pixels::ZERO
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        minimap_width: Pixels,
+        scroll_position: gpui::Point<f32>,
+        rows_per_page: f32,
+        max_scroll_top: f32,
+        cx: &mut WindowContext,
+    ) -> Option<MinimapLayout> {
+        if minimap_width == Pixels::ZERO {
+            return None;
+        }
+
+        let minimap_bounds = Bounds::from_corners(
+            point(
+                self.scrollbar_left(&bounds) - minimap_width,
+                bounds.origin.y,
+            ),
+            point(self.scrollbar_left(&bounds), bounds.lower_left().y),
+        );
+
+        // The minimap scrolls proportionally to the editor, so that its top and
+        // bottom line up with the top and bottom of the buffer.
+        let total_rows = snapshot.max_point().row().next_row().as_f32();
+        let minimap_rows = minimap_bounds.size.height / MinimapLayout::LINE_HEIGHT;
+        let overflowing_rows = (total_rows - minimap_rows).max(0.);
+        let (scroll_top, row_height) = if max_scroll_top > 0. {
+            let scroll_fraction = (scroll_position.y / max_scroll_top).clamp(0., 1.);
+            (
+                overflowing_rows * scroll_fraction,
+                (MinimapLayout::LINE_HEIGHT * (1. - overflowing_rows / max_scroll_top))
+                    .max(MinimapLayout::MIN_ROW_HEIGHT),
+            )
+        } else {
+            (0., MinimapLayout::LINE_HEIGHT)
+        };
+
+        let start_row = DisplayRow(scroll_top as u32);
+        let end_row = DisplayRow(
+            ((scroll_top + minimap_rows).ceil() as u32)
+                .min(snapshot.max_point().row().next_row().0),
+        );
+        let y_for_row = |row: DisplayRow| {
+            minimap_bounds.top() + (row.as_f32() - scroll_top) * MinimapLayout::LINE_HEIGHT
+        };
+
+        let max_column = ((minimap_width - MinimapLayout::HORIZONTAL_PADDING * 2.)
+            / MinimapLayout::COLUMN_WIDTH) as u32;
+        let text_quad = |row: DisplayRow, columns: Range<u32>, color: Hsla| {
+            let columns = columns.start.min(max_column)..columns.end.min(max_column);
+            if columns.is_empty() {
+                return None;
+            }
+            let left = minimap_bounds.left() + MinimapLayout::HORIZONTAL_PADDING;
+            let top = y_for_row(row);
+            Some(fill(
+                Bounds::from_corners(
+                    point(
+                        left + columns.start as f32 * MinimapLayout::COLUMN_WIDTH,
+                        top,
+                    ),
+                    point(
+                        left + columns.end as f32 * MinimapLayout::COLUMN_WIDTH,
+                        top + MinimapLayout::LINE_HEIGHT,
+                    ),
+                ),
+                color,
+            ))
+        };
+
+        let mut line_quads = Vec::new();
+        let mut row = start_row;
+        let mut column = 0;
+        for chunk in snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let mut color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            color.fade_out(0.3);
+
+            for (ix, line) in chunk.text.split('\n').enumerate() {
+                if ix > 0 {
+                    row.0 += 1;
+                    column = 0;
+                }
+
+                let mut run_start = None;
+                for ch in line.chars() {
+                    if ch.is_whitespace() {
+                        if let Some(run_start) = run_start.take() {
+                            line_quads.extend(text_quad(row, run_start..column, color));
+                        }
+                    } else if run_start.is_none() {
+                        run_start = Some(column);
+                    }
+                    column += 1;
+                }
+                if let Some(run_start) = run_start {
+                    line_quads.extend(text_quad(row, run_start..column, color));
+                }
+            }
+        }
+
+        let selection_color = self.style.local_player.selection;
+        let selection_quads = self
+            .editor
+            .read(cx)
+            .selections
+            .disjoint_anchors()
+            .iter()
+            .filter_map(|selection| {
+                let start = selection
+                    .start
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                let end = selection
+                    .end
+                    .to_display_point(&snapshot.display_snapshot)
+                    .row();
+                if end < start_row || start >= end_row {
+                    return None;
+                }
+                Some(fill(
+                    Bounds::from_corners(
+                        point(minimap_bounds.left(), y_for_row(start)),
+                        point(minimap_bounds.right(), y_for_row(end.next_row())),
+                    ),
+                    selection_color,
+                ))
+            })
+            .collect();
+
+        Some(MinimapLayout {
+            hitbox: cx.insert_hitbox(minimap_bounds, false),
+            scroll_top,
+            visible_row_range: scroll_position.y..scroll_position.y + rows_per_page,
+            row_height,
+            line_quads,
+            selection_quads,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn prepaint_gutter_fold_toggles(
         &self,
@@ -1300,6 +1804,87 @@ impl EditorElement {
         display_hunks
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_inline_diagnostics(
+        &self,
+        start_row: DisplayRow,
+        line_layouts: &[LineWithInvisibles],
+        crease_trailers: &[Option<CreaseTrailerLayout>],
+        snapshot: &EditorSnapshot,
+        em_width: Pixels,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        text_bounds: Bounds<Pixels>,
+        cx: &mut WindowContext,
+    ) -> HashMap<DisplayRow, AnyElement> {
+        const INLINE_DIAGNOSTIC_PADDING_EM_WIDTHS: f32 = 4.;
+
+        let mut inline_diagnostics = HashMap::default();
+        let settings = EditorSettings::get_global(cx).inline_diagnostics;
+        if !settings.enabled || !matches!(self.editor.read(cx).mode, EditorMode::Full) {
+            return inline_diagnostics;
+        }
+
+        for (ix, line_layout) in line_layouts.iter().enumerate() {
+            let display_row = start_row + DisplayRow(ix as u32);
+            let buffer_row =
+                MultiBufferRow(DisplayPoint::new(display_row, 0).to_point(snapshot).row);
+
+            // Soft wrapped lines show their diagnostic after the last wrapped row.
+            let line_end = Point::new(buffer_row.0, snapshot.buffer_snapshot.line_len(buffer_row));
+            if line_end.to_display_point(snapshot).row() != display_row {
+                continue;
+            }
+
+            let Some((buffer, line_range)) =
+                snapshot.buffer_snapshot.buffer_line_for_row(buffer_row)
+            else {
+                continue;
+            };
+            let Some(entry) = buffer
+                .diagnostics_in_range::<_, Point>(line_range.clone(), false)
+                .filter(|entry| {
+                    entry.diagnostic.is_primary
+                        && entry.range.start.row == line_range.start.row
+                        && settings.min_severity.includes(entry.diagnostic.severity)
+                })
+                .min_by_key(|entry| entry.diagnostic.severity)
+            else {
+                continue;
+            };
+
+            let line_end_x = if let Some(crease_trailer) = crease_trailers[ix].as_ref() {
+                crease_trailer.bounds.right()
+            } else {
+                content_origin.x - scroll_pixel_position.x + line_layout.width
+            };
+            let start_x = line_end_x + em_width * INLINE_DIAGNOSTIC_PADDING_EM_WIDTHS;
+            let max_chars = ((text_bounds.right() - start_x) / em_width).floor();
+            if max_chars < 5. {
+                continue;
+            }
+            let message = entry.diagnostic.message.lines().next().unwrap_or_default();
+            let message = util::truncate_and_trailoff(message, max_chars as usize);
+
+            let mut color = diagnostic_style(entry.diagnostic.severity, cx.theme().status());
+            color.fade_out(0.3);
+            let mut element = div()
+                .font_family(self.style.text.font().family)
+                .line_height(self.style.text.line_height)
+                .text_color(color)
+                .child(message)
+                .into_any();
+
+            let start_y = content_origin.y
+                + line_height * (display_row.as_f32() - scroll_pixel_position.y / line_height);
+            element.prepaint_as_root(point(start_x, start_y), AvailableSpace::min_size(), cx);
+            inline_diagnostics.insert(display_row, element);
+        }
+
+        inline_diagnostics
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_inline_blame(
         &self,
@@ -1669,6 +2254,7 @@ impl EditorElement {
         snapshot: &EditorSnapshot,
         rows: &Range<DisplayRow>,
         relative_to: Option<DisplayRow>,
+        number_relative_to: bool,
     ) -> HashMap<DisplayRow, DisplayRowDelta> {
         let mut relative_rows: HashMap<DisplayRow, DisplayRowDelta> = Default::default();
         let Some(relative_to) = relative_to else {
@@ -1700,6 +2286,9 @@ impl EditorElement {
         while i > 0 && buffer_rows[i as usize].is_none() {
             i -= 1;
         }
+        if number_relative_to && rows.contains(&DisplayRow(i + start.0)) {
+            relative_rows.insert(DisplayRow(i + start.0), 0);
+        }
 
         while i > 0 {
             i -= 1;
@@ -1746,13 +2335,18 @@ impl EditorElement {
         });
         let font_size = self.style.text.font_size.to_pixels(cx.rem_size());
 
-        let is_relative = EditorSettings::get_global(cx).relative_line_numbers;
-        let relative_to = if is_relative {
-            Some(newest_selection_head.row())
-        } else {
+        let relative_mode = EditorSettings::get_global(cx).relative_line_numbers.mode();
+        let relative_to = if relative_mode == RelativeLineNumbersMode::Disabled {
             None
+        } else {
+            Some(newest_selection_head.row())
         };
-        let relative_rows = self.calculate_relative_line_numbers(snapshot, &rows, relative_to);
+        let relative_rows = self.calculate_relative_line_numbers(
+            snapshot,
+            &rows,
+            relative_to,
+            relative_mode == RelativeLineNumbersMode::Relative,
+        );
         let mut line_number = String::new();
         buffer_rows
             .into_iter()
@@ -2646,18 +3240,150 @@ impl EditorElement {
         true
     }
 
-    fn layout_mouse_context_menu(
+    fn layout_scrollbar_drag_tooltip(
         &self,
-        editor_snapshot: &EditorSnapshot,
-        visible_range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        scrollbar_layout: Option<&ScrollbarLayout>,
+        text_hitbox: &Hitbox,
+        scroll_position: gpui::Point<f32>,
         cx: &mut WindowContext,
     ) -> Option<AnyElement> {
-        let position = self.editor.update(cx, |editor, cx| {
-            let visible_start_point = editor.display_to_pixel_point(
-                DisplayPoint::new(visible_range.start, 0),
-                editor_snapshot,
-                cx,
-            )?;
+        const GAP: Pixels = px(4.);
+
+        let scrollbar_layout = scrollbar_layout?;
+        if !self.editor.read(cx).scroll_manager.is_dragging_scrollbar() {
+            return None;
+        }
+
+        let (line_label, symbol) = Self::scrollbar_drag_tooltip_label(snapshot, scroll_position);
+        let text_color = cx.theme().colors().text;
+        let muted_text_color = cx.theme().colors().text_muted;
+        let mut element = h_flex()
+            .elevation_2(cx)
+            .font(ThemeSettings::get_global(cx).ui_font.clone())
+            .text_ui_sm(cx)
+            .text_color(text_color)
+            .px_1p5()
+            .py_0p5()
+            .gap_1()
+            .whitespace_nowrap()
+            .child(line_label)
+            .children(symbol.map(|symbol| div().text_color(muted_text_color).child(symbol)))
+            .into_any_element();
+        let size = element.layout_as_root(AvailableSpace::min_size(), cx);
+
+        let thumb_bounds = scrollbar_layout.thumb_bounds();
+        let x = (scrollbar_layout.hitbox.left() - GAP - size.width).max(text_hitbox.left());
+        let y = (thumb_bounds.center().y - size.height / 2.)
+            .min(text_hitbox.bottom() - size.height)
+            .max(text_hitbox.top());
+        element.prepaint_at(point(x, y), cx);
+        Some(element)
+    }
+
+    /// Returns the line at the top of the viewport and the innermost outline item it is in.
+    fn scrollbar_drag_tooltip_label(
+        snapshot: &EditorSnapshot,
+        scroll_position: gpui::Point<f32>,
+    ) -> (String, Option<String>) {
+        let top_display_row = DisplayRow(scroll_position.y.max(0.) as u32);
+        let top_offset = DisplayPoint::new(top_display_row, 0).to_offset(snapshot, Bias::Left);
+        let top_row = DisplayPoint::new(top_display_row, 0).to_point(snapshot).row;
+        let symbol = snapshot
+            .buffer_snapshot
+            .symbols_containing(top_offset, None)
+            .and_then(|(_, items)| items.into_iter().last())
+            .map(|item| item.text);
+        (format!("Line {}", top_row + 1), symbol)
+    }
+
+    fn layout_scrollbar_preview(
+        &self,
+        snapshot: &EditorSnapshot,
+        scrollbar_layout: Option<&ScrollbarLayout>,
+        text_hitbox: &Hitbox,
+        line_height: Pixels,
+        em_width: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        const GAP: Pixels = px(4.);
+
+        let scrollbar_layout = scrollbar_layout.filter(|layout| layout.visible)?;
+        if !EditorSettings::get_global(cx).scrollbar.preview {
+            return None;
+        }
+        let row = self
+            .editor
+            .read(cx)
+            .scroll_manager
+            .scrollbar_preview_row()?
+            .min(snapshot.max_point().row());
+        let (text, highlights) = self.scrollbar_preview_text(snapshot, row);
+
+        let mut element = div()
+            .elevation_2(cx)
+            .px_1()
+            .max_w((80. * em_width).min(text_hitbox.size.width / 2.))
+            .overflow_hidden()
+            .whitespace_nowrap()
+            .font(self.style.text.font())
+            .text_size(self.style.text.font_size)
+            .line_height(line_height)
+            .child(StyledText::new(text).with_highlights(&self.style.text, highlights))
+            .into_any_element();
+        let size = element.layout_as_root(AvailableSpace::min_size(), cx);
+
+        let x = (scrollbar_layout.hitbox.left() - GAP - size.width).max(text_hitbox.left());
+        let y = (scrollbar_layout.y_for_row(row.as_f32()) - size.height / 2.)
+            .min(text_hitbox.bottom() - size.height)
+            .max(text_hitbox.top());
+        element.prepaint_at(point(x, y), cx);
+        Some(element)
+    }
+
+    /// Returns the highlighted text of the given row, along with a few lines of context
+    /// around it.
+    fn scrollbar_preview_text(
+        &self,
+        snapshot: &EditorSnapshot,
+        row: DisplayRow,
+    ) -> (String, Vec<(Range<usize>, HighlightStyle)>) {
+        // The number of lines shown above and below the previewed one.
+        const CONTEXT_LINES: u32 = 2;
+
+        let rows = DisplayRow(row.0.saturating_sub(CONTEXT_LINES))
+            ..DisplayRow((row.0 + CONTEXT_LINES).min(snapshot.max_point().row().0) + 1);
+        let mut text = String::new();
+        let mut highlights = Vec::new();
+        for chunk in snapshot.highlighted_chunks(rows, true, &self.style) {
+            let start = text.len();
+            text.push_str(chunk.text);
+            if let Some(style) = chunk.style {
+                highlights.push((start..text.len(), style));
+            }
+        }
+        let text_len = text.trim_end_matches('\n').len();
+        text.truncate(text_len);
+        let highlights = highlights
+            .into_iter()
+            .filter(|(range, _)| range.start < text_len)
+            .map(|(range, style)| (range.start..range.end.min(text_len), style))
+            .collect();
+        (text, highlights)
+    }
+
+    fn layout_mouse_context_menu(
+        &self,
+        editor_snapshot: &EditorSnapshot,
+        visible_range: Range<DisplayRow>,
+        cx: &mut WindowContext,
+    ) -> Option<AnyElement> {
+        let position = self.editor.update(cx, |editor, cx| {
+            let visible_start_point = editor.display_to_pixel_point(
+                DisplayPoint::new(visible_range.start, 0),
+                editor_snapshot,
+                cx,
+            )?;
             let visible_end_point = editor.display_to_pixel_point(
                 DisplayPoint::new(visible_range.end, 0),
                 editor_snapshot,
@@ -3358,9 +4084,10 @@ impl EditorElement {
                 bounds: layout.text_hitbox.bounds,
             }),
             |cx| {
-                let cursor_style = if self
-                    .editor
-                    .read(cx)
+                let editor = self.editor.read(cx);
+                let cursor_style = if editor.scroll_manager.is_middle_click_autoscrolling() {
+                    CursorStyle::ResizeUpDown
+                } else if editor
                     .hovered_link_state
                     .as_ref()
                     .is_some_and(|hovered_link_state| !hovered_link_state.links.is_empty())
@@ -3375,6 +4102,7 @@ impl EditorElement {
                 self.paint_lines(&invisible_display_ranges, layout, cx);
                 self.paint_redactions(layout, cx);
                 self.paint_cursors(layout, cx);
+                self.paint_inline_diagnostics(layout, cx);
                 self.paint_inline_blame(layout, cx);
                 cx.with_element_namespace("crease_trailers", |cx| {
                     for trailer in layout.crease_trailers.iter_mut().flatten() {
@@ -3487,6 +4215,195 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_headers(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(sticky_headers) = layout.sticky_headers.as_ref() else {
+            return;
+        };
+
+        let line_height = layout.position_map.line_height;
+        let bounds = sticky_headers.hitbox.bounds;
+        cx.paint_layer(bounds, |cx| {
+            cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                cx.paint_quad(fill(bounds, self.style.background));
+
+                for (ix, header) in sticky_headers.headers.iter().enumerate() {
+                    let mut fragment_origin = point(
+                        layout.content_origin.x - layout.position_map.scroll_pixel_position.x,
+                        bounds.top() + line_height * ix as f32 - header.offset,
+                    );
+                    for fragment in &header.line.fragments {
+                        match fragment {
+                            LineFragment::Text(line) => {
+                                line.paint(fragment_origin, line_height, cx).log_err();
+                                fragment_origin.x += line.width;
+                            }
+                            LineFragment::Element { size, .. } => {
+                                fragment_origin.x += size.width;
+                            }
+                        }
+                    }
+                }
+            });
+
+            cx.paint_quad(fill(
+                Bounds::from_corners(
+                    point(bounds.left(), bounds.bottom() - px(1.)),
+                    bounds.lower_right(),
+                ),
+                cx.theme().colors().border_variant,
+            ));
+        });
+        cx.set_cursor_style(CursorStyle::PointingHand, &sticky_headers.hitbox);
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = sticky_headers.hitbox.clone();
+            let targets = sticky_headers
+                .headers
+                .iter()
+                .map(|header| (header.target, header.offset))
+                .collect::<Vec<_>>();
+            move |event: &MouseDownEvent, phase, cx| {
+                if phase == DispatchPhase::Capture
+                    || event.button != MouseButton::Left
+                    || !hitbox.is_hovered(cx)
+                {
+                    return;
+                }
+
+                let y = event.position.y - hitbox.top();
+                let Some((ix, (target, _))) = targets
+                    .iter()
+                    .enumerate()
+                    .find(|(ix, (_, offset))| y < line_height * (*ix + 1) as f32 - *offset)
+                else {
+                    return;
+                };
+
+                // Keep the declaration visible below the headers of its enclosing scopes.
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::top_relative(ix)), cx, |s| {
+                        s.select_ranges([*target..*target]);
+                    });
+                });
+                cx.stop_propagation();
+            }
+        });
+    }
+
+    fn paint_minimap(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap_layout) = layout.minimap_layout.as_ref() else {
+            return;
+        };
+
+        let thumb_bounds = minimap_layout.thumb_bounds();
+        cx.paint_layer(minimap_layout.hitbox.bounds, |cx| {
+            cx.with_content_mask(
+                Some(ContentMask {
+                    bounds: minimap_layout.hitbox.bounds,
+                }),
+                |cx| {
+                    cx.paint_quad(fill(minimap_layout.hitbox.bounds, self.style.background));
+                    for quad in minimap_layout
+                        .selection_quads
+                        .iter()
+                        .chain(&minimap_layout.line_quads)
+                    {
+                        cx.paint_quad(quad.clone());
+                    }
+
+                    let mut thumb_color = cx.theme().colors().scrollbar_thumb_background;
+                    thumb_color.fade_out(0.5);
+                    cx.paint_quad(quad(
+                        thumb_bounds,
+                        Corners::default(),
+                        thumb_color,
+                        Edges::all(ScrollbarLayout::BORDER_WIDTH),
+                        cx.theme().colors().scrollbar_thumb_border,
+                    ));
+                },
+            );
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &minimap_layout.hitbox);
+
+        let row_height = minimap_layout.row_height;
+        let scroll_top = minimap_layout.scroll_top;
+        let rows_per_page =
+            minimap_layout.visible_row_range.end - minimap_layout.visible_row_range.start;
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let mut mouse_position = cx.mouse_position();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if event.pressed_button == Some(MouseButton::Left)
+                        && editor.scroll_manager.is_dragging_minimap()
+                    {
+                        let mut position = editor.scroll_position(cx);
+                        position.y += (event.position.y - mouse_position.y) / row_height;
+                        if position.y < 0.0 {
+                            position.y = 0.0;
+                        }
+                        editor.set_scroll_position(position, cx);
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self.editor.read(cx).scroll_manager.is_dragging_minimap() {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let hitbox = minimap_layout.hitbox.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_is_dragging_minimap(true, cx);
+
+                        let y = event.position.y;
+                        if y < thumb_bounds.top() || thumb_bounds.bottom() < y {
+                            let center_row =
+                                scroll_top + (y - hitbox.top()) / MinimapLayout::LINE_HEIGHT;
+                            let mut position = editor.scroll_position(cx);
+                            position.y = (center_row - rows_per_page / 2.).max(0.);
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn paint_scrollbar(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         let Some(scrollbar_layout) = layout.scrollbar_layout.as_ref() else {
             return;
@@ -3532,6 +4449,12 @@ impl EditorElement {
                     },
                     cx.theme().colors().scrollbar_thumb_border,
                 ));
+
+                for band in
+                    scrollbar_layout.collaborator_viewport_quads(&layout.collaborator_viewports)
+                {
+                    cx.paint_quad(band);
+                }
             });
         }
 
@@ -3539,6 +4462,8 @@ impl EditorElement {
 
         let row_height = scrollbar_layout.row_height;
         let row_range = scrollbar_layout.visible_row_range.clone();
+        let show_preview =
+            scrollbar_layout.visible && EditorSettings::get_global(cx).scrollbar.preview;
 
         cx.on_mouse_event({
             let editor = self.editor.clone();
@@ -3564,12 +4489,21 @@ impl EditorElement {
                             editor.set_scroll_position(position, cx);
                         }
 
+                        editor.scroll_manager.set_scrollbar_preview_row(None, cx);
                         cx.stop_propagation();
                     } else {
                         editor.scroll_manager.set_is_dragging_scrollbar(false, cx);
                         if hitbox.is_hovered(cx) {
                             editor.scroll_manager.show_scrollbar(cx);
                         }
+                        let preview_row = (show_preview && hitbox.is_hovered(cx)).then(|| {
+                            DisplayRow(
+                                ((event.position.y - hitbox.top()) / row_height).max(0.) as u32
+                            )
+                        });
+                        editor
+                            .scroll_manager
+                            .set_scrollbar_preview_row(preview_row, cx);
                     }
                     mouse_position = event.position;
                 })
@@ -3600,9 +4534,26 @@ impl EditorElement {
                     }
 
                     editor.update(cx, |editor, cx| {
+                        let y = event.position.y;
+                        if y < thumb_bounds.top() || thumb_bounds.bottom() < y {
+                            if let Some(search_match) = Self::search_match_for_scrollbar_position(
+                                editor,
+                                y - hitbox.top(),
+                                row_height,
+                                cx,
+                            ) {
+                                editor.unfold_ranges([search_match.clone()], false, false, cx);
+                                let range = editor.range_for_match(&search_match);
+                                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                    s.select_ranges([range]);
+                                });
+                                cx.stop_propagation();
+                                return;
+                            }
+                        }
+
                         editor.scroll_manager.set_is_dragging_scrollbar(true, cx);
 
-                        let y = event.position.y;
                         if y < thumb_bounds.top() || thumb_bounds.bottom() < y {
                             let center_row = ((y - hitbox.top()) / row_height).round() as u32;
                             let top_row = center_row
@@ -3621,6 +4572,175 @@ impl EditorElement {
         }
     }
 
+    fn paint_scrollbar_preview(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        if let Some(scrollbar_preview) = layout.scrollbar_preview.as_mut() {
+            scrollbar_preview.paint(cx);
+        }
+        if let Some(scrollbar_drag_tooltip) = layout.scrollbar_drag_tooltip.as_mut() {
+            scrollbar_drag_tooltip.paint(cx);
+        }
+    }
+
+    fn paint_horizontal_scrollbar(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        let Some(scrollbar_layout) = layout.horizontal_scrollbar_layout.as_ref() else {
+            return;
+        };
+
+        let thumb_bounds = scrollbar_layout.thumb_bounds();
+        if scrollbar_layout.visible {
+            cx.paint_layer(scrollbar_layout.hitbox.bounds, |cx| {
+                cx.paint_quad(quad(
+                    scrollbar_layout.hitbox.bounds,
+                    Corners::default(),
+                    cx.theme().colors().scrollbar_track_background,
+                    Edges {
+                        top: ScrollbarLayout::BORDER_WIDTH,
+                        right: Pixels::ZERO,
+                        bottom: Pixels::ZERO,
+                        left: Pixels::ZERO,
+                    },
+                    cx.theme().colors().scrollbar_track_border,
+                ));
+
+                cx.paint_quad(quad(
+                    thumb_bounds,
+                    Corners::default(),
+                    cx.theme().colors().scrollbar_thumb_background,
+                    Edges {
+                        top: ScrollbarLayout::BORDER_WIDTH,
+                        right: Pixels::ZERO,
+                        bottom: Pixels::ZERO,
+                        left: Pixels::ZERO,
+                    },
+                    cx.theme().colors().scrollbar_thumb_border,
+                ));
+            });
+            cx.set_cursor_style(CursorStyle::Arrow, &scrollbar_layout.hitbox);
+        }
+
+        let column_width = scrollbar_layout.column_width;
+        let column_range = scrollbar_layout.visible_column_range.clone();
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            let hitbox = scrollbar_layout.hitbox.clone();
+            let mut mouse_position = cx.mouse_position();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    if event.pressed_button == Some(MouseButton::Left)
+                        && editor.scroll_manager.is_dragging_horizontal_scrollbar()
+                    {
+                        let x = mouse_position.x;
+                        let new_x = event.position.x;
+                        if (hitbox.left()..hitbox.right()).contains(&x) {
+                            let mut position = editor.scroll_position(cx);
+                            position.x += (new_x - x) / column_width;
+                            if position.x < 0.0 {
+                                position.x = 0.0;
+                            }
+                            editor.set_scroll_position(position, cx);
+                        }
+
+                        cx.stop_propagation();
+                    } else {
+                        editor
+                            .scroll_manager
+                            .set_is_dragging_horizontal_scrollbar(false, cx);
+                        if hitbox.is_hovered(cx) {
+                            editor.scroll_manager.show_scrollbar(cx);
+                        }
+                    }
+                    mouse_position = event.position;
+                })
+            }
+        });
+
+        if self
+            .editor
+            .read(cx)
+            .scroll_manager
+            .is_dragging_horizontal_scrollbar()
+        {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor
+                            .scroll_manager
+                            .set_is_dragging_horizontal_scrollbar(false, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        } else {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let hitbox = scrollbar_layout.hitbox.clone();
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture || !hitbox.is_hovered(cx) {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor
+                            .scroll_manager
+                            .set_is_dragging_horizontal_scrollbar(true, cx);
+
+                        let x = event.position.x;
+                        if x < thumb_bounds.left() || thumb_bounds.right() < x {
+                            let center_column = (x - hitbox.left()) / column_width;
+                            let mut position = editor.scroll_position(cx);
+                            position.x = (center_column
+                                - (column_range.end - column_range.start) / 2.)
+                                .max(0.);
+                            editor.set_scroll_position(position, cx);
+                        } else {
+                            editor.scroll_manager.show_scrollbar(cx);
+                        }
+
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
+    /// Returns the buffer search match whose scrollbar marker is closest to the given
+    /// position (relative to the top of the scrollbar track), if any marker was hit.
+    fn search_match_for_scrollbar_position(
+        editor: &mut Editor,
+        y: Pixels,
+        row_height: Pixels,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<Range<Anchor>> {
+        if !EditorSettings::get_global(cx).scrollbar.search_results || !editor.is_singleton(cx) {
+            return None;
+        }
+        let (_, search_matches) = editor
+            .background_highlights
+            .get(&TypeId::of::<BufferSearchHighlights>())?;
+        let search_matches = search_matches.clone();
+        let snapshot = editor.snapshot(cx);
+        search_matches
+            .iter()
+            .filter_map(|search_match| {
+                let row = search_match.start.to_display_point(&snapshot).row();
+                let distance = (row.as_f32() * row_height - y).abs();
+                (distance <= ScrollbarLayout::MIN_MARKER_HEIGHT)
+                    .then(|| (distance, search_match.clone()))
+            })
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, search_match)| search_match)
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -3859,6 +4979,17 @@ impl EditorElement {
         }
     }
 
+    fn paint_inline_diagnostics(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
+        if layout.inline_diagnostics.is_empty() {
+            return;
+        }
+        cx.paint_layer(layout.text_hitbox.bounds, |cx| {
+            for inline_diagnostic in layout.inline_diagnostics.values_mut() {
+                inline_diagnostic.paint(cx);
+            }
+        })
+    }
+
     fn paint_inline_blame(&mut self, layout: &mut EditorLayout, cx: &mut WindowContext) {
         if let Some(mut inline_blame) = layout.inline_blame.take() {
             cx.paint_layer(layout.text_hitbox.bounds, |cx| {
@@ -3888,38 +5019,73 @@ impl EditorElement {
 
             // Set a minimum scroll_sensitivity of 0.01 to make sure the user doesn't
             // accidentally turn off their scrolling.
-            let scroll_sensitivity = EditorSettings::get_global(cx).scroll_sensitivity.max(0.01);
+            let settings = EditorSettings::get_global(cx);
+            let scroll_sensitivity = settings.scroll_sensitivity.max(0.01);
+            let trackpad_sensitivity =
+                scroll_sensitivity * settings.trackpad_scroll_sensitivity.max(0.01);
+            let mouse_wheel_sensitivity =
+                scroll_sensitivity * settings.mouse_wheel_scroll_sensitivity.max(0.01);
+            let fast_scroll_modifier = settings.fast_scroll_modifier;
+            let fast_scroll_sensitivity = settings.fast_scroll_sensitivity.max(0.01);
+            let axis_lock = settings.scroll_axis_lock;
+            let elastic_overscroll = settings.elastic_overscroll;
 
             move |event: &ScrollWheelEvent, phase, cx| {
                 if phase == DispatchPhase::Bubble && hitbox.is_hovered(cx) {
                     delta = delta.coalesce(event.delta);
+                    let fast_scroll = match fast_scroll_modifier {
+                        FastScrollModifier::Alt => event.modifiers.alt,
+                        FastScrollModifier::Shift => event.modifiers.shift,
+                        FastScrollModifier::CmdOrCtrl => event.modifiers.secondary(),
+                    };
+                    let multiplier = if fast_scroll {
+                        fast_scroll_sensitivity
+                    } else {
+                        1.
+                    };
                     editor.update(cx, |editor, cx| {
                         let position_map: &PositionMap = &position_map;
 
                         let line_height = position_map.line_height;
                         let max_glyph_width = position_map.em_width;
+                        let is_trackpad = matches!(delta, gpui::ScrollDelta::Pixels(_));
                         let (delta, axis) = match delta {
-                            gpui::ScrollDelta::Pixels(mut pixels) => {
+                            gpui::ScrollDelta::Pixels(pixels) => {
                                 //Trackpad
-                                let axis = position_map.snapshot.ongoing_scroll.filter(&mut pixels);
+                                let mut pixels = pixels * (trackpad_sensitivity * multiplier);
+                                let axis = position_map
+                                    .snapshot
+                                    .ongoing_scroll
+                                    .filter(&mut pixels, &axis_lock);
                                 (pixels, axis)
                             }
 
-                            gpui::ScrollDelta::Lines(lines) => {
+                            gpui::ScrollDelta::Lines(mut lines) => {
                                 //Not trackpad
+                                // Shift+wheel scrolls horizontally, unless shift is already
+                                // used for scrolling faster.
+                                if event.modifiers.shift
+                                    && fast_scroll_modifier != FastScrollModifier::Shift
+                                    && lines.x == 0.
+                                {
+                                    lines = point(lines.y, 0.);
+                                }
                                 let pixels =
-                                    point(lines.x * max_glyph_width, lines.y * line_height);
+                                    point(lines.x * max_glyph_width, lines.y * line_height)
+                                        * (mouse_wheel_sensitivity * multiplier);
                                 (pixels, None)
                             }
                         };
 
-                        let current_scroll_position = position_map.snapshot.scroll_position();
-                        let x = (current_scroll_position.x * max_glyph_width
-                            - (delta.x * scroll_sensitivity))
+                        // The rendered scroll position includes the elastic overscroll, so that
+                        // scrolling back first undoes the stretch.
+                        let overscroll = position_map.snapshot.overscroll;
+                        let rendered_scroll_position = position_map.snapshot.scroll_position();
+                        let current_scroll_position =
+                            rendered_scroll_position - point(0., overscroll);
+                        let x = (rendered_scroll_position.x * max_glyph_width - delta.x)
                             / max_glyph_width;
-                        let y = (current_scroll_position.y * line_height
-                            - (delta.y * scroll_sensitivity))
-                            / line_height;
+                        let y = (rendered_scroll_position.y * line_height - delta.y) / line_height;
                         let mut scroll_position =
                             point(x, y).clamp(&point(0., 0.), &position_map.scroll_max);
                         let forbid_vertical_scroll = editor.scroll_manager.forbid_vertical_scroll();
@@ -3936,6 +5102,16 @@ impl EditorElement {
                             // on the next frame.
                             cx.notify();
                         }
+
+                        if elastic_overscroll
+                            && is_trackpad
+                            && !forbid_vertical_scroll
+                            && position_map.snapshot.mode == EditorMode::Full
+                            && (overscroll != 0. || y != scroll_position.y)
+                        {
+                            editor.stretch_overscroll(overscroll, y - scroll_position.y, cx);
+                            cx.stop_propagation();
+                        }
                     });
                 }
             }
@@ -3958,6 +5134,10 @@ impl EditorElement {
 
             move |event: &MouseDownEvent, phase, cx| {
                 if phase == DispatchPhase::Bubble {
+                    if editor.update(cx, |editor, cx| editor.stop_middle_click_autoscroll(cx)) {
+                        cx.stop_propagation();
+                        return;
+                    }
                     match event.button {
                         MouseButton::Left => editor.update(cx, |editor, cx| {
                             Self::mouse_left_down(
@@ -4004,6 +5184,10 @@ impl EditorElement {
             move |event: &MouseMoveEvent, phase, cx| {
                 if phase == DispatchPhase::Bubble {
                     editor.update(cx, |editor, cx| {
+                        if editor.scroll_manager.is_middle_click_autoscrolling() {
+                            editor.update_middle_click_autoscroll(event.position);
+                            return;
+                        }
                         if editor.hover_state.focused(cx) {
                             return;
                         }
@@ -4158,7 +5342,14 @@ fn render_inline_blame_entry(
     let relative_timestamp = blame_entry_relative_timestamp(&blame_entry);
 
     let author = blame_entry.author.as_deref().unwrap_or_default();
-    let text = format!("{}, {}", author, relative_timestamp);
+    let show_commit_summary = ProjectSettings::get_global(cx)
+        .git
+        .inline_blame
+        .map_or(true, |settings| settings.show_commit_summary);
+    let text = match blame_entry.summary.as_ref().filter(|_| show_commit_summary) {
+        Some(summary) => format!("{}, {} — {}", author, relative_timestamp, summary),
+        None => format!("{}, {}", author, relative_timestamp),
+    };
 
     let details = blame.read(cx).details_for_entry(&blame_entry);
 
@@ -4945,19 +6136,36 @@ impl Element for EditorElement {
                         self.max_line_number_width(&snapshot, cx),
                         cx,
                     );
-                    let text_width = bounds.size.width - gutter_dimensions.width;
+                    let minimap_width = self.minimap_width(&snapshot, bounds, line_height, cx);
+                    // The minimap sits to the left of the scrollbar, so the text ends where the
+                    // minimap starts rather than running under the scrollbar.
+                    let text_right_inset = if minimap_width > Pixels::ZERO {
+                        minimap_width + self.style.scrollbar_width
+                    } else {
+                        Pixels::ZERO
+                    };
+                    let text_width = bounds.size.width - gutter_dimensions.width - text_right_inset;
+                    let horizontal_scrollbar_height =
+                        self.horizontal_scrollbar_height(&snapshot, cx);
+                    let text_height = bounds.size.height - horizontal_scrollbar_height;
 
                     let right_margin = if snapshot.mode == EditorMode::Full {
                         EditorElement::SCROLLBAR_WIDTH
                     } else {
                         px(0.)
                     };
-                    let overscroll = size(em_width + right_margin, px(0.));
+                    let horizontal_scroll_margin = if snapshot.mode == EditorMode::Full {
+                        em_width * self.editor.read(cx).scroll_manager.horizontal_scroll_margin
+                    } else {
+                        px(0.)
+                    };
+                    let overscroll =
+                        size(em_width + right_margin + horizontal_scroll_margin, px(0.));
 
                     snapshot = self.editor.update(cx, |editor, cx| {
                         editor.last_bounds = Some(bounds);
                         editor.gutter_dimensions = gutter_dimensions;
-                        editor.set_visible_line_count(bounds.size.height / line_height, cx);
+                        editor.set_visible_line_count(text_height / line_height, cx);
 
                         if matches!(editor.mode, EditorMode::AutoHeight { .. }) {
                             snapshot
@@ -4997,7 +6205,7 @@ impl Element for EditorElement {
                     let text_hitbox = cx.insert_hitbox(
                         Bounds {
                             origin: gutter_hitbox.upper_right(),
-                            size: size(text_width, bounds.size.height),
+                            size: size(text_width, text_height),
                         },
                         false,
                     );
@@ -5006,7 +6214,7 @@ impl Element for EditorElement {
                     let content_origin =
                         text_hitbox.origin + point(gutter_dimensions.margin, Pixels::ZERO);
 
-                    let height_in_lines = bounds.size.height / line_height;
+                    let height_in_lines = text_height / line_height;
                     let max_row = snapshot.max_point().row().as_f32();
                     let max_scroll_top = if matches!(snapshot.mode, EditorMode::AutoHeight { .. }) {
                         (max_row - height_in_lines + 1.).max(0.)
@@ -5029,8 +6237,15 @@ impl Element for EditorElement {
                         autoscroll_request = editor.autoscroll_request();
                         autoscroll_containing_element =
                             autoscroll_request.is_some() || editor.has_pending_selection();
-                        autoscroll_horizontally =
-                            editor.autoscroll_vertically(bounds, line_height, max_scroll_top, cx);
+                        autoscroll_horizontally = editor.autoscroll_vertically(
+                            Bounds {
+                                origin: bounds.origin,
+                                size: size(bounds.size.width, text_height),
+                            },
+                            line_height,
+                            max_scroll_top,
+                            cx,
+                        );
                         snapshot = editor.snapshot(cx);
                     });
 
@@ -5229,10 +6444,25 @@ impl Element for EditorElement {
                         )
                     });
 
+                    let inline_diagnostics = self.layout_inline_diagnostics(
+                        start_row,
+                        &line_layouts,
+                        &crease_trailers,
+                        &snapshot,
+                        em_width,
+                        content_origin,
+                        scroll_pixel_position,
+                        line_height,
+                        text_hitbox.bounds,
+                        cx,
+                    );
+
                     let mut inline_blame = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         let display_row = newest_selection_head.row();
-                        if (start_row..end_row).contains(&display_row) {
+                        if (start_row..end_row).contains(&display_row)
+                            && !inline_diagnostics.contains_key(&display_row)
+                        {
                             let line_ix = display_row.minus(start_row) as usize;
                             let line_layout = &line_layouts[line_ix];
                             let crease_trailer_layout = crease_trailers[line_ix].as_ref();
@@ -5307,6 +6537,7 @@ impl Element for EditorElement {
                     });
 
                     let cursors = self.collect_cursors(&snapshot, cx);
+                    let collaborator_viewports = self.collect_collaborator_viewports(&snapshot, cx);
                     let visible_row_range = start_row..end_row;
                     let non_visible_cursors = cursors
                         .iter()
@@ -5327,12 +6558,42 @@ impl Element for EditorElement {
                         cx,
                     );
 
-                    let scrollbar_layout = self.layout_scrollbar(
+                    let sticky_headers = self.layout_sticky_headers(
+                        &snapshot,
+                        scroll_position,
+                        &gutter_hitbox,
+                        &text_hitbox,
+                        line_height,
+                        cx,
+                    );
+
+                    let scrollbar_layout = self.layout_scrollbar(
+                        &snapshot,
+                        bounds,
+                        scroll_position,
+                        height_in_lines,
+                        non_visible_cursors,
+                        cx,
+                    );
+
+                    let horizontal_scrollbar_layout = self.layout_horizontal_scrollbar(
+                        bounds,
+                        text_hitbox.bounds,
+                        minimap_width,
+                        horizontal_scrollbar_height,
+                        scroll_position,
+                        scroll_max.x,
+                        em_width,
+                        cx,
+                    );
+
+                    let minimap_layout = self.layout_minimap(
                         &snapshot,
                         bounds,
+                        minimap_width,
                         scroll_position,
                         height_in_lines,
-                        non_visible_cursors,
+                        max_scroll_top,
                         cx,
                     );
 
@@ -5487,6 +6748,28 @@ impl Element for EditorElement {
                     let mouse_context_menu =
                         self.layout_mouse_context_menu(&snapshot, start_row..end_row, cx);
 
+                    let scrollbar_drag_tooltip =
+                        cx.with_element_namespace("scrollbar_drag_tooltip", |cx| {
+                            self.layout_scrollbar_drag_tooltip(
+                                &snapshot,
+                                scrollbar_layout.as_ref(),
+                                &text_hitbox,
+                                scroll_position,
+                                cx,
+                            )
+                        });
+
+                    let scrollbar_preview = cx.with_element_namespace("scrollbar_preview", |cx| {
+                        self.layout_scrollbar_preview(
+                            &snapshot,
+                            scrollbar_layout.as_ref(),
+                            &text_hitbox,
+                            line_height,
+                            em_width,
+                            cx,
+                        )
+                    });
+
                     cx.with_element_namespace("gutter_fold_toggles", |cx| {
                         self.prepaint_gutter_fold_toggles(
                             &mut gutter_fold_toggles,
@@ -5552,7 +6835,10 @@ impl Element for EditorElement {
                         gutter_dimensions,
                         display_hunks,
                         content_origin,
+                        sticky_headers,
                         scrollbar_layout,
+                        horizontal_scrollbar_layout,
+                        minimap_layout,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -5561,12 +6847,16 @@ impl Element for EditorElement {
                         line_elements,
                         line_numbers,
                         blamed_display_rows,
+                        inline_diagnostics,
                         inline_blame,
                         blocks,
                         cursors,
+                        collaborator_viewports,
                         visible_cursors,
                         selections,
                         mouse_context_menu,
+                        scrollbar_preview,
+                        scrollbar_drag_tooltip,
                         test_indicators,
                         close_indicators,
                         code_actions_indicator,
@@ -5656,7 +6946,11 @@ impl Element for EditorElement {
                         });
                     }
 
+                    self.paint_sticky_headers(layout, cx);
+                    self.paint_minimap(layout, cx);
                     self.paint_scrollbar(layout, cx);
+                    self.paint_horizontal_scrollbar(layout, cx);
+                    self.paint_scrollbar_preview(layout, cx);
                     self.paint_mouse_context_menu(layout, cx);
                 });
             })
@@ -5689,7 +6983,10 @@ pub struct EditorLayout {
     gutter_hitbox: Hitbox,
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
+    sticky_headers: Option<StickyHeadersLayout>,
     scrollbar_layout: Option<ScrollbarLayout>,
+    horizontal_scrollbar_layout: Option<HorizontalScrollbarLayout>,
+    minimap_layout: Option<MinimapLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -5700,12 +6997,14 @@ pub struct EditorLayout {
     line_numbers: Vec<Option<ShapedLine>>,
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
     blamed_display_rows: Option<Vec<AnyElement>>,
+    inline_diagnostics: HashMap<DisplayRow, AnyElement>,
     inline_blame: Option<AnyElement>,
     blocks: Vec<BlockLayout>,
     highlighted_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     highlighted_gutter_ranges: Vec<(Range<DisplayPoint>, Hsla)>,
     redacted_ranges: Vec<Range<DisplayPoint>>,
     cursors: Vec<(DisplayPoint, Hsla)>,
+    collaborator_viewports: Vec<(usize, ColoredRange<DisplayRow>)>,
     visible_cursors: Vec<CursorLayout>,
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
@@ -5714,6 +7013,8 @@ pub struct EditorLayout {
     gutter_fold_toggles: Vec<Option<AnyElement>>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
     mouse_context_menu: Option<AnyElement>,
+    scrollbar_preview: Option<AnyElement>,
+    scrollbar_drag_tooltip: Option<AnyElement>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
 }
@@ -5744,6 +7045,7 @@ impl ScrollbarLayout {
     const LINE_MARKER_HEIGHT: Pixels = px(2.0);
     const MIN_MARKER_HEIGHT: Pixels = px(5.0);
     const MIN_THUMB_HEIGHT: Pixels = px(20.0);
+    const COLLABORATOR_VIEWPORT_WIDTH: Pixels = px(2.0);
 
     fn thumb_bounds(&self) -> Bounds<Pixels> {
         let thumb_top = self.y_for_row(self.visible_row_range.start);
@@ -5758,6 +7060,34 @@ impl ScrollbarLayout {
         self.hitbox.top() + row * self.row_height
     }
 
+    fn collaborator_viewport_quads(
+        &self,
+        viewports: &[(usize, ColoredRange<DisplayRow>)],
+    ) -> Vec<PaintQuad> {
+        viewports
+            .iter()
+            .map(|(lane, range)| {
+                let left = self.hitbox.left()
+                    + Self::BORDER_WIDTH
+                    + Self::COLLABORATOR_VIEWPORT_WIDTH * *lane as f32;
+                let top = self.y_for_row(range.start.as_f32());
+                let bottom = self
+                    .y_for_row(range.end.as_f32())
+                    .max(top + Self::MIN_MARKER_HEIGHT);
+                quad(
+                    Bounds::from_corners(
+                        point(left, top),
+                        point(left + Self::COLLABORATOR_VIEWPORT_WIDTH, bottom),
+                    ),
+                    Corners::default(),
+                    range.color,
+                    Edges::default(),
+                    Hsla::transparent_black(),
+                )
+            })
+            .collect()
+    }
+
     fn marker_quads_for_ranges(
         &self,
         row_ranges: impl IntoIterator<Item = ColoredRange<DisplayRow>>,
@@ -5836,6 +7166,72 @@ impl ScrollbarLayout {
     }
 }
 
+struct StickyHeadersLayout {
+    hitbox: Hitbox,
+    headers: Vec<StickyHeader>,
+}
+
+struct StickyHeader {
+    line: LineWithInvisibles,
+    target: Anchor,
+    /// How far the header is pushed up by the end of its scope.
+    offset: Pixels,
+}
+
+#[derive(Clone)]
+struct HorizontalScrollbarLayout {
+    hitbox: Hitbox,
+    visible_column_range: Range<f32>,
+    visible: bool,
+    column_width: Pixels,
+    thumb_width: Pixels,
+}
+
+impl HorizontalScrollbarLayout {
+    const MIN_THUMB_WIDTH: Pixels = px(20.0);
+
+    fn thumb_bounds(&self) -> Bounds<Pixels> {
+        let thumb_left = self.hitbox.left() + self.visible_column_range.start * self.column_width;
+        Bounds::from_corners(
+            point(thumb_left, self.hitbox.top()),
+            point(thumb_left + self.thumb_width, self.hitbox.bottom()),
+        )
+    }
+}
+
+struct MinimapLayout {
+    hitbox: Hitbox,
+    /// The display row (possibly fractional) shown at the top of the minimap.
+    scroll_top: f32,
+    visible_row_range: Range<f32>,
+    /// How far the viewport thumb moves for each row the editor scrolls.
+    row_height: Pixels,
+    line_quads: Vec<PaintQuad>,
+    selection_quads: Vec<PaintQuad>,
+}
+
+impl MinimapLayout {
+    const WIDTH: Pixels = px(100.0);
+    const LINE_HEIGHT: Pixels = px(2.0);
+    const COLUMN_WIDTH: Pixels = px(1.0);
+    const HORIZONTAL_PADDING: Pixels = px(4.0);
+    const MIN_ROW_HEIGHT: Pixels = px(0.1);
+    const MIN_EDITOR_WIDTH_RATIO: f32 = 4.0;
+
+    fn thumb_bounds(&self) -> Bounds<Pixels> {
+        let thumb_top = self.y_for_row(self.visible_row_range.start);
+        let thumb_bottom = self.y_for_row(self.visible_row_range.end);
+        Bounds::from_corners(
+            point(self.hitbox.left(), thumb_top),
+            point(self.hitbox.right(), thumb_bottom),
+        )
+    }
+
+    fn y_for_row(&self, row: f32) -> Pixels {
+        self.hitbox.top() + (row - self.scroll_top) * Self::LINE_HEIGHT
+    }
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -6197,10 +7593,6 @@ impl HighlightedRange {
     }
 }
 
-pub fn scale_vertical_mouse_autoscroll_delta(delta: Pixels) -> f32 {
-    (delta.pow(1.5) / 100.0).into()
-}
-
 fn scale_horizontal_mouse_autoscroll_delta(delta: Pixels) -> f32 {
     (delta.pow(1.2) / 300.0).into()
 }
@@ -6210,12 +7602,20 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockDisposition, BlockProperties},
+        editor_settings::{
+            InlineDiagnosticsContent, MinimapContent, ScrollbarContent, StickyScrollContent,
+        },
         editor_tests::{init_test, update_test_language_settings},
-        Editor, MultiBuffer,
+        Editor, FoldPlaceholder, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
-    use language::language_settings;
+    use language::{
+        language_settings, Buffer, Diagnostic, DiagnosticEntry, DiagnosticSet, Language,
+        LanguageConfig, PointUtf16,
+    };
     use log::info;
+    use lsp::LanguageServerId;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use ui::Context;
     use util::test::sample_text;
@@ -6254,6 +7654,7 @@ mod tests {
                     &snapshot,
                     &(DisplayRow(0)..DisplayRow(6)),
                     Some(DisplayRow(3)),
+                    false,
                 )
             })
             .unwrap();
@@ -6272,6 +7673,7 @@ mod tests {
                     &snapshot,
                     &(DisplayRow(3)..DisplayRow(6)),
                     Some(DisplayRow(1)),
+                    false,
                 )
             })
             .unwrap();
@@ -6288,6 +7690,7 @@ mod tests {
                     &snapshot,
                     &(DisplayRow(0)..DisplayRow(3)),
                     Some(DisplayRow(6)),
+                    false,
                 )
             })
             .unwrap();
@@ -6295,6 +7698,48 @@ mod tests {
         assert_eq!(relative_rows[&DisplayRow(0)], 5);
         assert_eq!(relative_rows[&DisplayRow(1)], 4);
         assert_eq!(relative_rows[&DisplayRow(2)], 3);
+
+        // current line is numbered 0 when not in hybrid mode
+        let relative_rows = window
+            .update(cx, |editor, cx| {
+                let snapshot = editor.snapshot(cx);
+                element.calculate_relative_line_numbers(
+                    &snapshot,
+                    &(DisplayRow(0)..DisplayRow(6)),
+                    Some(DisplayRow(3)),
+                    true,
+                )
+            })
+            .unwrap();
+        assert_eq!(relative_rows.len(), 6);
+        assert_eq!(relative_rows[&DisplayRow(2)], 1);
+        assert_eq!(relative_rows[&DisplayRow(3)], 0);
+        assert_eq!(relative_rows[&DisplayRow(4)], 1);
+
+        // folded lines count as a single line
+        window
+            .update(cx, |editor, cx| {
+                editor.fold_ranges(
+                    [(Point::new(1, 0)..Point::new(3, 6), FoldPlaceholder::test())],
+                    false,
+                    cx,
+                )
+            })
+            .unwrap();
+        let relative_rows = window
+            .update(cx, |editor, cx| {
+                let snapshot = editor.snapshot(cx);
+                element.calculate_relative_line_numbers(
+                    &snapshot,
+                    &(DisplayRow(0)..DisplayRow(4)),
+                    Some(DisplayRow(0)),
+                    false,
+                )
+            })
+            .unwrap();
+        assert_eq!(relative_rows[&DisplayRow(1)], 1);
+        assert_eq!(relative_rows[&DisplayRow(2)], 2);
+        assert_eq!(relative_rows[&DisplayRow(3)], 3);
     }
 
     #[gpui::test]
@@ -6493,6 +7938,326 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(6, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        let minimap = state.minimap_layout.as_ref().unwrap();
+        assert_eq!(minimap.hitbox.size.width, MinimapLayout::WIDTH);
+        assert_eq!(minimap.scroll_top, 0.);
+        assert!(state.text_hitbox.right() <= minimap.hitbox.left());
+        // Each line of the sample text is a single run of non-whitespace characters.
+        assert_eq!(minimap.line_quads.len(), 6);
+        assert_eq!(minimap.selection_quads.len(), 1);
+    }
+
+    #[gpui::test]
+    fn test_horizontal_scrollbar_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&"a".repeat(200), cx);
+            let mut editor = Editor::new(EditorMode::Full, buffer, None, true, cx);
+            editor.set_soft_wrap_mode(language_settings::SoftWrap::None, cx);
+            editor
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        // The scrollbar sits below the text, rather than over its last visible line.
+        let scrollbar = state.horizontal_scrollbar_layout.as_ref().unwrap();
+        assert_eq!(scrollbar.hitbox.size.height, style.scrollbar_width);
+        assert_eq!(
+            state.text_hitbox.size.height,
+            px(500.) - style.scrollbar_width
+        );
+        assert!(scrollbar.hitbox.top() >= state.text_hitbox.bottom());
+    }
+
+    #[gpui::test]
+    fn test_search_match_for_scrollbar_position(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(100, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+
+        let row_height = px(2.);
+        _ = window.update(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            let search_match =
+                snapshot.anchor_before(Point::new(50, 1))..snapshot.anchor_after(Point::new(50, 3));
+            editor.highlight_background::<BufferSearchHighlights>(
+                &[search_match.clone()],
+                |theme| theme.search_match_background,
+                cx,
+            );
+
+            // Positions near a match's marker hit it, while the ones farther away don't.
+            assert_eq!(
+                EditorElement::search_match_for_scrollbar_position(
+                    editor,
+                    px(101.),
+                    row_height,
+                    cx
+                ),
+                Some(search_match)
+            );
+            assert_eq!(
+                EditorElement::search_match_for_scrollbar_position(
+                    editor,
+                    px(120.),
+                    row_height,
+                    cx
+                ),
+                None
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn test_sticky_headers(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.sticky_scroll = Some(StickyScrollContent {
+                        enabled: Some(true),
+                        max_lines: None,
+                    });
+                });
+            });
+        });
+
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig::default(),
+                Some(tree_sitter_rust::language()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let body = "        let a = 1;\n".repeat(20);
+        let text = format!("mod outer {{\n    fn inner() {{\n{body}    }}\n}}\n");
+
+        let window = cx.add_window(|cx| {
+            let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+            let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        cx.run_until_parked();
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(
+            state.sticky_headers.is_none(),
+            "No headers should be pinned when the declarations are visible"
+        );
+
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(point(0., 10.), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let sticky_headers = state.sticky_headers.as_ref().unwrap();
+        let snapshot = &state.position_map.snapshot;
+        let header_rows = sticky_headers
+            .headers
+            .iter()
+            .map(|header| header.target.to_point(&snapshot.buffer_snapshot).row)
+            .collect::<Vec<_>>();
+        assert_eq!(header_rows, vec![0, 1]);
+        assert_eq!(
+            sticky_headers.hitbox.size.height,
+            state.position_map.line_height * 2.
+        );
+        assert!(state.scrollbar_drag_tooltip.is_none());
+
+        // While dragging the scrollbar, a tooltip names the top row and the symbol it is in.
+        window
+            .update(cx, |editor, cx| {
+                editor.scroll_manager.set_is_dragging_scrollbar(true, cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        assert!(state.scrollbar_drag_tooltip.is_some());
+        assert_eq!(
+            EditorElement::scrollbar_drag_tooltip_label(
+                &state.position_map.snapshot,
+                point(0., 10.)
+            ),
+            ("Line 11".to_string(), Some("fn inner".to_string()))
+        );
+    }
+
+    #[gpui::test]
+    fn test_inline_diagnostics(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.inline_diagnostics = Some(InlineDiagnosticsContent {
+                        enabled: Some(true),
+                        min_severity: None,
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple("one\ntwo\nthree\n", cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+        window
+            .update(cx, |editor, cx| {
+                let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+                buffer.update(cx, |buffer, cx| {
+                    let snapshot = buffer.text_snapshot();
+                    let entry = |row, severity, message: &str| DiagnosticEntry {
+                        range: PointUtf16::new(row, 0)..PointUtf16::new(row, 3),
+                        diagnostic: Diagnostic {
+                            severity,
+                            message: message.to_string(),
+                            is_primary: true,
+                            ..Default::default()
+                        },
+                    };
+                    let diagnostics = DiagnosticSet::new(
+                        [
+                            entry(0, DiagnosticSeverity::WARNING, "a warning"),
+                            entry(0, DiagnosticSeverity::ERROR, "an error"),
+                            entry(1, DiagnosticSeverity::HINT, "a hint"),
+                        ],
+                        &snapshot,
+                    );
+                    buffer.update_diagnostics(LanguageServerId(0), diagnostics, cx);
+                });
+            })
+            .unwrap();
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style)
+        });
+        // Hints are below the default severity threshold.
+        let mut rows = state.inline_diagnostics.keys().copied().collect::<Vec<_>>();
+        rows.sort();
+        assert_eq!(rows, vec![DisplayRow(0)]);
+    }
+
+    #[gpui::test]
+    fn test_scrollbar_preview(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.scrollbar = Some(ScrollbarContent {
+                        show: Some(ShowScrollbar::Always),
+                        git_diff: None,
+                        selected_symbol: None,
+                        search_results: None,
+                        diagnostics: None,
+                        cursors: None,
+                        horizontal: None,
+                        preview: None,
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(50, 6, '0'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(state.scrollbar_preview.is_none());
+
+        // The hovered row is previewed along with a few lines of context around it.
+        window
+            .update(cx, |editor, cx| {
+                editor
+                    .scroll_manager
+                    .set_scrollbar_preview_row(Some(DisplayRow(30)), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(state.scrollbar_preview.is_some());
+        let element = EditorElement::new(&editor, style.clone());
+        let snapshot = &state.position_map.snapshot;
+        assert_eq!(
+            element.scrollbar_preview_text(snapshot, DisplayRow(30)).0,
+            "LLLLLL\nMMMMMM\nNNNNNN\nOOOOOO\nPPPPPP"
+        );
+
+        // Rows past the end of the buffer preview the last lines.
+        window
+            .update(cx, |editor, cx| {
+                editor
+                    .scroll_manager
+                    .set_scrollbar_preview_row(Some(DisplayRow(100)), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(state.scrollbar_preview.is_some());
+        let element = EditorElement::new(&editor, style);
+        let snapshot = &state.position_map.snapshot;
+        assert_eq!(
+            element
+                .scrollbar_preview_text(snapshot, snapshot.max_point().row())
+                .0,
+            "______\n``````\naaaaaa"
+        );
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
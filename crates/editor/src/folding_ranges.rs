@@ -0,0 +1,40 @@
+use gpui::{Model, Task};
+use project::Project;
+use ui::ViewContext;
+use util::ResultExt;
+
+use crate::{viewport_refresh::VisibleBuffers, Editor};
+
+/// Queries the language servers of the visible buffers edited since the last refresh for their
+/// foldable ranges, which then take precedence over the syntax based ones when folding.
+pub(super) fn refresh_folding_ranges(
+    project: &Model<Project>,
+    visible: &VisibleBuffers,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Option<()>> {
+    let tasks = project.update(cx, |project, cx| {
+        visible
+            .edited
+            .iter()
+            .filter_map(|buffer_id| {
+                let (buffer, _) = visible.buffers.get(buffer_id)?;
+                Some((*buffer_id, project.folding_ranges(buffer, cx)))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    cx.spawn(|this, mut cx| async move {
+        for (buffer_id, task) in tasks {
+            let Some(ranges) = task.await.log_err() else {
+                continue;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.display_map
+                    .update(cx, |map, cx| map.set_lsp_fold_ranges(buffer_id, ranges, cx));
+                cx.notify();
+            })
+            .ok()?;
+        }
+        Some(())
+    })
+}
@@ -9,12 +9,12 @@ use language::{Bias, ToOffset};
 use linkify::{LinkFinder, LinkKind};
 use lsp::LanguageServerId;
 use project::{
-    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink,
+    HoverBlock, HoverBlockKind, InlayHintLabelPartTooltip, InlayHintTooltip, LocationLink, Project,
     ResolveState,
 };
-use std::ops::Range;
+use std::{ops::Range, path::PathBuf};
 use theme::ActiveTheme as _;
-use util::{maybe, ResultExt, TryFutureExt};
+use util::{maybe, paths::PathWithPosition, ResultExt, TryFutureExt};
 
 #[derive(Debug)]
 pub struct HoveredLinkState {
@@ -63,6 +63,7 @@ impl RangeInEditor {
 #[derive(Debug, Clone)]
 pub enum HoverLink {
     Url(String),
+    File(PathWithPosition),
     Text(LocationLink),
     InlayHint(lsp::Location, LanguageServerId),
 }
@@ -486,7 +487,7 @@ pub fn show_link_definition(
         || hovered_link_state
             .links
             .first()
-            .is_some_and(|d| matches!(d, HoverLink::Url(_)));
+            .is_some_and(|d| matches!(d, HoverLink::Url(_) | HoverLink::File(_)));
 
     if same_kind {
         if is_cached && (&hovered_link_state.last_trigger_point == &trigger_point)
@@ -508,51 +509,60 @@ pub fn show_link_definition(
     let snapshot = snapshot.buffer_snapshot.clone();
     hovered_link_state.task = Some(cx.spawn(|this, mut cx| {
         async move {
+            let range_in_editor = |range: Range<text::Anchor>| {
+                let start = snapshot.anchor_in_excerpt(excerpt_id, range.start)?;
+                let end = snapshot.anchor_in_excerpt(excerpt_id, range.end)?;
+                Some(RangeInEditor::Text(start..end))
+            };
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
-                        this.update(&mut cx, |_, _| {
-                            let range = maybe!({
-                                let start =
-                                    snapshot.anchor_in_excerpt(excerpt_id, url_range.start)?;
-                                let end = snapshot.anchor_in_excerpt(excerpt_id, url_range.end)?;
-                                Some(RangeInEditor::Text(start..end))
-                            });
-                            (range, vec![HoverLink::Url(url)])
-                        })
-                        .ok()
-                    } else if let Some(project) = project {
-                        // query the LSP for definition info
-                        project
-                            .update(&mut cx, |project, cx| match preferred_kind {
+                    // Links take precedence over definitions, but they're looked up alongside
+                    // them so that going to a definition isn't delayed by the lookup.
+                    let (document_link, definition) = match &project {
+                        Some(project) => project.update(&mut cx, |project, cx| {
+                            let document_link =
+                                project.document_link_at(&buffer, buffer_position, cx);
+                            // query the LSP for definition info
+                            let definition = match preferred_kind {
                                 LinkDefinitionKind::Symbol => {
                                     project.definition(&buffer, buffer_position, cx)
                                 }
-
                                 LinkDefinitionKind::Type => {
                                     project.type_definition(&buffer, buffer_position, cx)
                                 }
-                            })?
-                            .await
-                            .ok()
-                            .map(|definition_result| {
-                                (
-                                    definition_result.iter().find_map(|link| {
-                                        link.origin.as_ref().and_then(|origin| {
-                                            let start = snapshot.anchor_in_excerpt(
-                                                excerpt_id,
-                                                origin.range.start,
-                                            )?;
-                                            let end = snapshot
-                                                .anchor_in_excerpt(excerpt_id, origin.range.end)?;
-                                            Some(RangeInEditor::Text(start..end))
-                                        })
-                                    }),
-                                    definition_result.into_iter().map(HoverLink::Text).collect(),
-                                )
-                            })
+                            };
+                            (Some(document_link), Some(definition))
+                        })?,
+                        None => (None, None),
+                    };
+                    let (document_link, file, definition) = futures::join!(
+                        async move { document_link?.await.log_err().flatten() },
+                        find_file(&buffer, project.as_ref(), buffer_position, &mut cx),
+                        async move { definition?.await.ok() },
+                    );
+
+                    if let Some(document_link) = document_link {
+                        Some((
+                            range_in_editor(document_link.range),
+                            vec![hover_link_for_target(document_link.target)],
+                        ))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
+                        Some((range_in_editor(url_range), vec![HoverLink::Url(url)]))
+                    } else if let Some((file_range, path)) = file {
+                        Some((range_in_editor(file_range), vec![HoverLink::File(path)]))
                     } else {
-                        None
+                        definition.map(|definition_result| {
+                            (
+                                definition_result.iter().find_map(|link| {
+                                    link.origin
+                                        .as_ref()
+                                        .and_then(|origin| range_in_editor(origin.range.clone()))
+                                }),
+                                definition_result.into_iter().map(HoverLink::Text).collect(),
+                            )
+                        })
                     }
                 }
                 TriggerPoint::InlayHint(highlight, lsp_location, server_id) => Some((
@@ -686,6 +696,108 @@ pub(crate) fn find_url(
     None
 }
 
+/// Converts the target of a language server's document link into a link, opening `file` URIs
+/// in Zed at the `#L<row>,<column>` position they conventionally point to.
+fn hover_link_for_target(target: lsp::Url) -> HoverLink {
+    if target.scheme() == "file" {
+        if let Ok(path) = target.to_file_path() {
+            let mut path = PathWithPosition::from_path(path);
+            if let Some(position) = target.fragment().and_then(|f| f.strip_prefix('L')) {
+                let (row, column) = position.split_once(',').unwrap_or((position, ""));
+                path.row = row.parse().ok();
+                path.column = path.row.and(column.parse().ok());
+            }
+            return HoverLink::File(path);
+        }
+    }
+    HoverLink::Url(target.to_string())
+}
+
+/// Finds a path to an existing file around the given position, resolved relative to the
+/// buffer's directory or to the project's worktrees, with an optional `:row:column` suffix.
+pub(crate) async fn find_file(
+    buffer: &Model<language::Buffer>,
+    project: Option<&Model<Project>>,
+    position: text::Anchor,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, PathWithPosition)> {
+    const LIMIT: usize = 1024;
+
+    fn is_path_char(ch: char) -> bool {
+        !ch.is_whitespace()
+            && !matches!(
+                ch,
+                '"' | '\'' | '`' | '(' | ')' | '[' | ']' | '{' | '}' | '<' | '>' | ',' | ';'
+            )
+    }
+
+    let project = project?;
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let offset = position.to_offset(&snapshot);
+    let mut token_start = offset;
+    for ch in snapshot.reversed_chars_at(offset).take(LIMIT) {
+        if !is_path_char(ch) {
+            break;
+        }
+        token_start -= ch.len_utf8();
+    }
+    let mut token_end = offset;
+    for ch in snapshot.chars_at(offset).take(LIMIT) {
+        if !is_path_char(ch) {
+            break;
+        }
+        token_end += ch.len_utf8();
+    }
+    let token = snapshot
+        .text_for_range(token_start..token_end)
+        .collect::<String>();
+    // Don't treat the punctuation ending a sentence as part of the path.
+    let token = token.trim_end_matches(['.', ':']);
+    // Only consider words that look like paths, so that identifiers keep going to their definition.
+    if token.len() >= LIMIT || !token.contains(['/', '.']) {
+        return None;
+    }
+    let path = PathWithPosition::parse_str(token);
+
+    let (fs, candidates) = project
+        .update(cx, |project, cx| {
+            if !project.is_local() {
+                return None;
+            }
+            let candidates = if path.path.is_absolute() {
+                vec![path.path.clone()]
+            } else {
+                project::File::from_dyn(buffer.read(cx).file())
+                    .and_then(|file| Some(file.abs_path(cx).parent()?.to_path_buf()))
+                    .into_iter()
+                    .chain(
+                        project
+                            .visible_worktrees(cx)
+                            .map(|worktree| worktree.read(cx).abs_path().to_path_buf()),
+                    )
+                    .map(|dir| dir.join(&path.path))
+                    .collect::<Vec<PathBuf>>()
+            };
+            Some((project.fs().clone(), candidates))
+        })
+        .ok()??;
+    for candidate in candidates {
+        if fs.is_file(&candidate).await {
+            let range = snapshot.anchor_before(token_start)
+                ..snapshot.anchor_after(token_start + token.len());
+            return Some((
+                range,
+                PathWithPosition {
+                    path: candidate,
+                    row: path.row,
+                    column: path.column,
+                },
+            ));
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1050,6 +1162,7 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                syntax_parameter_hints: false,
             })
         });
 
@@ -1219,6 +1332,44 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_file_paths(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            See (file.rs:1:3) and missing.rs for moreˇ.
+        "});
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            See (fiˇle.rs:1:3) and missing.rs for more.
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            See («file.rs:1:3ˇ») and missing.rs for more.
+        "});
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            Seˇe (file.rs:1:3) and missing.rs for more.
+        "});
+
+        let screen_coord = cx.pixel_position(indoc! {"
+            See (file.rs:1:3) and missˇing.rs for more.
+        "});
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            See (file.rs:1:3) and missing.rs for more.
+        "});
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -187,6 +187,56 @@ pub fn hide_hover(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
     did_hide
 }
 
+impl Editor {
+    /// Hides the hover popovers, returning the position whose documentation they were showing.
+    pub fn dismiss_hover(&mut self, cx: &mut ViewContext<Self>) -> Option<Anchor> {
+        let anchor = self
+            .hover_state
+            .info_popovers
+            .iter()
+            .find_map(|info_popover| info_popover.anchor);
+        hide_hover(self, cx);
+        anchor
+    }
+
+    /// Queries the LSP for the documentation of the symbol at the given position,
+    /// without showing a hover popover.
+    pub fn hover_documentation(
+        &self,
+        anchor: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Vec<View<Markdown>>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Vec::new());
+        };
+        let Some((buffer, buffer_position)) =
+            self.buffer.read(cx).text_anchor_for_position(anchor, cx)
+        else {
+            return Task::ready(Vec::new());
+        };
+
+        let hover_request = project.update(cx, |project, cx| {
+            project.hover(&buffer, buffer_position, cx)
+        });
+        let language_registry = project.read(cx).languages().clone();
+        cx.spawn(|_, mut cx| async move {
+            let mut documentation = Vec::new();
+            for hover_result in hover_request.await {
+                documentation.extend(
+                    parse_blocks(
+                        &hover_result.contents,
+                        &language_registry,
+                        hover_result.language,
+                        &mut cx,
+                    )
+                    .await,
+                );
+            }
+            documentation
+        })
+    }
+}
+
 /// Queries the LSP and shows type info and documentation
 /// about the symbol the mouse is currently hovering over.
 /// Triggered by the `Hover` action when the cursor may be over a symbol.
@@ -724,6 +774,8 @@ impl DiagnosticPopover {
         let diagnostic_div = div()
             .id("diagnostic")
             .block()
+            .overflow_y_scroll()
+            .max_w(max_size.width)
             .max_h(max_size.height)
             .elevation_2_borderless(cx)
             // Don't draw the background color if the theme
@@ -1118,6 +1170,62 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_hover_documentation(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn test() { printˇln!(); }
+        "});
+        let symbol_range = cx.lsp_range(indoc! {"
+            fn test() { «println!»(); }
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::HoverRequest, _, _>(move |_, _, _| async move {
+                Ok(Some(lsp::Hover {
+                    contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                        kind: lsp::MarkupKind::Markdown,
+                        value: "some pinned docs".to_string(),
+                    }),
+                    range: Some(symbol_range),
+                }))
+            });
+
+        cx.update_editor(|editor, cx| hover(editor, &Hover, cx));
+        requests.next().await;
+        cx.condition(|editor, _| editor.hover_state.visible()).await;
+
+        // Dismissing the hover returns the position it was showing documentation for.
+        let anchor = cx
+            .update_editor(|editor, cx| editor.dismiss_hover(cx))
+            .expect("hover should have an anchor");
+        assert!(!cx.editor(|editor, _| editor.hover_state.visible()));
+
+        // The documentation can be fetched again without showing a popover.
+        let documentation = cx.update_editor(|editor, cx| editor.hover_documentation(anchor, cx));
+        requests.next().await;
+        let documentation = documentation.await;
+        assert!(!cx.editor(|editor, _| editor.hover_state.visible()));
+        cx.update(|cx| {
+            assert_eq!(
+                documentation
+                    .iter()
+                    .map(|markdown| markdown.read(cx).source().to_string())
+                    .collect::<Vec<_>>(),
+                vec!["some pinned docs".to_string()]
+            );
+        });
+    }
+
     #[gpui::test]
     async fn test_empty_hovers_filtered(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
@@ -1324,6 +1432,7 @@ mod tests {
                 show_type_hints: true,
                 show_parameter_hints: true,
                 show_other_hints: true,
+                syntax_parameter_hints: false,
             })
         });
 
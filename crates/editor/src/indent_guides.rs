@@ -193,14 +193,18 @@ async fn resolve_indented_range(
             (point.start.row, snapshot, buffer_id)
         };
 
-    buffer_snapshot
-        .enclosing_indent(buffer_row)
-        .await
-        .map(|(row_range, indent)| ActiveIndentedRange {
-            row_range,
-            indent,
-            buffer_id,
-        })
+    // Prefer the block from the syntax tree, falling back to indentation for blank lines
+    // and buffers without a language.
+    let enclosing_indent = match buffer_snapshot.syntax_enclosing_indent(buffer_row) {
+        Some(enclosing_indent) => Some(enclosing_indent),
+        None => buffer_snapshot.enclosing_indent(buffer_row).await,
+    };
+
+    enclosing_indent.map(|(row_range, indent)| ActiveIndentedRange {
+        row_range,
+        indent,
+        buffer_id,
+    })
 }
 
 fn should_recalculate_indented_range(
@@ -139,6 +139,7 @@ impl FollowableItem for Editor {
                     scroll_top_anchor: state.scroll_top_anchor,
                     scroll_x: state.scroll_x,
                     scroll_y: state.scroll_y,
+                    text_scale: state.text_scale,
                     ..Default::default()
                 },
                 &mut cx,
@@ -151,9 +152,13 @@ impl FollowableItem for Editor {
 
     fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
         self.leader_peer_id = leader_peer_id;
+        if self.leader_peer_id.is_none() {
+            self.set_leader_text_scale(None, cx);
+        }
         if self.leader_peer_id.is_some() {
             self.buffer.update(cx, |buffer, cx| {
                 buffer.remove_active_selections(cx);
+                buffer.set_active_viewport(None, cx);
             });
         } else if self.focus_handle.is_focused(cx) {
             self.buffer.update(cx, |buffer, cx| {
@@ -164,6 +169,7 @@ impl FollowableItem for Editor {
                     cx,
                 );
             });
+            self.broadcast_viewport(cx);
         }
         cx.notify();
     }
@@ -205,6 +211,7 @@ impl FollowableItem for Editor {
             scroll_top_anchor: Some(serialize_anchor(&scroll_anchor.anchor)),
             scroll_x: scroll_anchor.offset.x,
             scroll_y: scroll_anchor.offset.y,
+            text_scale: Some(self.text_scale(cx)),
             selections: self
                 .selections
                 .disjoint_anchors()
@@ -279,6 +286,10 @@ impl FollowableItem for Editor {
                     update.scroll_y = scroll_anchor.offset.y;
                     true
                 }
+                EditorEvent::TextScaleChanged => {
+                    update.text_scale = Some(self.text_scale(cx));
+                    true
+                }
                 EditorEvent::SelectionsChanged { .. } => {
                     update.selections = self
                         .selections
@@ -439,9 +450,17 @@ async fn update_editor_from_message(
 
     // Update the editor's state.
     this.update(cx, |editor, cx| {
+        if let Some(text_scale) = message.text_scale {
+            editor.set_leader_text_scale(Some(text_scale), cx);
+        }
         if !selections.is_empty() || pending_selection.is_some() {
             editor.set_selections_from_remote(selections, pending_selection, cx);
             editor.request_autoscroll_remotely(Autoscroll::newest(), cx);
+            // The leader's explicit horizontal scroll is kept, while their vertical
+            // position is still followed through the autoscroll.
+            if scroll_top_anchor.is_some() {
+                editor.set_scroll_x_remote(message.scroll_x, cx);
+            }
         } else if let Some(scroll_top_anchor) = scroll_top_anchor {
             editor.set_scroll_anchor_remote(
                 ScrollAnchor {
@@ -690,7 +709,8 @@ impl Item for Editor {
     }
 
     fn is_dirty(&self, cx: &AppContext) -> bool {
-        self.buffer().read(cx).read(cx).is_dirty()
+        // Closing a rename preview undoes its edits, so there's nothing to save.
+        self.rename_preview.is_none() && self.buffer().read(cx).read(cx).is_dirty()
     }
 
     fn has_conflict(&self, cx: &AppContext) -> bool {
@@ -857,8 +877,12 @@ impl Item for Editor {
         Some(breadcrumbs)
     }
 
-    fn added_to_workspace(&mut self, workspace: &mut Workspace, _: &mut ViewContext<Self>) {
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
+        if let Some(workspace_id) = workspace.database_id() {
+            self.read_scroll_position_for_path_from_db(workspace_id, cx);
+        }
+        self.read_remembered_scroll_position_from_db(cx);
     }
 
     fn to_item_events(event: &EditorEvent, mut f: impl FnMut(ItemEvent)) {
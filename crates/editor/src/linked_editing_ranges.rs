@@ -8,6 +8,8 @@ use util::ResultExt;
 
 use crate::Editor;
 
+enum LinkedEditingRangeHighlight {}
+
 #[derive(Clone, Default)]
 pub(super) struct LinkedEditingRanges(
     /// Ranges are non-overlapping and sorted by .0 (thus, [x + 1].start > [x].end must hold)
@@ -56,6 +58,7 @@ pub(super) fn refresh_linked_ranges(this: &mut Editor, cx: &mut ViewContext<Edit
         if let Some(buffer) = end_position.buffer_id.and_then(|id| buffer.buffer(id)) {
             applicable_selections.push((
                 buffer,
+                start_position.excerpt_id,
                 start_position.text_anchor,
                 end_position.text_anchor,
             ));
@@ -69,7 +72,7 @@ pub(super) fn refresh_linked_ranges(this: &mut Editor, cx: &mut ViewContext<Edit
             .update(&mut cx, |project, cx| {
                 let mut linked_edits_tasks = vec![];
 
-                for (buffer, start, end) in &applicable_selections {
+                for (buffer, excerpt_id, start, end) in &applicable_selections {
                     let snapshot = buffer.read(cx).snapshot();
                     let buffer_id = buffer.read(cx).remote_id();
 
@@ -106,7 +109,7 @@ pub(super) fn refresh_linked_ranges(this: &mut Editor, cx: &mut ViewContext<Edit
                         }
                         let mut siblings: Vec<(_, _)> = siblings.into_iter().collect();
                         siblings.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0, &snapshot));
-                        Some((buffer_id, siblings))
+                        Some((buffer_id, *excerpt_id, siblings))
                     };
                     linked_edits_tasks.push(highlights());
                 }
@@ -118,10 +121,20 @@ pub(super) fn refresh_linked_ranges(this: &mut Editor, cx: &mut ViewContext<Edit
 
         this.update(&mut cx, |this, cx| {
             this.linked_edit_ranges.0.clear();
+            this.clear_background_highlights::<LinkedEditingRangeHighlight>(cx);
             if this.pending_rename.is_some() {
                 return;
             }
-            for (buffer_id, ranges) in highlights.into_iter().flatten() {
+            let multi_buffer_snapshot = this.buffer.read(cx).snapshot(cx);
+            let mut highlighted_ranges = Vec::new();
+            for (buffer_id, excerpt_id, ranges) in highlights.into_iter().flatten() {
+                // Every linked range is a key, so highlighting the keys covers all of them.
+                highlighted_ranges.extend(ranges.iter().filter_map(|(range, _)| {
+                    Some(
+                        multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.start)?
+                            ..multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end)?,
+                    )
+                }));
                 this.linked_edit_ranges
                     .0
                     .entry(buffer_id)
@@ -139,6 +152,13 @@ pub(super) fn refresh_linked_ranges(this: &mut Editor, cx: &mut ViewContext<Edit
                 };
                 values.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0, &snapshot));
             }
+            if !highlighted_ranges.is_empty() {
+                this.highlight_background::<LinkedEditingRangeHighlight>(
+                    &highlighted_ranges,
+                    |theme| theme.editor_document_highlight_read_background,
+                    cx,
+                );
+            }
 
             cx.notify();
         })
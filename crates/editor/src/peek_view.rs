@@ -0,0 +1,414 @@
+use collections::{HashMap, HashSet};
+use gpui::{AnyElement, ClickEvent, FocusableView, Task, View, WeakView};
+use language::{Location, OffsetRangeExt};
+use multi_buffer::Anchor;
+use ui::{prelude::*, Tooltip};
+
+use crate::{
+    actions::{
+        Cancel, GrowPeekView, PeekDefinition, PeekNextDefinition, PeekPreviousDefinition,
+        ShrinkPeekView,
+    },
+    display_map::{
+        BlockContext, BlockDisposition, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    hover_links,
+    scroll::Autoscroll,
+    Editor, EditorEvent,
+};
+
+/// How many lines of the peeked definition are shown when the peek view opens.
+const DEFAULT_PEEK_VIEW_LINES: u32 = 12;
+const MIN_PEEK_VIEW_LINES: u32 = 4;
+const MAX_PEEK_VIEW_LINES: u32 = 48;
+/// How many lines `GrowPeekView` and `ShrinkPeekView` resize the peek view by.
+const PEEK_VIEW_RESIZE_LINES: u32 = 4;
+
+/// An embedded editor shown in a block below the cursor's line, peeking at the definitions
+/// of the symbol under the cursor.
+pub(super) struct PeekView {
+    block_id: CustomBlockId,
+    editor: View<Editor>,
+    locations: Vec<Location>,
+    index: usize,
+    lines: u32,
+}
+
+impl Editor {
+    pub fn peek_definition(
+        &mut self,
+        _: &PeekDefinition,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<anyhow::Result<()>>> {
+        let project = self.project.clone()?;
+        let position = self.selections.newest_anchor().head();
+        let (buffer, head) = self
+            .buffer
+            .read(cx)
+            .text_anchor_for_position(position, cx)?;
+        let definitions = project.update(cx, |project, cx| project.definition(&buffer, head, cx));
+
+        Some(cx.spawn(|editor, mut cx| async move {
+            let definitions = definitions.await?;
+            editor.update(&mut cx, |editor, cx| {
+                let locations = definitions
+                    .into_iter()
+                    .filter(|link| hover_links::exclude_link_to_position(&buffer, &head, link, cx))
+                    .map(|link| link.target)
+                    .collect::<Vec<_>>();
+                editor.show_peek_view(position, locations, cx);
+            })
+        }))
+    }
+
+    /// Opens the peek view below the line of the given position, showing the first of the given
+    /// locations.
+    pub(super) fn show_peek_view(
+        &mut self,
+        position: Anchor,
+        locations: Vec<Location>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.close_peek_view(cx);
+        let Some(editor) = self.peek_view_editor(locations.first(), cx) else {
+            return;
+        };
+
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                style: BlockStyle::Sticky,
+                position,
+                height: DEFAULT_PEEK_VIEW_LINES + 1,
+                render: Box::new(|_| div().into_any_element()),
+                disposition: BlockDisposition::Below,
+            }],
+            None,
+            cx,
+        )[0];
+        cx.focus(&editor.focus_handle(cx));
+        self.peek_view = Some(PeekView {
+            block_id,
+            editor,
+            locations,
+            index: 0,
+            lines: DEFAULT_PEEK_VIEW_LINES,
+        });
+        self.render_peek_view(cx);
+    }
+
+    fn peek_view_editor(
+        &mut self,
+        location: Option<&Location>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Editor>> {
+        let location = location?;
+        let project = self.project.clone()?;
+        let editor =
+            cx.new_view(|cx| Editor::for_buffer(location.buffer.clone(), Some(project), cx));
+        editor.update(cx, |editor, cx| {
+            let buffer = location.buffer.read(cx);
+            let range = location.range.to_offset(buffer);
+            editor.change_selections(Some(Autoscroll::center()), cx, |s| s.select_ranges([range]));
+        });
+        cx.subscribe(&editor, |_, _, event, cx| {
+            if let EditorEvent::Focused = event {
+                cx.emit(EditorEvent::FocusedIn)
+            }
+        })
+        .detach();
+        Some(editor)
+    }
+
+    /// Closes the peek view, returning focus to this editor without moving its selections.
+    pub(super) fn close_peek_view(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(peek_view) = self.peek_view.take() else {
+            return false;
+        };
+        if peek_view.editor.focus_handle(cx).contains_focused(cx) {
+            cx.focus(&self.focus_handle);
+        }
+        self.remove_blocks(HashSet::from_iter([peek_view.block_id]), None, cx);
+        true
+    }
+
+    pub fn peek_next_definition(&mut self, _: &PeekNextDefinition, cx: &mut ViewContext<Self>) {
+        self.peek_definition_by(1, cx);
+    }
+
+    pub fn peek_previous_definition(
+        &mut self,
+        _: &PeekPreviousDefinition,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.peek_definition_by(-1, cx);
+    }
+
+    fn peek_definition_by(&mut self, delta: isize, cx: &mut ViewContext<Self>) {
+        let Some(peek_view) = self.peek_view.as_ref() else {
+            cx.propagate();
+            return;
+        };
+        let count = peek_view.locations.len();
+        if count < 2 {
+            return;
+        }
+
+        let index = (peek_view.index as isize + delta).rem_euclid(count as isize) as usize;
+        let location = peek_view.locations[index].clone();
+        let was_focused = peek_view.editor.focus_handle(cx).contains_focused(cx);
+        let Some(editor) = self.peek_view_editor(Some(&location), cx) else {
+            return;
+        };
+        if was_focused {
+            cx.focus(&editor.focus_handle(cx));
+        }
+        if let Some(peek_view) = self.peek_view.as_mut() {
+            peek_view.index = index;
+            peek_view.editor = editor;
+        }
+        self.render_peek_view(cx);
+    }
+
+    pub fn grow_peek_view(&mut self, _: &GrowPeekView, cx: &mut ViewContext<Self>) {
+        self.resize_peek_view(PEEK_VIEW_RESIZE_LINES as i32, cx);
+    }
+
+    pub fn shrink_peek_view(&mut self, _: &ShrinkPeekView, cx: &mut ViewContext<Self>) {
+        self.resize_peek_view(-(PEEK_VIEW_RESIZE_LINES as i32), cx);
+    }
+
+    fn resize_peek_view(&mut self, delta: i32, cx: &mut ViewContext<Self>) {
+        let Some(peek_view) = self.peek_view.as_mut() else {
+            cx.propagate();
+            return;
+        };
+        peek_view.lines = peek_view
+            .lines
+            .saturating_add_signed(delta)
+            .clamp(MIN_PEEK_VIEW_LINES, MAX_PEEK_VIEW_LINES);
+        let heights = HashMap::from_iter([(peek_view.block_id, peek_view.lines + 1)]);
+        self.resize_blocks(heights, None, cx);
+        self.render_peek_view(cx);
+    }
+
+    /// Replaces the peek view's block renderer with one for its current state.
+    fn render_peek_view(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(peek_view) = self.peek_view.as_ref() else {
+            return;
+        };
+        let location = &peek_view.locations[peek_view.index];
+        let title: SharedString = location
+            .buffer
+            .read(cx)
+            .file()
+            .map(|file| file.full_path(cx).to_string_lossy().to_string())
+            .unwrap_or_else(|| "untitled".to_string())
+            .into();
+        let block_id = peek_view.block_id;
+        let renderer = peek_view_renderer(
+            cx.view().downgrade(),
+            peek_view.editor.clone(),
+            title,
+            peek_view.index,
+            peek_view.locations.len(),
+            peek_view.lines,
+        );
+        self.replace_blocks(HashMap::from_iter([(block_id, renderer)]), None, cx);
+        cx.notify();
+    }
+}
+
+fn peek_view_renderer(
+    parent: WeakView<Editor>,
+    editor: View<Editor>,
+    title: SharedString,
+    index: usize,
+    count: usize,
+    lines: u32,
+) -> RenderBlock {
+    Box::new(move |cx: &mut BlockContext| {
+        let button = |id: &'static str, icon: IconName| {
+            IconButton::new(id, icon)
+                .icon_size(IconSize::Small)
+                .icon_color(Color::Muted)
+        };
+        let on_click = |action: fn(&mut Editor, &mut ViewContext<Editor>)| {
+            let parent = parent.clone();
+            move |_: &ClickEvent, cx: &mut WindowContext| {
+                parent.update(cx, |editor, cx| action(editor, cx)).ok();
+            }
+        };
+
+        v_flex()
+            .w_full()
+            .pl(cx.gutter_dimensions.full_width())
+            .bg(cx.theme().colors().editor_subheader_background)
+            .child(
+                h_flex()
+                    .h(cx.line_height)
+                    .px_2()
+                    .gap_2()
+                    .child(Label::new(title.clone()).size(LabelSize::Small))
+                    .when(count > 1, |this| {
+                        this.child(
+                            Label::new(format!("{} of {}", index + 1, count))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .child(div().flex_1())
+                    .when(count > 1, |this| {
+                        this.child(
+                            button("peek-previous-definition", IconName::ChevronUp)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Previous Definition",
+                                        &PeekPreviousDefinition,
+                                        cx,
+                                    )
+                                })
+                                .on_click(on_click(|editor, cx| {
+                                    editor.peek_previous_definition(&PeekPreviousDefinition, cx)
+                                })),
+                        )
+                        .child(
+                            button("peek-next-definition", IconName::ChevronDown)
+                                .tooltip(|cx| {
+                                    Tooltip::for_action("Next Definition", &PeekNextDefinition, cx)
+                                })
+                                .on_click(on_click(|editor, cx| {
+                                    editor.peek_next_definition(&PeekNextDefinition, cx)
+                                })),
+                        )
+                    })
+                    .child(
+                        button("shrink-peek-view", IconName::Dash)
+                            .disabled(lines <= MIN_PEEK_VIEW_LINES)
+                            .tooltip(|cx| Tooltip::for_action("Shrink", &ShrinkPeekView, cx))
+                            .on_click(on_click(|editor, cx| {
+                                editor.shrink_peek_view(&ShrinkPeekView, cx)
+                            })),
+                    )
+                    .child(
+                        button("grow-peek-view", IconName::Plus)
+                            .disabled(lines >= MAX_PEEK_VIEW_LINES)
+                            .tooltip(|cx| Tooltip::for_action("Grow", &GrowPeekView, cx))
+                            .on_click(on_click(|editor, cx| {
+                                editor.grow_peek_view(&GrowPeekView, cx)
+                            })),
+                    )
+                    .child(
+                        button("close-peek-view", IconName::Close)
+                            .tooltip(|cx| Tooltip::text("Close", cx))
+                            .on_click(on_click(|editor, cx| {
+                                editor.close_peek_view(cx);
+                            })),
+                    ),
+            )
+            .child(
+                div()
+                    .h(cx.line_height * lines as f32)
+                    // Close the peek view rather than collapsing the selection of the peeked
+                    // definition, unless the peek editor has a menu of its own to dismiss.
+                    .capture_action({
+                        let parent = parent.clone();
+                        let editor = editor.downgrade();
+                        move |_: &Cancel, cx| {
+                            let has_menu = editor
+                                .update(cx, |editor, _| editor.context_menu_visible())
+                                .unwrap_or(false);
+                            if !has_menu {
+                                parent
+                                    .update(cx, |editor, cx| editor.close_peek_view(cx))
+                                    .ok();
+                                cx.stop_propagation();
+                            }
+                        }
+                    })
+                    .child(editor.clone()),
+            )
+            .into_any_element()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{editor_tests::init_test, test::editor_test_context::EditorTestContext};
+    use gpui::KeyBinding;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_peek_view(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| cx.bind_keys([KeyBinding::new("escape", Cancel, Some("Editor"))]));
+
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state(indoc! {"
+            fn one() {}
+            fn two() {}
+            fn main() { ˇone(); }
+        "});
+        cx.update_editor(|editor, cx| {
+            let buffer = editor.buffer.read(cx).as_singleton().unwrap();
+            let locations = [3..6, 15..18]
+                .into_iter()
+                .map(|range| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    Location {
+                        buffer: buffer.clone(),
+                        range: snapshot.anchor_before(range.start)
+                            ..snapshot.anchor_after(range.end),
+                    }
+                })
+                .collect();
+            let position = editor.selections.newest_anchor().head();
+            editor.show_peek_view(position, locations, cx);
+        });
+
+        // The peek view opens at the first location and navigates between them, wrapping around.
+        cx.update_editor(|editor, cx| {
+            let peek_view = editor.peek_view.as_ref().unwrap();
+            assert_eq!(peek_view.index, 0);
+            assert_eq!(
+                peek_view
+                    .editor
+                    .read(cx)
+                    .selections
+                    .newest::<usize>(cx)
+                    .range(),
+                3..6
+            );
+            editor.peek_next_definition(&PeekNextDefinition, cx);
+            let peek_view = editor.peek_view.as_ref().unwrap();
+            assert_eq!(peek_view.index, 1);
+            assert_eq!(
+                peek_view
+                    .editor
+                    .read(cx)
+                    .selections
+                    .newest::<usize>(cx)
+                    .range(),
+                15..18
+            );
+            editor.peek_next_definition(&PeekNextDefinition, cx);
+            assert_eq!(editor.peek_view.as_ref().unwrap().index, 0);
+
+            editor.grow_peek_view(&GrowPeekView, cx);
+            assert_eq!(
+                editor.peek_view.as_ref().unwrap().lines,
+                DEFAULT_PEEK_VIEW_LINES + PEEK_VIEW_RESIZE_LINES
+            );
+        });
+
+        // Dismissing the peek view from its editor closes it right away, keeping the cursor
+        // where it was.
+        cx.simulate_keystrokes("escape");
+        cx.update_editor(|editor, _| assert!(editor.peek_view.is_none()));
+        cx.assert_editor_state(indoc! {"
+            fn one() {}
+            fn two() {}
+            fn main() { ˇone(); }
+        "});
+    }
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
 use db::sqlez::bindable::{Bind, Column, StaticColumnCount};
 use db::sqlez::statement::Statement;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use db::sqlez_macros::sql;
@@ -89,6 +90,23 @@ define_connection!(
     //   mtime_seconds: Option<i64>,
     //   mtime_nanos: Option<i32>,
     // )
+    //
+    // editor_scroll_positions(
+    //   workspace_id: usize,
+    //   path: PathBuf, // Absolute, or starting with the worktree's root name for remote files
+    //   scroll_top_row: usize,
+    //   scroll_horizontal_offset: f32,
+    //   scroll_vertical_offset: f32,
+    // )
+    //
+    // editor_remembered_scroll_positions(
+    //   workspace_id: usize,
+    //   key: String, // What a multibuffer shows, like the query of a project search
+    //   path: PathBuf, // The file the top row is in
+    //   scroll_top_row: usize,
+    //   scroll_horizontal_offset: f32,
+    //   scroll_vertical_offset: f32,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -136,6 +154,33 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN mtime_seconds INTEGER DEFAULT NULL;
             ALTER TABLE editors ADD COLUMN mtime_nanos INTEGER DEFAULT NULL;
         ),
+        sql! (
+            CREATE TABLE editor_scroll_positions(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                scroll_top_row INTEGER NOT NULL DEFAULT 0,
+                scroll_horizontal_offset REAL NOT NULL DEFAULT 0,
+                scroll_vertical_offset REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY(workspace_id, path),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
+        sql! (
+            CREATE TABLE editor_remembered_scroll_positions(
+                workspace_id INTEGER NOT NULL,
+                key TEXT NOT NULL,
+                path BLOB NOT NULL,
+                scroll_top_row INTEGER NOT NULL DEFAULT 0,
+                scroll_horizontal_offset REAL NOT NULL DEFAULT 0,
+                scroll_vertical_offset REAL NOT NULL DEFAULT 0,
+                PRIMARY KEY(workspace_id, key),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
+        ),
         ];
 );
 
@@ -190,6 +235,90 @@ impl EditorDb {
         }
     }
 
+    // Returns the scroll top row, and offset
+    query! {
+        pub fn get_scroll_position_for_path(workspace_id: WorkspaceId, path: Arc<Path>) -> Result<Option<(u32, f32, f32)>> {
+            SELECT scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset
+            FROM editor_scroll_positions
+            WHERE workspace_id = ? AND path = ?
+        }
+    }
+
+    // Replacing the row moves it to the end of the rowid order, which the oldest positions
+    // are deleted by.
+    query! {
+        pub async fn save_scroll_position_for_path(
+            workspace_id: WorkspaceId,
+            path: Arc<Path>,
+            top_row: u32,
+            horizontal_offset: f32,
+            vertical_offset: f32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO editor_scroll_positions
+                (workspace_id, path, scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5)
+        }
+    }
+
+    query! {
+        pub async fn delete_oldest_scroll_positions_for_paths(workspace_id: WorkspaceId, kept_count: usize) -> Result<()> {
+            DELETE FROM editor_scroll_positions
+            WHERE workspace_id = ?1 AND rowid NOT IN (
+                SELECT rowid FROM editor_scroll_positions
+                WHERE workspace_id = ?1
+                ORDER BY rowid DESC
+                LIMIT ?2
+            )
+        }
+    }
+
+    // Returns the path of the file the top row is in, the scroll top row, and offset
+    query! {
+        pub fn get_remembered_scroll_position(workspace_id: WorkspaceId, key: String) -> Result<Option<(PathBuf, u32, f32, f32)>> {
+            SELECT path, scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset
+            FROM editor_remembered_scroll_positions
+            WHERE workspace_id = ? AND key = ?
+        }
+    }
+
+    // Replacing the row moves it to the end of the rowid order, which the oldest positions
+    // are deleted by.
+    query! {
+        pub async fn save_remembered_scroll_position(
+            workspace_id: WorkspaceId,
+            key: String,
+            path: Arc<Path>,
+            top_row: u32,
+            horizontal_offset: f32,
+            vertical_offset: f32
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO editor_remembered_scroll_positions
+                (workspace_id, key, path, scroll_top_row, scroll_horizontal_offset, scroll_vertical_offset)
+            VALUES
+                (?1, ?2, ?3, ?4, ?5, ?6)
+        }
+    }
+
+    query! {
+        pub async fn delete_remembered_scroll_position(workspace_id: WorkspaceId, key: String) -> Result<()> {
+            DELETE FROM editor_remembered_scroll_positions
+            WHERE workspace_id = ? AND key = ?
+        }
+    }
+
+    query! {
+        pub async fn delete_oldest_remembered_scroll_positions(workspace_id: WorkspaceId, kept_count: usize) -> Result<()> {
+            DELETE FROM editor_remembered_scroll_positions
+            WHERE workspace_id = ?1 AND rowid NOT IN (
+                SELECT rowid FROM editor_remembered_scroll_positions
+                WHERE workspace_id = ?1
+                ORDER BY rowid DESC
+                LIMIT ?2
+            )
+        }
+    }
+
     pub async fn delete_unloaded_items(
         &self,
         workspace: WorkspaceId,
@@ -298,4 +427,107 @@ mod tests {
             .unwrap();
         assert_eq!(have, serialized_editor);
     }
+
+    #[gpui::test]
+    async fn test_save_and_get_scroll_position_for_path() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let path: Arc<Path> = Path::new("/root/a/src/main.rs").into();
+        let other_worktree_path: Arc<Path> = Path::new("/root/b/src/main.rs").into();
+
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, path.clone())
+                .unwrap(),
+            None
+        );
+
+        DB.save_scroll_position_for_path(workspace_id, path.clone(), 10, 0.5, 0.25)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, path.clone())
+                .unwrap(),
+            Some((10, 0.5, 0.25))
+        );
+        // The same relative path in another worktree has its own position.
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, other_worktree_path)
+                .unwrap(),
+            None
+        );
+
+        // Saving again overwrites the previous position.
+        DB.save_scroll_position_for_path(workspace_id, path.clone(), 42, 0., 0.)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, path.clone())
+                .unwrap(),
+            Some((42, 0., 0.))
+        );
+
+        // Only the most recently saved positions are kept.
+        let other_path: Arc<Path> = Path::new("/root/a/src/lib.rs").into();
+        DB.save_scroll_position_for_path(workspace_id, other_path.clone(), 7, 0., 0.)
+            .await
+            .unwrap();
+        DB.delete_oldest_scroll_positions_for_paths(workspace_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, path).unwrap(),
+            None
+        );
+        assert_eq!(
+            DB.get_scroll_position_for_path(workspace_id, other_path)
+                .unwrap(),
+            Some((7, 0., 0.))
+        );
+    }
+
+    #[gpui::test]
+    async fn test_remembered_scroll_positions() {
+        let workspace_id = workspace::WORKSPACE_DB.next_id().await.unwrap();
+        let path: Arc<Path> = Path::new("/root/a/src/main.rs").into();
+
+        for (key, top_row) in [("a", 1), ("b", 2), ("a", 3)] {
+            DB.save_remembered_scroll_position(
+                workspace_id,
+                key.to_string(),
+                path.clone(),
+                top_row,
+                0.,
+                0.5,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(
+            DB.get_remembered_scroll_position(workspace_id, "a".to_string())
+                .unwrap(),
+            Some((PathBuf::from("/root/a/src/main.rs"), 3, 0., 0.5))
+        );
+
+        // Saving a position again makes it the most recent one.
+        DB.delete_oldest_remembered_scroll_positions(workspace_id, 1)
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_remembered_scroll_position(workspace_id, "b".to_string())
+                .unwrap(),
+            None
+        );
+        assert!(DB
+            .get_remembered_scroll_position(workspace_id, "a".to_string())
+            .unwrap()
+            .is_some());
+
+        DB.delete_remembered_scroll_position(workspace_id, "a".to_string())
+            .await
+            .unwrap();
+        assert_eq!(
+            DB.get_remembered_scroll_position(workspace_id, "a".to_string())
+                .unwrap(),
+            None
+        );
+    }
 }
@@ -0,0 +1,33 @@
+use gpui::{Model, Task};
+use project::Project;
+use ui::ViewContext;
+use util::ResultExt;
+
+use crate::{viewport_refresh::VisibleBuffers, Editor};
+
+/// Requests the diagnostics of the visible buffers edited since the last refresh from their
+/// language servers that report diagnostics on request. Servers that publish diagnostics on
+/// their own are not affected.
+pub(super) fn refresh_pulled_diagnostics(
+    project: &Model<Project>,
+    visible: &VisibleBuffers,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Option<()>> {
+    let tasks = project.update(cx, |project, cx| {
+        visible
+            .edited
+            .iter()
+            .filter_map(|buffer_id| {
+                let (buffer, _) = visible.buffers.get(buffer_id)?;
+                Some(project.pull_diagnostics(buffer, cx))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    cx.spawn(|_, _| async move {
+        for task in tasks {
+            task.await.log_err();
+        }
+        Some(())
+    })
+}
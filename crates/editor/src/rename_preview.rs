@@ -0,0 +1,298 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use gpui::{AppContext, AsyncWindowContext, Context, Task, VisualContext, WeakView};
+use language::Capability;
+use multi_buffer::{Anchor, AnchorRangeExt, MultiBuffer};
+use project::ProjectTransaction;
+use ui::ViewContext;
+use workspace::{SaveIntent, Workspace};
+
+use crate::{
+    actions::{ConfirmRenamePreview, ExcludeRenameEdit},
+    Editor, DEFAULT_MULTIBUFFER_CONTEXT,
+};
+
+/// A rename whose edits are previewed in a multibuffer while the `preview_renames` setting is
+/// enabled. The edits are saved once the preview is confirmed, and undone when the preview is
+/// closed without confirming it.
+pub(super) struct RenamePreview {
+    old_name: String,
+    new_name: String,
+    /// The edits that haven't been excluded from the rename.
+    edits: Vec<Range<Anchor>>,
+}
+
+impl Editor {
+    pub(super) async fn open_rename_preview(
+        this: &WeakView<Editor>,
+        workspace: WeakView<Workspace>,
+        transaction: ProjectTransaction,
+        old_name: String,
+        new_name: String,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let replica_id = this.update(&mut cx, |this, cx| this.replica_id(cx))?;
+
+        let mut entries = transaction.0.into_iter().collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Ok(());
+        }
+        cx.update(|cx| {
+            entries.sort_unstable_by_key(|(buffer, _)| {
+                buffer.read(cx).file().map(|f| f.path().clone())
+            });
+        })?;
+
+        let mut edits = Vec::new();
+        let title = format!("Rename: {} → {}", old_name, new_name);
+        let excerpt_buffer = cx.new_model(|cx| {
+            let mut multibuffer =
+                MultiBuffer::new(replica_id, Capability::ReadWrite).with_title(title);
+            for (buffer_handle, transaction) in &entries {
+                let buffer = buffer_handle.read(cx);
+                edits.extend(
+                    multibuffer.push_excerpts_with_context_lines(
+                        buffer_handle.clone(),
+                        buffer
+                            .edited_ranges_for_transaction::<usize>(transaction)
+                            .collect(),
+                        DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    ),
+                );
+            }
+            multibuffer.push_transaction(entries.iter().map(|(b, t)| (b, t)), cx);
+            multibuffer
+        })?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let editor =
+                cx.new_view(|cx| Editor::for_multibuffer(excerpt_buffer, Some(project), true, cx));
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+            editor.update(cx, |editor, cx| {
+                editor.rename_preview = Some(RenamePreview {
+                    old_name,
+                    new_name,
+                    edits,
+                });
+                editor.highlight_rename_preview(cx);
+                cx.on_release(|editor, _, cx| editor.undo_rename_preview(cx))
+                    .detach();
+            });
+        })?;
+
+        Ok(())
+    }
+
+    /// Undoes the edits of a previewed rename that wasn't confirmed, including the edits made
+    /// in the preview since.
+    fn undo_rename_preview(&mut self, cx: &mut AppContext) {
+        if self.rename_preview.take().is_some() {
+            self.buffer
+                .update(cx, |buffer, cx| while buffer.undo(cx).is_some() {});
+        }
+    }
+
+    fn highlight_rename_preview(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(preview) = &self.rename_preview {
+            let edits = preview.edits.clone();
+            self.highlight_background::<RenamePreview>(
+                &edits,
+                |theme| theme.editor_highlighted_line_background,
+                cx,
+            );
+        } else {
+            self.clear_background_highlights::<RenamePreview>(cx);
+        }
+    }
+
+    /// Restores the old name at the previewed rename's edits that intersect the selections.
+    pub fn exclude_rename_edit(&mut self, _: &ExcludeRenameEdit, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+        let Some(preview) = self.rename_preview.as_mut() else {
+            cx.propagate();
+            return;
+        };
+
+        let mut excluded = Vec::new();
+        preview.edits.retain(|edit| {
+            let edit = edit.to_offset(&buffer);
+            let selected = selections
+                .iter()
+                .any(|selection| selection.start <= edit.end && selection.end >= edit.start);
+            // Only the edits that replaced the old name can be undone by restoring it.
+            if selected
+                && buffer.text_for_range(edit.clone()).collect::<String>() == preview.new_name
+            {
+                excluded.push((edit, preview.old_name.clone()));
+                false
+            } else {
+                true
+            }
+        });
+
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(excluded, None, cx));
+        self.highlight_rename_preview(cx);
+    }
+
+    /// Saves the buffers edited by the previewed rename and closes the preview.
+    pub fn confirm_rename_preview(
+        &mut self,
+        _: &ConfirmRenamePreview,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<Task<Result<()>>> {
+        self.rename_preview.take()?;
+        self.highlight_rename_preview(cx);
+
+        let project = self.project.clone()?;
+        let workspace = self.workspace()?.downgrade();
+        let buffers = self.buffer.read(cx).all_buffers();
+        let save = project.update(cx, |project, cx| project.save_buffers(buffers, cx));
+        Some(cx.spawn(|editor, mut cx| async move {
+            save.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let editor = editor.upgrade()?;
+                let pane = workspace.pane_for(&editor)?;
+                pane.update(cx, |pane, cx| {
+                    pane.close_item_by_id(editor.entity_id(), SaveIntent::Skip, cx)
+                })
+                .detach_and_log_err(cx);
+                Some(())
+            })?;
+            Ok(())
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        actions::{ConfirmRename, Rename},
+        editor_tests::init_test,
+        test::{
+            editor_lsp_test_context::EditorLspTestContext, editor_test_context::EditorTestContext,
+        },
+        EditorSettings,
+    };
+    use futures::StreamExt;
+    use indoc::indoc;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    async fn test_exclude_rename_edit(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorTestContext::new(cx).await;
+        cx.set_state(indoc! {"
+            let total = 1;
+            ˇtotal + 1;
+            let totals = vec![total];
+        "});
+        cx.update_editor(|editor, cx| {
+            let buffer = editor.buffer.read(cx).snapshot(cx);
+            let edits = [(15, 20), (44, 49)]
+                .into_iter()
+                .map(|(start, end)| buffer.anchor_after(start)..buffer.anchor_before(end))
+                .collect();
+            editor.rename_preview = Some(RenamePreview {
+                old_name: "count".into(),
+                new_name: "total".into(),
+                edits,
+            });
+            editor.exclude_rename_edit(&ExcludeRenameEdit, cx);
+        });
+
+        // Only the rename's edits that intersect the selections are excluded.
+        cx.update_editor(|editor, cx| {
+            assert_eq!(
+                editor.text(cx),
+                indoc! {"
+                    let total = 1;
+                    count + 1;
+                    let totals = vec![total];
+                "}
+            );
+            assert_eq!(editor.rename_preview.as_ref().unwrap().edits.len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    async fn test_closing_rename_preview_undoes_rename(cx: &mut gpui::TestAppContext) {
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                rename_provider: Some(lsp::OneOf::Right(lsp::RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                })),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.preview_renames = Some(true);
+                });
+            });
+        });
+
+        cx.set_state("fn ˇcount() {}\nfn main() { count(); }");
+        let definition = cx.lsp_range("fn «count»() {}\nfn main() { count(); }");
+        let call = cx.lsp_range("fn count() {}\nfn main() { «count»(); }");
+        let mut prepare_request = cx.handle_request::<lsp::request::PrepareRenameRequest, _, _>(
+            move |_, _, _| async move { Ok(Some(lsp::PrepareRenameResponse::Range(definition))) },
+        );
+        let mut rename_request =
+            cx.handle_request::<lsp::request::Rename, _, _>(move |url, params, _| async move {
+                Ok(Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [(
+                            url,
+                            vec![
+                                lsp::TextEdit::new(definition, params.new_name.clone()),
+                                lsp::TextEdit::new(call, params.new_name),
+                            ],
+                        )]
+                        .into(),
+                    ),
+                    ..Default::default()
+                }))
+            });
+
+        let rename = cx.update_editor(|editor, cx| editor.rename(&Rename, cx).unwrap());
+        prepare_request.next().await.unwrap();
+        rename.await.unwrap();
+        let confirm = cx.update_editor(|editor, cx| {
+            let rename_editor = editor.pending_rename.as_ref().unwrap().editor.clone();
+            rename_editor.update(cx, |rename_editor, cx| rename_editor.set_text("total", cx));
+            editor.confirm_rename(&ConfirmRename, cx).unwrap()
+        });
+        rename_request.next().await.unwrap();
+        confirm.await.unwrap();
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.text(cx), "fn total() {}\nfn main() { total(); }")
+        });
+
+        // Closing the preview without confirming it undoes the rename, without asking to save
+        // its edits first.
+        cx.update_workspace(|workspace, cx| {
+            let preview = workspace.active_item_as::<Editor>(cx).unwrap();
+            assert!(preview.read(cx).rename_preview.is_some());
+            workspace.active_pane().update(cx, |pane, cx| {
+                pane.close_item_by_id(preview.entity_id(), SaveIntent::Close, cx)
+            })
+        })
+        .await
+        .unwrap();
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.text(cx), "fn count() {}\nfn main() { count(); }")
+        });
+    }
+}
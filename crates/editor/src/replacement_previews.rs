@@ -0,0 +1,104 @@
+use std::{any::TypeId, mem, ops::Range};
+
+use collections::HashMap;
+use gpui::{HighlightStyle, StrikethroughStyle};
+use multi_buffer::Anchor;
+use ui::prelude::*;
+use util::post_inc;
+
+use crate::{display_map::Inlay, hover_links::InlayHighlight, Editor};
+
+enum ReplacementPreviewHighlight {}
+
+/// A replacement of the text in the given range, previewed before it is applied.
+#[derive(Clone, Debug)]
+pub struct ReplacementPreview {
+    pub range: Range<Anchor>,
+    pub new_text: String,
+}
+
+impl Editor {
+    /// Previews the given replacements by striking through the text they replace and showing
+    /// their new text right after it, in place of the previews shown before.
+    ///
+    /// The inlays of previews that are shown already are kept, so that only new or changed
+    /// previews are inserted.
+    pub fn set_replacement_previews(
+        &mut self,
+        previews: Vec<ReplacementPreview>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let old_previews = mem::take(&mut self.replacement_previews);
+        if old_previews.is_empty() && previews.is_empty() {
+            return;
+        }
+
+        let mut old_inlays = old_previews
+            .into_iter()
+            .filter_map(|(preview, inlay_id)| Some(((preview.range, preview.new_text), inlay_id?)))
+            .collect::<HashMap<_, _>>();
+        let mut to_insert = Vec::new();
+        let mut inlay_highlights = Vec::new();
+        for preview in &previews {
+            let inlay_id = if preview.new_text.is_empty() {
+                None
+            } else {
+                let key = (preview.range.clone(), preview.new_text.clone());
+                let inlay_id = old_inlays.remove(&key).unwrap_or_else(|| {
+                    let inlay = Inlay::replacement(
+                        post_inc(&mut self.next_inlay_id),
+                        preview.range.end,
+                        preview.new_text.as_str(),
+                    );
+                    let inlay_id = inlay.id;
+                    to_insert.push(inlay);
+                    inlay_id
+                });
+                inlay_highlights.push(InlayHighlight {
+                    inlay: inlay_id,
+                    inlay_position: preview.range.end,
+                    range: 0..preview.new_text.len(),
+                });
+                Some(inlay_id)
+            };
+            self.replacement_previews.push((preview.clone(), inlay_id));
+        }
+        let to_remove = old_inlays.into_values().collect::<Vec<_>>();
+
+        let status = cx.theme().status();
+        let replaced_text_style = HighlightStyle {
+            color: Some(status.deleted),
+            strikethrough: Some(StrikethroughStyle {
+                thickness: 1.0.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let new_text_style = HighlightStyle {
+            color: Some(status.created),
+            background_color: Some(status.created_background),
+            ..Default::default()
+        };
+        let replaced_ranges = previews
+            .into_iter()
+            .map(|preview| preview.range)
+            .collect::<Vec<_>>();
+
+        self.display_map.update(cx, |display_map, cx| {
+            let type_id = TypeId::of::<ReplacementPreviewHighlight>();
+            display_map.clear_highlights(type_id);
+            if !to_remove.is_empty() || !to_insert.is_empty() {
+                display_map.splice_inlays(to_remove, to_insert, cx);
+            }
+            if !replaced_ranges.is_empty() {
+                display_map.highlight_text(type_id, replaced_ranges, replaced_text_style);
+                display_map.highlight_inlays(type_id, inlay_highlights, new_text_style);
+            }
+        });
+        cx.notify();
+    }
+
+    pub fn clear_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        self.set_replacement_previews(Vec::new(), cx);
+    }
+}
@@ -2,21 +2,30 @@ mod actions;
 pub(crate) mod autoscroll;
 pub(crate) mod scroll_amount;
 
-use crate::editor_settings::ScrollBeyondLastLine;
+use crate::editor_settings::{ScrollAxisLock, ScrollBeyondLastLine};
 use crate::{
+    bracket_colorization::refresh_bracket_colors,
     display_map::{DisplaySnapshot, ToDisplayPoint},
     hover_popover::hide_hover,
     persistence::DB,
+    viewport_refresh::{refresh_viewport, ViewportRefreshReason},
     Anchor, DisplayPoint, DisplayRow, Editor, EditorEvent, EditorMode, EditorSettings,
-    InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ToPoint,
+    InlayHintRefreshReason, MultiBufferSnapshot, RowExt, ScrollHistoryBack, ScrollHistoryForward,
+    SelectPhase, ToPoint, ToggleScrollLock,
 };
 pub use autoscroll::{Autoscroll, AutoscrollStrategy};
-use gpui::{point, px, AppContext, Entity, Global, Pixels, Task, ViewContext, WindowContext};
+use gpui::{
+    point, px, AppContext, Entity, Global, Pixels, SharedString, Subscription, Task, View,
+    ViewContext, WeakView, WindowContext,
+};
 use language::{Bias, Point};
 pub use scroll_amount::ScrollAmount;
 use settings::Settings;
 use std::{
     cmp::Ordering,
+    collections::VecDeque,
+    path::Path,
+    sync::Arc,
     time::{Duration, Instant},
 };
 use util::ResultExt;
@@ -24,12 +33,43 @@ use workspace::{ItemId, WorkspaceId};
 
 pub const SCROLL_EVENT_SEPARATION: Duration = Duration::from_millis(28);
 const SCROLLBAR_SHOW_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_SCROLL_HISTORY_LEN: usize = 100;
+const SCROLL_ANIMATION_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+const MIDDLE_CLICK_AUTOSCROLL_INTERVAL: Duration = Duration::from_millis(16);
+const DRAG_AUTOSCROLL_INTERVAL: Duration = Duration::from_millis(16);
+/// How far the mouse can move from the origin before middle-click autoscroll starts scrolling.
+const MIDDLE_CLICK_AUTOSCROLL_DEAD_ZONE: Pixels = px(8.);
+/// Pixels scrolled per second, for every pixel the mouse is away from the origin.
+const MIDDLE_CLICK_AUTOSCROLL_SPEED: f32 = 5.;
+/// How much of a scroll past the start or end of the buffer stretches the editor,
+/// before it gets harder to stretch further.
+const ELASTIC_OVERSCROLL_RESISTANCE: f32 = 0.5;
+/// The furthest the editor can be stretched, as a fraction of its visible lines.
+const MAX_ELASTIC_OVERSCROLL: f32 = 0.25;
+/// How long the scrolling has to pause for the stretch to spring back.
+const ELASTIC_OVERSCROLL_SETTLE_DELAY: Duration = Duration::from_millis(60);
+/// The fraction of the stretch that is left after each frame of the spring-back.
+const ELASTIC_OVERSCROLL_DECAY: f32 = 0.75;
+const MAX_REMEMBERED_SCROLL_POSITIONS: usize = 50;
+/// How long scrolling has to pause before the position is saved for the file's path.
+const SCROLL_POSITION_FOR_PATH_SAVE_DEBOUNCE: Duration = Duration::from_millis(100);
+/// How many files' scroll positions are kept for each workspace.
+const MAX_SCROLL_POSITIONS_FOR_PATHS: usize = 500;
 
 #[derive(Default)]
 pub struct ScrollbarAutoHide(pub bool);
 
 impl Global for ScrollbarAutoHide {}
 
+/// A scroll position relative to a row of a file rather than to an excerpt, so that it
+/// stays valid when a multibuffer's excerpts are recreated, and across restarts.
+#[derive(Clone)]
+struct RememberedScrollPosition {
+    path: Arc<Path>,
+    top_row: u32,
+    offset: gpui::Point<f32>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct ScrollAnchor {
     pub offset: gpui::Point<f32>,
@@ -80,9 +120,16 @@ impl OngoingScroll {
         }
     }
 
-    pub fn filter(&self, delta: &mut gpui::Point<Pixels>) -> Option<Axis> {
-        const UNLOCK_PERCENT: f32 = 1.9;
-        const UNLOCK_LOWER_BOUND: Pixels = px(6.);
+    pub fn filter(
+        &self,
+        delta: &mut gpui::Point<Pixels>,
+        axis_lock: &ScrollAxisLock,
+    ) -> Option<Axis> {
+        if !axis_lock.enabled {
+            return None;
+        }
+        let unlock_ratio = axis_lock.unlock_ratio;
+        let unlock_threshold = px(axis_lock.unlock_threshold);
         let mut axis = self.axis;
 
         let x = delta.x.abs();
@@ -95,17 +142,17 @@ impl OngoingScroll {
             } else {
                 Some(Axis::Horizontal)
             };
-        } else if x.max(y) >= UNLOCK_LOWER_BOUND {
+        } else if x.max(y) >= unlock_threshold {
             //Check if the current ongoing will need to unlock
             match axis {
                 Some(Axis::Vertical) => {
-                    if x > y && x >= y * UNLOCK_PERCENT {
+                    if x > y && x >= y * unlock_ratio {
                         axis = None;
                     }
                 }
 
                 Some(Axis::Horizontal) => {
-                    if y > x && y >= x * UNLOCK_PERCENT {
+                    if y > x && y >= x * unlock_ratio {
                         axis = None;
                     }
                 }
@@ -128,32 +175,168 @@ impl OngoingScroll {
     }
 }
 
+/// Keeps the vertical scroll position of an editor locked to another editor's.
+///
+/// The lock is released once either editor is released, so that items moved to another pane
+/// stay locked.
+pub(crate) struct ScrollLock {
+    editor: WeakView<Editor>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ScrollLock {
+    fn new(leader: &View<Editor>, row_offset: f32, cx: &mut ViewContext<Editor>) -> Self {
+        let leader_id = leader.entity_id();
+        let subscriptions = vec![
+            cx.subscribe(leader, move |editor, leader, event, cx| {
+                // Only follow scrolls that originated in the leader, so that the locked
+                // editors don't keep echoing the positions they set on each other.
+                if let EditorEvent::ScrollPositionChanged { local: true, .. } = event {
+                    let leader_top = leader.update(cx, |leader, cx| leader.scroll_position(cx).y);
+                    let mut scroll_position = editor.scroll_position(cx);
+                    scroll_position.y = (leader_top + row_offset).max(0.);
+                    editor.set_scroll_position_internal(scroll_position, false, false, cx);
+                }
+            }),
+            cx.observe_release(leader, move |editor, _, _| {
+                editor
+                    .scroll_locks
+                    .retain(|lock| lock.editor.entity_id() != leader_id);
+            }),
+        ];
+        Self {
+            editor: leader.downgrade(),
+            _subscriptions: subscriptions,
+        }
+    }
+}
+
+/// The viewports an editor jumped away from, so that they can be returned to
+/// without moving the selections.
+#[derive(Default)]
+struct ScrollHistory {
+    back: VecDeque<ScrollAnchor>,
+    forward: VecDeque<ScrollAnchor>,
+}
+
+impl ScrollHistory {
+    fn push(&mut self, anchor: ScrollAnchor) {
+        if self.back.back() != Some(&anchor) {
+            push_capped(&mut self.back, anchor);
+        }
+        self.forward.clear();
+    }
+}
+
+/// Pushes an anchor onto one of the stacks of a [`ScrollHistory`], dropping the oldest one
+/// once the stack is full.
+fn push_capped(stack: &mut VecDeque<ScrollAnchor>, anchor: ScrollAnchor) {
+    if stack.len() >= MAX_SCROLL_HISTORY_LEN {
+        stack.pop_front();
+    }
+    stack.push_back(anchor);
+}
+
+/// The panning mode entered by pressing the middle mouse button, in which the editor
+/// keeps scrolling with a speed proportional to the mouse's distance from `origin`.
+struct MiddleClickAutoscroll {
+    origin: gpui::Point<Pixels>,
+    position: gpui::Point<Pixels>,
+    line_height: Pixels,
+    em_width: Pixels,
+    scroll_max: gpui::Point<f32>,
+    /// Whether the mouse has left the dead zone since the mode was entered.
+    moved: bool,
+    _task: Task<()>,
+}
+
+impl MiddleClickAutoscroll {
+    /// The distance the mouse has moved from the origin, outside of the dead zone.
+    fn offset(&self) -> gpui::Point<Pixels> {
+        let offset = self.position - self.origin;
+        let outside_dead_zone = |delta: Pixels| {
+            if delta.abs() <= MIDDLE_CLICK_AUTOSCROLL_DEAD_ZONE {
+                px(0.)
+            } else if delta > px(0.) {
+                delta - MIDDLE_CLICK_AUTOSCROLL_DEAD_ZONE
+            } else {
+                delta + MIDDLE_CLICK_AUTOSCROLL_DEAD_ZONE
+            }
+        };
+        point(outside_dead_zone(offset.x), outside_dead_zone(offset.y))
+    }
+}
+
+/// Keeps scrolling while a selection is dragged past the top or bottom of the editor.
+struct DragAutoscroll {
+    /// How far past the edge the pointer is, in lines. Negative above the top edge.
+    lines_past_edge: f32,
+    column: u32,
+    goal_column: u32,
+    _task: Task<()>,
+}
+
+/// How far the editor is stretched past the start or end of the buffer by elastic
+/// overscroll, which springs back once the scrolling stops.
+struct Overscroll {
+    /// The stretch in lines. Negative above the first line.
+    lines: f32,
+    _spring_back: Task<()>,
+}
+
 pub struct ScrollManager {
     pub(crate) vertical_scroll_margin: f32,
+    pub(crate) horizontal_scroll_margin: f32,
+    /// Whether focusing a position centers it when the vertical scroll margin doesn't fit in
+    /// the viewport, like a `scrolloff` of 999 does in vim.
+    pub(crate) center_on_large_vertical_scroll_margin: bool,
     anchor: ScrollAnchor,
     ongoing: OngoingScroll,
     autoscroll_request: Option<(Autoscroll, bool)>,
     last_autoscroll: Option<(gpui::Point<f32>, f32, f32, AutoscrollStrategy)>,
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
+    save_scroll_position_for_path_task: Option<Task<()>>,
     dragging_scrollbar: bool,
+    dragging_horizontal_scrollbar: bool,
+    dragging_minimap: bool,
+    scrollbar_preview_row: Option<DisplayRow>,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
+    middle_click_autoscroll: Option<MiddleClickAutoscroll>,
+    drag_autoscroll: Option<DragAutoscroll>,
+    overscroll: Option<Overscroll>,
+    scroll_position_key: Option<SharedString>,
+    /// A remembered position to restore once the excerpt it is in gets added.
+    pending_scroll_restore: Option<RememberedScrollPosition>,
+    history: ScrollHistory,
 }
 
 impl ScrollManager {
     pub fn new(cx: &mut WindowContext) -> Self {
         ScrollManager {
             vertical_scroll_margin: EditorSettings::get_global(cx).vertical_scroll_margin,
+            horizontal_scroll_margin: EditorSettings::get_global(cx).horizontal_scroll_margin,
+            center_on_large_vertical_scroll_margin: false,
             anchor: ScrollAnchor::new(),
             ongoing: OngoingScroll::new(),
             autoscroll_request: None,
             show_scrollbars: true,
             hide_scrollbar_task: None,
+            save_scroll_position_for_path_task: None,
             dragging_scrollbar: false,
+            dragging_horizontal_scrollbar: false,
+            dragging_minimap: false,
+            scrollbar_preview_row: None,
             last_autoscroll: None,
             visible_line_count: None,
             forbid_vertical_scroll: false,
+            middle_click_autoscroll: None,
+            drag_autoscroll: None,
+            overscroll: None,
+            scroll_position_key: None,
+            pending_scroll_restore: None,
+            history: ScrollHistory::default(),
         }
     }
 
@@ -179,6 +362,15 @@ impl ScrollManager {
         self.anchor.scroll_position(snapshot)
     }
 
+    /// How many lines the editor is stretched past the start (negative) or end
+    /// (positive) of the buffer by elastic overscroll.
+    pub fn overscroll(&self) -> f32 {
+        self.overscroll
+            .as_ref()
+            .map_or(0., |overscroll| overscroll.lines)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn set_scroll_position(
         &mut self,
         scroll_position: gpui::Point<f32>,
@@ -186,6 +378,7 @@ impl ScrollManager {
         local: bool,
         autoscroll: bool,
         workspace_id: Option<WorkspaceId>,
+        path: Option<Arc<Path>>,
         cx: &mut ViewContext<Editor>,
     ) {
         if self.forbid_vertical_scroll {
@@ -225,6 +418,9 @@ impl ScrollManager {
 
             let scroll_top_buffer_point =
                 DisplayPoint::new(DisplayRow(scroll_top as u32), 0).to_point(&map);
+            // Bias the anchor to the right, so that text inserted above the viewport,
+            // including at the very start of the first visible line, pushes it down
+            // instead of shifting the visible text.
             let top_anchor = map
                 .buffer_snapshot
                 .anchor_at(scroll_top_buffer_point, Bias::Right);
@@ -241,9 +437,29 @@ impl ScrollManager {
             )
         };
 
-        self.set_anchor(new_anchor, top_row, local, autoscroll, workspace_id, cx);
+        // Remember where the viewport jumped away from, so that it can be returned to.
+        if local {
+            if let Some(visible_line_count) = self.visible_line_count {
+                let previous_top = self.anchor.scroll_position(map).y;
+                let new_top = new_anchor.scroll_position(map).y;
+                if (new_top - previous_top).abs() >= (visible_line_count - 1.).max(1.) {
+                    self.history.push(self.anchor);
+                }
+            }
+        }
+
+        self.set_anchor(
+            new_anchor,
+            top_row,
+            local,
+            autoscroll,
+            workspace_id,
+            path,
+            cx,
+        );
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn set_anchor(
         &mut self,
         anchor: ScrollAnchor,
@@ -251,12 +467,14 @@ impl ScrollManager {
         local: bool,
         autoscroll: bool,
         workspace_id: Option<WorkspaceId>,
+        path: Option<Arc<Path>>,
         cx: &mut ViewContext<Editor>,
     ) {
         if self.forbid_vertical_scroll {
             return;
         }
         self.anchor = anchor;
+        self.overscroll = None;
         cx.emit(EditorEvent::ScrollPositionChanged { local, autoscroll });
         self.show_scrollbar(cx);
         self.autoscroll_request.take();
@@ -273,9 +491,34 @@ impl ScrollManager {
                         anchor.offset.y,
                     )
                     .await
-                    .log_err()
+                    .log_err();
                 })
-                .detach()
+                .detach();
+
+            // Also key the position by path, so that it can be restored when the file is
+            // reopened in a new item, once the scrolling settles.
+            if let Some(path) = path {
+                self.save_scroll_position_for_path_task = Some(cx.spawn(|_, cx| async move {
+                    cx.background_executor()
+                        .timer(SCROLL_POSITION_FOR_PATH_SAVE_DEBOUNCE)
+                        .await;
+                    DB.save_scroll_position_for_path(
+                        workspace_id,
+                        path,
+                        top_row,
+                        anchor.offset.x,
+                        anchor.offset.y,
+                    )
+                    .await
+                    .log_err();
+                    DB.delete_oldest_scroll_positions_for_paths(
+                        workspace_id,
+                        MAX_SCROLL_POSITIONS_FOR_PATHS,
+                    )
+                    .await
+                    .log_err();
+                }));
+            }
         }
         cx.notify();
     }
@@ -322,6 +565,48 @@ impl ScrollManager {
         }
     }
 
+    pub fn is_dragging_horizontal_scrollbar(&self) -> bool {
+        self.dragging_horizontal_scrollbar
+    }
+
+    pub fn set_is_dragging_horizontal_scrollbar(
+        &mut self,
+        dragging: bool,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        if dragging != self.dragging_horizontal_scrollbar {
+            self.dragging_horizontal_scrollbar = dragging;
+            cx.notify();
+        }
+    }
+
+    pub fn is_dragging_minimap(&self) -> bool {
+        self.dragging_minimap
+    }
+
+    pub fn set_is_dragging_minimap(&mut self, dragging: bool, cx: &mut ViewContext<Editor>) {
+        if dragging != self.dragging_minimap {
+            self.dragging_minimap = dragging;
+            cx.notify();
+        }
+    }
+
+    /// The row previewed while hovering over the scrollbar.
+    pub fn scrollbar_preview_row(&self) -> Option<DisplayRow> {
+        self.scrollbar_preview_row
+    }
+
+    pub fn set_scrollbar_preview_row(
+        &mut self,
+        row: Option<DisplayRow>,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        if row != self.scrollbar_preview_row {
+            self.scrollbar_preview_row = row;
+            cx.notify();
+        }
+    }
+
     pub fn clamp_scroll_left(&mut self, max: f32) -> bool {
         if max < self.anchor.offset.x {
             self.anchor.offset.x = max;
@@ -338,6 +623,21 @@ impl ScrollManager {
     pub fn forbid_vertical_scroll(&self) -> bool {
         self.forbid_vertical_scroll
     }
+
+    pub fn is_drag_autoscrolling(&self) -> bool {
+        self.drag_autoscroll.is_some()
+    }
+
+    pub fn is_middle_click_autoscrolling(&self) -> bool {
+        self.middle_click_autoscroll.is_some()
+    }
+
+    /// Whether the mouse has left the dead zone since middle-click autoscroll started.
+    pub fn has_middle_click_autoscroll_moved(&self) -> bool {
+        self.middle_click_autoscroll
+            .as_ref()
+            .is_some_and(|autoscroll| autoscroll.moved)
+    }
 }
 
 impl Editor {
@@ -350,6 +650,25 @@ impl Editor {
         cx.notify();
     }
 
+    /// Sets whether focusing a position centers it when the vertical scroll margin is larger
+    /// than half of the viewport, instead of scrolling as far as the margin allows.
+    pub fn set_center_on_large_vertical_scroll_margin(&mut self, center: bool) {
+        self.scroll_manager.center_on_large_vertical_scroll_margin = center;
+    }
+
+    pub fn horizontal_scroll_margin(&self) -> usize {
+        self.scroll_manager.horizontal_scroll_margin as usize
+    }
+
+    pub fn set_horizontal_scroll_margin(
+        &mut self,
+        margin_columns: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.scroll_manager.horizontal_scroll_margin = margin_columns as f32;
+        cx.notify();
+    }
+
     pub fn visible_line_count(&self) -> Option<f32> {
         self.scroll_manager.visible_line_count
     }
@@ -361,11 +680,17 @@ impl Editor {
 
     pub(crate) fn set_visible_line_count(&mut self, lines: f32, cx: &mut ViewContext<Self>) {
         let opened_first_time = self.scroll_manager.visible_line_count.is_none();
+        let changed = self.scroll_manager.visible_line_count != Some(lines);
         self.scroll_manager.visible_line_count = Some(lines);
+        if changed {
+            self.broadcast_viewport(cx);
+        }
         if opened_first_time {
             cx.spawn(|editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
+                        refresh_bracket_colors(editor, cx);
+                        refresh_viewport(editor, ViewportRefreshReason::EditorOpened, cx);
                         editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
                     })
                     .ok()
@@ -419,6 +744,7 @@ impl Editor {
     ) {
         hide_hover(self, cx);
         let workspace_id = self.workspace.as_ref().and_then(|workspace| workspace.1);
+        let path = self.scroll_persistence_path(cx);
 
         self.scroll_manager.set_scroll_position(
             scroll_position,
@@ -426,10 +752,44 @@ impl Editor {
             local,
             autoscroll,
             workspace_id,
+            path,
             cx,
         );
 
+        refresh_bracket_colors(self, cx);
+        refresh_viewport(self, ViewportRefreshReason::ViewportChanged, cx);
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        self.broadcast_viewport(cx);
+        if local {
+            self.remember_scroll_position(autoscroll, cx);
+        }
+    }
+
+    /// Shares the range this editor is scrolled to with collaborators, who show it
+    /// on their scrollbars.
+    pub(crate) fn broadcast_viewport(&mut self, cx: &mut ViewContext<Self>) {
+        if self.mode != EditorMode::Full
+            || self.leader_peer_id.is_some()
+            || !self.focus_handle.is_focused(cx)
+        {
+            return;
+        }
+        let Some(visible_line_count) = self.visible_line_count() else {
+            return;
+        };
+
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let scroll_top = self.scroll_manager.anchor.scroll_position(&display_map).y;
+        let max_row = display_map.max_point().row();
+        let start_row = DisplayRow(scroll_top as u32).min(max_row);
+        let end_row = DisplayRow((scroll_top + visible_line_count).ceil() as u32).min(max_row);
+        let start = DisplayPoint::new(start_row, 0).to_point(&display_map);
+        let end = DisplayPoint::new(end_row, 0).to_point(&display_map);
+        let viewport = display_map.buffer_snapshot.anchor_before(start)
+            ..display_map.buffer_snapshot.anchor_after(end);
+        self.buffer.update(cx, |buffer, cx| {
+            buffer.set_active_viewport(Some(viewport), cx)
+        });
     }
 
     pub fn scroll_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
@@ -444,8 +804,216 @@ impl Editor {
             .anchor
             .to_point(&self.buffer().read(cx).snapshot(cx))
             .row;
+        let path = self.scroll_persistence_path(cx);
         self.scroll_manager
-            .set_anchor(scroll_anchor, top_row, true, false, workspace_id, cx);
+            .set_anchor(scroll_anchor, top_row, true, false, workspace_id, path, cx);
+        self.broadcast_viewport(cx);
+        self.remember_scroll_position(false, cx);
+    }
+
+    /// Sets the key this editor's scroll position is remembered under, for multibuffers
+    /// whose excerpts get recreated, like the results of a search that is run again.
+    /// Scrolls to the position already remembered under the key, as soon as the excerpt
+    /// it was in is shown.
+    pub fn set_scroll_position_key(
+        &mut self,
+        key: Option<SharedString>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.scroll_manager.scroll_position_key = key;
+        self.read_remembered_scroll_position_from_db(cx);
+    }
+
+    /// Reads the position remembered under this editor's scroll position key in its
+    /// workspace, once both are known, and scrolls to it.
+    pub(crate) fn read_remembered_scroll_position_from_db(&mut self, cx: &mut ViewContext<Self>) {
+        self.scroll_manager.pending_scroll_restore = None;
+        let Some(key) = self.scroll_manager.scroll_position_key.clone() else {
+            return;
+        };
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|(_, id)| *id) else {
+            return;
+        };
+        let position = DB.get_remembered_scroll_position(workspace_id, key.to_string());
+        if let Ok(Some((path, top_row, x, y))) = position {
+            self.scroll_manager.pending_scroll_restore = Some(RememberedScrollPosition {
+                path: path.into(),
+                top_row,
+                offset: gpui::Point::new(x, y),
+            });
+            self.restore_pending_scroll_position(cx);
+        }
+    }
+
+    fn remember_scroll_position(&mut self, autoscroll: bool, cx: &mut ViewContext<Self>) {
+        // Scrolling on purpose gives up on restoring a remembered position, while
+        // autoscrolling as the excerpts come in doesn't.
+        if !autoscroll {
+            self.scroll_manager.pending_scroll_restore = None;
+        }
+        if self.scroll_manager.pending_scroll_restore.is_some() {
+            return;
+        }
+        let Some(key) = self.scroll_manager.scroll_position_key.clone() else {
+            return;
+        };
+        let Some(workspace_id) = self.workspace.as_ref().and_then(|(_, id)| *id) else {
+            return;
+        };
+        // The multibuffer is empty while it is being rebuilt.
+        if self.buffer.read(cx).excerpt_ids().is_empty() {
+            return;
+        }
+
+        let scroll_anchor = self.scroll_manager.anchor;
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        // The top of the multibuffer is where it opens anyway.
+        let position = scroll_anchor.anchor.buffer_id.and_then(|_| {
+            let buffer = snapshot.buffer_for_excerpt(scroll_anchor.anchor.excerpt_id)?;
+            let path = persistence_path(buffer.file()?, cx);
+            let top_row = buffer
+                .summary_for_anchor::<Point>(&scroll_anchor.anchor.text_anchor)
+                .row;
+            Some((path, top_row))
+        });
+        let key = key.to_string();
+        cx.foreground_executor()
+            .spawn(async move {
+                match position {
+                    Some((path, top_row)) => {
+                        DB.save_remembered_scroll_position(
+                            workspace_id,
+                            key,
+                            path,
+                            top_row,
+                            scroll_anchor.offset.x,
+                            scroll_anchor.offset.y,
+                        )
+                        .await?;
+                        DB.delete_oldest_remembered_scroll_positions(
+                            workspace_id,
+                            MAX_REMEMBERED_SCROLL_POSITIONS,
+                        )
+                        .await
+                    }
+                    None => {
+                        DB.delete_remembered_scroll_position(workspace_id, key)
+                            .await
+                    }
+                }
+            })
+            .detach_and_log_err(cx);
+    }
+
+    pub(crate) fn restore_pending_scroll_position(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(position) = self.scroll_manager.pending_scroll_restore.clone() else {
+            return;
+        };
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let top = Point::new(position.top_row, 0);
+        let anchor = snapshot
+            .excerpts()
+            .find(|(_, buffer, range)| {
+                buffer
+                    .file()
+                    .is_some_and(|file| persistence_path(file, cx) == position.path)
+                    && buffer.summary_for_anchor::<Point>(&range.context.start) <= top
+                    && top <= buffer.summary_for_anchor::<Point>(&range.context.end)
+            })
+            .and_then(|(excerpt_id, buffer, _)| {
+                snapshot.anchor_in_excerpt(excerpt_id, buffer.anchor_before(top))
+            });
+        if let Some(anchor) = anchor {
+            self.set_scroll_anchor(
+                ScrollAnchor {
+                    anchor,
+                    offset: position.offset,
+                },
+                cx,
+            );
+        }
+    }
+
+    /// Scrolls back to the viewport the editor was showing before its last large
+    /// scroll jump, leaving the selections where they are.
+    pub fn scroll_history_back(&mut self, _: &ScrollHistoryBack, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        while let Some(anchor) = self.scroll_manager.history.back.pop_back() {
+            if anchor.anchor.is_valid(&snapshot) {
+                let current_anchor = self.scroll_manager.anchor;
+                push_capped(&mut self.scroll_manager.history.forward, current_anchor);
+                self.set_scroll_anchor(anchor, cx);
+                return;
+            }
+        }
+        cx.propagate();
+    }
+
+    /// Undoes the last [`Editor::scroll_history_back`].
+    pub fn scroll_history_forward(&mut self, _: &ScrollHistoryForward, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer().read(cx).snapshot(cx);
+        while let Some(anchor) = self.scroll_manager.history.forward.pop_back() {
+            if anchor.anchor.is_valid(&snapshot) {
+                let current_anchor = self.scroll_manager.anchor;
+                push_capped(&mut self.scroll_manager.history.back, current_anchor);
+                self.set_scroll_anchor(anchor, cx);
+                return;
+            }
+        }
+        cx.propagate();
+    }
+
+    /// Smoothly scrolls the editor over `duration` until `anchor` is at the top of the
+    /// viewport, with `easing` mapping the elapsed fraction of the duration to the
+    /// fraction of the distance scrolled.
+    ///
+    /// The returned task resolves once the animation finishes, and dropping it stops
+    /// the animation. The animation also stops early when something else scrolls the
+    /// editor, such as the user or another animation.
+    pub fn scroll_to_anchor_animated(
+        &mut self,
+        anchor: Anchor,
+        duration: Duration,
+        easing: impl Fn(f32) -> f32 + 'static,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<()> {
+        let start = self.scroll_position(cx);
+        // The animation moves in small steps, so record it as a single jump up front.
+        if let Some(visible_line_count) = self.visible_line_count() {
+            let snapshot = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+            let target_y = anchor.to_display_point(&snapshot).row().as_f32();
+            if (target_y - start.y).abs() >= (visible_line_count - 1.).max(1.) {
+                self.scroll_manager.history.push(self.scroll_manager.anchor);
+            }
+        }
+        let frame_count = duration
+            .as_nanos()
+            .div_ceil(SCROLL_ANIMATION_FRAME_INTERVAL.as_nanos())
+            .max(1) as u32;
+        cx.spawn(|editor, mut cx| async move {
+            let mut last_position = start;
+            for frame in 1..=frame_count {
+                cx.background_executor()
+                    .timer(SCROLL_ANIMATION_FRAME_INTERVAL)
+                    .await;
+                let progress = easing(frame as f32 / frame_count as f32);
+                let Ok(Some(position)) = editor.update(&mut cx, |editor, cx| {
+                    if editor.scroll_position(cx) != last_position {
+                        return None;
+                    }
+                    // Resolve the anchor on every frame, so that edits made during the
+                    // animation don't make it miss its target.
+                    let snapshot = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+                    let target_y = anchor.to_display_point(&snapshot).row().as_f32();
+                    let y = start.y + (target_y - start.y) * progress;
+                    editor.set_scroll_position(point(start.x, y), cx);
+                    Some(editor.scroll_position(cx))
+                }) else {
+                    return;
+                };
+                last_position = position;
+            }
+        })
     }
 
     pub(crate) fn set_scroll_anchor_remote(
@@ -461,8 +1029,329 @@ impl Editor {
             return;
         }
         let top_row = scroll_anchor.anchor.to_point(snapshot).row;
-        self.scroll_manager
-            .set_anchor(scroll_anchor, top_row, false, false, workspace_id, cx);
+        let path = self.scroll_persistence_path(cx);
+        self.scroll_manager.set_anchor(
+            scroll_anchor,
+            top_row,
+            false,
+            false,
+            workspace_id,
+            path,
+            cx,
+        );
+    }
+
+    /// Applies a collaborator's horizontal scroll offset, without cancelling the
+    /// autoscroll requested for their selections.
+    pub(crate) fn set_scroll_x_remote(&mut self, scroll_x: f32, cx: &mut ViewContext<Self>) {
+        if self.scroll_manager.anchor.offset.x != scroll_x {
+            self.scroll_manager.anchor.offset.x = scroll_x;
+            cx.notify();
+        }
+    }
+
+    /// Locks the scroll position of this editor to the active editors of the other panes
+    /// in the workspace, or unlocks it if it is already locked.
+    pub fn toggle_scroll_lock(&mut self, _: &ToggleScrollLock, cx: &mut ViewContext<Self>) {
+        if !self.scroll_locks.is_empty() {
+            self.unlock_scroll(cx);
+            return;
+        }
+
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let editor_id = cx.entity_id();
+        let editors = workspace
+            .read(cx)
+            .panes()
+            .iter()
+            .filter_map(|pane| pane.read(cx).active_item())
+            .filter(|item| item.item_id() != editor_id)
+            .filter_map(|item| item.act_as::<Editor>(cx))
+            .collect::<Vec<_>>();
+        for editor in editors {
+            self.lock_scroll_with(&editor, cx);
+        }
+    }
+
+    /// Locks the vertical scroll positions of this editor and `other` together.
+    ///
+    /// The distance between their current scroll positions is preserved, so the
+    /// editors don't need to show the same buffer.
+    pub fn lock_scroll_with(&mut self, other: &View<Editor>, cx: &mut ViewContext<Self>) {
+        let other_handle = other.downgrade();
+        if self
+            .scroll_locks
+            .iter()
+            .any(|lock| lock.editor == other_handle)
+        {
+            return;
+        }
+
+        let this = cx.view().clone();
+        let other_top = other.update(cx, |other, cx| other.scroll_position(cx).y);
+        let row_offset = self.scroll_position(cx).y - other_top;
+        self.scroll_locks
+            .push(ScrollLock::new(other, row_offset, cx));
+        other.update(cx, |other, cx| {
+            other
+                .scroll_locks
+                .push(ScrollLock::new(&this, -row_offset, cx));
+        });
+    }
+
+    pub fn unlock_scroll(&mut self, cx: &mut ViewContext<Self>) {
+        let this = cx.view().downgrade();
+        for lock in self.scroll_locks.drain(..) {
+            if let Some(editor) = lock.editor.upgrade() {
+                editor.update(cx, |editor, _| {
+                    editor.scroll_locks.retain(|lock| lock.editor != this);
+                });
+            }
+        }
+    }
+
+    pub fn is_scroll_locked(&self) -> bool {
+        !self.scroll_locks.is_empty()
+    }
+
+    /// Updates how far past the top or bottom edge a selection is being dragged, in
+    /// lines, starting or stopping the autoscroll as needed.
+    pub(crate) fn update_drag_autoscroll(
+        &mut self,
+        lines_past_edge: Option<f32>,
+        column: u32,
+        goal_column: u32,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(lines_past_edge) = lines_past_edge else {
+            self.scroll_manager.drag_autoscroll = None;
+            return;
+        };
+        if let Some(autoscroll) = self.scroll_manager.drag_autoscroll.as_mut() {
+            autoscroll.lines_past_edge = lines_past_edge;
+            autoscroll.column = column;
+            autoscroll.goal_column = goal_column;
+            return;
+        }
+
+        let task = cx.spawn(|editor, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(DRAG_AUTOSCROLL_INTERVAL)
+                    .await;
+                if editor
+                    .update(&mut cx, |editor, cx| editor.step_drag_autoscroll(cx))
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        self.scroll_manager.drag_autoscroll = Some(DragAutoscroll {
+            lines_past_edge,
+            column,
+            goal_column,
+            _task: task,
+        });
+    }
+
+    fn step_drag_autoscroll(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(autoscroll) = self.scroll_manager.drag_autoscroll.as_ref() else {
+            return;
+        };
+        if !self.has_pending_selection() {
+            self.scroll_manager.drag_autoscroll = None;
+            return;
+        }
+        let Some(visible_line_count) = self.visible_line_count() else {
+            return;
+        };
+
+        let settings = EditorSettings::get_global(cx).drag_autoscroll;
+        let lines_per_second =
+            settings.speed.max(0.) * settings.ramp.apply(autoscroll.lines_past_edge.abs());
+        let delta = (lines_per_second * DRAG_AUTOSCROLL_INTERVAL.as_secs_f32())
+            .copysign(autoscroll.lines_past_edge);
+        let (column, goal_column) = (autoscroll.column, autoscroll.goal_column);
+
+        // Extend the selection to the edge that is scrolled into view.
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let scroll_top =
+            (self.scroll_manager.anchor.scroll_position(&display_map).y + delta).max(0.);
+        let row = if delta < 0. {
+            scroll_top
+        } else {
+            scroll_top + visible_line_count - 1.
+        };
+        let row = DisplayRow((row as u32).min(display_map.max_point().row().0));
+        let position = display_map.clip_point(DisplayPoint::new(row, column), Bias::Left);
+
+        self.select(
+            SelectPhase::Update {
+                position,
+                goal_column,
+                scroll_delta: point(0., delta),
+            },
+            cx,
+        );
+    }
+
+    pub(crate) fn start_middle_click_autoscroll(
+        &mut self,
+        origin: gpui::Point<Pixels>,
+        line_height: Pixels,
+        em_width: Pixels,
+        scroll_max: gpui::Point<f32>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let task = cx.spawn(|editor, mut cx| async move {
+            loop {
+                cx.background_executor()
+                    .timer(MIDDLE_CLICK_AUTOSCROLL_INTERVAL)
+                    .await;
+                if editor
+                    .update(&mut cx, |editor, cx| {
+                        editor.step_middle_click_autoscroll(cx)
+                    })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        self.scroll_manager.middle_click_autoscroll = Some(MiddleClickAutoscroll {
+            origin,
+            position: origin,
+            line_height,
+            em_width,
+            scroll_max,
+            moved: false,
+            _task: task,
+        });
+        cx.notify();
+    }
+
+    pub(crate) fn update_middle_click_autoscroll(&mut self, position: gpui::Point<Pixels>) {
+        if let Some(autoscroll) = self.scroll_manager.middle_click_autoscroll.as_mut() {
+            autoscroll.position = position;
+            autoscroll.moved |= autoscroll.offset() != gpui::Point::default();
+        }
+    }
+
+    /// Leaves middle-click autoscroll, returning whether it was active.
+    pub fn stop_middle_click_autoscroll(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if self.scroll_manager.middle_click_autoscroll.take().is_some() {
+            cx.notify();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn step_middle_click_autoscroll(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(autoscroll) = self.scroll_manager.middle_click_autoscroll.as_ref() else {
+            return;
+        };
+        let mut offset = autoscroll.offset();
+        if offset == gpui::Point::default() {
+            return;
+        }
+
+        // Treat the distance from the origin like a scroll wheel delta, so that the
+        // scroll stays locked to one axis unless the mouse clearly moves along the other.
+        let axis_lock = EditorSettings::get_global(cx).scroll_axis_lock;
+        let axis = self.scroll_manager.ongoing.filter(&mut offset, &axis_lock);
+        let seconds = MIDDLE_CLICK_AUTOSCROLL_INTERVAL.as_secs_f32();
+        let delta = offset * (MIDDLE_CLICK_AUTOSCROLL_SPEED * seconds);
+        let (line_height, em_width, scroll_max) = (
+            autoscroll.line_height,
+            autoscroll.em_width,
+            autoscroll.scroll_max,
+        );
+
+        let current_scroll_position = self.scroll_position(cx);
+        let mut scroll_position = point(
+            current_scroll_position.x + delta.x / em_width,
+            current_scroll_position.y + delta.y / line_height,
+        )
+        .clamp(&point(0., 0.), &scroll_max);
+        if self.scroll_manager.forbid_vertical_scroll() {
+            scroll_position.y = current_scroll_position.y;
+        }
+        if scroll_position != current_scroll_position {
+            self.scroll(scroll_position, axis, cx);
+        }
+    }
+
+    /// Stretches the editor past the start or end of the buffer, towards `target` lines
+    /// of overscroll from the `previous` stretch. Stretching further meets more
+    /// resistance, and the stretch springs back once it stops changing.
+    pub(crate) fn stretch_overscroll(
+        &mut self,
+        previous: f32,
+        target: f32,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if target == previous {
+            return;
+        }
+        let Some(visible_line_count) = self.visible_line_count() else {
+            return;
+        };
+
+        let max_lines = visible_line_count * MAX_ELASTIC_OVERSCROLL;
+        // Stretching past the other end starts over without any stretch.
+        let previous = if target.signum() == previous.signum() {
+            previous
+        } else {
+            0.
+        };
+        let lines = if target.abs() > previous.abs() {
+            let resistance = ELASTIC_OVERSCROLL_RESISTANCE * (1. - previous.abs() / max_lines);
+            previous + (target - previous) * resistance.max(0.)
+        } else {
+            target
+        };
+        let lines = lines.clamp(-max_lines, max_lines);
+
+        if lines == 0. {
+            self.scroll_manager.overscroll = None;
+        } else {
+            let spring_back = cx.spawn(|editor, mut cx| async move {
+                cx.background_executor()
+                    .timer(ELASTIC_OVERSCROLL_SETTLE_DELAY)
+                    .await;
+                loop {
+                    cx.background_executor()
+                        .timer(SCROLL_ANIMATION_FRAME_INTERVAL)
+                        .await;
+                    if editor
+                        .update(&mut cx, |editor, cx| editor.step_overscroll_spring_back(cx))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+            self.scroll_manager.overscroll = Some(Overscroll {
+                lines,
+                _spring_back: spring_back,
+            });
+        }
+        cx.notify();
+    }
+
+    fn step_overscroll_spring_back(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(overscroll) = self.scroll_manager.overscroll.as_mut() else {
+            return;
+        };
+        overscroll.lines *= ELASTIC_OVERSCROLL_DECAY;
+        if overscroll.lines.abs() < 0.01 {
+            self.scroll_manager.overscroll = None;
+        }
+        cx.notify();
     }
 
     pub fn scroll_screen(&mut self, amount: &ScrollAmount, cx: &mut ViewContext<Self>) {
@@ -521,16 +1410,65 @@ impl Editor {
     ) {
         let scroll_position = DB.get_scroll_position(item_id, workspace_id);
         if let Ok(Some((top_row, x, y))) = scroll_position {
-            let top_anchor = self
-                .buffer()
-                .read(cx)
-                .snapshot(cx)
-                .anchor_at(Point::new(top_row, 0), Bias::Left);
-            let scroll_anchor = ScrollAnchor {
-                offset: gpui::Point::new(x, y),
-                anchor: top_anchor,
-            };
-            self.set_scroll_anchor(scroll_anchor, cx);
+            self.restore_scroll_position(top_row, x, y, cx);
         }
     }
+
+    /// Restores the scroll position last persisted for this editor's file in the given
+    /// workspace, unless the editor has already been scrolled.
+    pub(crate) fn read_scroll_position_for_path_from_db(
+        &mut self,
+        workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        if self.scroll_manager.anchor() != ScrollAnchor::new()
+            || self.scroll_manager.autoscroll_request().is_some()
+        {
+            return;
+        }
+        let Some(path) = self.scroll_persistence_path(cx) else {
+            return;
+        };
+
+        let scroll_position = DB.get_scroll_position_for_path(workspace_id, path);
+        if let Ok(Some((top_row, x, y))) = scroll_position {
+            self.restore_scroll_position(top_row, x, y, cx);
+        }
+    }
+
+    fn restore_scroll_position(
+        &mut self,
+        top_row: u32,
+        x: f32,
+        y: f32,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        let top_anchor = self
+            .buffer()
+            .read(cx)
+            .snapshot(cx)
+            .anchor_at(Point::new(top_row, 0), Bias::Left);
+        let scroll_anchor = ScrollAnchor {
+            offset: gpui::Point::new(x, y),
+            anchor: top_anchor,
+        };
+        self.set_scroll_anchor(scroll_anchor, cx);
+    }
+
+    /// The path under which the scroll position of this editor is persisted, if it is editing
+    /// a single file, which keeps files with the same path in different worktrees apart.
+    fn scroll_persistence_path(&self, cx: &AppContext) -> Option<Arc<Path>> {
+        let buffer = self.buffer.read(cx).as_singleton()?;
+        let file = buffer.read(cx).file()?;
+        Some(persistence_path(file, cx))
+    }
+}
+
+/// The absolute path of local files, and the path starting with the worktree's root name
+/// for remote ones.
+fn persistence_path(file: &Arc<dyn language::File>, cx: &AppContext) -> Arc<Path> {
+    match file.as_local() {
+        Some(file) => file.abs_path(cx).into(),
+        None => file.full_path(cx).into(),
+    }
 }
@@ -1,7 +1,9 @@
 use super::Axis;
 use crate::{
-    Autoscroll, Bias, Editor, EditorMode, NextScreen, ScrollAnchor, ScrollCursorBottom,
-    ScrollCursorCenter, ScrollCursorTop,
+    scroll::ScrollAmount, Autoscroll, Bias, Editor, EditorMode, NextScreen, ScrollAnchor,
+    ScrollCursorBottom, ScrollCursorBottomWithContext, ScrollCursorCenter,
+    ScrollCursorToViewportPercent, ScrollCursorTop, ScrollCursorTopWithContext, ScrollDownByLines,
+    ScrollUpByLines,
 };
 use gpui::{Point, ViewContext};
 
@@ -32,6 +34,18 @@ impl Editor {
         self.set_scroll_position(scroll_position, cx);
     }
 
+    pub fn scroll_up_by_lines(&mut self, action: &ScrollUpByLines, cx: &mut ViewContext<Editor>) {
+        self.scroll_screen(&ScrollAmount::Line(-(action.lines as f32)), cx);
+    }
+
+    pub fn scroll_down_by_lines(
+        &mut self,
+        action: &ScrollDownByLines,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.scroll_screen(&ScrollAmount::Line(action.lines as f32), cx);
+    }
+
     pub fn scroll_cursor_top(&mut self, _: &ScrollCursorTop, cx: &mut ViewContext<Editor>) {
         let snapshot = self.snapshot(cx).display_snapshot;
         let scroll_margin_rows = self.vertical_scroll_margin() as u32;
@@ -100,4 +114,28 @@ impl Editor {
             cx,
         )
     }
+
+    pub fn scroll_cursor_to_viewport_percent(
+        &mut self,
+        action: &ScrollCursorToViewportPercent,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.request_autoscroll(Autoscroll::viewport_percent(action.percent), cx);
+    }
+
+    pub fn scroll_cursor_top_with_context(
+        &mut self,
+        action: &ScrollCursorTopWithContext,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.request_autoscroll(Autoscroll::top_relative(action.lines as usize), cx);
+    }
+
+    pub fn scroll_cursor_bottom_with_context(
+        &mut self,
+        action: &ScrollCursorBottomWithContext,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.request_autoscroll(Autoscroll::bottom_relative(action.lines as usize), cx);
+    }
 }
@@ -1,8 +1,10 @@
 use crate::{
-    display_map::ToDisplayPoint, DisplayRow, Editor, EditorMode, LineWithInvisibles, RowExt,
+    display_map::ToDisplayPoint, DisplayRow, Editor, EditorMode, EditorSettings,
+    LineWithInvisibles, RowExt,
 };
 use gpui::{px, Bounds, Pixels, ViewContext};
 use language::Point;
+use settings::Settings;
 use std::{cmp, f32};
 
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -36,6 +38,15 @@ impl Autoscroll {
     pub fn top_relative(n: usize) -> Self {
         Self::Strategy(AutoscrollStrategy::TopRelative(n))
     }
+    /// Scrolls so that the newest cursor is roughly an n-th line from the bottom.
+    pub fn bottom_relative(n: usize) -> Self {
+        Self::Strategy(AutoscrollStrategy::BottomRelative(n))
+    }
+    /// Scrolls so that the newest cursor is at the given percentage of the viewport
+    /// height from the top, e.g. 25 places it a quarter of the way down.
+    pub fn viewport_percent(percent: u32) -> Self {
+        Self::Strategy(AutoscrollStrategy::ViewportPercent(percent.min(100)))
+    }
 }
 
 #[derive(PartialEq, Eq, Default, Clone, Copy)]
@@ -48,6 +59,8 @@ pub enum AutoscrollStrategy {
     Top,
     Bottom,
     TopRelative(usize),
+    BottomRelative(usize),
+    ViewportPercent(u32),
 }
 
 impl AutoscrollStrategy {
@@ -164,7 +177,15 @@ impl Editor {
             }
         };
 
+        let typewriter_scrolling = self.mode == EditorMode::Full
+            && EditorSettings::get_global(cx).typewriter_scrolling
+            && target_bottom - target_top <= visible_lines;
+
         match strategy {
+            AutoscrollStrategy::Fit | AutoscrollStrategy::Newest if typewriter_scrolling => {
+                scroll_position.y = (target_top - margin).max(0.0);
+                self.set_scroll_position_internal(scroll_position, local, true, cx);
+            }
             AutoscrollStrategy::Fit | AutoscrollStrategy::Newest => {
                 let margin = margin.min(self.scroll_manager.vertical_scroll_margin);
                 let target_top = (target_top - margin).max(0.0);
@@ -189,8 +210,12 @@ impl Editor {
                 self.set_scroll_position_internal(scroll_position, local, true, cx);
             }
             AutoscrollStrategy::Focused => {
-                scroll_position.y =
-                    (target_top - self.scroll_manager.vertical_scroll_margin).max(0.0);
+                let margin = if self.scroll_manager.center_on_large_vertical_scroll_margin {
+                    margin.min(self.scroll_manager.vertical_scroll_margin)
+                } else {
+                    self.scroll_manager.vertical_scroll_margin
+                };
+                scroll_position.y = (target_top - margin).max(0.0);
                 self.set_scroll_position_internal(scroll_position, local, true, cx);
             }
             AutoscrollStrategy::Top => {
@@ -205,6 +230,17 @@ impl Editor {
                 scroll_position.y = target_top - lines as f32;
                 self.set_scroll_position_internal(scroll_position, local, true, cx);
             }
+            AutoscrollStrategy::BottomRelative(lines) => {
+                scroll_position.y = (target_bottom + lines as f32 - visible_lines).max(0.0);
+                self.set_scroll_position_internal(scroll_position, local, true, cx);
+            }
+            AutoscrollStrategy::ViewportPercent(percent) => {
+                let rows_above = ((visible_lines - (target_bottom - target_top)).max(0.)
+                    * (percent as f32 / 100.))
+                    .floor();
+                scroll_position.y = (target_top - rows_above).max(0.0);
+                self.set_scroll_position_internal(scroll_position, local, true, cx);
+            }
         }
 
         self.scroll_manager.last_autoscroll = Some((
@@ -236,6 +272,12 @@ impl Editor {
             .highlighted_display_row_for_autoscroll(&display_map)
             .is_none()
         {
+            // A margin wider than half the viewport keeps the cursor centered.
+            let max_margin = ((viewport_width / max_glyph_width - 1.) / 2.).max(0.);
+            let margin = self
+                .scroll_manager
+                .horizontal_scroll_margin
+                .clamp(0., max_margin) as u32;
             target_left = px(f32::INFINITY);
             target_right = px(0.);
             for selection in selections {
@@ -243,8 +285,11 @@ impl Editor {
                 if head.row() >= start_row
                     && head.row() < DisplayRow(start_row.0 + layouts.len() as u32)
                 {
-                    let start_column = head.column().saturating_sub(3);
-                    let end_column = cmp::min(display_map.line_len(head.row()), head.column() + 3);
+                    let start_column = head.column().saturating_sub(margin);
+                    let margin_end_column = head.column().saturating_add(margin);
+                    let end_column = cmp::min(display_map.line_len(head.row()), margin_end_column);
+                    // Past the end of the line, keep the rest of the margin as empty columns.
+                    let columns_past_end = margin_end_column - end_column;
                     target_left = target_left.min(
                         layouts[head.row().minus(start_row) as usize]
                             .x_for_index(start_column as usize),
@@ -252,7 +297,7 @@ impl Editor {
                     target_right = target_right.max(
                         layouts[head.row().minus(start_row) as usize]
                             .x_for_index(end_column as usize)
-                            + max_glyph_width,
+                            + max_glyph_width * (columns_past_end + 1) as f32,
                     );
                 }
             }
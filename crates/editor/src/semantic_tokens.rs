@@ -0,0 +1,254 @@
+use collections::HashMap;
+use gpui::{HighlightStyle, Model, Task};
+use language::OffsetRangeExt as _;
+use project::{BufferSemanticTokens, Project, SemanticToken};
+use settings::Settings;
+use theme::{ActiveTheme, SyntaxTheme};
+use ui::ViewContext;
+use util::ResultExt;
+
+use crate::{viewport_refresh::VisibleBuffers, Editor, EditorSettings};
+
+/// Requests the semantic tokens of the visible buffers that were edited since the last refresh,
+/// or that the editor has no tokens for, from their language servers, asking only for the
+/// changes since the tokens the editor already has.
+pub(super) fn refresh_semantic_tokens(
+    this: &mut Editor,
+    project: &Model<Project>,
+    visible: &VisibleBuffers,
+    cx: &mut ViewContext<Editor>,
+) -> Task<Option<()>> {
+    if !EditorSettings::get_global(cx).semantic_tokens {
+        if !this.semantic_tokens.is_empty() {
+            this.semantic_tokens.clear();
+            update_semantic_highlights(this, cx);
+        }
+        return Task::ready(None);
+    }
+
+    let tasks = project.update(cx, |project, cx| {
+        visible
+            .buffers
+            .iter()
+            .filter(|(buffer_id, _)| {
+                visible.edited.contains(buffer_id) || !this.semantic_tokens.contains_key(buffer_id)
+            })
+            .map(|(buffer_id, (buffer, _))| {
+                let previous = this.semantic_tokens.get(buffer_id);
+                (*buffer_id, project.semantic_tokens(buffer, previous, cx))
+            })
+            .collect::<Vec<_>>()
+    });
+    let visible_buffer_ids = visible.buffers.keys().copied().collect::<Vec<_>>();
+
+    cx.spawn(|this, mut cx| async move {
+        let mut semantic_tokens = HashMap::default();
+        for (buffer_id, task) in tasks {
+            if let Some(tokens) = task.await.log_err().flatten() {
+                semantic_tokens.insert(buffer_id, tokens);
+            }
+        }
+
+        this.update(&mut cx, |this, cx| {
+            // Only the tokens of the visible buffers are kept, the others are requested in full
+            // when their buffers are shown again.
+            this.semantic_tokens
+                .retain(|buffer_id, _| visible_buffer_ids.contains(buffer_id));
+            this.semantic_tokens.extend(semantic_tokens);
+            update_semantic_highlights(this, cx);
+        })
+        .ok()
+    })
+}
+
+/// Highlights the semantic tokens in every excerpt of their buffers, styled by the current theme.
+pub(super) fn update_semantic_highlights(this: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let snapshot = this.buffer.read(cx).snapshot(cx);
+    let syntax = cx.theme().syntax().clone();
+    let mut highlights = Vec::new();
+    for (excerpt_id, buffer, excerpt_range) in snapshot.excerpts() {
+        let Some(tokens) = this.semantic_tokens.get(&buffer.remote_id()) else {
+            continue;
+        };
+
+        let context = excerpt_range.context.to_offset(buffer);
+        let mut styles = HashMap::default();
+        for token in &tokens.tokens {
+            let range = token.range.to_offset(buffer);
+            if range.start >= context.end {
+                break;
+            }
+            if range.is_empty() || range.start < context.start || range.end > context.end {
+                continue;
+            }
+
+            let style = *styles
+                .entry((token.token_type, token.token_modifiers))
+                .or_insert_with(|| semantic_token_style(tokens, token, &syntax));
+            let Some(style) = style else {
+                continue;
+            };
+            let start = snapshot.anchor_in_excerpt(excerpt_id, token.range.start);
+            let end = snapshot.anchor_in_excerpt(excerpt_id, token.range.end);
+            if let Some((start, end)) = start.zip(end) {
+                highlights.push((start..end, style));
+            }
+        }
+    }
+
+    this.display_map.update(cx, |display_map, _| {
+        display_map.set_semantic_highlights(highlights)
+    });
+    cx.notify();
+}
+
+/// Styles a token with the theme's style for its type and one of its modifiers, such as
+/// `variable.mutable`, or else with the style for its type.
+fn semantic_token_style(
+    tokens: &BufferSemanticTokens,
+    token: &SemanticToken,
+    syntax: &SyntaxTheme,
+) -> Option<HighlightStyle> {
+    let scope = syntax_scope(tokens.token_type(token)?);
+    tokens
+        .token_modifiers(token)
+        .find_map(|modifier| {
+            let ix = syntax.highlight_id(&format!("{scope}.{modifier}"))?;
+            Some(syntax.highlights[ix as usize].1)
+        })
+        .or_else(|| style_for_scope(syntax, scope))
+}
+
+/// Returns the style for the given scope, falling back to less specific scopes as syntax
+/// highlighting does, e.g. from `function.method` to `function`.
+fn style_for_scope(syntax: &SyntaxTheme, mut scope: &str) -> Option<HighlightStyle> {
+    loop {
+        if let Some(ix) = syntax.highlight_id(scope) {
+            return Some(syntax.highlights[ix as usize].1);
+        }
+        scope = &scope[..scope.rfind('.')?];
+    }
+}
+
+/// Maps the standard semantic token types to the names of the syntax styles used for the same
+/// kind of text by tree-sitter highlighting.
+fn syntax_scope(token_type: &str) -> &str {
+    match token_type {
+        "class" | "interface" | "struct" | "type" | "typeParameter" => "type",
+        "enumMember" => "variant",
+        "parameter" => "variable.parameter",
+        "event" => "property",
+        "method" => "function.method",
+        "macro" => "function.macro",
+        "decorator" => "attribute",
+        "modifier" => "keyword",
+        "regexp" => "string.regex",
+        token_type => token_type,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+        viewport_refresh::{refresh_viewport, ViewportRefreshReason},
+    };
+    use futures::StreamExt;
+    use gpui::Hsla;
+    use indoc::indoc;
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_style_for_scope() {
+        let syntax = SyntaxTheme::new_test([
+            ("function", Hsla::red()),
+            ("variable.mutable", Hsla::blue()),
+        ]);
+
+        assert_eq!(
+            style_for_scope(&syntax, syntax_scope("macro")),
+            Some(syntax.get("function"))
+        );
+        assert_eq!(
+            style_for_scope(&syntax, "variable.mutable"),
+            Some(syntax.get("variable.mutable"))
+        );
+        assert_eq!(style_for_scope(&syntax, syntax_scope("variable")), None);
+    }
+
+    #[gpui::test]
+    async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![lsp::SemanticTokenType::VARIABLE],
+                                token_modifiers: Vec::new(),
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            let ˇx = 1;
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(|_, _, _| async {
+                Ok(Some(lsp::SemanticTokensResult::Tokens(
+                    lsp::SemanticTokens {
+                        result_id: None,
+                        data: vec![lsp::SemanticToken {
+                            delta_line: 0,
+                            delta_start: 4,
+                            length: 1,
+                            token_type: 0,
+                            token_modifiers_bitset: 0,
+                        }],
+                    },
+                )))
+            });
+        cx.update_editor(|editor, cx| {
+            refresh_viewport(editor, ViewportRefreshReason::SettingsChanged, cx)
+        });
+        requests.next().await;
+        cx.run_until_parked();
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer.read(cx).snapshot(cx);
+            let tokens = editor
+                .semantic_tokens
+                .values()
+                .flat_map(|tokens| {
+                    tokens.tokens.iter().map(|token| {
+                        let buffer = snapshot.as_singleton().unwrap().2;
+                        buffer
+                            .text_for_range(token.range.clone())
+                            .collect::<String>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(tokens, ["x"]);
+        });
+
+        cx.update(|cx| {
+            SettingsStore::update_global(cx, |store, cx| {
+                store.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.semantic_tokens = Some(false);
+                });
+            });
+        });
+        cx.run_until_parked();
+        cx.update_editor(|editor, _| assert!(editor.semantic_tokens.is_empty()));
+    }
+}
@@ -0,0 +1,111 @@
+use std::{ops::Range, time::Duration};
+
+use collections::{HashMap, HashSet};
+use gpui::Model;
+use language::{Buffer, BufferId};
+use multi_buffer::{ExcerptId, MultiBufferSnapshot};
+use ui::ViewContext;
+
+use crate::{
+    color_swatches, folding_ranges, pull_diagnostics, semantic_tokens, Editor, EditorMode,
+};
+
+const VIEWPORT_REFRESH_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(250);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum ViewportRefreshReason {
+    /// The editor was created, and nothing was requested for it yet.
+    EditorOpened,
+    /// Settings changed, which may enable what was not requested before.
+    SettingsChanged,
+    /// The buffers were edited, or other parts of them were shown.
+    ViewportChanged,
+    /// Language servers were started, or the language of a buffer changed, so that everything
+    /// they reported before is requested again.
+    LanguageServersChanged,
+}
+
+/// The buffers visible in an editor, which the data reported by language servers is requested
+/// for.
+pub(super) struct VisibleBuffers {
+    pub(super) snapshot: MultiBufferSnapshot,
+    /// The visible range of each visible excerpt, in offsets of its buffer.
+    pub(super) excerpts: Vec<(ExcerptId, Range<usize>)>,
+    pub(super) buffers: HashMap<BufferId, (Model<Buffer>, clock::Global)>,
+    /// The visible buffers that were edited since the last refresh, or all of them when what
+    /// was reported for them was invalidated.
+    pub(super) edited: HashSet<BufferId>,
+}
+
+/// Refreshes what language servers report about the buffers visible in the editor: folding
+/// ranges, colors, semantic tokens and pulled diagnostics.
+pub(super) fn refresh_viewport(
+    this: &mut Editor,
+    reason: ViewportRefreshReason,
+    cx: &mut ViewContext<Editor>,
+) {
+    if this.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = this.project.clone() else {
+        return;
+    };
+    if reason == ViewportRefreshReason::LanguageServersChanged {
+        this.viewport_buffer_versions.clear();
+    }
+    let debounce = matches!(
+        reason,
+        ViewportRefreshReason::ViewportChanged | ViewportRefreshReason::LanguageServersChanged
+    );
+
+    this.viewport_refresh_task = Some(cx.spawn(|this, mut cx| async move {
+        if debounce {
+            cx.background_executor()
+                .timer(VIEWPORT_REFRESH_DEBOUNCE_TIMEOUT)
+                .await;
+        }
+
+        let (buffers, tasks) = this
+            .update(&mut cx, |this, cx| {
+                let visible = visible_buffers(this, cx);
+                let tasks = [
+                    folding_ranges::refresh_folding_ranges(&project, &visible, cx),
+                    color_swatches::refresh_color_swatches(this, &project, &visible, cx),
+                    semantic_tokens::refresh_semantic_tokens(this, &project, &visible, cx),
+                    pull_diagnostics::refresh_pulled_diagnostics(&project, &visible, cx),
+                ];
+                (visible.buffers, tasks)
+            })
+            .ok()?;
+        futures::future::join_all(tasks).await;
+
+        this.update(&mut cx, |this, _| {
+            for (buffer_id, (_, version)) in buffers {
+                this.viewport_buffer_versions.insert(buffer_id, version);
+            }
+        })
+        .ok()
+    }));
+}
+
+fn visible_buffers(this: &Editor, cx: &mut ViewContext<Editor>) -> VisibleBuffers {
+    let mut excerpts = Vec::new();
+    let mut buffers = HashMap::default();
+    for (excerpt_id, (buffer, version, range)) in this.excerpts_for_inlay_hints_query(None, cx) {
+        excerpts.push((excerpt_id, range));
+        buffers.insert(buffer.read(cx).remote_id(), (buffer, version));
+    }
+    let edited = buffers
+        .iter()
+        .filter(|(buffer_id, (_, version))| {
+            this.viewport_buffer_versions.get(buffer_id) != Some(version)
+        })
+        .map(|(buffer_id, _)| *buffer_id)
+        .collect();
+    VisibleBuffers {
+        snapshot: this.buffer.read(cx).snapshot(cx),
+        excerpts,
+        buffers,
+        edited,
+    }
+}
@@ -0,0 +1,213 @@
+use collections::HashMap;
+use language::{Point, Selection};
+use multi_buffer::{Anchor, MultiBufferRow, MultiBufferSnapshot, ToOffset};
+use ui::ViewContext;
+
+use crate::{display_map::DisplaySnapshot, DisplayPoint, Editor, ToDisplayPoint};
+
+/// Cursors placed past the end of their line while the `virtual_space` setting is enabled,
+/// by selection id, with how many columns past the end they are.
+#[derive(Default)]
+pub(super) struct VirtualSpace(HashMap<usize, (Anchor, u32)>);
+
+impl VirtualSpace {
+    pub(super) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Forgets cursors that were moved or removed since they were placed in virtual space.
+    pub(super) fn invalidate(
+        &mut self,
+        selections: &[Selection<Anchor>],
+        buffer: &MultiBufferSnapshot,
+    ) {
+        self.0.retain(|id, (head, _)| {
+            selections.iter().any(|selection| {
+                selection.id == *id
+                    && selection.start.to_offset(buffer) == head.to_offset(buffer)
+                    && selection.end.to_offset(buffer) == head.to_offset(buffer)
+            })
+        });
+    }
+
+    /// Returns the display position of each cursor in virtual space, with its columns past the
+    /// end of the line.
+    pub(super) fn cursors(&self, map: &DisplaySnapshot) -> Vec<(DisplayPoint, u32)> {
+        self.0
+            .values()
+            .map(|(head, columns)| (head.to_display_point(map), *columns))
+            .collect()
+    }
+}
+
+impl Editor {
+    /// Places the empty selections at the given line ends the given number of columns past them.
+    pub(super) fn place_cursors_in_virtual_space(
+        &mut self,
+        line_ends: Vec<(Point, u32)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        for selection in self.selections.all::<Point>(cx) {
+            let head = selection.head();
+            // Only the ends of buffer lines can be padded, not those of soft-wrapped lines.
+            if !selection.is_empty() || head.column != buffer.line_len(MultiBufferRow(head.row)) {
+                continue;
+            }
+            if let Some((_, columns)) = line_ends.iter().find(|(line_end, _)| *line_end == head) {
+                if *columns > 0 {
+                    self.virtual_space
+                        .0
+                        .insert(selection.id, (buffer.anchor_before(head), *columns));
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    /// Places the newest cursor the given number of columns past the end of its line.
+    pub(super) fn place_newest_cursor_in_virtual_space(
+        &mut self,
+        columns: u32,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let head = self.selections.newest::<Point>(cx).head();
+        self.place_cursors_in_virtual_space(vec![(head, columns)], cx);
+    }
+
+    /// Pads the lines of the cursors in virtual space with spaces up to the cursors, so that
+    /// text can be inserted at them. This is called within the transaction inserting the text,
+    /// so that undoing it also removes the padding.
+    pub(super) fn fill_virtual_space(&mut self, cx: &mut ViewContext<Self>) {
+        if self.virtual_space.is_empty() {
+            return;
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut padding = self
+            .virtual_space
+            .0
+            .drain()
+            .map(|(id, (head, columns))| (head.to_offset(&buffer), columns, id))
+            .collect::<Vec<_>>();
+        padding.sort_unstable();
+        let edits = padding
+            .iter()
+            .map(|(offset, columns, _)| (*offset..*offset, " ".repeat(*columns as usize)))
+            .collect::<Vec<_>>();
+
+        let mut new_heads = HashMap::default();
+        let mut inserted_len = 0;
+        for (offset, columns, id) in padding {
+            inserted_len += columns as usize;
+            new_heads.insert(id, offset + inserted_len);
+        }
+
+        self.buffer
+            .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+        let mut selections = self.selections.all::<usize>(cx);
+        for selection in &mut selections {
+            if let Some(head) = new_heads.get(&selection.id) {
+                selection.start = *head;
+                selection.end = *head;
+            }
+        }
+        self.change_selections(None, cx, |s| s.select(selections));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_settings::EditorSettings, editor_tests::init_test,
+        test::editor_test_context::EditorTestContext, DisplayRow, MoveDown, Undo,
+    };
+    use gpui::{point, Modifiers, MouseButton};
+    use settings::SettingsStore;
+
+    fn init_virtual_space_test(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.virtual_space = Some(true);
+                });
+            });
+        });
+    }
+
+    #[gpui::test]
+    async fn test_virtual_space(cx: &mut gpui::TestAppContext) {
+        init_virtual_space_test(cx);
+        let mut cx = EditorTestContext::new(cx).await;
+
+        // Typing at a cursor past the end of the line pads the line up to it, and undoing the
+        // typing removes the padding too.
+        cx.set_state("oneˇ\ntwo");
+        cx.update_editor(|editor, cx| {
+            editor.place_newest_cursor_in_virtual_space(3, cx);
+            editor.handle_input("x", cx);
+        });
+        cx.assert_editor_state("one   xˇ\ntwo");
+        cx.update_editor(|editor, cx| editor.undo(&Undo, cx));
+        cx.assert_editor_state("oneˇ\ntwo");
+
+        // Moving a cursor out of virtual space doesn't pad the line.
+        cx.set_state("oneˇ\ntwo");
+        cx.update_editor(|editor, cx| {
+            editor.place_newest_cursor_in_virtual_space(3, cx);
+            editor.move_down(&MoveDown, cx);
+            editor.handle_input("x", cx);
+        });
+        cx.assert_editor_state("one\ntwoxˇ");
+
+        // Block selections past the end of shorter lines place all of their cursors in the
+        // same column.
+        cx.set_state("onetwo\na\nthree");
+        cx.update_editor(|editor, cx| {
+            let display_map = editor.display_map.update(cx, |map, cx| map.snapshot(cx));
+            editor.select_columns(
+                DisplayPoint::new(DisplayRow(0), 4),
+                DisplayPoint::new(DisplayRow(2), 4),
+                4,
+                &display_map,
+                cx,
+            );
+            editor.handle_input("|", cx);
+        });
+        cx.assert_editor_state("onet|ˇwo\na   |ˇ\nthre|ˇe");
+    }
+
+    #[gpui::test]
+    async fn test_virtual_space_with_mouse(cx: &mut gpui::TestAppContext) {
+        init_virtual_space_test(cx);
+        let mut cx = EditorTestContext::new(cx).await;
+
+        // Clicking past the end of a line places the cursor where it was clicked.
+        cx.set_state("oneˇ\ntwo");
+        let line_end = cx.pixel_position("oneˇ\ntwo");
+        let column_width = line_end.x - cx.pixel_position("onˇe\ntwo").x;
+        cx.simulate_click(
+            point(line_end.x + column_width * 3.5, line_end.y),
+            Modifiers::none(),
+        );
+        cx.update_editor(|editor, cx| editor.handle_input("x", cx));
+        cx.assert_editor_state("one   xˇ\ntwo");
+
+        // Dragging a block selection across shorter lines places their cursors in its column.
+        cx.set_state("ˇonetwo\na\nthree");
+        let start = cx.pixel_position_for(DisplayPoint::new(DisplayRow(0), 4));
+        let end = cx.pixel_position_for(DisplayPoint::new(DisplayRow(2), 4));
+        let modifiers = Modifiers {
+            shift: true,
+            alt: true,
+            ..Default::default()
+        };
+        cx.simulate_mouse_down(start, MouseButton::Left, modifiers);
+        cx.simulate_mouse_move(end, MouseButton::Left, modifiers);
+        cx.simulate_mouse_up(end, MouseButton::Left, modifiers);
+        cx.update_editor(|editor, cx| editor.handle_input("|", cx));
+        cx.assert_editor_state("onet|ˇwo\na   |ˇ\nthre|ˇe");
+    }
+}
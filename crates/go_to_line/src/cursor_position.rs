@@ -6,8 +6,8 @@ use settings::{Settings, SettingsSources};
 use std::fmt::Write;
 use text::{Point, Selection};
 use ui::{
-    div, Button, ButtonCommon, Clickable, FluentBuilder, IntoElement, LabelSize, ParentElement,
-    Render, Tooltip, ViewContext,
+    h_flex, Button, ButtonCommon, Clickable, FluentBuilder, IntoElement, LabelSize, ParentElement,
+    Render, Styled, Tooltip, ViewContext,
 };
 use util::paths::FILE_ROW_COLUMN_DELIMITER;
 use workspace::{item::ItemHandle, StatusItemView, Workspace};
@@ -17,11 +17,13 @@ struct SelectionStats {
     lines: usize,
     characters: usize,
     selections: usize,
+    occurrences: usize,
 }
 
 pub struct CursorPosition {
     position: Option<Point>,
     selected_count: SelectionStats,
+    overtype: bool,
     workspace: WeakView<Workspace>,
     _observe_active_editor: Option<Subscription>,
 }
@@ -31,6 +33,7 @@ impl CursorPosition {
         Self {
             position: None,
             selected_count: Default::default(),
+            overtype: false,
             workspace: workspace.weak_handle(),
             _observe_active_editor: None,
         }
@@ -60,7 +63,9 @@ impl CursorPosition {
                 }
             }
         }
+        self.selected_count.occurrences = editor.selection_occurrence_count();
         self.position = last_selection.map(|s| s.head().to_point(&buffer));
+        self.overtype = editor.overtype();
 
         cx.notify();
     }
@@ -79,19 +84,24 @@ impl CursorPosition {
             lines,
             characters,
             selections,
+            occurrences,
         } = self.selected_count;
         let format = LineIndicatorFormat::get(None, cx);
         let is_short_format = format == &LineIndicatorFormat::Short;
         let lines = (lines > 1).then_some((lines, "line"));
         let selections = (selections > 1).then_some((selections, "selection"));
         let characters = (characters > 0).then_some((characters, "character"));
+        let occurrences = (occurrences > 1).then_some((occurrences, "occurrence"));
         if (None, None, None) == (characters, selections, lines) {
             // Nothing to display.
             return;
         }
         write!(text, " (").unwrap();
         let mut wrote_once = false;
-        for (count, name) in [selections, lines, characters].into_iter().flatten() {
+        for (count, name) in [selections, lines, characters, occurrences]
+            .into_iter()
+            .flatten()
+        {
             if wrote_once {
                 write!(text, ", ").unwrap();
             }
@@ -110,7 +120,7 @@ impl CursorPosition {
 
 impl Render for CursorPosition {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        div().when_some(self.position, |el, position| {
+        h_flex().gap_2().when_some(self.position, |el, position| {
             let mut text = format!(
                 "{}{FILE_ROW_COLUMN_DELIMITER}{}",
                 position.row + 1,
@@ -118,7 +128,37 @@ impl Render for CursorPosition {
             );
             self.write_position(&mut text, cx);
 
-            el.child(
+            el.when(self.overtype, |el| {
+                el.child(
+                    Button::new("overtype", "Overtype")
+                        .label_size(LabelSize::Small)
+                        .on_click(cx.listener(|this, _, cx| {
+                            if let Some(workspace) = this.workspace.upgrade() {
+                                workspace.update(cx, |workspace, cx| {
+                                    if let Some(editor) = workspace
+                                        .active_item(cx)
+                                        .and_then(|item| item.act_as::<Editor>(cx))
+                                    {
+                                        editor.update(cx, |editor, cx| {
+                                            editor.toggle_overtype(
+                                                &editor::actions::ToggleOvertype,
+                                                cx,
+                                            )
+                                        });
+                                    }
+                                });
+                            }
+                        }))
+                        .tooltip(|cx| {
+                            Tooltip::for_action(
+                                "Toggle Overtype",
+                                &editor::actions::ToggleOvertype,
+                                cx,
+                            )
+                        }),
+                )
+            })
+            .child(
                 Button::new("go-to-line-column", text)
                     .label_size(LabelSize::Small)
                     .on_click(cx.listener(|this, _, cx| {
@@ -0,0 +1,681 @@
+mod persistence;
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc, sync::Arc};
+
+use editor::{scroll::Autoscroll, Editor};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, impl_actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Global, Keystroke, ParentElement, Render, Styled, Task, View, ViewContext,
+    VisualContext, WeakView, WindowContext,
+};
+use persistence::KEYBOARD_MACROS_DB;
+use picker::{Picker, PickerDelegate};
+use serde::Deserialize;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace, WorkspaceId};
+
+actions!(
+    keyboard_macros,
+    [ToggleRecording, ReplayOverSelections, OpenNamedMacros]
+);
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct Replay {
+    /// How many times to replay the last macro. Defaults to once.
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+impl_actions!(keyboard_macros, [Replay]);
+
+/// The most keystrokes replayed before a replay is aborted, in case a named macro replays
+/// itself.
+const MAX_REPLAYED_KEYSTROKES: usize = 10000;
+
+pub fn init(cx: &mut AppContext) {
+    cx.set_global(KeyboardMacros::default());
+    cx.observe_keystrokes(|event, cx| {
+        let has_pending_keystrokes = cx.has_pending_keystrokes();
+        // Keystrokes typed in modals, like the command palette or the named macros picker,
+        // aren't recorded, since they don't replay reliably.
+        let modal_open = cx
+            .window_handle()
+            .downcast::<Workspace>()
+            .and_then(|workspace| {
+                workspace
+                    .update(cx, |workspace, cx| workspace.has_active_modal(cx))
+                    .ok()
+            })
+            .unwrap_or(false);
+        cx.update_global::<KeyboardMacros, _>(|macros, _| {
+            macros.record(
+                &event.keystroke,
+                event.action.as_deref(),
+                has_pending_keystrokes,
+                modal_open,
+            )
+        });
+    })
+    .detach();
+    cx.observe_new_views(register).detach();
+}
+
+fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|_, _: &ToggleRecording, cx| {
+        KeyboardMacros::toggle_recording(cx);
+    });
+    workspace.register_action(|_, action: &Replay, cx| {
+        if let Some(keystrokes) = cx.global::<KeyboardMacros>().last.clone() {
+            let times = action.times.unwrap_or(1);
+            replay(
+                (0..times)
+                    .flat_map(|_| keystrokes.iter().cloned().map(ReplayStep::Keystroke))
+                    .collect(),
+                cx,
+            );
+        }
+    });
+    workspace.register_action(|workspace, _: &ReplayOverSelections, cx| {
+        let Some(keystrokes) = cx.global::<KeyboardMacros>().last.clone() else {
+            return;
+        };
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        replay(replay_over_selections(editor, &keystrokes, cx), cx);
+    });
+    workspace.register_action(|workspace, _: &OpenNamedMacros, cx| {
+        NamedMacrosModal::toggle(workspace, cx);
+    });
+}
+
+/// Records the keystrokes typed in any window into keyboard macros.
+///
+/// A macro holds the keystrokes themselves rather than the actions they were bound to, so
+/// that replaying it also types text, and so that it can be stored. Replays of other macros
+/// during a recording are recorded as the keystrokes they replay.
+#[derive(Default)]
+pub struct KeyboardMacros {
+    /// The keystrokes recorded so far, while recording.
+    recording: Option<Vec<Keystroke>>,
+    /// The recorded keystrokes that are part of a binding that hasn't been completed yet.
+    pending: Vec<Keystroke>,
+    /// Whether the keystroke being dispatched toggled the recording, and so isn't part of it.
+    ignore_current_keystroke: bool,
+    /// The most recently recorded macro.
+    last: Option<Arc<[Keystroke]>>,
+    replayer: Option<Replayer>,
+}
+
+impl Global for KeyboardMacros {}
+
+impl KeyboardMacros {
+    pub fn is_recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    pub fn toggle_recording(cx: &mut WindowContext) {
+        cx.update_global::<Self, _>(|macros, _| {
+            if let Some(recording) = macros.recording.take() {
+                if !recording.is_empty() {
+                    macros.last = Some(recording.into());
+                }
+            } else {
+                macros.recording = Some(Vec::new());
+            }
+            macros.pending.clear();
+            macros.ignore_current_keystroke = true;
+        });
+        cx.defer(|cx| cx.global_mut::<Self>().ignore_current_keystroke = false);
+    }
+
+    fn record(
+        &mut self,
+        keystroke: &Keystroke,
+        action: Option<&dyn Action>,
+        has_pending_keystrokes: bool,
+        modal_open: bool,
+    ) {
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        if self.ignore_current_keystroke || modal_open || keystroke.is_ime_in_progress() {
+            return;
+        }
+
+        self.pending.push(keystroke.clone());
+        if let Some(action) = action {
+            // The keystrokes that replay macros are recorded as the keystrokes they replay.
+            if action.name().starts_with("keyboard_macros::") {
+                self.pending.clear();
+                return;
+            }
+        } else if has_pending_keystrokes {
+            return;
+        }
+        recording.append(&mut self.pending);
+    }
+}
+
+enum ReplayStep {
+    Keystroke(Keystroke),
+    Update(Box<dyn FnOnce(&mut WindowContext)>),
+}
+
+/// The steps left to replay, dispatched one at a time so that the effects of each keystroke
+/// are flushed before the next one is dispatched.
+#[derive(Default)]
+struct Replayer {
+    steps: VecDeque<ReplayStep>,
+    replayed_keystrokes: usize,
+}
+
+fn replay(steps: Vec<ReplayStep>, cx: &mut WindowContext) {
+    let running = cx.update_global::<KeyboardMacros, _>(|macros, _| {
+        // Macros replayed by a macro are replayed before the rest of it.
+        let running = macros.replayer.is_some();
+        let replayer = macros.replayer.get_or_insert_with(Replayer::default);
+        for step in steps.into_iter().rev() {
+            replayer.steps.push_front(step);
+        }
+        running
+    });
+    if !running {
+        cx.defer(replay_next_step);
+    }
+}
+
+fn replay_next_step(cx: &mut WindowContext) {
+    let step = cx.update_global::<KeyboardMacros, _>(|macros, _| {
+        let replayer = macros.replayer.as_mut()?;
+        let step = replayer.steps.pop_front();
+        if let Some(ReplayStep::Keystroke(_)) = step {
+            replayer.replayed_keystrokes += 1;
+            if replayer.replayed_keystrokes > MAX_REPLAYED_KEYSTROKES {
+                log::error!("aborting macro replay after {MAX_REPLAYED_KEYSTROKES} keystrokes");
+                macros.replayer = None;
+                return None;
+            }
+        }
+        if step.is_none() {
+            macros.replayer = None;
+        }
+        step
+    });
+    let Some(step) = step else {
+        return;
+    };
+
+    match step {
+        ReplayStep::Keystroke(keystroke) => {
+            cx.dispatch_keystroke(keystroke);
+        }
+        ReplayStep::Update(update) => update(cx),
+    }
+    cx.defer(replay_next_step);
+}
+
+/// Replays the keystrokes once at each of the editor's selections, with that selection as its
+/// only one, and then selects everything that the replays left selected.
+fn replay_over_selections(
+    editor: View<Editor>,
+    keystrokes: &[Keystroke],
+    cx: &mut WindowContext,
+) -> Vec<ReplayStep> {
+    let selections = editor.read(cx).selections.disjoint_anchor_ranges();
+    let replayed_selections = Rc::new(RefCell::new(Vec::new()));
+    let mut steps = Vec::new();
+    for selection in selections {
+        let editor_handle = editor.downgrade();
+        steps.push(ReplayStep::Update(Box::new(move |cx| {
+            editor_handle
+                .update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                        s.select_anchor_ranges([selection])
+                    })
+                })
+                .log_err();
+        })));
+        steps.extend(keystrokes.iter().cloned().map(ReplayStep::Keystroke));
+
+        let editor_handle = editor.downgrade();
+        let replayed_selections = replayed_selections.clone();
+        steps.push(ReplayStep::Update(Box::new(move |cx| {
+            editor_handle
+                .update(cx, |editor, _| {
+                    replayed_selections
+                        .borrow_mut()
+                        .extend(editor.selections.disjoint_anchor_ranges())
+                })
+                .log_err();
+        })));
+    }
+
+    let editor_handle = editor.downgrade();
+    steps.push(ReplayStep::Update(Box::new(move |cx| {
+        editor_handle
+            .update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                    s.select_anchor_ranges(replayed_selections.take())
+                })
+            })
+            .log_err();
+    })));
+    steps
+}
+
+#[derive(Clone)]
+struct NamedMacro {
+    name: String,
+    keystrokes: Arc<[Keystroke]>,
+}
+
+pub struct NamedMacrosModal {
+    picker: View<Picker<NamedMacrosDelegate>>,
+}
+
+impl NamedMacrosModal {
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let workspace_id = workspace.database_id();
+        workspace.toggle_modal(cx, move |cx| NamedMacrosModal::new(workspace_id, cx));
+    }
+
+    fn new(workspace_id: Option<WorkspaceId>, cx: &mut ViewContext<Self>) -> Self {
+        let macros = workspace_id
+            .and_then(|workspace_id| KEYBOARD_MACROS_DB.macros(workspace_id).log_err())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|(name, keystrokes)| {
+                let keystrokes = serde_json::from_str::<Vec<Keystroke>>(&keystrokes).log_err()?;
+                Some(NamedMacro {
+                    name,
+                    keystrokes: keystrokes.into(),
+                })
+            })
+            .collect();
+
+        let delegate = NamedMacrosDelegate::new(cx.view().downgrade(), workspace_id, macros);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for NamedMacrosModal {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for NamedMacrosModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for NamedMacrosModal {}
+impl ModalView for NamedMacrosModal {}
+
+pub struct NamedMacrosDelegate {
+    modal: WeakView<NamedMacrosModal>,
+    workspace_id: Option<WorkspaceId>,
+    macros: Vec<NamedMacro>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    /// The name to save the last recorded macro as, which is shown before the matches.
+    save_as: Option<String>,
+    selected_index: usize,
+}
+
+impl NamedMacrosDelegate {
+    fn new(
+        modal: WeakView<NamedMacrosModal>,
+        workspace_id: Option<WorkspaceId>,
+        macros: Vec<NamedMacro>,
+    ) -> Self {
+        let candidates = macros
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, named_macro)| {
+                StringMatchCandidate::new(candidate_id, named_macro.name.clone())
+            })
+            .collect();
+
+        Self {
+            modal,
+            workspace_id,
+            macros,
+            candidates,
+            matches: Vec::new(),
+            save_as: None,
+            selected_index: 0,
+        }
+    }
+
+    fn save_last_macro(&self, name: String, cx: &mut AppContext) {
+        let Some(workspace_id) = self.workspace_id else {
+            return;
+        };
+        let Some(keystrokes) = cx.global::<KeyboardMacros>().last.clone() else {
+            return;
+        };
+        let Some(keystrokes) = serde_json::to_string(&*keystrokes).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(KEYBOARD_MACROS_DB.save_macro(workspace_id, name, keystrokes))
+            .detach_and_log_err(cx);
+    }
+}
+
+impl PickerDelegate for NamedMacrosDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Replay a macro, or name the last one to save it...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.save_as.iter().count() + self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let match_ix = match &self.save_as {
+            Some(name) if self.selected_index == 0 => {
+                self.save_last_macro(name.clone(), cx);
+                None
+            }
+            Some(_) => Some(self.selected_index - 1),
+            None => Some(self.selected_index),
+        };
+        let keystrokes = match_ix
+            .and_then(|ix| self.matches.get(ix))
+            .map(|mat| self.macros[mat.candidate_id].keystrokes.clone());
+
+        self.dismissed(cx);
+        if let Some(keystrokes) = keystrokes {
+            replay(
+                keystrokes
+                    .iter()
+                    .cloned()
+                    .map(ReplayStep::Keystroke)
+                    .collect(),
+                cx,
+            );
+        }
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let name = query.trim();
+        self.save_as = (!name.is_empty()
+            && self.workspace_id.is_some()
+            && cx.global::<KeyboardMacros>().last.is_some())
+        .then(|| name.to_string());
+
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.match_count().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let list_item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(selected);
+        let ix = match &self.save_as {
+            Some(name) if ix == 0 => {
+                return Some(list_item.child(Label::new(format!("Save Last Macro as \"{name}\""))));
+            }
+            Some(_) => ix - 1,
+            None => ix,
+        };
+
+        let mat = &self.matches[ix];
+        let named_macro = &self.macros[mat.candidate_id];
+        Some(
+            list_item
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(format!("{} keystrokes", named_macro.keystrokes.len()))
+                        .color(Color::Muted)
+                        .size(LabelSize::Small),
+                ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::{
+        actions::MoveToEndOfLine, test::editor_lsp_test_context::EditorLspTestContext, Editor,
+    };
+    use gpui::{KeyBinding, NoAction, TestAppContext};
+    use indoc::indoc;
+    use project::Project;
+    use workspace::AppState;
+
+    #[test]
+    fn test_record() {
+        let keystroke = |source| Keystroke::parse(source).unwrap();
+        let mut macros = KeyboardMacros {
+            recording: Some(Vec::new()),
+            ..Default::default()
+        };
+
+        macros.record(&keystroke("a"), None, false, false);
+        // Keystrokes are recorded once the binding they're part of is complete.
+        macros.record(&keystroke("ctrl-k"), None, true, false);
+        assert_eq!(macros.recording.as_deref(), Some(&[keystroke("a")][..]));
+        macros.record(&keystroke("ctrl-u"), Some(&NoAction), false, false);
+        // Keystrokes bound to keyboard macro actions aren't recorded.
+        macros.record(&keystroke("ctrl-r"), Some(&Replay::default()), false, false);
+        // Neither are keystrokes typed while a modal is open.
+        macros.record(&keystroke("b"), None, false, true);
+
+        assert_eq!(
+            macros.recording.as_deref(),
+            Some(&[keystroke("a"), keystroke("ctrl-k"), keystroke("ctrl-u")][..])
+        );
+    }
+
+    #[gpui::test]
+    async fn test_replay(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+        cx.set_state(indoc! {"
+            ˇone
+            two
+            three
+        "});
+        record(&mut cx, "end !");
+        cx.assert_editor_state(indoc! {"
+            one!ˇ
+            two
+            three
+        "});
+
+        cx.set_selections_state(indoc! {"
+            one!
+            ˇtwo
+            three
+        "});
+        cx.dispatch_action(Replay::default());
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            one!
+            two!ˇ
+            three
+        "});
+
+        // Each keystroke's effects are flushed before the next one is replayed.
+        cx.set_selections_state(indoc! {"
+            one!
+            two!
+            ˇthree
+        "});
+        cx.dispatch_action(Replay { times: Some(2) });
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            one!
+            two!
+            three!!ˇ
+        "});
+    }
+
+    #[gpui::test]
+    async fn test_replay_over_selections(cx: &mut TestAppContext) {
+        let mut cx = init_test(cx).await;
+        cx.set_state(indoc! {"
+            ˇzero
+            one
+            two
+        "});
+        record(&mut cx, "end !");
+
+        // The macro is replayed at each selection on its own, and what each replay left
+        // selected is selected afterwards.
+        cx.set_selections_state(indoc! {"
+            zero!
+            ˇone
+            t«wˇ»o
+        "});
+        cx.dispatch_action(ReplayOverSelections);
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            zero!
+            one!ˇ
+            two!ˇ
+        "});
+    }
+
+    #[gpui::test]
+    async fn test_toggle_recording_from_command_palette(cx: &mut TestAppContext) {
+        let app_state = cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            menu::init();
+            workspace::init(app_state.clone(), cx);
+            command_palette::init(cx);
+            Project::init_settings(cx);
+            init(cx);
+            cx.bind_keys([
+                KeyBinding::new("ctrl-q", ToggleRecording, None),
+                KeyBinding::new("cmd-shift-p", command_palette::Toggle, None),
+                KeyBinding::new("enter", menu::Confirm, None),
+            ]);
+            app_state
+        });
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+        let editor = cx.new_view(|cx| Editor::single_line(cx));
+        workspace.update(cx, |workspace, cx| {
+            workspace.add_item_to_active_pane(Box::new(editor.clone()), None, true, cx);
+            editor.update(cx, |editor, cx| editor.focus(cx))
+        });
+
+        // The keystrokes typed in the command palette to stop the recording aren't recorded.
+        cx.simulate_keystrokes("ctrl-q a b cmd-shift-p");
+        cx.simulate_input("keyboard macros: toggle recording");
+        cx.simulate_keystrokes("enter");
+        cx.update(|cx| {
+            let macros = cx.global::<KeyboardMacros>();
+            assert!(!macros.is_recording());
+            assert_eq!(macros.last.as_ref().map(|last| last.len()), Some(2));
+        });
+
+        cx.dispatch_action(Replay::default());
+        cx.run_until_parked();
+        editor.update(cx, |editor, cx| assert_eq!(editor.text(cx), "abab"));
+    }
+
+    async fn init_test(cx: &mut TestAppContext) -> EditorLspTestContext {
+        let mut cx = EditorLspTestContext::new_rust(Default::default(), cx).await;
+        cx.update(|cx| {
+            init(cx);
+            cx.bind_keys([
+                KeyBinding::new("ctrl-q", ToggleRecording, None),
+                KeyBinding::new(
+                    "end",
+                    MoveToEndOfLine {
+                        stop_at_soft_wraps: true,
+                    },
+                    Some("Editor"),
+                ),
+            ]);
+        });
+        // The workspace was created before the actions were registered for new workspaces.
+        cx.update_workspace(register);
+        cx
+    }
+
+    fn record(cx: &mut EditorLspTestContext, keystrokes: &str) {
+        cx.simulate_keystrokes("ctrl-q");
+        cx.simulate_keystrokes(keystrokes);
+        cx.simulate_keystrokes("ctrl-q");
+        let recorded = cx.update(|cx| cx.global::<KeyboardMacros>().last.clone());
+        assert_eq!(
+            recorded.map(|recorded| recorded.len()),
+            Some(keystrokes.split(' ').count())
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use db::{define_connection, query, sqlez_macros::sql};
+use workspace::{WorkspaceDb, WorkspaceId};
+
+define_connection! {
+    pub static ref KEYBOARD_MACROS_DB: KeyboardMacrosDb<WorkspaceDb> =
+        &[sql!(
+            CREATE TABLE keyboard_macros (
+                workspace_id INTEGER,
+                name TEXT NOT NULL,
+                keystrokes TEXT NOT NULL,
+                PRIMARY KEY(workspace_id, name),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+            ) STRICT;
+        )];
+}
+
+impl KeyboardMacrosDb {
+    query! {
+        pub fn macros(workspace_id: WorkspaceId) -> Result<Vec<(String, String)>> {
+            SELECT name, keystrokes
+            FROM keyboard_macros
+            WHERE workspace_id = ?
+            ORDER BY name
+        }
+    }
+
+    query! {
+        pub async fn save_macro(
+            workspace_id: WorkspaceId,
+            name: String,
+            keystrokes: String
+        ) -> Result<()> {
+            INSERT OR REPLACE INTO keyboard_macros(workspace_id, name, keystrokes)
+            VALUES (?, ?, ?)
+        }
+    }
+}
@@ -19,6 +19,7 @@ use crate::{
 use anyhow::{anyhow, Context, Result};
 use async_watch as watch;
 pub use clock::ReplicaId;
+use collections::HashMap;
 use futures::channel::oneshot;
 use gpui::{
     AnyElement, AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel,
@@ -110,6 +111,7 @@ pub struct Buffer {
     non_text_state_update_count: usize,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
+    remote_viewports: TreeMap<ReplicaId, ViewportSet>,
     diagnostics_timestamp: clock::Lamport,
     completion_triggers: Vec<String>,
     completion_triggers_timestamp: clock::Lamport,
@@ -137,6 +139,7 @@ pub struct BufferSnapshot {
     file: Option<Arc<dyn File>>,
     diagnostics: SmallVec<[(LanguageServerId, DiagnosticSet); 2]>,
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
+    remote_viewports: TreeMap<ReplicaId, ViewportSet>,
     language: Option<Arc<Language>>,
     non_text_state_update_count: usize,
 }
@@ -183,6 +186,12 @@ struct SelectionSet {
     lamport_timestamp: clock::Lamport,
 }
 
+#[derive(Clone, Debug)]
+struct ViewportSet {
+    viewport: Option<Range<Anchor>>,
+    lamport_timestamp: clock::Lamport,
+}
+
 /// A diagnostic associated with a certain range of a buffer.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Diagnostic {
@@ -291,6 +300,14 @@ pub enum Operation {
         cursor_shape: CursorShape,
     },
 
+    /// An update to the range of this buffer that a replica is looking at.
+    UpdateViewport {
+        /// The visible range, or `None` if the buffer is no longer visible.
+        viewport: Option<Range<Anchor>>,
+        /// The buffer's lamport timestamp.
+        lamport_timestamp: clock::Lamport,
+    },
+
     /// An update to the characters that should trigger autocompletion
     /// for this buffer.
     UpdateCompletionTriggers {
@@ -549,6 +566,25 @@ impl IndentGuide {
     }
 }
 
+/// A foldable region of a buffer, as determined by its language's folds query.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SyntaxFold {
+    /// The range of the syntax node that can be folded.
+    pub range: Range<usize>,
+    /// The range of text describing the region, shown in place of the folded text.
+    pub summary_range: Option<Range<usize>>,
+}
+
+/// The name of the parameter an argument is passed to, as determined by the language's parameters
+/// query from the function definitions in the buffer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParameterHint {
+    /// The offset at which the argument starts.
+    pub position: usize,
+    /// The name of the parameter.
+    pub name: String,
+}
+
 impl Buffer {
     /// Create a new buffer with the given base text.
     pub fn local<T: Into<String>>(base_text: T, cx: &mut ModelContext<Self>) -> Self {
@@ -645,6 +681,12 @@ impl Buffer {
                 cursor_shape: set.cursor_shape,
             })
         }));
+        operations.extend(self.remote_viewports.iter().map(|(_, set)| {
+            proto::serialize_operation(&Operation::UpdateViewport {
+                viewport: set.viewport.clone(),
+                lamport_timestamp: set.lamport_timestamp,
+            })
+        }));
 
         for (server_id, diagnostics) in &self.diagnostics {
             operations.push(proto::serialize_operation(&Operation::UpdateDiagnostics {
@@ -727,6 +769,7 @@ impl Buffer {
             pending_autoindent: Default::default(),
             language: None,
             remote_selections: Default::default(),
+            remote_viewports: Default::default(),
             diagnostics: Default::default(),
             diagnostics_timestamp: Default::default(),
             completion_triggers: Default::default(),
@@ -750,6 +793,7 @@ impl Buffer {
             git_diff: self.git_diff.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
+            remote_viewports: self.remote_viewports.clone(),
             diagnostics: self.diagnostics.clone(),
             language: self.language.clone(),
             non_text_state_update_count: self.non_text_state_update_count,
@@ -1725,6 +1769,38 @@ impl Buffer {
         }
     }
 
+    /// Stores the range of the buffer that is visible on this replica, so that it can be
+    /// broadcasted to all of the buffer's replicas. Passing `None` clears it.
+    pub fn set_active_viewport(
+        &mut self,
+        viewport: Option<Range<Anchor>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let replica_id = self.text.replica_id();
+        let current = self.remote_viewports.get(&replica_id);
+        if current.map_or(viewport.is_none(), |set| set.viewport == viewport) {
+            return;
+        }
+
+        let lamport_timestamp = self.text.lamport_clock.tick();
+        self.remote_viewports.insert(
+            replica_id,
+            ViewportSet {
+                viewport: viewport.clone(),
+                lamport_timestamp,
+            },
+        );
+        self.send_operation(
+            Operation::UpdateViewport {
+                viewport,
+                lamport_timestamp,
+            },
+            cx,
+        );
+        self.non_text_state_update_count += 1;
+        cx.notify();
+    }
+
     /// Replaces the buffer's entire text.
     pub fn set_text<T>(&mut self, text: T, cx: &mut ModelContext<Self>) -> Option<clock::Lamport>
     where
@@ -1941,6 +2017,9 @@ impl Buffer {
             Operation::UpdateSelections { selections, .. } => selections
                 .iter()
                 .all(|s| self.can_resolve(&s.start) && self.can_resolve(&s.end)),
+            Operation::UpdateViewport { viewport, .. } => viewport.as_ref().map_or(true, |range| {
+                self.can_resolve(&range.start) && self.can_resolve(&range.end)
+            }),
             Operation::UpdateCompletionTriggers { .. } => true,
         }
     }
@@ -1987,6 +2066,26 @@ impl Buffer {
                 self.text.lamport_clock.observe(lamport_timestamp);
                 self.non_text_state_update_count += 1;
             }
+            Operation::UpdateViewport {
+                viewport,
+                lamport_timestamp,
+            } => {
+                if let Some(set) = self.remote_viewports.get(&lamport_timestamp.replica_id) {
+                    if set.lamport_timestamp > lamport_timestamp {
+                        return;
+                    }
+                }
+
+                self.remote_viewports.insert(
+                    lamport_timestamp.replica_id,
+                    ViewportSet {
+                        viewport,
+                        lamport_timestamp,
+                    },
+                );
+                self.text.lamport_clock.observe(lamport_timestamp);
+                self.non_text_state_update_count += 1;
+            }
             Operation::UpdateCompletionTriggers {
                 triggers,
                 lamport_timestamp,
@@ -2031,6 +2130,7 @@ impl Buffer {
     /// Removes the selections for a given peer.
     pub fn remove_peer(&mut self, replica_id: ReplicaId, cx: &mut ModelContext<Self>) {
         self.remote_selections.remove(&replica_id);
+        self.remote_viewports.remove(&replica_id);
         cx.notify();
     }
 
@@ -2705,6 +2805,73 @@ impl BufferSnapshot {
         result
     }
 
+    /// Returns the ranges of the name of the HTML or JSX tag at the given position and of the
+    /// name of its matching opening or closing tag, so that both can be renamed together.
+    pub fn linked_tag_name_ranges<T: ToOffset>(&self, position: T) -> Vec<Range<usize>> {
+        const OPENING_TAG_KINDS: &[&str] = &["start_tag", "jsx_opening_element"];
+        const CLOSING_TAG_KINDS: &[&str] = &["end_tag", "jsx_closing_element"];
+
+        let offset = position.to_offset(self);
+        for layer in self.syntax.layers_for_range(offset..offset, &self.text) {
+            // The position can be at either end of the tag name.
+            for candidate_offset in [offset, offset.saturating_sub(1)] {
+                let Some(mut name) = layer
+                    .node()
+                    .named_descendant_for_byte_range(candidate_offset, candidate_offset)
+                else {
+                    continue;
+                };
+                // Ascend to the tag, keeping track of its child that contains the position.
+                let tag = loop {
+                    let Some(parent) = name.parent() else {
+                        break None;
+                    };
+                    if OPENING_TAG_KINDS.contains(&parent.kind())
+                        || CLOSING_TAG_KINDS.contains(&parent.kind())
+                    {
+                        break Some(parent);
+                    }
+                    name = parent;
+                };
+                let Some(tag) = tag else {
+                    continue;
+                };
+                if tag.named_child(0) != Some(name)
+                    || offset < name.start_byte()
+                    || offset > name.end_byte()
+                {
+                    continue;
+                }
+
+                let matching_kinds = if OPENING_TAG_KINDS.contains(&tag.kind()) {
+                    CLOSING_TAG_KINDS
+                } else {
+                    OPENING_TAG_KINDS
+                };
+                let Some(element) = tag.parent() else {
+                    continue;
+                };
+                let mut cursor = element.walk();
+                let matching_name = element
+                    .named_children(&mut cursor)
+                    .find(|child| matching_kinds.contains(&child.kind()))
+                    .and_then(|matching_tag| matching_tag.named_child(0));
+                if let Some(matching_name) = matching_name {
+                    let name_text = self.text_for_range(name.byte_range()).collect::<String>();
+                    let matching_name_text = self
+                        .text_for_range(matching_name.byte_range())
+                        .collect::<String>();
+                    if name_text == matching_name_text {
+                        let mut ranges = vec![name.byte_range(), matching_name.byte_range()];
+                        ranges.sort_by_key(|range| range.start);
+                        return ranges;
+                    }
+                }
+            }
+        }
+        Vec::new()
+    }
+
     /// Returns the outline for the buffer.
     ///
     /// This method allows passing an optional [SyntaxTheme] to
@@ -3099,6 +3266,127 @@ impl BufferSnapshot {
         })
     }
 
+    /// Returns the ranges of the syntax nodes matched by the folds query that intersect
+    /// the given range, which can be folded away.
+    pub fn syntax_folds<T: ToOffset>(
+        &self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = SyntaxFold> + '_ {
+        let offset_range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(offset_range, self, |grammar| {
+            grammar.folds_config.as_ref().map(|config| &config.query)
+        });
+
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref())
+            .collect::<Vec<_>>();
+
+        iter::from_fn(move || {
+            while let Some(mat) = syntax_matches.peek() {
+                let fold = configs[mat.grammar_index].and_then(|config| {
+                    let mut fold = None;
+                    let mut summary_range = None;
+                    for capture in mat.captures {
+                        if capture.index == config.fold_capture_ix {
+                            fold = Some(capture.node.byte_range());
+                        } else if Some(capture.index) == config.summary_capture_ix {
+                            summary_range = Some(capture.node.byte_range());
+                        }
+                    }
+                    Some(SyntaxFold {
+                        range: fold?,
+                        summary_range,
+                    })
+                });
+                syntax_matches.advance();
+                if fold.is_some() {
+                    return fold;
+                }
+            }
+            None
+        })
+    }
+
+    /// Returns hints naming the parameters that the arguments starting in the given range are
+    /// passed to, for calls of the functions defined in the buffer. Arguments that are already
+    /// spelled like their parameter are not hinted.
+    pub fn parameter_hints<T: ToOffset>(&self, range: Range<T>) -> Vec<ParameterHint> {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+        let mut syntax_matches = self.syntax.matches(0..self.len(), self, |grammar| {
+            grammar
+                .parameters_config
+                .as_ref()
+                .map(|config| &config.query)
+        });
+        let configs = syntax_matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.parameters_config.as_ref())
+            .collect::<Vec<_>>();
+
+        // Group the parameters and arguments by the name of the definition or call they belong to.
+        let mut definitions = HashMap::<Range<usize>, Vec<Range<usize>>>::default();
+        let mut calls = HashMap::<Range<usize>, Vec<Range<usize>>>::default();
+        while let Some(mat) = syntax_matches.peek() {
+            if let Some(config) = configs[mat.grammar_index] {
+                let capture_range = |capture_ix| {
+                    mat.captures
+                        .iter()
+                        .find(|capture| capture.index == capture_ix && !capture.node.is_extra())
+                        .map(|capture| capture.node.byte_range())
+                };
+                if let Some((name, parameter)) = capture_range(config.definition_name_capture_ix)
+                    .zip(capture_range(config.parameter_capture_ix))
+                {
+                    definitions.entry(name).or_default().push(parameter);
+                } else if let Some((name, argument)) = capture_range(config.call_name_capture_ix)
+                    .zip(capture_range(config.argument_capture_ix))
+                {
+                    calls.entry(name).or_default().push(argument);
+                }
+            }
+            syntax_matches.advance();
+        }
+
+        let text = |range: &Range<usize>| self.text_for_range(range.clone()).collect::<String>();
+        // Functions defined more than once with different parameters can't be told apart.
+        let mut signatures = HashMap::<String, Option<Vec<String>>>::default();
+        for (name, mut parameters) in definitions {
+            parameters.sort_by_key(|parameter| parameter.start);
+            parameters.dedup();
+            let parameters = parameters.iter().map(text).collect::<Vec<_>>();
+            signatures
+                .entry(text(&name))
+                .and_modify(|signature| {
+                    if signature.as_ref() != Some(&parameters) {
+                        *signature = None;
+                    }
+                })
+                .or_insert(Some(parameters));
+        }
+
+        let mut hints = Vec::new();
+        for (name, mut arguments) in calls {
+            let Some(Some(parameters)) = signatures.get(&text(&name)) else {
+                continue;
+            };
+            arguments.sort_by_key(|argument| argument.start);
+            arguments.dedup();
+            for (argument, parameter) in arguments.iter().zip(parameters) {
+                if range.contains(&argument.start) && text(argument) != *parameter {
+                    hints.push(ParameterHint {
+                        position: argument.start,
+                        name: parameter.clone(),
+                    });
+                }
+            }
+        }
+        hints.sort_by_key(|hint| hint.position);
+        hints
+    }
+
     pub fn injections_intersecting_range<T: ToOffset>(
         &self,
         range: Range<T>,
@@ -3475,6 +3763,70 @@ impl BufferSnapshot {
         Some((start_row..end_row, indent))
     }
 
+    /// Returns the row range and indentation of the innermost syntax node that encloses
+    /// the given row and whose body is indented relative to the line the node starts on.
+    ///
+    /// Unlike [`Self::enclosing_indent`], this follows the syntax tree, so the line that
+    /// opens a block and the line that closes it both resolve to that block.
+    pub fn syntax_enclosing_indent(
+        &self,
+        buffer_row: BufferRow,
+    ) -> Option<(Range<BufferRow>, LineIndent)> {
+        let line_indent = self.line_indent_for_row(buffer_row);
+        if line_indent.is_line_empty() {
+            return None;
+        }
+
+        let start = self.point_to_offset(Point::new(buffer_row, line_indent.raw_len()));
+        let end = self.point_to_offset(Point::new(buffer_row, self.line_len(buffer_row)));
+
+        let mut result: Option<(Range<BufferRow>, LineIndent)> = None;
+        for layer in self.syntax.layers_for_range(start..end, &self.text) {
+            let mut node = layer.node().descendant_for_byte_range(start, end);
+            while let Some(candidate) = node {
+                // Never treat the whole layer as a block.
+                let Some(parent) = candidate.parent() else {
+                    break;
+                };
+
+                let start_row = candidate.start_position().row as u32;
+                let end_row = candidate.end_position().row as u32;
+                if start_row < end_row {
+                    let start_indent = self.line_indent_for_row(start_row);
+                    let has_indented_body = self
+                        .text
+                        .line_indents_in_row_range(start_row + 1..end_row + 1)
+                        .find(|(_, indent)| !indent.is_line_empty())
+                        .map_or(false, |(_, indent)| {
+                            indent.raw_len() > start_indent.raw_len()
+                        });
+
+                    if has_indented_body {
+                        // Leave out a trailing line that only closes the block.
+                        let end_row = if self.line_indent_for_row(end_row).raw_len()
+                            <= start_indent.raw_len()
+                        {
+                            end_row - 1
+                        } else {
+                            end_row
+                        };
+
+                        let is_innermost = result
+                            .as_ref()
+                            .map_or(true, |(row_range, _)| start_row >= row_range.start);
+                        if is_innermost {
+                            result = Some((start_row..end_row, start_indent));
+                        }
+                        break;
+                    }
+                }
+
+                node = Some(parent);
+            }
+        }
+        result
+    }
+
     /// Returns selections for remote peers intersecting the given range.
     #[allow(clippy::type_complexity)]
     pub fn selections_in_range(
@@ -3516,6 +3868,14 @@ impl BufferSnapshot {
             })
     }
 
+    /// Returns the ranges of the buffer that remote peers are looking at.
+    pub fn remote_viewports(&self) -> impl Iterator<Item = (ReplicaId, Range<Anchor>)> + '_ {
+        self.remote_viewports
+            .iter()
+            .filter(move |(replica_id, _)| **replica_id != self.text.replica_id())
+            .filter_map(|(replica_id, set)| Some((*replica_id, set.viewport.clone()?)))
+    }
+
     /// Whether the buffer contains any git changes.
     pub fn has_git_diff(&self) -> bool {
         !self.git_diff.is_empty()
@@ -3696,6 +4056,7 @@ impl Clone for BufferSnapshot {
             syntax: self.syntax.clone(),
             file: self.file.clone(),
             remote_selections: self.remote_selections.clone(),
+            remote_viewports: self.remote_viewports.clone(),
             diagnostics: self.diagnostics.clone(),
             language: self.language.clone(),
             non_text_state_update_count: self.non_text_state_update_count,
@@ -3950,6 +4311,9 @@ impl operation_queue::Operation for Operation {
             | Operation::UpdateSelections {
                 lamport_timestamp, ..
             }
+            | Operation::UpdateViewport {
+                lamport_timestamp, ..
+            }
             | Operation::UpdateCompletionTriggers {
                 lamport_timestamp, ..
             } => *lamport_timestamp,
@@ -1103,6 +1103,83 @@ fn test_range_for_syntax_ancestor(cx: &mut AppContext) {
     }
 }
 
+#[gpui::test]
+fn test_linked_tag_name_ranges(cx: &mut AppContext) {
+    cx.new_model(|cx| {
+        let text = "<div><p class=\"a\">b</p></div><br>";
+        let buffer = Buffer::local(text, cx).with_language(Arc::new(html_lang()), cx);
+        let snapshot = buffer.snapshot();
+
+        let div_ranges = vec![1..4, 25..28];
+        assert_eq!(snapshot.linked_tag_name_ranges(2), div_ranges);
+        assert_eq!(snapshot.linked_tag_name_ranges(4), div_ranges);
+        assert_eq!(snapshot.linked_tag_name_ranges(26), div_ranges);
+        assert_eq!(snapshot.linked_tag_name_ranges(22), vec![6..7, 21..22]);
+
+        // Positions outside of tag names, and tags without a closing tag.
+        assert!(snapshot.linked_tag_name_ranges(10).is_empty());
+        assert!(snapshot.linked_tag_name_ranges(18).is_empty());
+        assert!(snapshot.linked_tag_name_ranges(31).is_empty());
+
+        buffer
+    });
+}
+
+#[gpui::test]
+fn test_structural_query_matches(cx: &mut AppContext) {
+    cx.new_model(|cx| {
+        let text = r#"
+            fn main() {
+                foo(1, 2);
+                foo(
+                    bar(),
+                    3,
+                );
+                foo(4);
+                baz(5, 6);
+            }
+        "#
+        .unindent();
+        let buffer = Buffer::local(text.clone(), cx).with_language(Arc::new(rust_lang()), cx);
+        let snapshot = buffer.snapshot();
+        let matched_text = |query: &StructuralQuery| {
+            query
+                .matches(&snapshot, 0..snapshot.len())
+                .into_iter()
+                .map(|range| text[range].to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Templates match calls with exactly two arguments, however they are formatted.
+        assert_eq!(
+            matched_text(&StructuralQuery::new("foo($A, $B)")),
+            ["foo(1, 2)", "foo(\n        bar(),\n        3,\n    )"]
+        );
+        assert_eq!(matched_text(&StructuralQuery::new("$F(4)")), ["foo(4)"]);
+
+        // Tree-sitter patterns match their whole top-level node, unless they capture another one.
+        assert_eq!(
+            matched_text(&StructuralQuery::new(
+                r#"(call_expression function: (identifier) @name (#eq? @name "baz"))"#
+            )),
+            ["baz(5, 6)"]
+        );
+        assert_eq!(
+            matched_text(&StructuralQuery::new(
+                r#"(call_expression arguments: (arguments . (integer_literal) .))"#
+            )),
+            ["foo(4)"]
+        );
+
+        // Queries that are neither a valid pattern nor template can't match anything.
+        let grammar = buffer.language().unwrap().grammar().unwrap();
+        assert!(StructuralQuery::new("foo($A, $B)").is_valid_in_any([grammar.as_ref()]));
+        assert!(!StructuralQuery::new("foo($A,").is_valid_in_any([grammar.as_ref()]));
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_autoindent_with_soft_tabs(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -2113,6 +2190,55 @@ fn test_serialization(cx: &mut gpui::AppContext) {
     assert_eq!(buffer2.read(cx).text(), "abcDF");
 }
 
+#[gpui::test]
+fn test_remote_viewports(cx: &mut gpui::AppContext) {
+    let buffer1 = cx.new_model(|cx| Buffer::local("one\ntwo\nthree\nfour\n", cx));
+    let buffer2 = cx.new_model(|cx| {
+        let state = buffer1.read(cx).to_proto(cx);
+        Buffer::from_proto(1, Capability::ReadWrite, state, None).unwrap()
+    });
+
+    let sync = |cx: &mut gpui::AppContext| {
+        let ops = cx
+            .background_executor()
+            .block(buffer1.read(cx).serialize_ops(None, cx));
+        buffer2.update(cx, |buffer, cx| {
+            buffer
+                .apply_ops(
+                    ops.into_iter()
+                        .map(|op| proto::deserialize_operation(op).unwrap()),
+                    cx,
+                )
+                .unwrap();
+        });
+    };
+
+    buffer1.update(cx, |buffer, cx| {
+        let viewport =
+            buffer.anchor_before(Point::new(1, 0))..buffer.anchor_after(Point::new(3, 0));
+        buffer.set_active_viewport(Some(viewport), cx);
+        assert_eq!(buffer.snapshot().remote_viewports().count(), 0);
+    });
+    sync(cx);
+    let snapshot = buffer2.read(cx).snapshot();
+    assert_eq!(
+        snapshot
+            .remote_viewports()
+            .map(|(replica_id, range)| {
+                (
+                    replica_id,
+                    range.start.to_point(&snapshot)..range.end.to_point(&snapshot),
+                )
+            })
+            .collect::<Vec<_>>(),
+        [(0, Point::new(1, 0)..Point::new(3, 0))]
+    );
+
+    buffer1.update(cx, |buffer, cx| buffer.set_active_viewport(None, cx));
+    sync(cx);
+    assert_eq!(buffer2.read(cx).snapshot().remote_viewports().count(), 0);
+}
+
 #[gpui::test]
 async fn test_find_matching_indent(cx: &mut TestAppContext) {
     cx.update(|cx| init_settings(cx, |_| {}));
@@ -2199,6 +2325,109 @@ async fn test_find_matching_indent(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_syntax_enclosing_indent(cx: &mut TestAppContext) {
+    cx.update(|cx| init_settings(cx, |_| {}));
+
+    let text = "
+        fn b() {
+            if c {
+                let d = 2;
+            }
+            let e = foo(
+                1,
+            );
+        }
+        let f = 3;"
+        .unindent();
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    cx.executor().run_until_parked();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let indent = |spaces| LineIndent {
+        tabs: 0,
+        spaces,
+        line_blank: false,
+    };
+
+    // The line that opens a block resolves to that block.
+    assert_eq!(snapshot.syntax_enclosing_indent(1), Some((1..2, indent(4))));
+    assert_eq!(snapshot.syntax_enclosing_indent(2), Some((1..2, indent(4))));
+    // So does the line that closes it.
+    assert_eq!(snapshot.syntax_enclosing_indent(3), Some((1..2, indent(4))));
+    assert_eq!(snapshot.syntax_enclosing_indent(5), Some((4..5, indent(4))));
+    assert_eq!(snapshot.syntax_enclosing_indent(0), Some((0..6, indent(0))));
+    assert_eq!(snapshot.syntax_enclosing_indent(8), None);
+}
+
+#[gpui::test]
+async fn test_parameter_hints(cx: &mut TestAppContext) {
+    let language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    )
+    .with_parameters_query(
+        r#"
+        (function_item
+            name: (identifier) @definition.name
+            parameters: (parameters (parameter pattern: (identifier) @parameter)))
+        (call_expression
+            function: (identifier) @call.name
+            arguments: (arguments (_) @argument))
+        "#,
+    )
+    .unwrap();
+
+    let text = "
+        fn add(a: i32, b: i32) -> i32 { a + b }
+        fn main() {
+            add(1, b);
+            add(add(2, 3), /* four */ 4);
+            sub(5, 6);
+        }"
+    .unindent();
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text.clone(), cx).with_language(Arc::new(language), cx));
+    cx.executor().run_until_parked();
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+
+    let text_with_hints = |range: Range<usize>| {
+        let mut text_with_hints = text.clone();
+        for hint in snapshot.parameter_hints(range).iter().rev() {
+            text_with_hints.insert_str(hint.position, &format!("{}: ", hint.name));
+        }
+        text_with_hints
+    };
+    // Arguments named like their parameter and calls of unknown functions are not hinted.
+    assert_eq!(
+        text_with_hints(0..text.len()),
+        "
+        fn add(a: i32, b: i32) -> i32 { a + b }
+        fn main() {
+            add(a: 1, b);
+            add(a: add(a: 2, b: 3), /* four */ b: 4);
+            sub(5, 6);
+        }"
+        .unindent()
+    );
+    let second_call_start = text.find("add(add").unwrap();
+    assert_eq!(
+        text_with_hints(second_call_start..second_call_start + 10),
+        "
+        fn add(a: i32, b: i32) -> i32 { a + b }
+        fn main() {
+            add(1, b);
+            add(a: add(a: 2, 3), /* four */ 4);
+            sub(5, 6);
+        }"
+        .unindent()
+    );
+}
+
 #[gpui::test(iterations = 100)]
 fn test_random_collaboration(cx: &mut AppContext, mut rng: StdRng) {
     let min_peers = env::var("MIN_PEERS")
@@ -13,6 +13,7 @@ mod language_registry;
 pub mod language_settings;
 mod outline;
 pub mod proto;
+mod structural_query;
 mod syntax_map;
 mod task_context;
 
@@ -72,6 +73,7 @@ pub use language_registry::{
 };
 pub use lsp::LanguageServerId;
 pub use outline::{render_item, Outline, OutlineItem};
+pub use structural_query::StructuralQuery;
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
 pub use text::{AnchorRangeExt, LineEnding};
 pub use tree_sitter::{Node, Parser, Tree, TreeCursor};
@@ -586,6 +588,9 @@ pub struct LanguageConfig {
     /// Starting and closing characters of a block comment.
     #[serde(default)]
     pub block_comment: Option<(Arc<str>, Arc<str>)>,
+    /// How to continue block comments, like doc comments, on the next line.
+    #[serde(default)]
+    pub block_comment_continuation: Option<BlockCommentContinuation>,
     /// A list of language servers that are allowed to run on subranges of a given language.
     #[serde(default)]
     pub scope_opt_in_language_servers: Vec<String>,
@@ -688,6 +693,7 @@ impl Default for LanguageConfig {
             autoclose_before: Default::default(),
             line_comments: Default::default(),
             block_comment: Default::default(),
+            block_comment_continuation: None,
             scope_opt_in_language_servers: Default::default(),
             overrides: Default::default(),
             word_characters: Default::default(),
@@ -789,6 +795,21 @@ impl<'de> Deserialize<'de> for BracketPairConfig {
     }
 }
 
+/// Describes the block comments whose lines are continued when inserting a newline in them,
+/// e.g. inserting ` * ` after a line starting with `/**`.
+#[derive(Clone, Debug, Deserialize, PartialEq, JsonSchema)]
+pub struct BlockCommentContinuation {
+    /// The text starting the comment.
+    pub start: Arc<str>,
+    /// The text ending the comment.
+    pub end: Arc<str>,
+    /// The text inserted at the start of each line continuing the comment.
+    pub prefix: Arc<str>,
+    /// How many spaces to indent the continued lines by, relative to the comment's start.
+    #[serde(default)]
+    pub indent: u32,
+}
+
 /// Describes a single bracket pair and how an editor should react to e.g. inserting
 /// an opening bracket or to a newline character insertion in between `start` and `end` characters.
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, JsonSchema)]
@@ -840,6 +861,8 @@ pub struct Grammar {
     pub(crate) brackets_config: Option<BracketConfig>,
     pub(crate) redactions_config: Option<RedactionConfig>,
     pub(crate) runnable_config: Option<RunnableConfig>,
+    pub(crate) folds_config: Option<FoldConfig>,
+    pub(crate) parameters_config: Option<ParametersConfig>,
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
@@ -889,6 +912,20 @@ struct RedactionConfig {
     pub redaction_capture_ix: u32,
 }
 
+struct FoldConfig {
+    pub query: Query,
+    pub fold_capture_ix: u32,
+    pub summary_capture_ix: Option<u32>,
+}
+
+struct ParametersConfig {
+    pub query: Query,
+    pub definition_name_capture_ix: u32,
+    pub parameter_capture_ix: u32,
+    pub call_name_capture_ix: u32,
+    pub argument_capture_ix: u32,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 enum RunnableCapture {
     Named(SharedString),
@@ -943,6 +980,8 @@ impl Language {
                     override_config: None,
                     redactions_config: None,
                     runnable_config: None,
+                    folds_config: None,
+                    parameters_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -1003,6 +1042,16 @@ impl Language {
                 .with_runnable_query(query.as_ref())
                 .context("Error loading tests query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
+        if let Some(query) = queries.parameters {
+            self = self
+                .with_parameters_query(query.as_ref())
+                .context("Error loading parameters query")?;
+        }
         Ok(self)
     }
 
@@ -1308,6 +1357,72 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_folds_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        let mut summary_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("fold", &mut fold_capture_ix),
+                ("summary", &mut summary_capture_ix),
+            ],
+        );
+
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.folds_config = Some(FoldConfig {
+                query,
+                fold_capture_ix,
+                summary_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn with_parameters_query(mut self, source: &str) -> anyhow::Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut definition_name_capture_ix = None;
+        let mut parameter_capture_ix = None;
+        let mut call_name_capture_ix = None;
+        let mut argument_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("definition.name", &mut definition_name_capture_ix),
+                ("parameter", &mut parameter_capture_ix),
+                ("call.name", &mut call_name_capture_ix),
+                ("argument", &mut argument_capture_ix),
+            ],
+        );
+
+        if let Some((
+            (definition_name_capture_ix, parameter_capture_ix),
+            (call_name_capture_ix, argument_capture_ix),
+        )) = definition_name_capture_ix
+            .zip(parameter_capture_ix)
+            .zip(call_name_capture_ix.zip(argument_capture_ix))
+        {
+            grammar.parameters_config = Some(ParametersConfig {
+                query,
+                definition_name_capture_ix,
+                parameter_capture_ix,
+                call_name_capture_ix,
+                argument_capture_ix,
+            });
+        }
+
+        Ok(self)
+    }
+
     fn grammar_mut(&mut self) -> Option<&mut Grammar> {
         Arc::get_mut(self.grammar.as_mut()?)
     }
@@ -1417,6 +1532,10 @@ impl LanguageScope {
         .map(|e| (&e.0, &e.1))
     }
 
+    pub fn block_comment_continuation(&self) -> Option<&BlockCommentContinuation> {
+        self.language.config.block_comment_continuation.as_ref()
+    }
+
     /// Returns a list of language-specific word characters.
     ///
     /// By default, Zed treats alphanumeric characters (and '_') as word characters for
@@ -126,6 +126,8 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("overrides", |q| &mut q.overrides),
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
+    ("folds", |q| &mut q.folds),
+    ("parameters", |q| &mut q.parameters),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -140,6 +142,8 @@ pub struct LanguageQueries {
     pub overrides: Option<Cow<'static, str>>,
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
+    pub folds: Option<Cow<'static, str>>,
+    pub parameters: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
@@ -488,6 +492,17 @@ impl LanguageRegistry {
         )
     }
 
+    /// Returns the language for a file at the given path that isn't open in a buffer, using the
+    /// file types assigned to languages in the settings.
+    pub fn language_for_unopened_file(
+        self: &Arc<Self>,
+        path: &Path,
+        cx: &AppContext,
+    ) -> impl Future<Output = Result<Arc<Language>>> {
+        let user_file_types = all_language_settings(None, cx);
+        self.language_for_file_internal(path, None, Some(&user_file_types.file_types))
+    }
+
     pub fn language_for_file_path<'a>(
         self: &Arc<Self>,
         path: &'a Path,
@@ -106,12 +106,17 @@ pub struct LanguageSettings {
     /// - `"!<language_server_id>"` - A language server ID prefixed with a `!` will be disabled.
     /// - `"..."` - A placeholder to refer to the **rest** of the registered language servers for this language.
     pub language_servers: Vec<Arc<str>>,
+    /// The language servers to use for particular features, by feature. Features that are not
+    /// listed use all language servers that support them, or the first one for features that
+    /// only a single server can provide.
+    pub language_servers_by_feature: HashMap<LanguageServerFeature, Vec<Arc<str>>>,
     /// Controls whether inline completions are shown immediately (true)
     /// or manually by triggering `editor::ShowInlineCompletion` (false).
     pub show_inline_completions: bool,
     /// Whether to show tabs and spaces in the editor.
     pub show_whitespaces: ShowWhitespaceSetting,
-    /// Whether to start a new line with a comment when a previous line is a comment as well.
+    /// Whether to start a new line with a comment when a previous line is a comment as well,
+    /// including lines inside block comments like `/** ... */`.
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
@@ -123,6 +128,8 @@ pub struct LanguageSettings {
     pub always_treat_brackets_as_autoclosed: bool,
     /// Which code actions to run on save
     pub code_actions_on_format: HashMap<String, bool>,
+    /// Which code actions to run on save, in order, before the formatter
+    pub code_actions_on_save: Vec<String>,
     /// Whether to perform linked edits
     pub linked_edits: bool,
     /// Task configuration for this language.
@@ -176,6 +183,23 @@ impl LanguageSettings {
     }
 }
 
+/// A feature provided by language servers, to which a subset of a language's servers can be
+/// restricted.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LanguageServerFeature {
+    Completions,
+    CodeActions,
+    Hover,
+    SignatureHelp,
+    Definition,
+    TypeDefinition,
+    Implementation,
+    References,
+    DocumentHighlights,
+    Rename,
+}
+
 /// The provider that supplies inline completions.
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -302,6 +326,13 @@ pub struct LanguageSettingsContent {
     /// Default: ["..."]
     #[serde(default)]
     pub language_servers: Option<Vec<Arc<str>>>,
+    /// The language servers to use for particular features, by feature, in the order they should
+    /// be preferred in. Features that are not listed use all language servers that support them,
+    /// or the first one for features that only a single server can provide.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub language_servers_by_feature: Option<HashMap<LanguageServerFeature, Vec<Arc<str>>>>,
     /// Controls whether inline completions are shown immediately (true)
     /// or manually by triggering `editor::ShowInlineCompletion` (false).
     ///
@@ -311,7 +342,8 @@ pub struct LanguageSettingsContent {
     /// Whether to show tabs and spaces in the editor.
     #[serde(default)]
     pub show_whitespaces: Option<ShowWhitespaceSetting>,
-    /// Whether to start a new line with a comment when a previous line is a comment as well.
+    /// Whether to start a new line with a comment when a previous line is a comment as well,
+    /// including lines inside block comments like `/** ... */`.
     ///
     /// Default: true
     #[serde(default)]
@@ -342,7 +374,14 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: {} (or {"source.organizeImports": true} for Go).
     pub code_actions_on_format: Option<HashMap<String, bool>>,
-    /// Whether to perform linked edits of associated ranges, if the language server supports it.
+    /// Which code actions to run on save, one after another in the given order, before the
+    /// formatter. These are run even if formatting is off, but not when saving without
+    /// formatting.
+    ///
+    /// Default: []
+    pub code_actions_on_save: Option<Vec<String>>,
+    /// Whether to perform linked edits of associated ranges, as reported by the language server
+    /// or, for HTML and JSX tags, found in the syntax tree.
     /// For example, when editing opening <html> tag, the contents of the closing </html> tag will be edited as well.
     ///
     /// Default: true
@@ -754,6 +793,12 @@ pub struct InlayHintSettings {
     /// Default: 50
     #[serde(default = "scroll_debounce_ms")]
     pub scroll_debounce_ms: u64,
+    /// Whether to compute parameter hints from the syntax tree, for buffers whose
+    /// language servers don't provide inlay hints.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub syntax_parameter_hints: bool,
 }
 
 fn edit_debounce_ms() -> u64 {
@@ -1041,6 +1086,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         &mut settings.code_actions_on_format,
         src.code_actions_on_format.clone(),
     );
+    merge(
+        &mut settings.code_actions_on_save,
+        src.code_actions_on_save.clone(),
+    );
     merge(&mut settings.linked_edits, src.linked_edits);
     merge(&mut settings.tasks, src.tasks.clone());
 
@@ -1064,6 +1113,10 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.enable_language_server,
     );
     merge(&mut settings.language_servers, src.language_servers.clone());
+    merge(
+        &mut settings.language_servers_by_feature,
+        src.language_servers_by_feature.clone(),
+    );
     merge(
         &mut settings.show_inline_completions,
         src.show_inline_completions,
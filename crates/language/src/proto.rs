@@ -76,6 +76,15 @@ pub fn serialize_operation(operation: &crate::Operation) -> proto::Operation {
                 diagnostics: serialize_diagnostics(diagnostics.iter()),
             }),
 
+            crate::Operation::UpdateViewport {
+                viewport,
+                lamport_timestamp,
+            } => proto::operation::Variant::UpdateViewport(proto::operation::UpdateViewport {
+                replica_id: lamport_timestamp.replica_id as u32,
+                lamport_timestamp: lamport_timestamp.value,
+                viewport: viewport.clone().map(serialize_anchor_range),
+            }),
+
             crate::Operation::UpdateCompletionTriggers {
                 triggers,
                 lamport_timestamp,
@@ -319,6 +328,15 @@ pub fn deserialize_operation(message: proto::Operation) -> Result<crate::Operati
                     diagnostics: deserialize_diagnostics(message.diagnostics),
                 }
             }
+            proto::operation::Variant::UpdateViewport(message) => {
+                crate::Operation::UpdateViewport {
+                    viewport: message.viewport.map(deserialize_anchor_range).transpose()?,
+                    lamport_timestamp: clock::Lamport {
+                        replica_id: message.replica_id as ReplicaId,
+                        value: message.lamport_timestamp,
+                    },
+                }
+            }
             proto::operation::Variant::UpdateCompletionTriggers(message) => {
                 crate::Operation::UpdateCompletionTriggers {
                     triggers: message.triggers,
@@ -469,6 +487,10 @@ pub fn lamport_timestamp_for_operation(operation: &proto::Operation) -> Option<c
             replica_id = op.replica_id;
             value = op.lamport_timestamp;
         }
+        proto::operation::Variant::UpdateViewport(op) => {
+            replica_id = op.replica_id;
+            value = op.lamport_timestamp;
+        }
         proto::operation::Variant::UpdateCompletionTriggers(op) => {
             replica_id = op.replica_id;
             value = op.lamport_timestamp;
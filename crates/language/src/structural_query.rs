@@ -0,0 +1,298 @@
+use crate::{
+    syntax_map::{QueryCursorHandle, TextProvider},
+    with_parser, BufferSnapshot, Grammar, GrammarId,
+};
+use collections::HashMap;
+use parking_lot::Mutex;
+use std::{fmt, ops::Range, sync::Arc};
+use tree_sitter::{Node, Query};
+
+/// The capture spanning the text matched by a pattern, when it has several captures.
+const MATCH_CAPTURE: &str = "match";
+/// The identifier metavariables are parsed as in templates, followed by their name.
+const METAVARIABLE_PREFIX: &str = "__metavariable_";
+
+/// A query for syntax nodes, matching them regardless of how their code is formatted.
+///
+/// The query is either a tree-sitter pattern, or a template of the code to find, such as
+/// `foo($A, $B)`, where each `$NAME` metavariable matches any single node, and the same text
+/// wherever the same name is used. `$_` matches any node without constraining other ones.
+/// A query is treated as a template for the languages it is not a valid pattern in.
+pub struct StructuralQuery {
+    source: String,
+    queries: Mutex<HashMap<GrammarId, Option<Arc<Query>>>>,
+}
+
+impl fmt::Debug for StructuralQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StructuralQuery")
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl StructuralQuery {
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            queries: Mutex::default(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the ranges of the nodes matching the query within the given range of the
+    /// buffer, in every syntax layer whose language the query is valid in.
+    ///
+    /// A pattern matches the text of its `@match` capture, which top-level patterns get when
+    /// they capture nothing else, or else the text spanned by all of its captures.
+    pub fn matches(&self, buffer: &BufferSnapshot, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        for layer in buffer.syntax.layers_for_range(range.clone(), buffer) {
+            let Some(grammar) = layer.language.grammar() else {
+                continue;
+            };
+            let Some(query) = self.query_for_grammar(grammar) else {
+                continue;
+            };
+            let match_capture_ix = query.capture_index_for_name(MATCH_CAPTURE);
+
+            let mut cursor = QueryCursorHandle::new();
+            cursor.set_byte_range(range.clone());
+            for mat in cursor.matches(&query, layer.node(), TextProvider(buffer.as_rope())) {
+                let is_match_capture = |index| Some(index) == match_capture_ix;
+                let has_match_capture = mat
+                    .captures
+                    .iter()
+                    .any(|capture| is_match_capture(capture.index));
+                let captured_nodes = mat
+                    .captures
+                    .iter()
+                    .filter(|capture| !has_match_capture || is_match_capture(capture.index))
+                    .map(|capture| capture.node);
+                let start = captured_nodes.clone().map(|node| node.start_byte()).min();
+                let end = captured_nodes.map(|node| node.end_byte()).max();
+                if let Some((start, end)) = start.zip(end) {
+                    if range.start <= start && end <= range.end {
+                        matches.push(start..end);
+                    }
+                }
+            }
+        }
+
+        matches.sort_unstable_by_key(|range| (range.start, range.end));
+        matches.dedup();
+        matches
+    }
+
+    /// Returns whether the query is a valid pattern or template in any of the given grammars,
+    /// as a query that none of them can compile matches nothing.
+    pub fn is_valid_in_any<'a>(&self, grammars: impl IntoIterator<Item = &'a Grammar>) -> bool {
+        grammars
+            .into_iter()
+            .any(|grammar| self.query_for_grammar(grammar).is_some())
+    }
+
+    fn query_for_grammar(&self, grammar: &Grammar) -> Option<Arc<Query>> {
+        self.queries
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| {
+                Query::new(&grammar.ts_language, &capture_patterns(&self.source))
+                    .ok()
+                    .or_else(|| {
+                        let pattern = template_pattern(&self.source, grammar)?;
+                        Query::new(&grammar.ts_language, &pattern).ok()
+                    })
+                    .map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+/// Adds a `@match` capture to the top-level patterns of a tree-sitter query that are not
+/// captured already, unless the query uses that capture itself.
+fn capture_patterns(source: &str) -> String {
+    if source.contains(&format!("@{MATCH_CAPTURE}")) {
+        return source.to_string();
+    }
+
+    let mut captured = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut depth = 0_usize;
+    while let Some(c) = chars.next() {
+        captured.push(c);
+        match c {
+            '"' => {
+                while let Some(c) = chars.next() {
+                    captured.push(c);
+                    if c == '\\' {
+                        captured.extend(chars.next());
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+            }
+            ';' => {
+                while let Some(c) = chars.next_if(|c| *c != '\n') {
+                    captured.push(c);
+                }
+            }
+            '(' | '[' => depth += 1,
+            ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    let next = chars.clone().find(|c| !c.is_whitespace());
+                    if !next.is_some_and(|c| matches!(c, '@' | '*' | '+' | '?')) {
+                        captured.push_str(" @");
+                        captured.push_str(MATCH_CAPTURE);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    captured
+}
+
+/// Turns a code template into a tree-sitter pattern matching the syntax tree the grammar parses
+/// it into, node by node. Named children are anchored in place, so that nodes with more or fewer
+/// children don't match, while other tokens, such as punctuation, are only compared when they
+/// are a field of their parent, like the operator of a binary expression.
+fn template_pattern(template: &str, grammar: &Grammar) -> Option<String> {
+    let text = replace_metavariables(template.trim());
+    if text.is_empty() {
+        return None;
+    }
+    let tree = with_parser(|parser| {
+        parser.set_language(&grammar.ts_language).ok()?;
+        parser.parse(&text, None)
+    })?;
+    let node = tree
+        .root_node()
+        .named_descendant_for_byte_range(0, text.len())?;
+    if node.has_error() {
+        return None;
+    }
+
+    let mut builder = TemplatePatternBuilder {
+        text: &text,
+        pattern: String::new(),
+        predicates: Vec::new(),
+        metavariable_captures: HashMap::default(),
+        capture_count: 0,
+    };
+    builder.push_node(node, MATCH_CAPTURE.to_string());
+    let TemplatePatternBuilder {
+        pattern,
+        predicates,
+        ..
+    } = builder;
+    if predicates.is_empty() {
+        Some(pattern)
+    } else {
+        Some(format!("({pattern} {})", predicates.join(" ")))
+    }
+}
+
+/// Replaces each `$NAME` metavariable with an identifier the grammars can parse.
+fn replace_metavariables(template: &str) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$'
+            && chars
+                .peek()
+                .is_some_and(|c| c.is_ascii_alphabetic() || *c == '_')
+        {
+            text.push_str(METAVARIABLE_PREFIX);
+        } else {
+            text.push(c);
+        }
+    }
+    text
+}
+
+struct TemplatePatternBuilder<'a> {
+    text: &'a str,
+    pattern: String,
+    predicates: Vec<String>,
+    metavariable_captures: HashMap<&'a str, String>,
+    capture_count: usize,
+}
+
+impl<'a> TemplatePatternBuilder<'a> {
+    fn push_node(&mut self, node: Node, capture: String) {
+        let text = self.text;
+        let text = &text[node.byte_range()];
+        if let Some(name) = text.strip_prefix(METAVARIABLE_PREFIX) {
+            self.pattern.push_str("(_)");
+            if name != "_" {
+                if let Some(first_capture) = self.metavariable_captures.get(name) {
+                    self.predicates
+                        .push(format!("(#eq? @{first_capture} @{capture})"));
+                } else {
+                    self.metavariable_captures.insert(name, capture.clone());
+                }
+            }
+        } else {
+            self.pattern.push('(');
+            self.pattern.push_str(node.kind());
+            let mut children = Vec::new();
+            let mut cursor = node.walk();
+            if cursor.goto_first_child() {
+                loop {
+                    let child = cursor.node();
+                    if !child.is_extra() {
+                        children.push((cursor.field_name(), child));
+                    }
+                    if !cursor.goto_next_sibling() {
+                        break;
+                    }
+                }
+            }
+
+            if children.iter().all(|(_, child)| !child.is_named()) {
+                self.predicates
+                    .push(format!("(#eq? @{capture} \"{}\")", escape_string(text)));
+            } else {
+                for (field_name, child) in children {
+                    if child.is_named() {
+                        self.pattern.push_str(" . ");
+                        let child_capture = self.next_capture();
+                        self.push_node(child, child_capture);
+                    } else if let Some(field_name) = field_name {
+                        self.pattern.push_str(&format!(
+                            " {field_name}: \"{}\"",
+                            escape_string(child.kind())
+                        ));
+                    }
+                }
+                self.pattern.push_str(" .");
+            }
+            self.pattern.push(')');
+        }
+        self.pattern.push_str(" @");
+        self.pattern.push_str(&capture);
+    }
+
+    fn next_capture(&mut self) -> String {
+        self.capture_count += 1;
+        format!("_{}", self.capture_count)
+    }
+}
+
+fn escape_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -891,11 +891,19 @@ impl Render for LspLogToolbarItemView {
         let Some(log_view) = self.log_view.clone() else {
             return div();
         };
-        let (menu_rows, current_server_id) = log_view.update(cx, |log_view, cx| {
-            let menu_rows = log_view.menu_items(cx).unwrap_or_default();
-            let current_server_id = log_view.current_server_id;
-            (menu_rows, current_server_id)
-        });
+        let (menu_rows, current_server_id, can_stop_server) =
+            log_view.update(cx, |log_view, cx| {
+                let menu_rows = log_view.menu_items(cx).unwrap_or_default();
+                let current_server_id = log_view.current_server_id;
+                let project = log_view.project.read(cx);
+                let can_stop_server = project.is_local()
+                    && current_server_id.map_or(false, |current_server_id| {
+                        project
+                            .language_servers()
+                            .any(|(server_id, _, _)| server_id == current_server_id)
+                    });
+                (menu_rows, current_server_id, can_stop_server)
+            });
 
         let current_server = current_server_id.and_then(|current_server_id| {
             if let Ok(ix) = menu_rows.binary_search_by_key(&current_server_id, |e| e.server_id) {
@@ -1044,6 +1052,22 @@ impl Render for LspLogToolbarItemView {
                     )
                     .ml_2(),
             )
+            .when(can_stop_server, |this| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .ml_2()
+                        .child(
+                            Button::new("restart_server_button", "Restart Server").on_click(
+                                cx.listener(|this, _, cx| this.restart_current_server(cx)),
+                            ),
+                        )
+                        .child(
+                            Button::new("stop_server_button", "Stop Server")
+                                .on_click(cx.listener(|this, _, cx| this.stop_current_server(cx))),
+                        ),
+                )
+            })
             .child(log_view.update(cx, |this, _| {
                 if this.active_entry_kind == LogKind::Trace {
                     let log_view = log_view.clone();
@@ -1114,6 +1138,30 @@ impl LspLogToolbarItemView {
         }
     }
 
+    fn restart_current_server(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(log_view) = &self.log_view {
+            log_view.update(cx, |log_view, cx| {
+                if let Some(server_id) = log_view.current_server_id {
+                    log_view.project.update(cx, |project, cx| {
+                        project.restart_language_server(server_id, cx)
+                    });
+                }
+            });
+        }
+    }
+
+    fn stop_current_server(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(log_view) = &self.log_view {
+            log_view.update(cx, |log_view, cx| {
+                if let Some(server_id) = log_view.current_server_id {
+                    log_view.project.update(cx, |project, cx| {
+                        project.stop_language_server_by_id(server_id, cx)
+                    });
+                }
+            });
+        }
+    }
+
     fn toggle_rpc_logging_for_server(
         &mut self,
         id: LanguageServerId,
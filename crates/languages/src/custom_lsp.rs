@@ -0,0 +1,215 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use gpui::{AppContext, AsyncAppContext};
+use language::{LanguageRegistry, LanguageServerName, LspAdapter, LspAdapterDelegate};
+use lsp::LanguageServerBinary;
+use project::project_settings::{BinarySettings, LspSettings, ProjectSettings};
+use serde_json::{json, Value};
+use settings::{Settings, SettingsStore};
+use std::{any::Any, path::PathBuf, sync::Arc};
+
+/// A language server declared in the `lsp` settings, for languages Zed has no language server
+/// for, that is started with the configured binary.
+pub struct CustomLspAdapter {
+    name: LanguageServerName,
+}
+
+impl CustomLspAdapter {
+    fn settings<'a>(&self, cx: &'a AppContext) -> Option<&'a LspSettings> {
+        ProjectSettings::get_global(cx).lsp.get(&self.name.0)
+    }
+}
+
+#[async_trait(?Send)]
+impl LspAdapter for CustomLspAdapter {
+    fn name(&self) -> LanguageServerName {
+        self.name.clone()
+    }
+
+    async fn check_if_user_installed(
+        &self,
+        delegate: &dyn LspAdapterDelegate,
+        cx: &AsyncAppContext,
+    ) -> Option<LanguageServerBinary> {
+        let configured_binary = cx
+            .update(|cx| self.settings(cx).and_then(|s| s.binary.clone()))
+            .ok()?;
+        let env = delegate.shell_env().await;
+        match configured_binary {
+            Some(BinarySettings {
+                path: Some(path),
+                arguments,
+                ..
+            }) => Some(LanguageServerBinary {
+                path: path.into(),
+                arguments: arguments
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|arg| arg.into())
+                    .collect(),
+                env: Some(env),
+            }),
+            Some(BinarySettings {
+                path_lookup: Some(false),
+                ..
+            }) => None,
+            configured_binary => {
+                let name: &str = &self.name.0;
+                let path = delegate.which(name.as_ref()).await?;
+                Some(LanguageServerBinary {
+                    path,
+                    arguments: configured_binary
+                        .and_then(|binary| binary.arguments)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|arg| arg.into())
+                        .collect(),
+                    env: Some(env),
+                })
+            }
+        }
+    }
+
+    async fn fetch_latest_server_version(
+        &self,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<Box<dyn 'static + Send + Any>> {
+        Err(anyhow!(
+            "{} was not found. Set its binary path in the `lsp` settings.",
+            self.name.0
+        ))
+    }
+
+    async fn fetch_server_binary(
+        &self,
+        _: Box<dyn 'static + Send + Any>,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Result<LanguageServerBinary> {
+        Err(anyhow!("{} cannot be installed by Zed", self.name.0))
+    }
+
+    async fn cached_server_binary(
+        &self,
+        _: PathBuf,
+        _: &dyn LspAdapterDelegate,
+    ) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    fn can_be_reinstalled(&self) -> bool {
+        false
+    }
+
+    async fn installation_test_binary(&self, _: PathBuf) -> Option<LanguageServerBinary> {
+        None
+    }
+
+    async fn workspace_configuration(
+        self: Arc<Self>,
+        _: &Arc<dyn LspAdapterDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<Value> {
+        cx.update(|cx| {
+            self.settings(cx)
+                .and_then(|s| s.settings.clone())
+                .unwrap_or_else(|| json!({}))
+        })
+    }
+}
+
+/// Registers the language servers declared in the `lsp` settings for their languages, and keeps
+/// them up to date as the settings change.
+pub(super) fn init(languages: Arc<LanguageRegistry>, cx: &mut AppContext) {
+    let mut registered = Vec::new();
+    update_custom_language_servers(&languages, &mut registered, cx);
+    cx.observe_global::<SettingsStore>(move |cx| {
+        update_custom_language_servers(&languages, &mut registered, cx);
+    })
+    .detach();
+}
+
+fn update_custom_language_servers(
+    languages: &LanguageRegistry,
+    registered: &mut Vec<(Arc<str>, LanguageServerName)>,
+    cx: &AppContext,
+) {
+    let mut servers = ProjectSettings::get_global(cx)
+        .lsp
+        .iter()
+        .flat_map(|(name, settings)| {
+            settings
+                .languages
+                .iter()
+                .flatten()
+                .map(|language| (language.clone(), LanguageServerName(name.clone())))
+        })
+        .collect::<Vec<_>>();
+    servers.sort();
+    if servers == *registered {
+        return;
+    }
+
+    for (language, name) in registered.drain(..) {
+        languages.remove_lsp_adapter(&language, &name);
+    }
+    for (language, name) in &servers {
+        languages.register_lsp_adapter(
+            language.clone(),
+            Arc::new(CustomLspAdapter { name: name.clone() }),
+        );
+    }
+    // Reload the languages so that open buffers start the newly registered language servers.
+    languages.reload();
+    *registered = servers;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{BorrowAppContext, TestAppContext};
+
+    #[gpui::test]
+    async fn test_custom_language_servers(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let test_settings = SettingsStore::test(cx);
+            cx.set_global(test_settings);
+            language::init(cx);
+            ProjectSettings::register(cx);
+        });
+        let languages = Arc::new(LanguageRegistry::test(cx.executor()));
+        cx.update(|cx| init(languages.clone(), cx));
+        let language = crate::language("rust", tree_sitter_rust::language());
+        let adapter_names = || {
+            languages
+                .lsp_adapters(&language)
+                .iter()
+                .map(|adapter| adapter.name.0.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert!(adapter_names().is_empty());
+
+        let update_lsp_settings =
+            |cx: &mut TestAppContext, server_languages: Option<Vec<Arc<str>>>| {
+                cx.update(|cx| {
+                    cx.update_global::<SettingsStore, _>(|store, cx| {
+                        store.update_user_settings::<ProjectSettings>(cx, |settings| {
+                            settings.lsp.insert(
+                                "my-rust-server".into(),
+                                LspSettings {
+                                    languages: server_languages,
+                                    ..Default::default()
+                                },
+                            );
+                        });
+                    });
+                });
+            };
+
+        update_lsp_settings(cx, Some(vec!["Rust".into()]));
+        assert_eq!(adapter_names(), ["my-rust-server"]);
+
+        update_lsp_settings(cx, None);
+        assert!(adapter_names().is_empty());
+    }
+}
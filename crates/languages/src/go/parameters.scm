@@ -0,0 +1,23 @@
+; Functions and methods, and the names of their parameters.
+(function_declaration
+    name: (identifier) @definition.name
+    parameters: (parameter_list
+        (parameter_declaration name: (identifier) @parameter)
+    )
+)
+
+(method_declaration
+    name: (field_identifier) @definition.name
+    parameters: (parameter_list
+        (parameter_declaration name: (identifier) @parameter)
+    )
+)
+
+; Calls of functions and methods, and their arguments.
+(call_expression
+    function: [
+        (identifier) @call.name
+        (selector_expression field: (field_identifier) @call.name)
+    ]
+    arguments: (argument_list (_) @argument)
+)
@@ -16,6 +16,7 @@ use crate::{bash::bash_task_context, go::GoContextProvider, rust::RustContextPro
 mod bash;
 mod c;
 mod css;
+mod custom_lsp;
 mod go;
 mod json;
 mod python;
@@ -246,6 +247,13 @@ pub fn init(
         );
     }
 
+    // Language servers can be declared in the project settings, which are registered after the
+    // languages.
+    cx.defer({
+        let languages = languages.clone();
+        move |cx| custom_lsp::init(languages, cx)
+    });
+
     let mut subscription = languages.subscribe();
     let mut prev_language_settings = languages.language_settings();
 
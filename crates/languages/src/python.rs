@@ -261,6 +261,7 @@ impl ContextProvider for PythonContextProvider {
                 label: format!("run '{}'", VariableName::File.template_value()),
                 command: "python3".to_owned(),
                 args: vec![VariableName::File.template_value()],
+                tags: vec!["python-main".to_owned()],
                 ..TaskTemplate::default()
             },
             TaskTemplate {
@@ -287,6 +288,25 @@ impl ContextProvider for PythonContextProvider {
                 ],
                 ..TaskTemplate::default()
             },
+            TaskTemplate {
+                label: format!(
+                    "pytest '{}::{}'",
+                    VariableName::RelativeFile.template_value(),
+                    VariableName::Symbol.template_value()
+                ),
+                command: "python3".to_owned(),
+                args: vec![
+                    "-m".to_owned(),
+                    "pytest".to_owned(),
+                    format!(
+                        "{}::{}",
+                        VariableName::RelativeFile.template_value(),
+                        VariableName::Symbol.template_value()
+                    ),
+                ],
+                tags: vec!["python-pytest-function".to_owned()],
+                ..TaskTemplate::default()
+            },
         ]))
     }
 }
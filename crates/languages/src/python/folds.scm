@@ -0,0 +1,23 @@
+[
+    (function_definition)
+    (class_definition)
+    (dictionary)
+    (list)
+    (set)
+    (tuple)
+    (argument_list)
+    (parameters)
+] @fold
+
+; Summarize folded functions and classes with the first sentence of their docstring.
+(function_definition
+    body: (block
+        .
+        (expression_statement
+            (string) @summary))) @fold
+
+(class_definition
+    body: (block
+        .
+        (expression_statement
+            (string) @summary))) @fold
@@ -0,0 +1,23 @@
+; Functions defined at the top level and the names of their positional parameters.
+(module
+    (function_definition
+        name: (identifier) @definition.name
+        parameters: (parameters
+            [
+                (identifier) @parameter
+                (typed_parameter . (identifier) @parameter)
+                (default_parameter name: (identifier) @parameter)
+                (typed_default_parameter name: (identifier) @parameter)
+            ]
+        )
+    )
+)
+
+; Calls of functions by name and their positional arguments.
+(call
+    function: (identifier) @call.name
+    arguments: (argument_list
+        (_) @argument
+        (#not-match? @argument "^(\\*|[A-Za-z_][A-Za-z0-9_]*\\s*=[^=])")
+    )
+)
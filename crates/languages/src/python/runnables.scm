@@ -29,3 +29,29 @@
             )
         )
 )
+
+; pytest functions whose names start with `test`, outside of classes
+(
+    (module
+        (function_definition
+            name: (identifier) @run @_pytest_function_name
+            (#match? @_pytest_function_name "^test.*")
+        ) @python-pytest-function
+    )
+    (#set! tag python-pytest-function)
+)
+
+; the `if __name__ == "__main__":` block of scripts
+(
+    (module
+        (if_statement
+            condition: (comparison_operator
+                (identifier) @run @_dunder_name
+                (string) @_main_string
+                (#eq? @_dunder_name "__name__")
+                (#match? @_main_string "^[\"']__main__[\"']$")
+            )
+        ) @python-main
+    )
+    (#set! tag python-main)
+)
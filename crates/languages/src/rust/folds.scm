@@ -0,0 +1,15 @@
+[
+    (block)
+    (declaration_list)
+    (field_declaration_list)
+    (ordered_field_declaration_list)
+    (enum_variant_list)
+    (field_initializer_list)
+    (match_block)
+    (use_list)
+    (parameters)
+    (arguments)
+    (array_expression)
+    (token_tree)
+    (block_comment)
+] @fold
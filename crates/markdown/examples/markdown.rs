@@ -46,6 +46,14 @@ Ordered lists use numbers followed by a period.
    1. Item 3a
    2. Item 3b
 
+## Tables
+Tables use pipes `|` to separate columns, and colons `:` in the delimiter row to align them.
+
+| Left | Center | Right |
+|:-----|:------:|------:|
+| a    | b      | c     |
+| `d`  | **e**  | _f_   |
+
 ## Links
 Links are created using the format [http://zed.dev](https://zed.dev).
 
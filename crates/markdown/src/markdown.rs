@@ -11,6 +11,7 @@ use gpui::{
 };
 use language::{Language, LanguageRegistry, Rope};
 use parser::{parse_links_only, parse_markdown, MarkdownEvent, MarkdownTag, MarkdownTagEnd};
+use pulldown_cmark::Alignment;
 
 use std::{iter, mem, ops::Range, rc::Rc, sync::Arc};
 use theme::SyntaxTheme;
@@ -651,6 +652,48 @@ impl Element for MarkdownElement {
                                 builder.push_text_style(self.style.link.clone())
                             }
                         }
+                        MarkdownTag::Table(alignments) => {
+                            builder.table_alignments = alignments.clone();
+                            builder.push_div(
+                                div()
+                                    .v_flex()
+                                    .w_full()
+                                    .mb_2()
+                                    .border_1()
+                                    .border_color(self.style.rule_color)
+                                    .rounded_md(),
+                                range,
+                                markdown_end,
+                            );
+                        }
+                        MarkdownTag::TableHead => {
+                            builder.push_div(
+                                div()
+                                    .h_flex()
+                                    .py_0p5()
+                                    .border_b_1()
+                                    .border_color(self.style.rule_color),
+                                range,
+                                markdown_end,
+                            );
+                            builder.push_text_style(TextStyleRefinement {
+                                font_weight: Some(FontWeight::BOLD),
+                                ..Default::default()
+                            });
+                        }
+                        MarkdownTag::TableRow => {
+                            builder.push_div(div().h_flex().py_0p5(), range, markdown_end);
+                        }
+                        MarkdownTag::TableCell => {
+                            let (column_count, alignment) = builder.next_table_cell();
+                            let cell = div().flex().px_1().w(relative(1. / column_count as f32));
+                            let cell = match alignment {
+                                Alignment::Center => cell.justify_center(),
+                                Alignment::Right => cell.justify_end(),
+                                Alignment::Left | Alignment::None => cell,
+                            };
+                            builder.push_div(cell, range, markdown_end);
+                        }
                         MarkdownTag::MetadataBlock(_) => {}
                         _ => log::error!("unsupported markdown tag {:?}", tag),
                     }
@@ -692,6 +735,20 @@ impl Element for MarkdownElement {
                             builder.pop_text_style()
                         }
                     }
+                    MarkdownTagEnd::Table => {
+                        builder.table_alignments.clear();
+                        builder.pop_div();
+                    }
+                    MarkdownTagEnd::TableHead => {
+                        builder.pop_text_style();
+                        builder.pop_div();
+                        builder.table_cell_index = 0;
+                    }
+                    MarkdownTagEnd::TableRow => {
+                        builder.pop_div();
+                        builder.table_cell_index = 0;
+                    }
+                    MarkdownTagEnd::TableCell => builder.pop_div(),
                     _ => log::error!("unsupported markdown tag end: {:?}", tag),
                 },
                 MarkdownEvent::Text => {
@@ -797,6 +854,8 @@ struct MarkdownElementBuilder {
     text_style_stack: Vec<TextStyleRefinement>,
     code_block_stack: Vec<Option<Arc<Language>>>,
     list_stack: Vec<ListStackEntry>,
+    table_alignments: Vec<Alignment>,
+    table_cell_index: usize,
     syntax_theme: Arc<SyntaxTheme>,
 }
 
@@ -823,6 +882,8 @@ impl MarkdownElementBuilder {
             text_style_stack: Vec::new(),
             code_block_stack: Vec::new(),
             list_stack: Vec::new(),
+            table_alignments: Vec::new(),
+            table_cell_index: 0,
             syntax_theme,
         }
     }
@@ -894,6 +955,17 @@ impl MarkdownElementBuilder {
         self.list_stack.pop();
     }
 
+    /// Returns the column count of the current table and the alignment of its next cell.
+    fn next_table_cell(&mut self) -> (usize, Alignment) {
+        let alignment = self
+            .table_alignments
+            .get(self.table_cell_index)
+            .copied()
+            .unwrap_or(Alignment::None);
+        self.table_cell_index += 1;
+        (self.table_alignments.len().max(1), alignment)
+    }
+
     fn push_code_block(&mut self, language: Option<Arc<Language>>) {
         self.code_block_stack.push(language);
     }
@@ -1006,6 +1006,63 @@ impl MultiBuffer {
         }
     }
 
+    /// Broadcasts the visible range to the replicas of every buffer it spans, and clears
+    /// it for the other buffers.
+    pub fn set_active_viewport(
+        &mut self,
+        viewport: Option<Range<Anchor>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let mut viewports_by_buffer: HashMap<BufferId, Vec<Range<text::Anchor>>> =
+            Default::default();
+        if let Some(viewport) = viewport {
+            let snapshot = self.read(cx);
+            let start_locator = snapshot.excerpt_locator_for_id(viewport.start.excerpt_id);
+            let end_locator = snapshot.excerpt_locator_for_id(viewport.end.excerpt_id);
+            let mut cursor = snapshot.excerpts.cursor::<Option<&Locator>>();
+            cursor.seek(&Some(start_locator), Bias::Left, &());
+            while let Some(excerpt) = cursor.item() {
+                if excerpt.locator > *end_locator {
+                    break;
+                }
+
+                let mut start = excerpt.range.context.start;
+                let mut end = excerpt.range.context.end;
+                if excerpt.id == viewport.start.excerpt_id {
+                    start = viewport.start.text_anchor;
+                }
+                if excerpt.id == viewport.end.excerpt_id {
+                    end = viewport.end.text_anchor;
+                }
+                viewports_by_buffer
+                    .entry(excerpt.buffer_id)
+                    .or_default()
+                    .push(start..end);
+
+                cursor.next(&());
+            }
+        }
+
+        for (buffer_id, buffer_state) in self.buffers.borrow().iter() {
+            let ranges = viewports_by_buffer.remove(buffer_id);
+            buffer_state.buffer.update(cx, |buffer, cx| {
+                // A buffer shown in several excerpts is visible across all of them.
+                let viewport = ranges.and_then(|ranges| {
+                    ranges.into_iter().reduce(|mut viewport, range| {
+                        if range.start.cmp(&viewport.start, buffer).is_lt() {
+                            viewport.start = range.start;
+                        }
+                        if range.end.cmp(&viewport.end, buffer).is_gt() {
+                            viewport.end = range.end;
+                        }
+                        viewport
+                    })
+                });
+                buffer.set_active_viewport(viewport, cx);
+            });
+        }
+    }
+
     pub fn undo(&mut self, cx: &mut ModelContext<Self>) -> Option<TransactionId> {
         let mut transaction_id = None;
         if let Some(buffer) = self.as_singleton() {
@@ -3844,6 +3901,44 @@ impl MultiBufferSnapshot {
         })
     }
 
+    /// Returns the ranges that remote peers are looking at, clipped to each excerpt.
+    pub fn remote_viewports(&self) -> impl '_ + Iterator<Item = (ReplicaId, Range<Anchor>)> {
+        self.excerpts.iter().flat_map(move |excerpt| {
+            let context = &excerpt.range.context;
+            excerpt
+                .buffer
+                .remote_viewports()
+                .filter_map(move |(replica_id, viewport)| {
+                    let start = if viewport.start.cmp(&context.start, &excerpt.buffer).is_lt() {
+                        context.start
+                    } else {
+                        viewport.start
+                    };
+                    let end = if viewport.end.cmp(&context.end, &excerpt.buffer).is_gt() {
+                        context.end
+                    } else {
+                        viewport.end
+                    };
+                    if start.cmp(&end, &excerpt.buffer).is_gt() {
+                        return None;
+                    }
+
+                    Some((
+                        replica_id,
+                        Anchor {
+                            buffer_id: Some(excerpt.buffer_id),
+                            excerpt_id: excerpt.id,
+                            text_anchor: start,
+                        }..Anchor {
+                            buffer_id: Some(excerpt.buffer_id),
+                            excerpt_id: excerpt.id,
+                            text_anchor: end,
+                        },
+                    ))
+                })
+        })
+    }
+
     pub fn selections_in_range<'a>(
         &'a self,
         range: &'a Range<Anchor>,
@@ -0,0 +1,237 @@
+use crate::{File, Location, Project};
+use anyhow::{anyhow, Result};
+use gpui::{AsyncAppContext, Model, ModelContext, Task, WeakModel};
+use language::{
+    point_from_lsp, point_to_lsp, Bias, Buffer, LanguageServerName, LocalFile, ToPointUtf16,
+};
+use lsp::{CallHierarchyServerCapability, LanguageServer, LanguageServerId};
+
+/// A function or method in a call hierarchy, as reported by the language server that prepared it.
+#[derive(Clone, Debug)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub detail: Option<String>,
+    pub kind: lsp::SymbolKind,
+    /// The name of the item in its definition.
+    pub location: Location,
+    language_server_id: LanguageServerId,
+    language_server_name: LanguageServerName,
+    lsp_item: lsp::CallHierarchyItem,
+}
+
+/// A call to or from a [`CallHierarchyItem`].
+#[derive(Clone, Debug)]
+pub struct CallHierarchyCall {
+    /// The calling item for incoming calls, or the called item for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// Where the calls are made, in the calling item.
+    pub call_sites: Vec<Location>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CallHierarchyDirection {
+    #[default]
+    Incoming,
+    Outgoing,
+}
+
+fn supports_call_hierarchy(server: &LanguageServer) -> bool {
+    match server.capabilities().call_hierarchy_provider {
+        Some(CallHierarchyServerCapability::Simple(supported)) => supported,
+        Some(CallHierarchyServerCapability::Options(_)) => true,
+        None => false,
+    }
+}
+
+impl Project {
+    /// Returns the call hierarchy items at the given position, whose calls can then be listed
+    /// with [`Project::call_hierarchy_calls`].
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "call hierarchy is only supported in local projects"
+            )));
+        }
+
+        let buffer = buffer.read(cx);
+        let position = position.to_point_utf16(buffer);
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Some((adapter, server)) = self
+            .language_servers_for_buffer(buffer, cx)
+            .find(|(_, server)| supports_call_hierarchy(server))
+        else {
+            return Task::ready(Ok(Vec::new()));
+        };
+        let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+            return Task::ready(Err(anyhow!("invalid path {abs_path:?}")));
+        };
+
+        let server = server.clone();
+        let server_name = adapter.name.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let lsp_items = server
+                .request::<lsp::request::CallHierarchyPrepare>(lsp::CallHierarchyPrepareParams {
+                    text_document_position_params: lsp::TextDocumentPositionParams {
+                        text_document: lsp::TextDocumentIdentifier { uri },
+                        position: point_to_lsp(position),
+                    },
+                    work_done_progress_params: Default::default(),
+                })
+                .await?
+                .unwrap_or_default();
+
+            let mut items = Vec::new();
+            for lsp_item in lsp_items {
+                items.push(
+                    call_hierarchy_item_from_lsp(
+                        &this,
+                        lsp_item,
+                        server.server_id(),
+                        server_name.clone(),
+                        &mut cx,
+                    )
+                    .await?,
+                );
+            }
+            Ok(items)
+        })
+    }
+
+    /// Returns the calls to or from the given call hierarchy item.
+    pub fn call_hierarchy_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        direction: CallHierarchyDirection,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        let Some(server) = self.language_server_for_id(item.language_server_id) else {
+            return Task::ready(Err(anyhow!(
+                "language server {} is not running",
+                item.language_server_name.0
+            )));
+        };
+
+        let item = item.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let lsp_calls = match direction {
+                CallHierarchyDirection::Incoming => server
+                    .request::<lsp::request::CallHierarchyIncomingCalls>(
+                        lsp::CallHierarchyIncomingCallsParams {
+                            item: item.lsp_item.clone(),
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| {
+                        let call_sites_uri = call.from.uri.clone();
+                        (call.from, call_sites_uri, call.from_ranges)
+                    })
+                    .collect::<Vec<_>>(),
+                CallHierarchyDirection::Outgoing => server
+                    .request::<lsp::request::CallHierarchyOutgoingCalls>(
+                        lsp::CallHierarchyOutgoingCallsParams {
+                            item: item.lsp_item.clone(),
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| (call.to, item.lsp_item.uri.clone(), call.from_ranges))
+                    .collect::<Vec<_>>(),
+            };
+
+            let mut calls = Vec::new();
+            for (lsp_item, call_sites_uri, call_site_ranges) in lsp_calls {
+                let mut call_sites = Vec::new();
+                for range in call_site_ranges {
+                    call_sites.push(
+                        location_from_lsp(
+                            &this,
+                            call_sites_uri.clone(),
+                            range,
+                            item.language_server_id,
+                            item.language_server_name.clone(),
+                            &mut cx,
+                        )
+                        .await?,
+                    );
+                }
+                calls.push(CallHierarchyCall {
+                    item: call_hierarchy_item_from_lsp(
+                        &this,
+                        lsp_item,
+                        item.language_server_id,
+                        item.language_server_name.clone(),
+                        &mut cx,
+                    )
+                    .await?,
+                    call_sites,
+                });
+            }
+            Ok(calls)
+        })
+    }
+}
+
+async fn call_hierarchy_item_from_lsp(
+    project: &WeakModel<Project>,
+    lsp_item: lsp::CallHierarchyItem,
+    language_server_id: LanguageServerId,
+    language_server_name: LanguageServerName,
+    cx: &mut AsyncAppContext,
+) -> Result<CallHierarchyItem> {
+    let location = location_from_lsp(
+        project,
+        lsp_item.uri.clone(),
+        lsp_item.selection_range,
+        language_server_id,
+        language_server_name.clone(),
+        cx,
+    )
+    .await?;
+    Ok(CallHierarchyItem {
+        name: lsp_item.name.clone(),
+        detail: lsp_item.detail.clone(),
+        kind: lsp_item.kind,
+        location,
+        language_server_id,
+        language_server_name,
+        lsp_item,
+    })
+}
+
+async fn location_from_lsp(
+    project: &WeakModel<Project>,
+    uri: lsp::Url,
+    range: lsp::Range,
+    language_server_id: LanguageServerId,
+    language_server_name: LanguageServerName,
+    cx: &mut AsyncAppContext,
+) -> Result<Location> {
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_via_lsp(uri, language_server_id, language_server_name, cx)
+        })?
+        .await?;
+    let range = buffer.update(cx, |buffer, _| {
+        let start = buffer.clip_point_utf16(point_from_lsp(range.start), Bias::Left);
+        let end = buffer.clip_point_utf16(point_from_lsp(range.end), Bias::Left);
+        buffer.anchor_after(start)..buffer.anchor_before(end)
+    })?;
+    Ok(Location { buffer, range })
+}
@@ -0,0 +1,167 @@
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use futures::{future::Shared, FutureExt};
+use gpui::{Model, ModelContext, Task};
+use language::{point_from_lsp, Bias, Buffer, LocalFile, OffsetRangeExt, ToOffset};
+use lsp::{LanguageServer, LanguageServerId};
+use std::{ops::Range, sync::Arc};
+use util::ResultExt;
+
+/// A link in a buffer, as reported by one of its language servers.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    pub range: Range<text::Anchor>,
+    pub target: lsp::Url,
+}
+
+/// The links reported for a buffer by its language servers, which are requested again once the
+/// buffer is edited or its language servers change.
+pub(crate) struct CachedDocumentLinks {
+    version: clock::Global,
+    server_ids: Vec<LanguageServerId>,
+    links: Shared<Task<Arc<[ServerDocumentLink]>>>,
+}
+
+struct ServerDocumentLink {
+    server: Arc<LanguageServer>,
+    range: Range<text::Anchor>,
+    link: lsp::DocumentLink,
+}
+
+impl Project {
+    /// Returns the link at the given position in the buffer reported by its language servers,
+    /// resolving its target when the server only computes targets on request.
+    pub fn document_link_at<T: ToOffset>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<DocumentLink>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(None));
+        }
+
+        let snapshot = buffer.read(cx).snapshot();
+        let offset = position.to_offset(&snapshot);
+        let links = match self.document_links(buffer, cx) {
+            Ok(links) => links,
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        cx.spawn(move |_, _| async move {
+            for server_link in links.await.iter() {
+                let range = server_link.range.to_offset(&snapshot);
+                if offset < range.start || range.end < offset {
+                    continue;
+                }
+
+                let resolve_provider = server_link
+                    .server
+                    .capabilities()
+                    .document_link_provider
+                    .and_then(|provider| provider.resolve_provider)
+                    .unwrap_or(false);
+                let target = match server_link.link.target.clone() {
+                    Some(target) => Some(target),
+                    None if resolve_provider => server_link
+                        .server
+                        .request::<lsp::request::DocumentLinkResolve>(server_link.link.clone())
+                        .await
+                        .log_err()
+                        .and_then(|link| link.target),
+                    None => None,
+                };
+                if let Some(target) = target {
+                    return Ok(Some(DocumentLink {
+                        range: server_link.range.clone(),
+                        target,
+                    }));
+                }
+            }
+            Ok(None)
+        })
+    }
+
+    /// Returns the links reported for the buffer by its language servers that provide them,
+    /// requesting them only when the buffer changed since they were last requested.
+    fn document_links(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<Shared<Task<Arc<[ServerDocumentLink]>>>> {
+        let buffer = buffer.read(cx);
+        let buffer_id = buffer.remote_id();
+        let version = buffer.version();
+        let servers = self
+            .language_servers_for_buffer(buffer, cx)
+            .filter(|(_, server)| server.capabilities().document_link_provider.is_some())
+            .map(|(_, server)| server.clone())
+            .collect::<Vec<_>>();
+        let server_ids = servers
+            .iter()
+            .map(|server| server.server_id())
+            .collect::<Vec<_>>();
+        if let Some(cached) = self.document_links.get(&buffer_id) {
+            if cached.version == version && cached.server_ids == server_ids {
+                return Ok(cached.links.clone());
+            }
+        }
+
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Ok(Task::ready(Vec::new().into()).shared());
+        };
+        let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+            return Err(anyhow!("invalid path {abs_path:?}"));
+        };
+        let snapshot = buffer.text_snapshot();
+        let requests = servers
+            .into_iter()
+            .map(|server| {
+                let request =
+                    server.request::<lsp::request::DocumentLinkRequest>(lsp::DocumentLinkParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    });
+                (server, request)
+            })
+            .collect::<Vec<_>>();
+
+        let links = cx
+            .spawn(move |_, _| async move {
+                let mut links = Vec::new();
+                for (server, request) in requests {
+                    let Some(server_links) = request.await.log_err().flatten() else {
+                        continue;
+                    };
+                    for link in server_links {
+                        // The ranges are relative to the version of the buffer that was sent to the
+                        // server when the links were requested.
+                        let start =
+                            snapshot.clip_point_utf16(point_from_lsp(link.range.start), Bias::Left);
+                        let end =
+                            snapshot.clip_point_utf16(point_from_lsp(link.range.end), Bias::Left);
+                        links.push(ServerDocumentLink {
+                            server: server.clone(),
+                            range: snapshot.anchor_before(start)..snapshot.anchor_after(end),
+                            link,
+                        });
+                    }
+                }
+                Arc::from(links)
+            })
+            .shared();
+        self.document_links.insert(
+            buffer_id,
+            CachedDocumentLinks {
+                version,
+                server_ids,
+                links: links.clone(),
+            },
+        );
+        Ok(links)
+    }
+}
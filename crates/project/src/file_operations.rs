@@ -0,0 +1,159 @@
+use crate::{CreatedEntry, Project, ProjectEntryId};
+use anyhow::{anyhow, Result};
+use futures::{future::join_all, select, FutureExt};
+use globset::GlobBuilder;
+use gpui::{ModelContext, Task};
+use lsp::LanguageServer;
+use std::{path::Path, pin::pin, sync::Arc, time::Duration};
+use util::ResultExt;
+
+const WILL_RENAME_FILES_TIMEOUT: Duration = Duration::from_secs(1);
+
+fn matches_filters(filters: &[lsp::FileOperationFilter], path: &Path, is_dir: bool) -> bool {
+    filters.iter().any(|filter| {
+        if filter
+            .scheme
+            .as_deref()
+            .is_some_and(|scheme| scheme != "file")
+        {
+            return false;
+        }
+        let pattern = &filter.pattern;
+        let matches_kind = match pattern.matches {
+            Some(lsp::FileOperationPatternKind::File) => !is_dir,
+            Some(lsp::FileOperationPatternKind::Folder) => is_dir,
+            None => true,
+        };
+        let ignore_case = pattern
+            .options
+            .as_ref()
+            .and_then(|options| options.ignore_case)
+            .unwrap_or(false);
+        matches_kind
+            && GlobBuilder::new(&pattern.glob)
+                .case_insensitive(ignore_case)
+                .build()
+                .log_err()
+                .is_some_and(|glob| glob.compile_matcher().is_match(path))
+    })
+}
+
+impl Project {
+    /// Renames the entry, asking the language servers of its worktree for the edits that keep
+    /// references to the renamed files correct, such as imports, before renaming it, and
+    /// notifying them once it is renamed.
+    pub fn rename_entry(
+        &mut self,
+        entry_id: ProjectEntryId,
+        new_path: impl Into<Arc<Path>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CreatedEntry>> {
+        let Some(worktree) = self.worktree_for_entry(entry_id, cx) else {
+            return Task::ready(Err(anyhow!(format!("No worktree for entry {entry_id:?}"))));
+        };
+        let new_path = new_path.into();
+
+        let worktree_snapshot = worktree.read(cx);
+        let rename = worktree_snapshot
+            .as_local()
+            .zip(worktree_snapshot.entry_for_id(entry_id))
+            .and_then(|(local_worktree, entry)| {
+                let old_abs_path = local_worktree.abs_path().join(&entry.path);
+                let new_abs_path = local_worktree.abs_path().join(&new_path);
+                let rename = lsp::FileRename {
+                    old_uri: lsp::Url::from_file_path(&old_abs_path).ok()?.to_string(),
+                    new_uri: lsp::Url::from_file_path(&new_abs_path).ok()?.to_string(),
+                };
+                Some((rename, old_abs_path, entry.is_dir()))
+            });
+        let servers = self
+            .language_servers_for_worktree(worktree_snapshot.id())
+            .map(|(adapter, _, server)| (adapter.clone(), server.clone()))
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            if let Some((rename, old_abs_path, is_dir)) = &rename {
+                let requests = servers
+                    .iter()
+                    .filter(|(_, server)| {
+                        let will_rename = server
+                            .capabilities()
+                            .workspace
+                            .and_then(|workspace| workspace.file_operations?.will_rename);
+                        will_rename.is_some_and(|options| {
+                            matches_filters(&options.filters, old_abs_path, *is_dir)
+                        })
+                    })
+                    .map(|(adapter, server)| {
+                        let timeout = cx.background_executor().timer(WILL_RENAME_FILES_TIMEOUT);
+                        let edit = Self::will_rename_file(server, rename, timeout);
+                        async move { (adapter, server, edit.await) }
+                    })
+                    .collect::<Vec<_>>();
+                for (adapter, server, edit) in join_all(requests).await {
+                    if let Some(edit) = edit.log_err().flatten() {
+                        let this = this.upgrade().ok_or_else(|| anyhow!("project dropped"))?;
+                        Self::deserialize_workspace_edit(
+                            this,
+                            edit,
+                            true,
+                            adapter.clone(),
+                            server.clone(),
+                            &mut cx,
+                        )
+                        .await
+                        .log_err();
+                    }
+                }
+            }
+
+            let entry = worktree
+                .update(&mut cx, |worktree, cx| {
+                    worktree.rename_entry(entry_id, new_path, cx)
+                })?
+                .await?;
+
+            if let Some((rename, old_abs_path, is_dir)) = &rename {
+                for (_, server) in &servers {
+                    let did_rename = server
+                        .capabilities()
+                        .workspace
+                        .and_then(|workspace| workspace.file_operations?.did_rename);
+                    if did_rename.is_some_and(|options| {
+                        matches_filters(&options.filters, old_abs_path, *is_dir)
+                    }) {
+                        server
+                            .notify::<lsp::notification::DidRenameFiles>(lsp::RenameFilesParams {
+                                files: vec![rename.clone()],
+                            })
+                            .log_err();
+                    }
+                }
+            }
+            Ok(entry)
+        })
+    }
+
+    /// Asks the server for the edits to apply before renaming the file, giving up once the
+    /// timeout elapses so that a slow server doesn't hold up the rename.
+    async fn will_rename_file(
+        server: &LanguageServer,
+        rename: &lsp::FileRename,
+        timeout: Task<()>,
+    ) -> Result<Option<lsp::WorkspaceEdit>> {
+        let mut request = pin!(server
+            .request::<lsp::request::WillRenameFiles>(lsp::RenameFilesParams {
+                files: vec![rename.clone()],
+            })
+            .fuse());
+        let mut timeout = timeout.fuse();
+        select! {
+            edit = request => edit,
+            _ = timeout => Err(anyhow!(
+                "timed out waiting for {} to handle the rename of {}",
+                server.name(),
+                rename.old_uri
+            )),
+        }
+    }
+}
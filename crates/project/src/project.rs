@@ -1,11 +1,16 @@
 pub mod buffer_store;
+mod call_hierarchy;
 pub mod connection_manager;
 pub mod debounced_delay;
+mod document_links;
+mod file_operations;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
 pub mod project_settings;
+mod pull_diagnostics;
 pub mod search;
+mod semantic_tokens;
 mod task_inventory;
 pub mod terminals;
 pub mod worktree_store;
@@ -44,7 +49,7 @@ use itertools::Itertools;
 use language::{
     language_settings::{
         language_settings, AllLanguageSettings, FormatOnSave, Formatter, InlayHintKind,
-        LanguageSettings, SelectedFormatter,
+        LanguageServerFeature, LanguageSettings, SelectedFormatter,
     },
     markdown, point_to_lsp, prepare_completion_documentation,
     proto::{
@@ -54,8 +59,8 @@ use language::{
     range_from_lsp, Bias, Buffer, BufferSnapshot, CachedLspAdapter, Capability, CodeLabel,
     ContextProvider, Diagnostic, DiagnosticEntry, DiagnosticSet, Diff, Documentation,
     Event as BufferEvent, File as _, Language, LanguageRegistry, LanguageServerName, LocalFile,
-    LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot, ToOffset,
-    ToPointUtf16, Transaction, Unclipped,
+    LspAdapter, LspAdapterDelegate, Patch, PendingLanguageServer, PointUtf16, TextBufferSnapshot,
+    ToOffset, ToPointUtf16, Transaction, Unclipped,
 };
 use log::error;
 use lsp::{
@@ -100,6 +105,7 @@ use std::{
     iter, mem,
     ops::Range,
     path::{self, Component, Path, PathBuf},
+    pin::pin,
     process::Stdio,
     str,
     sync::{
@@ -123,10 +129,14 @@ use worktree::{CreatedEntry, Snapshot, Traversal};
 use worktree_store::{WorktreeStore, WorktreeStoreEvent};
 use yarn::YarnPathStore;
 
+pub use call_hierarchy::{CallHierarchyCall, CallHierarchyDirection, CallHierarchyItem};
+use document_links::CachedDocumentLinks;
+pub use document_links::DocumentLink;
 pub use fs::*;
 pub use language::Location;
 #[cfg(any(test, feature = "test-support"))]
 pub use prettier::FORMAT_SUFFIX as TEST_PRETTIER_FORMAT_SUFFIX;
+pub use semantic_tokens::{BufferSemanticTokens, SemanticToken};
 pub use task_inventory::{
     BasicContextProvider, ContextProviderWithTasks, Inventory, TaskSourceKind,
 };
@@ -140,8 +150,10 @@ const MAX_SERVER_REINSTALL_ATTEMPT_COUNT: u64 = 4;
 const SERVER_REINSTALL_DEBOUNCE_TIMEOUT: Duration = Duration::from_secs(1);
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const CODE_ACTION_ON_SAVE_TIMEOUT: Duration = Duration::from_secs(5);
 
 const MAX_PROJECT_SEARCH_HISTORY_SIZE: usize = 500;
+const MAX_BUFFER_SEARCH_HISTORY_SIZE: usize = 50;
 
 pub trait Item {
     fn try_open(
@@ -178,6 +190,8 @@ pub struct Project {
     language_server_statuses: BTreeMap<LanguageServerId, LanguageServerStatus>,
     last_formatting_failure: Option<String>,
     last_workspace_edits_by_language_server: HashMap<LanguageServerId, ProjectTransaction>,
+    /// The result ids of the diagnostics last pulled from each language server, by document.
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
     language_server_watched_paths: HashMap<LanguageServerId, HashMap<WorktreeId, GlobSet>>,
     language_server_watcher_registrations:
         HashMap<LanguageServerId, HashMap<String, Vec<FileSystemWatcher>>>,
@@ -211,6 +225,7 @@ pub struct Project {
     loading_worktrees:
         HashMap<Arc<Path>, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    document_links: HashMap<BufferId, CachedDocumentLinks>,
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay<Self>,
@@ -227,6 +242,9 @@ pub struct Project {
     hosted_project_id: Option<ProjectId>,
     dev_server_project_id: Option<client::DevServerProjectId>,
     search_history: SearchHistory,
+    /// The history of searches within buffers, shared by the buffer search bars of all of the
+    /// project's panes.
+    buffer_search_history: Model<SearchHistory>,
     snippets: Model<SnippetProvider>,
     yarn: Model<YarnPathStore>,
     cached_shell_environments: HashMap<WorktreeId, HashMap<String, String>>,
@@ -526,6 +544,12 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+#[derive(Clone, Debug)]
+pub struct DocumentColor {
+    pub range: Range<language::Anchor>,
+    pub color: lsp::Color,
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -534,6 +558,8 @@ pub struct Symbol {
     pub label: CodeLabel,
     pub name: String,
     pub kind: lsp::SymbolKind,
+    /// The name of the symbol containing this one, as reported by the language server.
+    pub container_name: Option<String>,
     pub range: Range<Unclipped<PointUtf16>>,
     pub signature: [u8; 32],
 }
@@ -545,6 +571,8 @@ struct CoreSymbol {
     pub path: ProjectPath,
     pub name: String,
     pub kind: lsp::SymbolKind,
+    /// The name of the symbol containing this one, as reported by the language server.
+    pub container_name: Option<String>,
     pub range: Range<Unclipped<PointUtf16>>,
     pub signature: [u8; 32],
 }
@@ -731,6 +759,8 @@ impl Project {
         client.add_model_request_handler(Self::handle_task_context_for_location);
         client.add_model_request_handler(Self::handle_task_templates);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentColors>);
 
         client.add_model_request_handler(WorktreeStore::handle_create_project_entry);
         client.add_model_request_handler(WorktreeStore::handle_rename_project_entry);
@@ -782,6 +812,7 @@ impl Project {
                 shared_buffers: Default::default(),
                 loading_worktrees: Default::default(),
                 buffer_snapshots: Default::default(),
+                document_links: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 client_subscriptions: Vec::new(),
@@ -810,6 +841,7 @@ impl Project {
                 language_server_statuses: Default::default(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
+                diagnostic_result_ids: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
                 buffers_being_formatted: Default::default(),
@@ -828,6 +860,7 @@ impl Project {
                 hosted_project_id: None,
                 dev_server_project_id: None,
                 search_history: Self::new_search_history(),
+                buffer_search_history: cx.new_model(|_| Self::new_buffer_search_history()),
                 cached_shell_environments: HashMap::default(),
             }
         })
@@ -1001,12 +1034,14 @@ impl Project {
                     .collect(),
                 last_formatting_failure: None,
                 last_workspace_edits_by_language_server: Default::default(),
+                diagnostic_result_ids: Default::default(),
                 language_server_watched_paths: HashMap::default(),
                 language_server_watcher_registrations: HashMap::default(),
                 buffers_being_formatted: Default::default(),
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DebouncedDelay::new(),
                 buffer_snapshots: Default::default(),
+                document_links: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
@@ -1023,6 +1058,7 @@ impl Project {
                     .dev_server_project_id
                     .map(|dev_server_project_id| DevServerProjectId(dev_server_project_id)),
                 search_history: Self::new_search_history(),
+                buffer_search_history: cx.new_model(|_| Self::new_buffer_search_history()),
                 cached_shell_environments: HashMap::default(),
             };
             this.set_role(role, cx);
@@ -1094,6 +1130,13 @@ impl Project {
         )
     }
 
+    pub fn new_buffer_search_history() -> SearchHistory {
+        SearchHistory::new(
+            Some(MAX_BUFFER_SEARCH_HISTORY_SIZE),
+            search_history::QueryInsertionBehavior::ReplacePreviousIfContains,
+        )
+    }
+
     fn release(&mut self, cx: &mut AppContext) {
         match &self.client_state {
             ProjectClientState::Local => {}
@@ -1263,20 +1306,20 @@ impl Project {
                 if !language_settings(Some(language), file.as_ref(), cx).enable_language_server {
                     language_servers_to_stop.push((*worktree_id, started_lsp_name.clone()));
                 } else if let Some(worktree) = worktree {
-                    let server_name = &adapter.name.0;
-                    match (
-                        current_lsp_settings.get(server_name),
-                        new_lsp_settings.get(server_name),
-                    ) {
-                        (None, None) => {}
-                        (Some(_), None) | (None, Some(_)) => {
-                            language_servers_to_restart.push((worktree, Arc::clone(language)));
-                        }
-                        (Some(current_lsp_settings), Some(new_lsp_settings)) => {
-                            if current_lsp_settings != new_lsp_settings {
-                                language_servers_to_restart.push((worktree, Arc::clone(language)));
-                            }
-                        }
+                    // Changed `settings` are sent to running servers as a configuration change,
+                    // while the other settings are only read when a server starts.
+                    let startup_settings = |settings: &HashMap<Arc<str>, LspSettings>| {
+                        settings
+                            .get(&adapter.name.0)
+                            .map(|settings| LspSettings {
+                                settings: None,
+                                ..settings.clone()
+                            })
+                            .filter(|settings| *settings != LspSettings::default())
+                    };
+                    if startup_settings(current_lsp_settings) != startup_settings(&new_lsp_settings)
+                    {
+                        language_servers_to_restart.push((worktree, Arc::clone(language)));
                     }
                 }
             }
@@ -1431,6 +1474,10 @@ impl Project {
         &mut self.search_history
     }
 
+    pub fn buffer_search_history(&self) -> &Model<SearchHistory> {
+        &self.buffer_search_history
+    }
+
     pub fn collaborators(&self) -> &HashMap<proto::PeerId, Collaborator> {
         &self.collaborators
     }
@@ -1552,20 +1599,6 @@ impl Project {
         })
     }
 
-    pub fn rename_entry(
-        &mut self,
-        entry_id: ProjectEntryId,
-        new_path: impl Into<Arc<Path>>,
-        cx: &mut ModelContext<Self>,
-    ) -> Task<Result<CreatedEntry>> {
-        let Some(worktree) = self.worktree_for_entry(entry_id, cx) else {
-            return Task::ready(Err(anyhow!(format!("No worktree for entry {entry_id:?}"))));
-        };
-        worktree.update(cx, |worktree, cx| {
-            worktree.rename_entry(entry_id, new_path, cx)
-        })
-    }
-
     pub fn delete_entry(
         &mut self,
         entry_id: ProjectEntryId,
@@ -2155,6 +2188,7 @@ impl Project {
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.document_links.remove(&buffer.remote_id());
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2286,7 +2320,11 @@ impl Project {
             }
 
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.document_links.remove(&buffer.remote_id());
             let file_url = lsp::Url::from_file_path(old_path).unwrap();
+            for result_ids in self.diagnostic_result_ids.values_mut() {
+                result_ids.remove(&file_url);
+            }
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
                     .notify::<lsp::notification::DidCloseTextDocument>(
@@ -2831,6 +2869,36 @@ impl Project {
         })
     }
 
+    /// Returns the workspace configuration of a language server, with the server's `settings`
+    /// from the user's and the worktree's `lsp` settings merged into the adapter's defaults.
+    async fn workspace_configuration(
+        adapter: &Arc<dyn LspAdapter>,
+        delegate: &Arc<dyn LspAdapterDelegate>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<serde_json::Value> {
+        let mut workspace_config = adapter
+            .clone()
+            .workspace_configuration(delegate, cx)
+            .await?;
+        let worktree_id = delegate.worktree_id();
+        let user_settings = cx.update(|cx| {
+            ProjectSettings::get(
+                Some(SettingsLocation {
+                    worktree_id: worktree_id as usize,
+                    path: Path::new(""),
+                }),
+                cx,
+            )
+            .lsp
+            .get(&adapter.name().0)
+            .and_then(|settings| settings.settings.clone())
+        })?;
+        if let Some(user_settings) = user_settings {
+            merge_json_value_into(user_settings, &mut workspace_config);
+        }
+        Ok(workspace_config)
+    }
+
     fn maintain_workspace_config(cx: &mut ModelContext<Project>) -> Task<Result<()>> {
         let (mut settings_changed_tx, mut settings_changed_rx) = watch::channel();
         let _ = postage::stream::Stream::try_recv(&mut settings_changed_rx);
@@ -2863,7 +2931,8 @@ impl Project {
                 })?;
 
                 for (adapter, server, delegate) in servers {
-                    let settings = adapter.workspace_configuration(&delegate, &mut cx).await?;
+                    let settings =
+                        Self::workspace_configuration(&adapter, &delegate, &mut cx).await?;
 
                     server
                         .notify::<lsp::notification::DidChangeConfiguration>(
@@ -3231,11 +3300,8 @@ impl Project {
         server_id: LanguageServerId,
         cx: &mut AsyncAppContext,
     ) -> Result<Arc<LanguageServer>> {
-        let workspace_config = adapter
-            .adapter
-            .clone()
-            .workspace_configuration(&delegate, cx)
-            .await?;
+        let workspace_config =
+            Self::workspace_configuration(&adapter.adapter, &delegate, cx).await?;
         let (language_server, mut initialization_options) = pending_server.task.await?;
 
         let name = language_server.name();
@@ -3271,7 +3337,7 @@ impl Project {
                     let delegate = delegate.clone();
                     async move {
                         let workspace_config =
-                            adapter.workspace_configuration(&delegate, &mut cx).await?;
+                            Self::workspace_configuration(&adapter, &delegate, &mut cx).await?;
                         Ok(params
                             .items
                             .into_iter()
@@ -3862,6 +3928,7 @@ impl Project {
 
             self.language_server_watched_paths.remove(&server_id);
             self.language_server_statuses.remove(&server_id);
+            self.diagnostic_result_ids.remove(&server_id);
             cx.notify();
 
             let server_state = self.language_servers.remove(&server_id);
@@ -3992,6 +4059,56 @@ impl Project {
             return;
         }
 
+        self.restart_stopped_language_servers(
+            worktree.clone(),
+            stop_tasks,
+            move |this, cx| this.start_language_servers(&worktree, language, cx),
+            cx,
+        );
+    }
+
+    /// Restarts the language server with the given id, in all of the worktrees it was started for.
+    pub fn restart_language_server(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(LanguageServerState::Running {
+            language, adapter, ..
+        }) = self.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let (language, adapter) = (language.clone(), adapter.clone());
+        let Some(worktree) = self
+            .language_server_ids
+            .iter()
+            .find(|(_, id)| **id == server_id)
+            .and_then(|((worktree_id, _), _)| self.worktree_for_id(*worktree_id, cx))
+        else {
+            return;
+        };
+
+        let worktree_id = worktree.read(cx).id();
+        let stop_task = self.stop_language_server(worktree_id, adapter.name.clone(), cx);
+        self.restart_stopped_language_servers(
+            worktree.clone(),
+            vec![(stop_task, adapter.name.clone())],
+            move |this, cx| this.start_language_server(&worktree, adapter, language, cx),
+            cx,
+        );
+    }
+
+    /// Waits for the given language servers to stop, then starts them again in the worktree, and
+    /// assigns the new servers to the other worktrees each stopped server was associated with.
+    fn restart_stopped_language_servers(
+        &mut self,
+        worktree: Model<Worktree>,
+        stop_tasks: Vec<(Task<Vec<WorktreeId>>, LanguageServerName)>,
+        start: impl 'static + FnOnce(&mut Self, &mut ModelContext<Self>),
+        cx: &mut ModelContext<Self>,
+    ) {
+        let worktree_id = worktree.read(cx).id();
         cx.spawn(move |this, mut cx| async move {
             // For each stopped language server, record all of the worktrees with which
             // it was associated.
@@ -4003,8 +4120,8 @@ impl Project {
             }
 
             this.update(&mut cx, |this, cx| {
-                // Restart the language server for the given worktree.
-                this.start_language_servers(&worktree, language.clone(), cx);
+                // Restart the language servers for the given worktree.
+                start(this, cx);
 
                 // Lookup new server ids and set them for each of the orphaned worktrees
                 for (affected_worktree_id, language_server_name) in affected_worktrees {
@@ -4023,6 +4140,44 @@ impl Project {
         .detach();
     }
 
+    /// Stops the language server with the given id, in all of the worktrees it was started for.
+    pub fn stop_language_server_by_id(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let key = self
+            .language_server_ids
+            .iter()
+            .find(|(_, id)| **id == server_id)
+            .map(|(key, _)| key.clone());
+        if let Some((worktree_id, adapter_name)) = key {
+            self.stop_language_server(worktree_id, adapter_name, cx)
+                .detach();
+        }
+    }
+
+    pub fn stop_language_servers_for_buffers(
+        &mut self,
+        buffers: impl IntoIterator<Item = Model<Buffer>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self.is_remote() {
+            return;
+        }
+
+        let servers = buffers
+            .into_iter()
+            .flat_map(|buffer| {
+                self.language_server_ids_for_buffer(buffer.read(cx), cx)
+                    .into_iter()
+            })
+            .collect::<HashSet<_>>();
+        for server_id in servers {
+            self.stop_language_server_by_id(server_id, cx);
+        }
+    }
+
     pub fn cancel_language_server_work_for_buffers(
         &mut self,
         buffers: impl IntoIterator<Item = Model<Buffer>>,
@@ -4914,6 +5069,46 @@ impl Project {
         }
     }
 
+    /// Applies the code actions of the given kind, such as `source.organizeImports`, that the
+    /// language servers of each buffer offer for its whole contents.
+    pub fn apply_code_actions_of_kind(
+        &mut self,
+        buffers: HashSet<Model<Buffer>>,
+        kind: lsp::CodeActionKind,
+        push_to_history: bool,
+        cx: &mut ModelContext<Project>,
+    ) -> Task<Result<ProjectTransaction>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "applying {} code actions is not supported in remote projects",
+                kind.as_str()
+            )));
+        }
+
+        cx.spawn(move |project, mut cx| async move {
+            let mut project_transaction = ProjectTransaction::default();
+            for buffer in &buffers {
+                let adapters_and_servers = project.update(&mut cx, |project, cx| {
+                    project
+                        .language_servers_for_buffer(buffer.read(cx), cx)
+                        .map(|(adapter, lsp)| (adapter.clone(), lsp.clone()))
+                        .collect::<Vec<_>>()
+                })?;
+                Self::execute_code_actions_on_servers(
+                    &project,
+                    &adapters_and_servers,
+                    vec![kind.clone()],
+                    buffer,
+                    push_to_history,
+                    &mut project_transaction,
+                    &mut cx,
+                )
+                .await?;
+            }
+            Ok(project_transaction)
+        })
+    }
+
     async fn format_locally(
         project: WeakModel<Project>,
         mut buffers_with_paths: Vec<(Model<Buffer>, Option<PathBuf>)>,
@@ -5010,6 +5205,33 @@ impl Project {
                 .await?;
             }
 
+            // Apply the `code_actions_on_save` in order, giving up on any that take too long so
+            // that the save isn't held up.
+            if trigger == FormatTrigger::Save {
+                for kind in &settings.code_actions_on_save {
+                    let mut timeout = cx
+                        .background_executor()
+                        .timer(CODE_ACTION_ON_SAVE_TIMEOUT)
+                        .fuse();
+                    let mut execute = pin!(Self::execute_code_actions_on_servers(
+                        &project,
+                        &adapters_and_servers,
+                        vec![kind.clone().into()],
+                        buffer,
+                        push_to_history,
+                        &mut project_transaction,
+                        &mut cx,
+                    )
+                    .fuse());
+                    select! {
+                        result = execute => result?,
+                        _ = timeout => {
+                            log::warn!("timed out running the {kind} code action on save");
+                        }
+                    }
+                }
+            }
+
             // Apply language-specific formatting using either the primary language server
             // or external command.
             // Except for code actions, which are applied with all connected language servers.
@@ -5291,7 +5513,7 @@ impl Project {
             }
             Formatter::External { command, arguments } => {
                 let buffer_abs_path = buffer_abs_path.as_ref().map(|path| path.as_path());
-                Self::format_via_external_command(
+                let diff = Self::format_via_external_command(
                     buffer,
                     buffer_abs_path,
                     &command,
@@ -5302,8 +5524,17 @@ impl Project {
                 .context(format!(
                     "failed to format via external command {:?}",
                     command
-                ))?
-                .map(FormatOperation::External)
+                ));
+                // Formatting errors are otherwise only logged, and a misconfigured or failing
+                // command is something the user needs to fix.
+                if let Err(error) = &diff {
+                    project
+                        .update(cx, |_, cx| {
+                            cx.emit(Event::Notification(format!("{error:#}")))
+                        })
+                        .ok();
+                }
+                diff?.map(FormatOperation::External)
             }
             Formatter::CodeActions(code_actions) => {
                 let code_actions = deserialize_code_actions(&code_actions);
@@ -5597,7 +5828,7 @@ impl Project {
                             let lsp_symbols = response.flatten().map(|symbol_response| match symbol_response {
                                 lsp::WorkspaceSymbolResponse::Flat(flat_responses) => {
                                     flat_responses.into_iter().map(|lsp_symbol| {
-                                        (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.location)
+                                        (lsp_symbol.name, lsp_symbol.kind, lsp_symbol.container_name, lsp_symbol.location)
                                     }).collect::<Vec<_>>()
                                 }
                                 lsp::WorkspaceSymbolResponse::Nested(nested_responses) => {
@@ -5609,7 +5840,7 @@ impl Project {
                                                 return None
                                             }
                                         };
-                                        Some((lsp_symbol.name, lsp_symbol.kind, location))
+                                        Some((lsp_symbol.name, lsp_symbol.kind, lsp_symbol.container_name, location))
                                     }).collect::<Vec<_>>()
                                 }
                             }).unwrap_or_default();
@@ -5639,37 +5870,42 @@ impl Project {
                     let core_symbols = this.update(&mut cx, |this, cx| {
                         lsp_symbols
                             .into_iter()
-                            .filter_map(|(symbol_name, symbol_kind, symbol_location)| {
-                                let abs_path = symbol_location.uri.to_file_path().ok()?;
-                                let source_worktree = source_worktree.upgrade()?;
-                                let source_worktree_id = source_worktree.read(cx).id();
-
-                                let path;
-                                let worktree;
-                                if let Some((tree, rel_path)) = this.find_worktree(&abs_path, cx) {
-                                    worktree = tree;
-                                    path = rel_path;
-                                } else {
-                                    worktree = source_worktree.clone();
-                                    path = relativize_path(&worktree_abs_path, &abs_path);
-                                }
+                            .filter_map(
+                                |(symbol_name, symbol_kind, container_name, symbol_location)| {
+                                    let abs_path = symbol_location.uri.to_file_path().ok()?;
+                                    let source_worktree = source_worktree.upgrade()?;
+                                    let source_worktree_id = source_worktree.read(cx).id();
+
+                                    let path;
+                                    let worktree;
+                                    if let Some((tree, rel_path)) =
+                                        this.find_worktree(&abs_path, cx)
+                                    {
+                                        worktree = tree;
+                                        path = rel_path;
+                                    } else {
+                                        worktree = source_worktree.clone();
+                                        path = relativize_path(&worktree_abs_path, &abs_path);
+                                    }
 
-                                let worktree_id = worktree.read(cx).id();
-                                let project_path = ProjectPath {
-                                    worktree_id,
-                                    path: path.into(),
-                                };
-                                let signature = this.symbol_signature(&project_path);
-                                Some(CoreSymbol {
-                                    language_server_name: adapter.name.clone(),
-                                    source_worktree_id,
-                                    path: project_path,
-                                    kind: symbol_kind,
-                                    name: symbol_name,
-                                    range: range_from_lsp(symbol_location.range),
-                                    signature,
-                                })
-                            })
+                                    let worktree_id = worktree.read(cx).id();
+                                    let project_path = ProjectPath {
+                                        worktree_id,
+                                        path: path.into(),
+                                    };
+                                    let signature = this.symbol_signature(&project_path);
+                                    Some(CoreSymbol {
+                                        language_server_name: adapter.name.clone(),
+                                        source_worktree_id,
+                                        path: project_path,
+                                        kind: symbol_kind,
+                                        container_name,
+                                        name: symbol_name,
+                                        range: range_from_lsp(symbol_location.range),
+                                        signature,
+                                    })
+                                },
+                            )
                             .collect()
                     })?;
 
@@ -5942,6 +6178,18 @@ impl Project {
         cx: &mut ModelContext<Self>,
     ) -> Task<Result<Vec<Range<Anchor>>>> {
         let snapshot = buffer.read(cx).snapshot();
+        let linked_edits_enabled = maybe!({
+            let language_name = buffer.read(cx).language_at(position)?.name();
+            Some(
+                AllLanguageSettings::get_global(cx)
+                    .language(Some(&language_name))
+                    .linked_edits,
+            )
+        }) == Some(true);
+        if !linked_edits_enabled {
+            return Task::ready(Ok(vec![]));
+        }
+
         let scope = snapshot.language_scope_at(position);
         let Some(server_id) = self
             .language_servers_for_buffer(buffer.read(cx), cx)
@@ -5960,18 +6208,14 @@ impl Project {
             .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
             .next()
             .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
-            .filter(|_| {
-                maybe!({
-                    let language_name = buffer.read(cx).language_at(position)?.name();
-                    Some(
-                        AllLanguageSettings::get_global(cx)
-                            .language(Some(&language_name))
-                            .linked_edits,
-                    )
-                }) == Some(true)
-            })
         else {
-            return Task::ready(Ok(vec![]));
+            // Without a language server to ask, link matching tags found in the syntax tree.
+            let ranges = snapshot
+                .linked_tag_name_ranges(position)
+                .into_iter()
+                .map(|range| snapshot.anchor_before(range.start)..snapshot.anchor_after(range.end))
+                .collect();
+            return Task::ready(Ok(ranges));
         };
 
         self.request_lsp(
@@ -5991,6 +6235,40 @@ impl Project {
         self.linked_edit_impl(buffer, position, cx)
     }
 
+    pub fn folding_ranges(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<Range<Anchor>>>> {
+        let Some(server_id) = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .find(|(_, server)| server.capabilities().folding_range_provider.is_some())
+            .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
+            .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
+        else {
+            return Task::ready(Ok(vec![]));
+        };
+
+        self.request_lsp(buffer.clone(), server_id, GetFoldingRanges, cx)
+    }
+
+    pub fn document_colors(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentColor>>> {
+        let Some(server_id) = self
+            .language_servers_for_buffer(buffer.read(cx), cx)
+            .find(|(_, server)| server.capabilities().color_provider.is_some())
+            .map(|(_, server)| LanguageServerToQuery::Other(server.server_id()))
+            .or_else(|| self.is_remote().then_some(LanguageServerToQuery::Primary))
+        else {
+            return Task::ready(Ok(vec![]));
+        };
+
+        self.request_lsp(buffer.clone(), server_id, GetDocumentColors, cx)
+    }
+
     #[inline(never)]
     fn completions_impl(
         &self,
@@ -6008,7 +6286,12 @@ impl Project {
             let language = snapshot.language().cloned();
 
             let server_ids: Vec<_> = self
-                .language_servers_for_buffer(buffer.read(cx), cx)
+                .language_servers_for_buffer_feature(
+                    buffer.read(cx),
+                    Some(LanguageServerFeature::Completions),
+                    cx,
+                )
+                .into_iter()
                 .filter(|(_, server)| server.capabilities().completion_provider.is_some())
                 .filter(|(adapter, _)| {
                     scope
@@ -6249,6 +6532,13 @@ impl Project {
                 completion.old_range = old_range;
             }
         }
+        // Some servers, such as `typescript-language-server`, only provide the signature of
+        // the completion once it is resolved.
+        if completion_item.detail.is_some() {
+            let mut completions = completions.write();
+            let completion = &mut completions[completion_index];
+            completion.lsp_completion.detail = completion_item.detail.clone();
+        }
         if completion_item.insert_text_format == Some(InsertTextFormat::SNIPPET) {
             // vtsls might change the type of completion after resolution.
             let mut completions = completions.write();
@@ -6338,11 +6628,15 @@ impl Project {
                     .as_ref()
                     .and_then(|options| options.resolve_provider)
                     .unwrap_or(false);
+                // Servers may defer computing the additional edits, such as auto-imports, until
+                // the completion is resolved.
                 let additional_text_edits = if can_resolve {
+                    let unresolved_edits = completion.lsp_completion.additional_text_edits.clone();
                     lang_server
                         .request::<lsp::request::ResolveCompletionItem>(completion.lsp_completion)
                         .await?
                         .additional_text_edits
+                        .or(unresolved_edits)
                 } else {
                     completion.lsp_completion.additional_text_edits
                 };
@@ -7033,8 +7327,28 @@ impl Project {
         let range_start = range.start;
         let range_end = range.end;
         let buffer_id = buffer.remote_id().into();
-        let lsp_request = InlayHints { range };
 
+        if self.is_local() && self.uses_syntax_parameter_hints(buffer, cx) {
+            let snapshot = buffer.snapshot();
+            return cx.background_executor().spawn(async move {
+                let hints = snapshot
+                    .parameter_hints(range_start..range_end)
+                    .into_iter()
+                    .map(|hint| InlayHint {
+                        position: snapshot.anchor_before(hint.position),
+                        label: InlayHintLabel::String(format!("{}:", hint.name)),
+                        kind: Some(InlayHintKind::Parameter),
+                        padding_left: false,
+                        padding_right: true,
+                        tooltip: None,
+                        resolve_state: ResolveState::Resolved,
+                    })
+                    .collect();
+                Ok(hints)
+            });
+        }
+
+        let lsp_request = InlayHints { range };
         if self.is_local() {
             let lsp_request_task = self.request_lsp(
                 buffer_handle.clone(),
@@ -7080,6 +7394,23 @@ impl Project {
         }
     }
 
+    /// Whether the hints of the buffer are computed from its syntax tree, because its language
+    /// servers don't provide any and the syntax parameter hints are enabled for its language.
+    fn uses_syntax_parameter_hints(&self, buffer: &Buffer, cx: &AppContext) -> bool {
+        language_settings(buffer.language(), buffer.file(), cx)
+            .inlay_hints
+            .syntax_parameter_hints
+            && !self
+                .language_servers_for_buffer(buffer, cx)
+                .any(
+                    |(_, server)| match server.capabilities().inlay_hint_provider {
+                        Some(lsp::OneOf::Left(enabled)) => enabled,
+                        Some(lsp::OneOf::Right(_)) => true,
+                        None => false,
+                    },
+                )
+    }
+
     pub fn resolve_inlay_hint(
         &self,
         hint: InlayHint,
@@ -7247,7 +7578,7 @@ impl Project {
                 }
             })
             .sum();
-        if path_count == 0 {
+        if path_count == 0 && !query.open_buffers_only() {
             let (_, rx) = smol::channel::bounded(1024);
             return rx;
         }
@@ -7321,6 +7652,27 @@ impl Project {
                 sort_search_matches(&mut matching_paths, cx);
             })?;
 
+            // Only files with a grammar can contain syntax nodes, so the others are skipped
+            // before their buffers are loaded.
+            if query.is_structural() {
+                let mut parsable_paths = Vec::with_capacity(matching_paths.len());
+                for matching_path in matching_paths {
+                    if let SearchMatchCandidate::Path { path, .. } = &matching_path {
+                        let language = this.update(&mut cx, |this, cx| {
+                            this.languages.language_for_unopened_file(path, cx)
+                        })?;
+                        if !language
+                            .await
+                            .map_or(false, |language| language.grammar().is_some())
+                        {
+                            continue;
+                        }
+                    }
+                    parsable_paths.push(matching_path);
+                }
+                matching_paths = parsable_paths;
+            }
+
             let mut range_count = 0;
             let query = Arc::new(query);
 
@@ -7426,6 +7778,9 @@ impl Project {
                 .await
                 .log_err();
         }
+        if query.open_buffers_only() {
+            return;
+        }
 
         let paths_per_worker = (path_count + workers - 1) / workers;
 
@@ -7494,7 +7849,12 @@ impl Project {
         if self.is_local() {
             let language_server = match server {
                 LanguageServerToQuery::Primary => {
-                    match self.primary_language_server_for_buffer(buffer, cx) {
+                    match self
+                        .language_servers_for_buffer_feature(buffer, request.feature(), cx)
+                        .into_iter()
+                        .find(|(_, server)| {
+                            request.check_capabilities(server.adapter_server_capabilities())
+                        }) {
                         Some((_, server)) => Some(Arc::clone(server)),
                         None => return Task::ready(Ok(Default::default())),
                     }
@@ -7600,7 +7960,8 @@ impl Project {
         let snapshot = buffer.read(cx).snapshot();
         let scope = position.and_then(|position| snapshot.language_scope_at(position));
         let mut response_results = self
-            .language_servers_for_buffer(buffer.read(cx), cx)
+            .language_servers_for_buffer_feature(buffer.read(cx), request.feature(), cx)
+            .into_iter()
             .filter(|(adapter, _)| {
                 scope
                     .as_ref()
@@ -7930,6 +8291,7 @@ impl Project {
                 .remove(&server_id_to_remove);
             self.last_workspace_edits_by_language_server
                 .remove(&server_id_to_remove);
+            self.diagnostic_result_ids.remove(&server_id_to_remove);
             self.language_servers.remove(&server_id_to_remove);
             cx.emit(Event::LanguageServerRemoved(server_id_to_remove));
         }
@@ -9651,6 +10013,7 @@ impl Project {
                         path: symbol.path,
                         name: symbol.name,
                         kind: symbol.kind,
+                        container_name: symbol.container_name,
                         range: symbol.range,
                         signature: symbol.signature,
                         label: CodeLabel {
@@ -9997,6 +10360,7 @@ impl Project {
             range: Unclipped(PointUtf16::new(start.row, start.column))
                 ..Unclipped(PointUtf16::new(end.row, end.column)),
             kind,
+            container_name: serialized_symbol.container_name,
             signature: serialized_symbol
                 .signature
                 .try_into()
@@ -10262,6 +10626,35 @@ impl Project {
         self.language_servers_for_buffer(buffer, cx).next()
     }
 
+    /// Returns the buffer's language servers to use for the given feature. When the language's
+    /// settings list servers for the feature, only those are returned, in the listed order.
+    fn language_servers_for_buffer_feature(
+        &self,
+        buffer: &Buffer,
+        feature: Option<LanguageServerFeature>,
+        cx: &AppContext,
+    ) -> Vec<(&Arc<CachedLspAdapter>, &Arc<LanguageServer>)> {
+        let servers = self.language_servers_for_buffer(buffer, cx);
+        let Some(server_names) = feature.and_then(|feature| {
+            language_settings(buffer.language(), buffer.file(), cx)
+                .language_servers_by_feature
+                .get(&feature)
+        }) else {
+            return servers.collect();
+        };
+
+        servers
+            .filter_map(|(adapter, server)| {
+                let ix = server_names
+                    .iter()
+                    .position(|name| *name == adapter.name.0)?;
+                Some((ix, (adapter, server)))
+            })
+            .sorted_by_key(|(ix, _)| *ix)
+            .map(|(_, server)| server)
+            .collect()
+    }
+
     pub fn language_server_for_buffer(
         &self,
         buffer: &Buffer,
@@ -10666,6 +11059,7 @@ async fn populate_labels_for_symbols(
                 label: label.unwrap_or_else(|| CodeLabel::plain(name.clone(), None)),
                 name,
                 kind: symbol.kind,
+                container_name: symbol.container_name,
                 range: symbol.range,
                 signature: symbol.signature,
             });
@@ -11131,6 +11525,7 @@ fn serialize_symbol(symbol: &Symbol) -> proto::Symbol {
         path: symbol.path.path.to_string_lossy().to_string(),
         name: symbol.name.clone(),
         kind: unsafe { mem::transmute::<lsp::SymbolKind, i32>(symbol.kind) },
+        container_name: symbol.container_name.clone(),
         start: Some(proto::PointUtf16 {
             row: symbol.range.start.0.row,
             column: symbol.range.start.0.column,
@@ -87,7 +87,7 @@ pub enum GitGutterSetting {
     Hide,
 }
 
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct InlineBlameSettings {
     /// Whether or not to show git blame data inline in
@@ -105,6 +105,22 @@ pub struct InlineBlameSettings {
     ///
     /// Default: 0
     pub min_column: Option<u32>,
+    /// Whether to show the summary of the commit after its author and time.
+    ///
+    /// Default: true
+    #[serde(default = "true_value")]
+    pub show_commit_summary: bool,
+}
+
+impl Default for InlineBlameSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true_value(),
+            delay_ms: None,
+            min_column: None,
+            show_commit_summary: true_value(),
+        }
+    }
 }
 
 const fn true_value() -> bool {
@@ -124,6 +140,10 @@ pub struct LspSettings {
     pub binary: Option<BinarySettings>,
     pub initialization_options: Option<serde_json::Value>,
     pub settings: Option<serde_json::Value>,
+    /// The languages to start this language server for, when it isn't one that Zed or
+    /// its extensions provide. Such language servers are started with the configured
+    /// binary, or else with the one named like the server on the `PATH`.
+    pub languages: Option<Vec<Arc<str>>>,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
@@ -4,7 +4,7 @@ use futures::{future, StreamExt};
 use gpui::{AppContext, SemanticVersion, UpdateGlobal};
 use http_client::Url;
 use language::{
-    language_settings::{AllLanguageSettings, LanguageSettingsContent},
+    language_settings::{AllLanguageSettings, LanguageServerFeature, LanguageSettingsContent},
     tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
     LanguageMatcher, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
@@ -1392,6 +1392,70 @@ async fn test_restarting_server_with_diagnostics_published(cx: &mut gpui::TestAp
     });
 }
 
+#[gpui::test]
+async fn test_changing_language_server_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            name: "the-language-server",
+            ..Default::default()
+        },
+    );
+
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server
+        .receive_notification::<lsp::notification::DidChangeConfiguration>()
+        .await;
+
+    // Changed settings are sent to the running server, without restarting it.
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.lsp.insert(
+                    "the-language-server".into(),
+                    LspSettings {
+                        settings: Some(json!({ "check": { "command": "clippy" } })),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+    let params = fake_server
+        .receive_notification::<lsp::notification::DidChangeConfiguration>()
+        .await;
+    assert_eq!(params.settings, json!({ "check": { "command": "clippy" } }));
+    cx.executor().run_until_parked();
+    assert!(fake_servers.try_next().is_err());
+
+    // Changed initialization options restart the server.
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings
+                    .lsp
+                    .get_mut("the-language-server")
+                    .unwrap()
+                    .initialization_options = Some(json!({ "cargo": { "features": "all" } }));
+            });
+        })
+    });
+    fake_servers.next().await.unwrap();
+}
+
 #[gpui::test]
 async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1431,6 +1495,58 @@ async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::T
     assert_eq!(notification.version, 0);
 }
 
+#[gpui::test]
+async fn test_restarting_and_stopping_server_by_id(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "" })).await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+
+    language_registry.add(rust_lang());
+    let mut fake_servers =
+        language_registry.register_fake_lsp_adapter("Rust", FakeLspAdapter::default());
+
+    let _buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let fake_server = fake_servers.next().await.unwrap();
+    let server_id = fake_server.server.server_id();
+
+    // The restarted server gets the open buffers.
+    project.update(cx, |project, cx| {
+        project.restart_language_server(server_id, cx);
+    });
+    let mut fake_server = fake_servers.next().await.unwrap();
+    let notification = fake_server
+        .receive_notification::<lsp::notification::DidOpenTextDocument>()
+        .await
+        .text_document;
+    assert_eq!(notification.uri.as_str(), "file:///dir/a.rs");
+    let server_id = fake_server.server.server_id();
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, _| {
+        assert_eq!(
+            project
+                .language_servers()
+                .map(|(server_id, _, _)| server_id)
+                .collect::<Vec<_>>(),
+            [server_id]
+        );
+    });
+
+    project.update(cx, |project, cx| {
+        project.stop_language_server_by_id(server_id, cx);
+    });
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, _| {
+        assert_eq!(project.language_servers().count(), 0);
+    });
+}
+
 #[gpui::test]
 async fn test_cancel_language_server_work(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2490,6 +2606,447 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() { b() }\nfn b() {}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let uri = lsp::Url::from_file_path("/dir/a.rs").unwrap();
+    let lsp_item = |name: &str, row: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(row, 0), lsp::Position::new(row, 14)),
+        selection_range: lsp::Range::new(lsp::Position::new(row, 3), lsp::Position::new(row, 4)),
+        data: None,
+    };
+    let b = lsp_item("b", 1);
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let b = b.clone();
+        move |params, _| {
+            let b = b.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 3)
+                );
+                Ok(Some(vec![b]))
+            }
+        }
+    });
+    let a = lsp_item("a", 0);
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>(
+        move |params, _| {
+            let a = a.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: a,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(0, 9),
+                        lsp::Position::new(0, 10),
+                    )],
+                }]))
+            }
+        },
+    );
+
+    let items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(1, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "b");
+
+    let calls = project
+        .update(cx, |project, cx| {
+            project.call_hierarchy_calls(&items[0], CallHierarchyDirection::Incoming, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].item.name, "a");
+    cx.update(|cx| {
+        let call_site = &calls[0].call_sites[0];
+        assert_eq!(call_site.range.to_offset(call_site.buffer.read(cx)), 9..10);
+    });
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "let mut x = f();\nx += 1;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::VARIABLE,
+                                    lsp::SemanticTokenType::FUNCTION,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::new("mutable")],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let token = |delta_line, delta_start, token_type, token_modifiers_bitset| lsp::SemanticToken {
+        delta_line,
+        delta_start,
+        length: 1,
+        token_type,
+        token_modifiers_bitset,
+    };
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+        move |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".into()),
+                    data: vec![token(0, 8, 0, 1), token(0, 4, 1, 0), token(1, 0, 0, 1)],
+                },
+            )))
+        },
+    );
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        move |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".into()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 5,
+                        delete_count: 5,
+                        data: Some(vec![token(0, 4, 0, 0)]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let describe = |tokens: &BufferSemanticTokens, cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let buffer = buffer.read(cx);
+            tokens
+                .tokens
+                .iter()
+                .map(|token| {
+                    (
+                        token.range.to_offset(buffer),
+                        tokens.token_type(token).unwrap().to_string(),
+                        tokens.token_modifiers(token).collect::<Vec<_>>().join(" "),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let tokens = project
+        .update(cx, |project, cx| project.semantic_tokens(&buffer, None, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        describe(&tokens, cx),
+        [
+            (8..9, "variable".to_string(), "mutable".to_string()),
+            (12..13, "function".to_string(), String::new()),
+            (17..18, "variable".to_string(), "mutable".to_string()),
+        ]
+    );
+
+    // The next request only asks for the tokens that changed.
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(&buffer, Some(&tokens), cx)
+        })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        describe(&tokens, cx),
+        [
+            (8..9, "variable".to_string(), "mutable".to_string()),
+            (12..13, "variable".to_string(), String::new()),
+            (17..18, "variable".to_string(), "mutable".to_string()),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_pull_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "let x = y;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let mut requests = fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>(
+        move |params, _| async move {
+            let report = match params.previous_result_id.as_deref() {
+                None => {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".into()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 8),
+                                    lsp::Position::new(0, 9),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::ERROR),
+                                message: "undefined variable 'y'".into(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                }
+                Some(result_id) => {
+                    assert_eq!(result_id, "1");
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: result_id.into(),
+                                },
+                        },
+                    )
+                }
+            };
+            Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+        },
+    );
+
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, usize>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    project
+        .update(cx, |project, cx| project.pull_diagnostics(&buffer, cx))
+        .await
+        .unwrap();
+    requests.next().await.unwrap();
+    assert_eq!(
+        diagnostics(cx),
+        [(8..9, "undefined variable 'y'".to_string())]
+    );
+
+    // When the server reports that nothing changed, the diagnostics are kept.
+    project
+        .update(cx, |project, cx| project.pull_diagnostics(&buffer, cx))
+        .await
+        .unwrap();
+    requests.next().await.unwrap();
+    assert_eq!(
+        diagnostics(cx),
+        [(8..9, "undefined variable 'y'".to_string())]
+    );
+}
+
+#[gpui::test]
+async fn test_document_link_at(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "// See https://zed.dev and b.rs",
+            "b.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let link_requests = Arc::new(AtomicUsize::new(0));
+    fake_server.handle_request::<lsp::request::DocumentLinkRequest, _, _>({
+        let link_requests = link_requests.clone();
+        move |_, _| {
+            link_requests.fetch_add(1, SeqCst);
+            async move {
+                Ok(Some(vec![
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(lsp::Position::new(0, 7), lsp::Position::new(0, 22)),
+                        target: Some("https://zed.dev".parse().unwrap()),
+                        tooltip: None,
+                        data: None,
+                    },
+                    lsp::DocumentLink {
+                        range: lsp::Range::new(
+                            lsp::Position::new(0, 27),
+                            lsp::Position::new(0, 31),
+                        ),
+                        target: None,
+                        tooltip: None,
+                        data: Some(json!("b.rs")),
+                    },
+                ]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::DocumentLinkResolve, _, _>(|link, _| async move {
+        assert_eq!(link.data, Some(json!("b.rs")));
+        Ok(lsp::DocumentLink {
+            target: Some(lsp::Url::from_file_path("/dir/b.rs").unwrap()),
+            ..link
+        })
+    });
+
+    async fn link_at(
+        project: &Model<Project>,
+        buffer: &Model<Buffer>,
+        offset: usize,
+        cx: &mut gpui::TestAppContext,
+    ) -> Option<(Range<usize>, String)> {
+        let link = project
+            .update(cx, |project, cx| {
+                project.document_link_at(buffer, offset, cx)
+            })
+            .await
+            .unwrap()?;
+        let range = buffer.read_with(cx, |buffer, _| {
+            link.range.start.to_offset(buffer)..link.range.end.to_offset(buffer)
+        });
+        Some((range, link.target.to_string()))
+    }
+
+    assert_eq!(
+        link_at(&project, &buffer, 10, cx).await,
+        Some((7..22, "https://zed.dev/".to_string()))
+    );
+    assert_eq!(
+        link_at(&project, &buffer, 29, cx).await,
+        Some((27..31, "file:///dir/b.rs".to_string()))
+    );
+    assert_eq!(link_at(&project, &buffer, 24, cx).await, None);
+    // The links are only requested again once the buffer is edited.
+    assert_eq!(link_requests.load(SeqCst), 1);
+
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "\n")], None, cx));
+    assert_eq!(link_at(&project, &buffer, 30, cx).await, None);
+    assert_eq!(link_requests.load(SeqCst), 2);
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2770,9 +3327,108 @@ async fn test_apply_code_actions_with_commands(cx: &mut gpui::TestAppContext) {
     let transaction = apply.await.unwrap();
     assert!(transaction.0.contains_key(&buffer));
     buffer.update(cx, |buffer, cx| {
-        assert_eq!(buffer.text(), "Xa");
+        assert_eq!(buffer.text(), "Xa");
+        buffer.undo(cx);
+        assert_eq!(buffer.text(), "a");
+    });
+}
+
+#[gpui::test]
+async fn test_apply_code_actions_of_kind(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.ts": "import { b } from './b';\nimport { a } from './a';\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(typescript_lang());
+    let mut fake_language_servers = language_registry.register_fake_lsp_adapter(
+        "TypeScript",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Options(
+                    lsp::CodeActionOptions {
+                        code_action_kinds: Some(vec![
+                            lsp::CodeActionKind::QUICKFIX,
+                            lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                        ]),
+                        ..lsp::CodeActionOptions::default()
+                    },
+                )),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.ts", cx))
+        .await
+        .unwrap();
+
+    let fake_server = fake_language_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeActionRequest, _, _>(|params, _| async move {
+        assert_eq!(
+            params.context.only,
+            Some(vec![lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS])
+        );
+        Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+            lsp::CodeAction {
+                title: "Organize Imports".into(),
+                kind: Some(lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+                edit: Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [(
+                            lsp::Url::from_file_path("/dir/a.ts").unwrap(),
+                            vec![lsp::TextEdit {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 0),
+                                    lsp::Position::new(2, 0),
+                                ),
+                                new_text: "import { a } from './a';\nimport { b } from './b';\n"
+                                    .into(),
+                            }],
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }),
+                ..lsp::CodeAction::default()
+            },
+        )]))
+    });
+
+    let transaction = project
+        .update(cx, |project, cx| {
+            project.apply_code_actions_of_kind(
+                HashSet::from_iter([buffer.clone()]),
+                lsp::CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                true,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    assert!(transaction.0.contains_key(&buffer));
+    buffer.update(cx, |buffer, cx| {
+        assert_eq!(
+            buffer.text(),
+            "import { a } from './a';\nimport { b } from './b';\n"
+        );
         buffer.undo(cx);
-        assert_eq!(buffer.text(), "a");
+        assert_eq!(
+            buffer.text(),
+            "import { b } from './b';\nimport { a } from './a';\n"
+        );
     });
 }
 
@@ -3210,6 +3866,111 @@ async fn test_buffer_identity_across_renames(cx: &mut gpui::TestAppContext) {
     buffer.update(cx, |buffer, _| assert!(!buffer.is_dirty()));
 }
 
+#[gpui::test]
+async fn test_rename_file_with_language_servers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "",
+            "main.rs": "mod a;",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let file_operation_options = lsp::FileOperationRegistrationOptions {
+        filters: vec![lsp::FileOperationFilter {
+            scheme: Some("file".into()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".into(),
+                matches: Some(lsp::FileOperationPatternKind::File),
+                options: None,
+            },
+        }],
+    };
+    let mut fake_servers = language_registry.register_fake_lsp_adapter(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        did_rename: Some(file_operation_options.clone()),
+                        will_rename: Some(file_operation_options),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::WillRenameFiles, _, _>(|params, _| async move {
+        assert_eq!(
+            params.files,
+            [lsp::FileRename {
+                old_uri: "file:///dir/a.rs".into(),
+                new_uri: "file:///dir/b.rs".into(),
+            }]
+        );
+        Ok(Some(lsp::WorkspaceEdit {
+            changes: Some(
+                [(
+                    lsp::Url::from_file_path("/dir/main.rs").unwrap(),
+                    vec![lsp::TextEdit::new(
+                        lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 5)),
+                        "b".into(),
+                    )],
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            ..Default::default()
+        }))
+    });
+
+    let entry_id = project.read_with(cx, |project, cx| {
+        let worktree_id = project.worktrees(cx).next().unwrap().read(cx).id();
+        project
+            .entry_for_path(&(worktree_id, "a.rs").into(), cx)
+            .unwrap()
+            .id
+    });
+    project
+        .update(cx, |project, cx| {
+            project.rename_entry(entry_id, Path::new("b.rs"), cx)
+        })
+        .await
+        .unwrap();
+
+    // The references to the renamed file are updated before it is renamed.
+    buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "mod b;"));
+    assert_eq!(
+        fake_server
+            .receive_notification::<lsp::notification::DidRenameFiles>()
+            .await
+            .files,
+        [lsp::FileRename {
+            old_uri: "file:///dir/a.rs".into(),
+            new_uri: "file:///dir/b.rs".into(),
+        }]
+    );
+}
+
 #[gpui::test]
 async fn test_buffer_deduping(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3989,6 +4750,125 @@ async fn test_search(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_search_open_buffers_only(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "const ONE: usize = 1;",
+            "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+            "four.rs": "const FOUR: usize = one::ONE + three::THREE;",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    let buffer_4 = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/four.rs", cx)
+        })
+        .await
+        .unwrap();
+    buffer_4.update(cx, |buffer, cx| {
+        buffer.edit([(20..28, "two::TWO")], None, cx);
+    });
+
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::text(
+                "TWO",
+                false,
+                true,
+                false,
+                Default::default(),
+                Default::default()
+            )
+            .unwrap()
+            .with_open_buffers_only(true),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("dir/four.rs".to_string(), vec![25..28])]),
+        "Only the unsaved contents of the open buffer should be searched"
+    );
+}
+
+#[gpui::test]
+async fn test_search_multiline_regex(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() {\n    1\n}\n",
+            "two.rs": "fn two() {\n    2\n}\n",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+
+    for query in [r"one\(\) \{\n\s*1", r"(?s)one.*1"] {
+        assert_eq!(
+            search(
+                &project,
+                SearchQuery::regex(
+                    query,
+                    false,
+                    true,
+                    false,
+                    Default::default(),
+                    Default::default()
+                )
+                .unwrap(),
+                cx
+            )
+            .await
+            .unwrap(),
+            HashMap::from_iter([("dir/one.rs".to_string(), vec![3..16])]),
+            "Query {query:?} should match across lines"
+        );
+    }
+}
+
+#[gpui::test]
+async fn test_search_structural_skips_files_without_grammar(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() { foo(1, 2); }",
+            "two.txt": "foo(1, 2)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+
+    // Every loaded buffer is reported, so the files that were skipped are the ones missing.
+    let results = search(
+        &project,
+        SearchQuery::structural("foo($A, $B)", false, Default::default(), Default::default())
+            .unwrap(),
+        cx,
+    )
+    .await
+    .unwrap();
+    assert!(results.contains_key("dir/one.rs"));
+    assert!(
+        !results.contains_key("dir/two.txt"),
+        "Files without a grammar should not be loaded"
+    );
+}
+
 #[gpui::test]
 async fn test_search_with_inclusions(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4750,6 +5630,162 @@ async fn test_multiple_language_server_hovers(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_language_servers_by_feature(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.tsx": "a",
+        }),
+    )
+    .await;
+
+    cx.update(|cx| {
+        SettingsStore::update_global(cx, |settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.languages.insert(
+                    Arc::from("tsx"),
+                    LanguageSettingsContent {
+                        language_servers_by_feature: Some(HashMap::from_iter([
+                            (LanguageServerFeature::Hover, vec!["TailwindServer".into()]),
+                            (
+                                LanguageServerFeature::Definition,
+                                vec!["TailwindServer".into(), "TypeScriptServer".into()],
+                            ),
+                            (
+                                LanguageServerFeature::TypeDefinition,
+                                vec!["TailwindServer".into(), "TypeScriptServer".into()],
+                            ),
+                        ])),
+                        ..Default::default()
+                    },
+                );
+            });
+        })
+    });
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(tsx_lang());
+    let language_server_names = ["TypeScriptServer", "TailwindServer"];
+    let mut fake_tsx_language_servers = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            name: &language_server_names[0],
+            capabilities: lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                type_definition_provider: Some(lsp::TypeDefinitionProviderCapability::Simple(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+    let _a = language_registry.register_fake_lsp_adapter(
+        "tsx",
+        FakeLspAdapter {
+            name: &language_server_names[1],
+            capabilities: lsp::ServerCapabilities {
+                hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..lsp::ServerCapabilities::default()
+            },
+            ..FakeLspAdapter::default()
+        },
+    );
+
+    let buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/a.tsx", cx))
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+
+    for _ in 0..language_server_names.len() {
+        let new_server = fake_tsx_language_servers.next().await.unwrap();
+        let name = new_server.server.name().to_string();
+        new_server.handle_request::<lsp::request::HoverRequest, _, _>({
+            let name = name.clone();
+            move |_, _| {
+                let name = name.clone();
+                async move {
+                    Ok(Some(lsp::Hover {
+                        contents: lsp::HoverContents::Scalar(lsp::MarkedString::String(format!(
+                            "{name} hover"
+                        ))),
+                        range: None,
+                    }))
+                }
+            }
+        });
+        new_server.handle_request::<lsp::request::GotoDefinition, _, _>({
+            let name = name.clone();
+            move |params, _| {
+                let name = name.clone();
+                async move {
+                    assert_eq!(name, "TailwindServer", "definition requested from {name}");
+                    Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                        lsp::Location::new(
+                            params.text_document_position_params.text_document.uri,
+                            lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+                        ),
+                    )))
+                }
+            }
+        });
+        new_server.handle_request::<lsp::request::GotoTypeDefinition, _, _>(move |params, _| {
+            let name = name.clone();
+            async move {
+                assert_eq!(
+                    name, "TypeScriptServer",
+                    "type definition requested from {name}"
+                );
+                Ok(Some(lsp::GotoDefinitionResponse::Scalar(
+                    lsp::Location::new(
+                        params.text_document_position_params.text_document.uri,
+                        lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 1)),
+                    ),
+                )))
+            }
+        });
+    }
+
+    // Only the servers listed for a feature are asked for it.
+    let hovers = project
+        .update(cx, |project, cx| {
+            project.hover(&buffer, Point::new(0, 0), cx)
+        })
+        .await;
+    assert_eq!(
+        hovers
+            .into_iter()
+            .map(|hover| hover.contents.iter().map(|block| &block.text).join("|"))
+            .collect::<Vec<_>>(),
+        vec!["TailwindServer hover"]
+    );
+
+    // Features that a single server provides use the first listed one.
+    let definitions = project
+        .update(cx, |project, cx| {
+            project.definition(&buffer, Point::new(0, 0), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(definitions.len(), 1);
+
+    // Servers that don't provide the feature are skipped.
+    let type_definitions = project
+        .update(cx, |project, cx| {
+            project.type_definition(&buffer, Point::new(0, 0), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(type_definitions.len(), 1);
+}
+
 #[gpui::test]
 async fn test_hovers_with_empty_parts(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use gpui::{Model, ModelContext, Task};
+use language::{Buffer, CachedLspAdapter, LocalFile};
+use lsp::{DiagnosticServerCapabilities, LanguageServer, LanguageServerId};
+use util::ResultExt;
+
+fn diagnostic_options(server: &LanguageServer) -> Option<lsp::DiagnosticOptions> {
+    match server.capabilities().diagnostic_provider? {
+        DiagnosticServerCapabilities::Options(options) => Some(options),
+        DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options)
+        }
+    }
+}
+
+impl Project {
+    /// Requests the diagnostics of the given buffer from its language servers that report them
+    /// on request rather than publishing them, passing the id of the last report so that
+    /// servers can skip computing diagnostics that haven't changed.
+    pub fn pull_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        if !self.is_local() {
+            return Task::ready(Ok(()));
+        }
+
+        let buffer = buffer.read(cx);
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(()));
+        };
+        let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+            return Task::ready(Err(anyhow!("invalid path {abs_path:?}")));
+        };
+
+        let buffer_id = buffer.remote_id();
+        let requests = self
+            .language_servers_for_buffer(buffer, cx)
+            .filter_map(|(adapter, server)| {
+                let options = diagnostic_options(server)?;
+                let server_id = server.server_id();
+                let version = self
+                    .buffer_snapshots
+                    .get(&buffer_id)
+                    .and_then(|snapshots| snapshots.get(&server_id)?.last())
+                    .map(|snapshot| snapshot.version);
+                let previous_result_id = self
+                    .diagnostic_result_ids
+                    .get(&server_id)
+                    .and_then(|result_ids| result_ids.get(&uri))
+                    .cloned();
+                let request = server.request::<lsp::request::DocumentDiagnosticRequest>(
+                    lsp::DocumentDiagnosticParams {
+                        text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                        identifier: options.identifier,
+                        previous_result_id,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    },
+                );
+                Some((adapter.clone(), server_id, version, request))
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(move |this, mut cx| async move {
+            for (adapter, server_id, version, request) in requests {
+                let Some(response) = request.await.log_err() else {
+                    continue;
+                };
+                let (report, related_documents) = match response {
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Full(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Full(
+                            report.full_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    lsp::DocumentDiagnosticReportResult::Report(
+                        lsp::DocumentDiagnosticReport::Unchanged(report),
+                    ) => (
+                        Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                            report.unchanged_document_diagnostic_report,
+                        )),
+                        report.related_documents,
+                    ),
+                    lsp::DocumentDiagnosticReportResult::Partial(partial) => {
+                        (None, partial.related_documents)
+                    }
+                };
+
+                this.update(&mut cx, |this, cx| {
+                    let reports = report
+                        .map(|report| (uri.clone(), version, report))
+                        .into_iter()
+                        .chain(
+                            related_documents
+                                .into_iter()
+                                .flatten()
+                                .map(|(uri, report)| (uri, None, report)),
+                        );
+                    for (uri, version, report) in reports {
+                        this.apply_pulled_diagnostics(
+                            server_id, &adapter, uri, version, report, cx,
+                        )
+                        .log_err();
+                    }
+                })?;
+            }
+            Ok(())
+        })
+    }
+
+    fn apply_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &Arc<CachedLspAdapter>,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        let result_ids = self.diagnostic_result_ids.entry(server_id).or_default();
+        match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                match report.result_id {
+                    Some(result_id) => result_ids.insert(uri.clone(), result_id),
+                    None => result_ids.remove(&uri),
+                };
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                self.update_diagnostics(
+                    server_id,
+                    params,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => {
+                result_ids.insert(uri, report.result_id);
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,25 +1,25 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::Result;
 use client::proto;
-use language::{char_kind, BufferSnapshot};
+use language::{char_kind, BufferSnapshot, StructuralQuery};
 use regex::{Captures, Regex, RegexBuilder};
+use regex_syntax::hir::{Class, Hir, HirKind, Literal};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
     io::{BufRead, BufReader, Read},
     ops::Range,
     path::Path,
-    sync::{Arc, OnceLock},
+    sync::Arc,
 };
 use util::paths::PathMatcher;
 
-static TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX: OnceLock<Regex> = OnceLock::new();
-
 #[derive(Clone, Debug)]
 pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: PathMatcher,
     files_to_exclude: PathMatcher,
+    open_buffers_only: bool,
 }
 
 impl SearchInputs {
@@ -38,6 +38,7 @@ pub enum SearchQuery {
     Text {
         search: Arc<AhoCorasick>,
         replacement: Option<String>,
+        preserve_case: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
@@ -47,12 +48,21 @@ pub enum SearchQuery {
     Regex {
         regex: Regex,
         replacement: Option<String>,
+        preserve_case: bool,
         multiline: bool,
         whole_word: bool,
         case_sensitive: bool,
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    Structural {
+        query: Arc<StructuralQuery>,
+        replacement: Option<String>,
+        preserve_case: bool,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -72,10 +82,12 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            open_buffers_only: false,
         };
         Ok(Self::Text {
             search: Arc::new(search),
             replacement: None,
+            preserve_case: false,
             whole_word,
             case_sensitive,
             include_ignored,
@@ -101,7 +113,7 @@ impl SearchQuery {
             query = word_query
         }
 
-        let multiline = query.contains('\n') || query.contains("\\n");
+        let multiline = can_match_across_lines(&query);
         let regex = RegexBuilder::new(&query)
             .case_insensitive(!case_sensitive)
             .multi_line(multiline)
@@ -110,10 +122,12 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            open_buffers_only: false,
         };
         Ok(Self::Regex {
             regex,
             replacement: None,
+            preserve_case: false,
             multiline,
             whole_word,
             case_sensitive,
@@ -122,8 +136,39 @@ impl SearchQuery {
         })
     }
 
+    /// Creates a query for syntax nodes, given as a tree-sitter pattern or as a template of the
+    /// code to find, matched in every buffer whose language can parse it.
+    pub fn structural(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: PathMatcher,
+        files_to_exclude: PathMatcher,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        let inner = SearchInputs {
+            query: Arc::from(query.as_str()),
+            files_to_exclude,
+            files_to_include,
+            open_buffers_only: false,
+        };
+        Ok(Self::Structural {
+            query: Arc::new(StructuralQuery::new(query)),
+            replacement: None,
+            preserve_case: false,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let query = if message.structural {
+            Self::structural(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -141,7 +186,8 @@ impl SearchQuery {
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
             )
-        }
+        }?;
+        Ok(query.with_open_buffers_only(message.open_buffers_only))
     }
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
@@ -152,22 +198,60 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Structural {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+    /// Restricts the search to the buffers that are open, searching their unsaved contents.
+    pub fn with_open_buffers_only(mut self, open_buffers_only: bool) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Structural { ref mut inner, .. } => {
+                inner.open_buffers_only = open_buffers_only;
+                self
+            }
+        }
+    }
+    /// Makes every replacement follow the case of the text it replaces: all uppercase,
+    /// all lowercase, or capitalized.
+    pub fn with_preserve_case(mut self, new_preserve_case: bool) -> Self {
+        match self {
+            Self::Text {
+                ref mut preserve_case,
+                ..
+            }
+            | Self::Regex {
+                ref mut preserve_case,
+                ..
+            }
+            | Self::Structural {
+                ref mut preserve_case,
+                ..
+            } => {
+                *preserve_case = new_preserve_case;
+                self
+            }
+        }
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            structural: self.is_structural(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
             files_to_include: self.files_to_include().sources().join(","),
             files_to_exclude: self.files_to_exclude().sources().join(","),
+            open_buffers_only: self.open_buffers_only(),
         }
     }
 
@@ -206,39 +290,63 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            // Whether a file contains matching nodes can only be told by parsing it.
+            Self::Structural { .. } => Ok(true),
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Structural { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
+    ///
+    /// Regex replacements can refer to capture groups with `$1` or `${name}`, and change the case
+    /// of the text that follows with `\u`/`\l` (next character) or `\U`/`\L` (until `\E`).
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
-            SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
-            SearchQuery::Regex {
-                regex, replacement, ..
+            SearchQuery::Text {
+                replacement,
+                preserve_case,
+                ..
+            }
+            | SearchQuery::Structural {
+                replacement,
+                preserve_case,
+                ..
             } => {
-                if let Some(replacement) = replacement {
-                    let replacement = TEXT_REPLACEMENT_SPECIAL_CHARACTERS_REGEX
-                        .get_or_init(|| Regex::new(r"\\\\|\\n|\\t").unwrap())
-                        .replace_all(replacement, |c: &Captures| {
-                            match c.get(0).unwrap().as_str() {
-                                r"\\" => "\\",
-                                r"\n" => "\n",
-                                r"\t" => "\t",
-                                x => unreachable!("Unexpected escape sequence: {}", x),
-                            }
-                        });
-                    Some(regex.replace(text, replacement))
+                let replacement = replacement.as_ref()?;
+                if *preserve_case {
+                    Some(Cow::Owned(match_case(text, replacement)))
                 } else {
-                    None
+                    Some(Cow::Owned(replacement.clone()))
                 }
             }
+            SearchQuery::Regex {
+                regex,
+                replacement,
+                preserve_case,
+                ..
+            } => {
+                let replacement = replacement.as_ref()?;
+                let Some(captures) = regex.captures(text) else {
+                    return Some(Cow::Borrowed(text));
+                };
+                let mat = captures.get(0).unwrap();
+                let mut expanded = String::new();
+                expand_replacement(replacement, &captures, &mut expanded);
+                if *preserve_case {
+                    expanded = match_case(mat.as_str(), &expanded);
+                }
+                let mut new_text = String::with_capacity(text.len());
+                new_text.push_str(&text[..mat.start()]);
+                new_text.push_str(&expanded);
+                new_text.push_str(&text[mat.end()..]);
+                Some(Cow::Owned(new_text))
+            }
         }
     }
 
@@ -326,6 +434,16 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Structural { query, .. } => {
+                let range = range_offset..range_offset + rope.len();
+                matches.extend(
+                    query
+                        .matches(buffer, range)
+                        .into_iter()
+                        .map(|range| range.start - range_offset..range.end - range_offset),
+                );
+            }
         }
 
         matches
@@ -343,6 +461,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -350,6 +469,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Structural { .. } => false,
         }
     }
 
@@ -361,13 +481,39 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Structural {
+                include_ignored, ..
+            } => *include_ignored,
+        }
+    }
+
+    pub fn preserve_case(&self) -> bool {
+        match self {
+            Self::Text { preserve_case, .. }
+            | Self::Regex { preserve_case, .. }
+            | Self::Structural { preserve_case, .. } => *preserve_case,
         }
     }
 
+    pub fn open_buffers_only(&self) -> bool {
+        self.as_inner().open_buffers_only
+    }
+
     pub fn is_regex(&self) -> bool {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Self::Structural { .. })
+    }
+
+    pub fn structural_query(&self) -> Option<&Arc<StructuralQuery>> {
+        match self {
+            Self::Structural { query, .. } => Some(query),
+            _ => None,
+        }
+    }
+
     pub fn files_to_include(&self) -> &PathMatcher {
         self.as_inner().files_to_include()
     }
@@ -397,11 +543,146 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. }
+            | Self::Text { inner, .. }
+            | Self::Structural { inner, .. } => inner,
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CaseChange {
+    Upper,
+    Lower,
+}
+
+/// Writes `replacement` to `dst`, expanding escapes and capture group references.
+fn expand_replacement(replacement: &str, captures: &Captures, dst: &mut String) {
+    let mut next_char_case = None;
+    let mut case = None;
+    let mut push =
+        |text: &str, next_char_case: &mut Option<CaseChange>, case: Option<CaseChange>| {
+            for c in text.chars() {
+                match next_char_case.take().or(case) {
+                    Some(CaseChange::Upper) => dst.extend(c.to_uppercase()),
+                    Some(CaseChange::Lower) => dst.extend(c.to_lowercase()),
+                    None => dst.push(c),
+                }
+            }
+        };
+
+    let mut chars = replacement.char_indices().peekable();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => match chars.peek().map(|(_, c)| *c) {
+                Some(escaped @ ('\\' | 'n' | 't')) => {
+                    chars.next();
+                    let text = match escaped {
+                        'n' => "\n",
+                        't' => "\t",
+                        _ => "\\",
+                    };
+                    push(text, &mut next_char_case, case);
+                }
+                Some(escaped @ ('u' | 'l' | 'U' | 'L' | 'E')) => {
+                    chars.next();
+                    match escaped {
+                        'u' => next_char_case = Some(CaseChange::Upper),
+                        'l' => next_char_case = Some(CaseChange::Lower),
+                        'U' => case = Some(CaseChange::Upper),
+                        'L' => case = Some(CaseChange::Lower),
+                        _ => case = None,
+                    }
+                }
+                _ => push("\\", &mut next_char_case, case),
+            },
+            '$' => {
+                let rest = &replacement[ix + 1..];
+                let reference_len = if rest.starts_with('$') {
+                    chars.next();
+                    push("$", &mut next_char_case, case);
+                    continue;
+                } else if rest.starts_with('{') {
+                    rest.find('}').map(|end| end + 1)
+                } else {
+                    let len = rest
+                        .find(|c: char| !(c == '_' || c.is_ascii_alphanumeric()))
+                        .unwrap_or(rest.len());
+                    (len > 0).then_some(len)
+                };
+                match reference_len {
+                    Some(len) => {
+                        let mut group = String::new();
+                        captures.expand(&replacement[ix..ix + 1 + len], &mut group);
+                        push(&group, &mut next_char_case, case);
+                        while chars.next_if(|(next_ix, _)| *next_ix <= ix + len).is_some() {}
+                    }
+                    None => push("$", &mut next_char_case, case),
+                }
+            }
+            _ => {
+                let mut buf = [0; 4];
+                push(c.encode_utf8(&mut buf), &mut next_char_case, case);
+            }
+        }
+    }
+}
+
+/// Whether the regex `query` can match text spanning several lines, because some part of it,
+/// such as `\n`, `\s`, `[^a]`, or `.` with the `s` flag enabled, can match a newline.
+/// Such queries are run against whole buffers instead of one line at a time.
+fn can_match_across_lines(query: &str) -> bool {
+    regex_syntax::Parser::new()
+        .parse(query)
+        .map_or(false, |hir| can_match_newline(&hir))
+}
+
+fn can_match_newline(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => false,
+        HirKind::Literal(Literal(bytes)) => bytes.contains(&b'\n'),
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= '\n' && '\n' <= range.end()),
+        HirKind::Class(Class::Bytes(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| range.start() <= b'\n' && b'\n' <= range.end()),
+        HirKind::Repetition(repetition) => {
+            repetition.max != Some(0) && can_match_newline(&repetition.sub)
+        }
+        HirKind::Capture(capture) => can_match_newline(&capture.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().any(can_match_newline),
+    }
+}
+
+/// Changes the case of `replacement` to follow `text`, when all of its letters are uppercase or
+/// lowercase, or when only its first letter is uppercase.
+fn match_case(text: &str, replacement: &str) -> String {
+    let mut letters = text.chars().filter(|c| c.is_alphabetic());
+    let Some(first_letter) = letters.next() else {
+        return replacement.to_string();
+    };
+    let rest_are_lowercase = letters.clone().all(|c| !c.is_uppercase());
+    let rest_are_uppercase = letters.clone().all(|c| !c.is_lowercase());
+    let has_more_letters = letters.next().is_some();
+
+    if first_letter.is_lowercase() && rest_are_lowercase {
+        replacement.to_lowercase()
+    } else if first_letter.is_uppercase() && rest_are_uppercase && has_more_letters {
+        replacement.to_uppercase()
+    } else if first_letter.is_uppercase() && rest_are_lowercase {
+        let mut chars = replacement.chars();
+        chars
+            .next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    } else {
+        replacement.to_string()
+    }
+}
+
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<PathMatcher> {
     let globs = glob_set
         .split(',')
@@ -458,4 +739,129 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn regex_replacement_with_capture_groups() {
+        let query = SearchQuery::regex(
+            r"(\w+)_(?<second>\w+)",
+            false,
+            true,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+        )
+        .unwrap()
+        .with_replacement("${second}-$1-$$1-\\n".to_string());
+        assert_eq!(
+            query.replacement_for("foo_bar").as_deref(),
+            Some("bar-foo-$1-\n")
+        );
+    }
+
+    #[test]
+    fn regex_replacement_with_case_changes() {
+        let query = SearchQuery::regex(
+            r"(\w+)_(\w+)",
+            false,
+            true,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+        )
+        .unwrap();
+
+        for (replacement, expected) in [
+            (r"\u$1\u$2", "FooBar"),
+            (r"\U$1\E_$2", "FOO_bar"),
+            (r"\L\u$1$2", "Foobar"),
+            (r"$1\l\U$2\E!", "foobAR!"),
+            (r"\x$1", r"\xfoo"),
+        ] {
+            let query = query.clone().with_replacement(replacement.to_string());
+            assert_eq!(
+                query.replacement_for("foo_bar").as_deref(),
+                Some(expected),
+                "replacement {replacement:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn multiline_regex_detection() {
+        for (query, multiline) in [
+            ("foo", false),
+            ("foo\nbar", true),
+            (r"foo\nbar", true),
+            (r"foo\\nbar", false),
+            (r"(?s)foo.*bar", true),
+            (r"(?is:foo.*)bar", true),
+            (r"(?i-s)foo.*bar", false),
+            (r"\(?s\)", false),
+            (r"foo\s+bar", true),
+            (r"foo\W+bar", true),
+            (r"foo[^a]bar", true),
+            (r"foo[a-z\n]bar", true),
+            (r"foo\S+bar", false),
+            (r"foo[^\n]bar", false),
+            (r"foo(\n){0}bar", false),
+        ] {
+            assert_eq!(can_match_across_lines(query), multiline, "query {query:?}");
+        }
+
+        let query = SearchQuery::regex(
+            r"(?s)one.*three",
+            false,
+            true,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+        )
+        .unwrap();
+        assert!(query.detect("one\ntwo\nthree".as_bytes()).unwrap());
+        assert!(!query.detect("two\nthree\none".as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn replacement_preserving_case() {
+        let query = SearchQuery::text(
+            "foo",
+            false,
+            false,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+        )
+        .unwrap()
+        .with_replacement("bar".to_string());
+        assert_eq!(query.replacement_for("Foo").as_deref(), Some("bar"));
+
+        let query = query.with_preserve_case(true);
+        for (text, expected) in [
+            ("foo", "bar"),
+            ("Foo", "Bar"),
+            ("FOO", "BAR"),
+            ("fOo", "bar"),
+        ] {
+            assert_eq!(
+                query.replacement_for(text).as_deref(),
+                Some(expected),
+                "replacing {text:?}"
+            );
+        }
+
+        let query = SearchQuery::regex(
+            r"(\w+)_id",
+            false,
+            false,
+            false,
+            PathMatcher::default(),
+            PathMatcher::default(),
+        )
+        .unwrap()
+        .with_replacement("${1}Key".to_string())
+        .with_preserve_case(true);
+        assert_eq!(query.replacement_for("USER_ID").as_deref(), Some("USERKEY"));
+        assert_eq!(query.replacement_for("user_id").as_deref(), Some("userkey"));
+        assert_eq!(query.replacement_for("User_id").as_deref(), Some("UserKey"));
+    }
 }
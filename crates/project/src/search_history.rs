@@ -1,3 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+use crate::search::SearchQuery;
+
 /// Determines the behavior to use when inserting a new query into the search history.
 #[derive(Default, Debug, Clone, PartialEq)]
 pub enum QueryInsertionBehavior {
@@ -26,9 +30,47 @@ impl SearchHistoryCursor {
     }
 }
 
+/// A query in the search history, along with the options it was searched with.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchHistoryEntry {
+    pub query: String,
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub include_ignored: bool,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub structural: bool,
+}
+
+impl From<String> for SearchHistoryEntry {
+    fn from(query: String) -> Self {
+        Self {
+            query,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<&SearchQuery> for SearchHistoryEntry {
+    fn from(query: &SearchQuery) -> Self {
+        Self {
+            query: query.as_str().to_string(),
+            whole_word: query.whole_word(),
+            case_sensitive: query.case_sensitive(),
+            include_ignored: query.include_ignored(),
+            regex: query.is_regex(),
+            structural: query.is_structural(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SearchHistory {
-    history: Vec<String>,
+    history: Vec<SearchHistoryEntry>,
     max_history_len: Option<usize>,
     insertion_behavior: QueryInsertionBehavior,
 }
@@ -42,24 +84,25 @@ impl SearchHistory {
         }
     }
 
-    pub fn add(&mut self, cursor: &mut SearchHistoryCursor, search_string: String) {
+    pub fn add(&mut self, cursor: &mut SearchHistoryCursor, entry: impl Into<SearchHistoryEntry>) {
+        let entry = entry.into();
         if let Some(selected_ix) = cursor.selection {
-            if self.history.get(selected_ix) == Some(&search_string) {
+            if self.history.get(selected_ix) == Some(&entry) {
                 return;
             }
         }
 
         if self.insertion_behavior == QueryInsertionBehavior::ReplacePreviousIfContains {
             if let Some(previously_searched) = self.history.last_mut() {
-                if search_string.contains(previously_searched.as_str()) {
-                    *previously_searched = search_string;
+                if entry.query.contains(previously_searched.query.as_str()) {
+                    *previously_searched = entry;
                     cursor.selection = Some(self.history.len() - 1);
                     return;
                 }
             }
         }
 
-        self.history.push(search_string);
+        self.history.push(entry);
         if let Some(max_history_len) = self.max_history_len {
             if self.history.len() > max_history_len {
                 self.history.remove(0);
@@ -69,6 +112,20 @@ impl SearchHistory {
         cursor.selection = Some(self.history.len() - 1);
     }
 
+    /// Puts the given entries, e.g. ones saved in a previous session, before the ones added so
+    /// far, dropping the oldest entries past the maximum history length.
+    pub fn restore(&mut self, entries: Vec<SearchHistoryEntry>) {
+        self.history.splice(0..0, entries);
+        if let Some(max_history_len) = self.max_history_len {
+            let excess = self.history.len().saturating_sub(max_history_len);
+            self.history.drain(..excess);
+        }
+    }
+
+    pub fn entries(&self) -> &[SearchHistoryEntry] {
+        &self.history
+    }
+
     pub fn next(&mut self, cursor: &mut SearchHistoryCursor) -> Option<&str> {
         let history_size = self.history.len();
         if history_size == 0 {
@@ -81,13 +138,18 @@ impl SearchHistory {
         }
         let next_index = selected + 1;
         cursor.selection = Some(next_index);
-        Some(&self.history[next_index])
+        Some(&self.history[next_index].query)
     }
 
     pub fn current(&self, cursor: &SearchHistoryCursor) -> Option<&str> {
+        self.current_entry(cursor).map(|entry| entry.query.as_str())
+    }
+
+    /// Returns the selected query, along with the options it was searched with.
+    pub fn current_entry(&self, cursor: &SearchHistoryCursor) -> Option<&SearchHistoryEntry> {
         cursor
             .selection
-            .and_then(|selected_ix| self.history.get(selected_ix).map(|s| s.as_str()))
+            .and_then(|selected_ix| self.history.get(selected_ix))
     }
 
     pub fn previous(&mut self, cursor: &mut SearchHistoryCursor) -> Option<&str> {
@@ -108,7 +170,7 @@ impl SearchHistory {
         };
 
         cursor.selection = Some(prev_index);
-        Some(&self.history[prev_index])
+        Some(&self.history[prev_index].query)
     }
 }
 
@@ -258,4 +320,50 @@ mod tests {
         assert_eq!(search_history.current(&cursor1), None);
         assert_eq!(search_history.current(&cursor2), None);
     }
+
+    #[test]
+    fn test_entry_options() {
+        let mut search_history = SearchHistory::new(None, QueryInsertionBehavior::AlwaysInsert);
+        let mut cursor = SearchHistoryCursor::default();
+
+        let regex_entry = SearchHistoryEntry {
+            query: "fn \\w+".to_string(),
+            case_sensitive: true,
+            regex: true,
+            ..Default::default()
+        };
+        search_history.add(&mut cursor, regex_entry.clone());
+        search_history.add(&mut cursor, "Rust".to_string());
+
+        assert_eq!(search_history.previous(&mut cursor), Some("fn \\w+"));
+        assert_eq!(search_history.current_entry(&cursor), Some(&regex_entry));
+
+        // The same query with other options is a separate entry.
+        search_history.add(&mut cursor, "fn \\w+".to_string());
+        assert_eq!(search_history.entries().len(), 3);
+    }
+
+    #[test]
+    fn test_restore() {
+        const MAX_HISTORY_LEN: usize = 3;
+        let mut search_history =
+            SearchHistory::new(Some(MAX_HISTORY_LEN), QueryInsertionBehavior::AlwaysInsert);
+        let mut cursor = SearchHistoryCursor::default();
+
+        search_history.add(&mut cursor, "TypeScript".to_string());
+        search_history.restore(vec![
+            "Rust".to_string().into(),
+            "Python".to_string().into(),
+            "JavaScript".to_string().into(),
+        ]);
+        assert_eq!(
+            search_history
+                .entries()
+                .iter()
+                .map(|entry| entry.query.as_str())
+                .collect::<Vec<_>>(),
+            ["Python", "JavaScript", "TypeScript"],
+            "Restored entries should come before the new ones, without exceeding the limit"
+        );
+    }
 }
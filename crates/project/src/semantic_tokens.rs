@@ -0,0 +1,219 @@
+use std::{ops::Range, sync::Arc};
+
+use crate::{File, Project};
+use anyhow::{anyhow, Result};
+use gpui::{Model, ModelContext, Task};
+use language::{point_from_lsp, Anchor, Bias, Buffer, BufferSnapshot, LocalFile};
+use lsp::{LanguageServer, LanguageServerId, SemanticTokensServerCapabilities};
+
+/// The semantic tokens of a buffer, as last reported by a language server.
+#[derive(Clone, Debug)]
+pub struct BufferSemanticTokens {
+    /// The tokens, sorted by their position in the buffer.
+    pub tokens: Vec<SemanticToken>,
+    /// The token types and modifiers the tokens refer to.
+    pub legend: Arc<lsp::SemanticTokensLegend>,
+    language_server_id: LanguageServerId,
+    /// Identifies the tokens to the language server, so that the next request only needs to
+    /// return the tokens that changed since.
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SemanticToken {
+    pub range: Range<Anchor>,
+    /// The index of the token's type in the legend.
+    pub token_type: u32,
+    /// A bitset of the indices of the token's modifiers in the legend.
+    pub token_modifiers: u32,
+}
+
+impl BufferSemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.legend
+            .token_types
+            .get(token.token_type as usize)
+            .map(|token_type| token_type.as_str())
+    }
+
+    pub fn token_modifiers<'a>(
+        &'a self,
+        token: &SemanticToken,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        let modifiers = token.token_modifiers;
+        self.legend
+            .token_modifiers
+            .iter()
+            .enumerate()
+            .filter(move |(ix, _)| *ix < 32 && modifiers & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
+fn semantic_tokens_options(server: &LanguageServer) -> Option<lsp::SemanticTokensOptions> {
+    let options = match server.capabilities().semantic_tokens_provider? {
+        SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+        SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            options.semantic_tokens_options
+        }
+    };
+    match options.full {
+        Some(lsp::SemanticTokensFullOptions::Bool(true))
+        | Some(lsp::SemanticTokensFullOptions::Delta { .. }) => Some(options),
+        _ => None,
+    }
+}
+
+impl Project {
+    /// Returns the semantic tokens of the given buffer, or `None` if none of its language servers
+    /// provides them.
+    ///
+    /// When the tokens previously returned for the buffer are given, only the changes since are
+    /// requested from language servers that support it.
+    pub fn semantic_tokens(
+        &self,
+        buffer: &Model<Buffer>,
+        previous: Option<&BufferSemanticTokens>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<BufferSemanticTokens>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(None));
+        }
+
+        let buffer = buffer.read(cx);
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let Some((server, options)) = self
+            .language_servers_for_buffer(buffer, cx)
+            .find_map(|(_, server)| Some((server.clone(), semantic_tokens_options(server)?)))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let Ok(uri) = lsp::Url::from_file_path(&abs_path) else {
+            return Task::ready(Err(anyhow!("invalid path {abs_path:?}")));
+        };
+
+        let supports_delta = matches!(
+            options.full,
+            Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) })
+        );
+        let previous = previous
+            .filter(|previous| {
+                supports_delta
+                    && previous.language_server_id == server.server_id()
+                    && previous.result_id.is_some()
+            })
+            .cloned();
+        let legend = Arc::new(options.legend);
+        let snapshot = buffer.snapshot();
+        cx.spawn(move |_, cx| async move {
+            let text_document = lsp::TextDocumentIdentifier::new(uri);
+            let (result_id, data) = if let Some(previous) = previous {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                        lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id: previous.result_id.unwrap_or_default(),
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    )
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => (
+                        delta.result_id,
+                        apply_semantic_tokens_edits(previous.data, delta.edits),
+                    ),
+                    Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta { edits }) => {
+                        (None, apply_semantic_tokens_edits(previous.data, edits))
+                    }
+                    None => return Ok(None),
+                }
+            } else {
+                let response = server
+                    .request::<lsp::request::SemanticTokensFullRequest>(lsp::SemanticTokensParams {
+                        text_document,
+                        work_done_progress_params: Default::default(),
+                        partial_result_params: Default::default(),
+                    })
+                    .await?;
+                match response {
+                    Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                        (tokens.result_id, tokens.data)
+                    }
+                    Some(lsp::SemanticTokensResult::Partial(partial)) => (None, partial.data),
+                    None => return Ok(None),
+                }
+            };
+
+            let (tokens, data) = cx
+                .background_executor()
+                .spawn(async move { (decode_semantic_tokens(&data, &snapshot), data) })
+                .await;
+            Ok(Some(BufferSemanticTokens {
+                tokens,
+                legend,
+                language_server_id: server.server_id(),
+                result_id,
+                data,
+            }))
+        })
+    }
+}
+
+/// Applies the edits of a delta response to the previously returned tokens. The edits' offsets
+/// count the integers of the encoded tokens, five per token.
+fn apply_semantic_tokens_edits(
+    mut data: Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) -> Vec<lsp::SemanticToken> {
+    edits.sort_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize / 5).min(data.len());
+        let end = ((edit.start + edit.delete_count) as usize / 5).clamp(start, data.len());
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+    data
+}
+
+/// Resolves the relative positions of the encoded tokens in the buffer they were computed for.
+fn decode_semantic_tokens(
+    data: &[lsp::SemanticToken],
+    snapshot: &BufferSnapshot,
+) -> Vec<SemanticToken> {
+    let mut tokens = Vec::with_capacity(data.len());
+    let mut line = 0;
+    let mut start = 0;
+    for token in data {
+        if token.delta_line == 0 {
+            start += token.delta_start;
+        } else {
+            line += token.delta_line;
+            start = token.delta_start;
+        }
+        if token.length == 0 {
+            continue;
+        }
+
+        let start_point =
+            snapshot.clip_point_utf16(point_from_lsp(lsp::Position::new(line, start)), Bias::Left);
+        let end_point = snapshot.clip_point_utf16(
+            point_from_lsp(lsp::Position::new(line, start + token.length)),
+            Bias::Left,
+        );
+        tokens.push(SemanticToken {
+            range: snapshot.anchor_after(start_point)..snapshot.anchor_before(end_point),
+            token_type: token.token_type,
+            token_modifiers: token.token_modifiers_bitset,
+        });
+    }
+    tokens
+}
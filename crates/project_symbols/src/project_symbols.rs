@@ -11,7 +11,10 @@ use std::{borrow::Cow, cmp::Reverse, sync::Arc};
 use theme::ActiveTheme;
 use util::ResultExt;
 use workspace::{
-    ui::{v_flex, Color, Label, LabelCommon, LabelLike, ListItem, ListItemSpacing, Selectable},
+    ui::{
+        h_flex, v_flex, Color, Icon, IconName, IconSize, Label, LabelCommon, LabelLike, LabelSize,
+        ListItem, ListItemSpacing, Selectable,
+    },
     Workspace,
 };
 
@@ -222,6 +225,7 @@ impl PickerDelegate for ProjectSymbolsDelegate {
         }
         let label = symbol.label.text.clone();
         let path = path.to_string().clone();
+        let container_name = symbol.container_name.clone();
 
         let highlights = gpui::combine_highlights(
             string_match
@@ -241,13 +245,27 @@ impl PickerDelegate for ProjectSymbolsDelegate {
                 .inset(true)
                 .spacing(ListItemSpacing::Sparse)
                 .selected(selected)
+                .start_slot(
+                    Icon::new(icon_for_symbol_kind(symbol.kind))
+                        .size(IconSize::Small)
+                        .color(Color::Muted),
+                )
                 .child(
                     v_flex()
                         .child(
-                            LabelLike::new().child(
-                                StyledText::new(label)
-                                    .with_highlights(&cx.text_style().clone(), highlights),
-                            ),
+                            h_flex()
+                                .gap_2()
+                                .child(
+                                    LabelLike::new().child(
+                                        StyledText::new(label)
+                                            .with_highlights(&cx.text_style().clone(), highlights),
+                                    ),
+                                )
+                                .children(container_name.map(|container_name| {
+                                    Label::new(container_name)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted)
+                                })),
                         )
                         .child(Label::new(path).color(Color::Muted)),
                 ),
@@ -255,6 +273,28 @@ impl PickerDelegate for ProjectSymbolsDelegate {
     }
 }
 
+fn icon_for_symbol_kind(kind: lsp::SymbolKind) -> IconName {
+    match kind {
+        lsp::SymbolKind::FILE => IconName::File,
+        lsp::SymbolKind::MODULE | lsp::SymbolKind::NAMESPACE | lsp::SymbolKind::PACKAGE => {
+            IconName::Library
+        }
+        lsp::SymbolKind::CONSTANT | lsp::SymbolKind::ENUM_MEMBER | lsp::SymbolKind::NUMBER => {
+            IconName::Hash
+        }
+        lsp::SymbolKind::STRING => IconName::Quote,
+        lsp::SymbolKind::VARIABLE | lsp::SymbolKind::FIELD | lsp::SymbolKind::PROPERTY => {
+            IconName::TextCursor
+        }
+        lsp::SymbolKind::CLASS
+        | lsp::SymbolKind::STRUCT
+        | lsp::SymbolKind::INTERFACE
+        | lsp::SymbolKind::ENUM
+        | lsp::SymbolKind::OBJECT => IconName::FileCode,
+        _ => IconName::Code,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,11 +339,12 @@ mod tests {
 
         // Set up fake language server to return fuzzy matches against
         // a fixed set of symbol names.
-        let fake_symbols = [
+        let mut fake_symbols = [
             symbol("one", "/external"),
             symbol("ton", "/dir/test.rs"),
             symbol("uno", "/dir/test.rs"),
         ];
+        fake_symbols[1].container_name = Some("Tons".to_string());
         let fake_server = fake_servers.next().await.unwrap();
         fake_server.handle_request::<lsp::WorkspaceSymbolRequest, _, _>(
             move |params: lsp::WorkspaceSymbolParams, cx| {
@@ -375,6 +416,12 @@ mod tests {
             assert_eq!(delegate.matches.len(), 2);
             assert_eq!(delegate.matches[0].string, "ton");
             assert_eq!(delegate.matches[1].string, "one");
+            assert_eq!(
+                delegate.symbols[delegate.matches[0].candidate_id]
+                    .container_name
+                    .as_deref(),
+                Some("Tons")
+            );
         });
 
         // Spawn more updates such that in the end, there are again no matches.
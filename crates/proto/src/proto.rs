@@ -396,6 +396,10 @@ messages!(
     (RestartLanguageServers, Foreground),
     (LinkedEditingRange, Background),
     (LinkedEditingRangeResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
+    (GetDocumentColors, Background),
+    (GetDocumentColorsResponse, Background),
     (AdvertiseContexts, Foreground),
     (OpenContext, Foreground),
     (OpenContextResponse, Foreground),
@@ -446,6 +450,8 @@ request_messages!(
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (LinkedEditingRange, LinkedEditingRangeResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
+    (GetDocumentColors, GetDocumentColorsResponse),
     (ListRemoteDirectory, ListRemoteDirectoryResponse),
     (UpdateDevServerProject, Ack),
     (GetUsers, UsersResponse),
@@ -549,7 +555,9 @@ entity_messages!(
     GetCompletions,
     GetDefinition,
     GetImplementation,
+    GetDocumentColors,
     GetDocumentHighlights,
+    GetFoldingRanges,
     GetHover,
     GetProjectSymbols,
     GetReferences,
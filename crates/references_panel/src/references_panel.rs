@@ -0,0 +1,664 @@
+mod references_panel_settings;
+
+use std::{ops::Range, sync::Arc};
+
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, Action, AppContext, AsyncWindowContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, KeyContext, Model, ParentElement, Pixels, Render,
+    SharedString, StatefulInteractiveElement, Styled, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
+};
+use language::{Buffer, Location, OffsetRangeExt, Point, ToPoint};
+use menu::{Confirm, SelectFirst, SelectLast, SelectNext, SelectPrev};
+use project::{Fs, Project};
+use references_panel_settings::ReferencesPanelSettings;
+use settings::Settings;
+use ui::{
+    h_flex, v_flex, ActiveTheme, Color, Icon, IconButton, IconName, IconSize, Label, LabelCommon,
+    LabelSize, ListItem, Selectable, Tooltip,
+};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPanelState, DockPosition, Panel, PanelEvent},
+    item::ItemHandle,
+    Workspace,
+};
+
+actions!(
+    references_panel,
+    [ToggleFocus, FindReferences, Refresh, RemoveReference]
+);
+
+const REFERENCES_PANEL_KEY: &str = "ReferencesPanel";
+
+pub fn init(cx: &mut AppContext) {
+    ReferencesPanelSettings::register(cx);
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<ReferencesPanel>(cx);
+        });
+        workspace.register_action(ReferencesPanel::find_references);
+    })
+    .detach();
+}
+
+/// Lists the references to a symbol, grouped by file. Unlike the multibuffer opened by
+/// `editor::FindAllReferences`, the list stays in place while navigating to its results.
+pub struct ReferencesPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    dock_state: DockPanelState,
+    query: Option<ReferencesQuery>,
+    files: Vec<FileReferences>,
+    /// The file index and reference index of the selected reference.
+    selected: Option<(usize, usize)>,
+    refresh_task: Task<()>,
+}
+
+/// The symbol whose references are listed, so that they can be refreshed.
+struct ReferencesQuery {
+    symbol: SharedString,
+    buffer: Model<Buffer>,
+    position: language::Anchor,
+}
+
+struct FileReferences {
+    buffer: Model<Buffer>,
+    path: SharedString,
+    references: Vec<Reference>,
+}
+
+struct Reference {
+    range: Range<language::Anchor>,
+    row: u32,
+    line: SharedString,
+}
+
+impl ReferencesPanel {
+    pub async fn load(
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> anyhow::Result<View<Self>> {
+        let dock_state =
+            DockPanelState::load(REFERENCES_PANEL_KEY, cx.background_executor().clone()).await;
+        workspace.update(&mut cx, |workspace, cx| {
+            Self::new(workspace, dock_state, cx)
+        })
+    }
+
+    fn new(
+        workspace: &mut Workspace,
+        dock_state: DockPanelState,
+        cx: &mut ViewContext<Workspace>,
+    ) -> View<Self> {
+        let workspace_handle = cx.view().downgrade();
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        cx.new_view(|cx| Self {
+            workspace: workspace_handle,
+            project,
+            fs,
+            focus_handle: cx.focus_handle(),
+            dock_state,
+            query: None,
+            files: Vec::new(),
+            selected: None,
+            refresh_task: Task::ready(()),
+        })
+    }
+
+    /// Lists the references to the symbol under the cursor of the active editor in the panel.
+    fn find_references(
+        workspace: &mut Workspace,
+        _: &FindReferences,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some(panel) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+        let Some((buffer, position)) = editor.update(cx, |editor, cx| {
+            let head = editor.selections.newest_anchor().head();
+            editor.buffer().read(cx).text_anchor_for_position(head, cx)
+        }) else {
+            return;
+        };
+
+        let snapshot = buffer.read(cx).snapshot();
+        let (word_range, _) = snapshot.surrounding_word(position);
+        let symbol = snapshot.text_for_range(word_range).collect::<String>();
+        workspace.open_panel::<Self>(cx);
+        panel.update(cx, |panel, cx| {
+            panel.query = Some(ReferencesQuery {
+                symbol: symbol.into(),
+                buffer,
+                position,
+            });
+            panel.files.clear();
+            panel.selected = None;
+            panel.refresh(&Refresh, cx);
+        });
+    }
+
+    /// Queries the language servers for the references again, replacing the listed ones.
+    fn refresh(&mut self, _: &Refresh, cx: &mut ViewContext<Self>) {
+        let Some(query) = self.query.as_ref() else {
+            return;
+        };
+        let references = self.project.update(cx, |project, cx| {
+            project.references(&query.buffer, query.position, cx)
+        });
+        self.refresh_task = cx.spawn(|panel, mut cx| async move {
+            let Some(locations) = references.await.log_err() else {
+                return;
+            };
+            panel
+                .update(&mut cx, |panel, cx| panel.set_references(locations, cx))
+                .ok();
+        });
+    }
+
+    fn set_references(&mut self, locations: Vec<Location>, cx: &mut ViewContext<Self>) {
+        let mut locations = locations
+            .into_iter()
+            .map(|location| {
+                let buffer = location.buffer.read(cx);
+                let path = buffer
+                    .file()
+                    .map(|file| file.full_path(cx).to_string_lossy().to_string())
+                    .unwrap_or_else(|| "untitled".to_string());
+                let start = location.range.start.to_point(buffer);
+                (path, start, location)
+            })
+            .collect::<Vec<_>>();
+        locations.sort_by(|(path_a, start_a, _), (path_b, start_b, _)| {
+            path_a.cmp(path_b).then(start_a.cmp(start_b))
+        });
+
+        self.files.clear();
+        for (path, start, location) in locations {
+            let buffer = location.buffer.read(cx);
+            let line_range =
+                Point::new(start.row, 0)..Point::new(start.row, buffer.line_len(start.row));
+            let line = buffer.text_for_range(line_range).collect::<String>();
+            let reference = Reference {
+                range: location.range,
+                row: start.row,
+                line: line.trim().to_string().into(),
+            };
+            match self.files.last_mut() {
+                Some(file) if file.buffer == location.buffer => file.references.push(reference),
+                _ => self.files.push(FileReferences {
+                    buffer: location.buffer,
+                    path: path.into(),
+                    references: vec![reference],
+                }),
+            }
+        }
+        self.selected = None;
+        cx.notify();
+    }
+
+    /// Removes the given reference from the list, along with its file if it was the last one.
+    fn remove_reference(
+        &mut self,
+        file_ix: usize,
+        reference_ix: usize,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(file) = self.files.get_mut(file_ix) else {
+            return;
+        };
+        if reference_ix >= file.references.len() {
+            return;
+        }
+        file.references.remove(reference_ix);
+        if file.references.is_empty() {
+            self.files.remove(file_ix);
+        }
+
+        let entries = self.entries();
+        self.selected = self.selected.and_then(|selected| {
+            let ix = entries
+                .iter()
+                .position(|entry| *entry >= selected)
+                .or_else(|| entries.len().checked_sub(1))?;
+            Some(entries[ix])
+        });
+        cx.notify();
+    }
+
+    fn remove_selected_reference(&mut self, _: &RemoveReference, cx: &mut ViewContext<Self>) {
+        if let Some((file_ix, reference_ix)) = self.selected {
+            self.remove_reference(file_ix, reference_ix, cx);
+        }
+    }
+
+    /// The file index and reference index of every listed reference, in order.
+    fn entries(&self) -> Vec<(usize, usize)> {
+        self.files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_ix, file)| {
+                (0..file.references.len()).map(move |reference_ix| (file_ix, reference_ix))
+            })
+            .collect()
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        let entries = self.entries();
+        let ix = match self
+            .selected
+            .and_then(|selected| entries.iter().position(|e| *e == selected))
+        {
+            Some(ix) => (ix + 1).min(entries.len().saturating_sub(1)),
+            None => 0,
+        };
+        self.selected = entries.get(ix).copied();
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        let entries = self.entries();
+        let ix = match self
+            .selected
+            .and_then(|selected| entries.iter().position(|e| *e == selected))
+        {
+            Some(ix) => ix.saturating_sub(1),
+            None => entries.len().saturating_sub(1),
+        };
+        self.selected = entries.get(ix).copied();
+        cx.notify();
+    }
+
+    fn select_first(&mut self, _: &SelectFirst, cx: &mut ViewContext<Self>) {
+        self.selected = self.entries().first().copied();
+        cx.notify();
+    }
+
+    fn select_last(&mut self, _: &SelectLast, cx: &mut ViewContext<Self>) {
+        self.selected = self.entries().last().copied();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some((file_ix, reference_ix)) = self.selected {
+            self.open_reference(file_ix, reference_ix, false, cx);
+        }
+    }
+
+    /// Opens the given reference in the active pane, keeping the panel's list as it is.
+    fn open_reference(
+        &mut self,
+        file_ix: usize,
+        reference_ix: usize,
+        focus_editor: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(file) = self.files.get(file_ix) else {
+            return;
+        };
+        let Some(reference) = file.references.get(reference_ix) else {
+            return;
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        let buffer = file.buffer.clone();
+        let range = reference.range.to_offset(buffer.read(cx));
+        self.selected = Some((file_ix, reference_ix));
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let editor =
+                workspace.open_project_item::<Editor>(pane, buffer, true, focus_editor, cx);
+            editor.update(cx, |editor, cx| {
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([range])
+                });
+            });
+        });
+        cx.notify();
+    }
+
+    fn dispatch_context(&self) -> KeyContext {
+        let mut dispatch_context = KeyContext::new_with_defaults();
+        dispatch_context.add("ReferencesPanel");
+        dispatch_context.add("menu");
+        dispatch_context
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let title = match &self.query {
+            Some(query) => {
+                let count = self.entries().len();
+                let noun = if count == 1 {
+                    "reference"
+                } else {
+                    "references"
+                };
+                format!("{count} {noun} to {}", query.symbol)
+            }
+            None => "References".to_string(),
+        };
+        h_flex()
+            .justify_between()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(Label::new(title).size(LabelSize::Small).color(Color::Muted))
+            .child(
+                IconButton::new("refresh-references", IconName::RotateCw)
+                    .icon_size(IconSize::Small)
+                    .disabled(self.query.is_none())
+                    .tooltip(|cx| Tooltip::for_action("Refresh References", &Refresh, cx))
+                    .on_click(cx.listener(|panel, _, cx| panel.refresh(&Refresh, cx))),
+            )
+    }
+
+    fn render_file(
+        &self,
+        file_ix: usize,
+        file: &FileReferences,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        v_flex()
+            .child(
+                ListItem::new(("references-file", file_ix))
+                    .start_slot(
+                        Icon::new(IconName::File)
+                            .size(IconSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(
+                        h_flex().gap_2().child(Label::new(file.path.clone())).child(
+                            Label::new(file.references.len().to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    ),
+            )
+            .children(
+                file.references
+                    .iter()
+                    .enumerate()
+                    .map(|(reference_ix, reference)| {
+                        self.render_reference(file_ix, reference_ix, reference, cx)
+                    }),
+            )
+    }
+
+    fn render_reference(
+        &self,
+        file_ix: usize,
+        reference_ix: usize,
+        reference: &Reference,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let id = SharedString::from(format!("reference-{file_ix}-{reference_ix}"));
+        ListItem::new(id)
+            .indent_level(1)
+            .selected(self.selected == Some((file_ix, reference_ix)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        Label::new((reference.row + 1).to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .child(Label::new(reference.line.clone()).size(LabelSize::Small)),
+            )
+            .end_hover_slot(
+                IconButton::new("remove-reference", IconName::Close)
+                    .icon_size(IconSize::XSmall)
+                    .tooltip(|cx| Tooltip::for_action("Remove Reference", &RemoveReference, cx))
+                    .on_click(cx.listener(move |panel, _, cx| {
+                        panel.remove_reference(file_ix, reference_ix, cx)
+                    })),
+            )
+            .on_click(cx.listener(move |panel, _, cx| {
+                panel.open_reference(file_ix, reference_ix, true, cx)
+            }))
+    }
+}
+
+impl Panel for ReferencesPanel {
+    fn persistent_name() -> &'static str {
+        "References Panel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        ReferencesPanelSettings::get_global(cx).dock.into()
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<ReferencesPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.dock_state
+            .size(self.position(cx), ReferencesPanelSettings::get_global(cx))
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        let position = self.position(cx);
+        self.dock_state.set_size(position, size, cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        ReferencesPanelSettings::get_global(cx)
+            .button
+            .then(|| IconName::MagnifyingGlass)
+    }
+
+    fn icon_tooltip(&self, _: &WindowContext) -> Option<&'static str> {
+        Some("References Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+
+    fn starts_open(&self, _: &WindowContext) -> bool {
+        self.dock_state.active()
+    }
+
+    fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
+        self.dock_state.set_active(active, cx);
+    }
+}
+
+impl FocusableView for ReferencesPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for ReferencesPanel {}
+
+impl Render for ReferencesPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let content = if self.files.is_empty() {
+            let label = if self.query.is_some() {
+                "No references found".to_string()
+            } else {
+                let keystroke = cx.keystroke_text_for(&FindReferences);
+                format!("Find the references to the symbol under the cursor with {keystroke}")
+            };
+            v_flex()
+                .id("references-empty")
+                .flex_1()
+                .justify_center()
+                .items_center()
+                .child(Label::new(label).color(Color::Muted))
+        } else {
+            v_flex()
+                .id("references")
+                .flex_1()
+                .overflow_y_scroll()
+                .py_1()
+                .children(
+                    self.files
+                        .iter()
+                        .enumerate()
+                        .map(|(file_ix, file)| self.render_file(file_ix, file, cx)),
+                )
+        };
+
+        v_flex()
+            .key_context(self.dispatch_context())
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::refresh))
+            .on_action(cx.listener(Self::remove_selected_reference))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::select_first))
+            .on_action(cx.listener(Self::select_last))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .child(self.render_header(cx))
+            .child(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use editor::test::editor_lsp_test_context::EditorLspTestContext;
+    use futures::StreamExt;
+    use gpui::TestAppContext;
+    use indoc::indoc;
+
+    #[gpui::test]
+    async fn test_references_panel(cx: &mut TestAppContext) {
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                references_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+        cx.update(|cx| init(cx));
+        let panel = cx.update_workspace(|workspace, cx| {
+            let panel = ReferencesPanel::new(workspace, DockPanelState::new("test"), cx);
+            workspace.add_panel(panel.clone(), cx);
+            panel
+        });
+
+        cx.set_state(indoc! {"
+            fn ˇfoo() {}
+            fn bar() {
+                foo();
+                foo();
+            }
+        "});
+        let declaration = cx.lsp_range(indoc! {"
+            fn «foo»() {}
+            fn bar() {
+                foo();
+                foo();
+            }
+        "});
+        let first_call = cx.lsp_range(indoc! {"
+            fn foo() {}
+            fn bar() {
+                «foo»();
+                foo();
+            }
+        "});
+        let second_call = cx.lsp_range(indoc! {"
+            fn foo() {}
+            fn bar() {
+                foo();
+                «foo»();
+            }
+        "});
+        let mut requests =
+            cx.handle_request::<lsp::request::References, _, _>(move |url, _, _| async move {
+                // The references are listed in order, whichever order they're found in.
+                Ok(Some(vec![
+                    lsp::Location::new(url.clone(), second_call),
+                    lsp::Location::new(url.clone(), declaration),
+                    lsp::Location::new(url, first_call),
+                ]))
+            });
+        cx.update_workspace(|workspace, cx| {
+            ReferencesPanel::find_references(workspace, &FindReferences, cx)
+        });
+        requests.next().await;
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            let panel = panel.read(cx);
+            assert_eq!(panel.query.as_ref().unwrap().symbol.as_ref(), "foo");
+            assert_eq!(panel.files.len(), 1);
+            let references = panel.files[0]
+                .references
+                .iter()
+                .map(|reference| (reference.row, reference.line.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                references,
+                [
+                    (0, "fn foo() {}".to_string()),
+                    (2, "foo();".to_string()),
+                    (3, "foo();".to_string()),
+                ]
+            );
+        });
+
+        // Opening a reference keeps the list as it is.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.select_next(&SelectNext, cx);
+                panel.select_next(&SelectNext, cx);
+                panel.confirm(&Confirm, cx);
+            })
+        });
+        cx.assert_editor_state(indoc! {"
+            fn foo() {}
+            fn bar() {
+                «fooˇ»();
+                foo();
+            }
+        "});
+        cx.update(|cx| assert_eq!(panel.read(cx).entries().len(), 3));
+
+        // Removing a reference selects the next one, and removing the last reference of a
+        // file removes the file.
+        cx.update(|cx| {
+            panel.update(cx, |panel, cx| {
+                panel.remove_selected_reference(&RemoveReference, cx);
+                assert_eq!(panel.selected, Some((0, 1)));
+                assert_eq!(panel.files[0].references[1].row, 3);
+
+                panel.remove_selected_reference(&RemoveReference, cx);
+                assert_eq!(panel.selected, Some((0, 0)));
+                panel.remove_selected_reference(&RemoveReference, cx);
+                assert!(panel.files.is_empty());
+                assert_eq!(panel.selected, None);
+            })
+        });
+    }
+}
@@ -0,0 +1,30 @@
+use anyhow;
+use serde::Deserialize;
+use settings::{Settings, SettingsSources};
+use std::ops::Deref;
+use workspace::dock::{DockPanelSettings, DockPanelSettingsContent};
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+pub struct ReferencesPanelSettings(pub DockPanelSettings);
+
+impl Deref for ReferencesPanelSettings {
+    type Target = DockPanelSettings;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Settings for ReferencesPanelSettings {
+    const KEY: Option<&'static str> = Some("references_panel");
+
+    type FileContent = DockPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
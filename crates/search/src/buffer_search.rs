@@ -1,9 +1,11 @@
 mod registrar;
 
 use crate::{
-    search_bar::render_nav_button, FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll,
-    ReplaceNext, SearchOptions, SelectAllMatches, SelectNextMatch, SelectPrevMatch,
-    ToggleCaseSensitive, ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
+    persistence::{load_search_history, save_search_history, SearchHistoryKind},
+    search_bar::render_nav_button,
+    FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
+    SelectAllMatches, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, TogglePreserveCase,
+    ToggleRegex, ToggleReplace, ToggleSelection, ToggleWholeWord,
 };
 use any_vec::AnyVec;
 use collections::HashMap;
@@ -14,12 +16,14 @@ use editor::{
 use futures::channel::oneshot;
 use gpui::{
     actions, div, impl_actions, Action, AppContext, ClickEvent, EventEmitter, FocusableView, Hsla,
-    InteractiveElement as _, IntoElement, KeyContext, ParentElement as _, Render, ScrollHandle,
-    Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext as _, WindowContext,
+    InteractiveElement as _, IntoElement, KeyContext, Model, ParentElement as _, Render,
+    ScrollHandle, Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext as _,
+    WindowContext,
 };
 use project::{
     search::SearchQuery,
-    search_history::{SearchHistory, SearchHistoryCursor},
+    search_history::{SearchHistory, SearchHistoryCursor, SearchHistoryEntry},
+    Project,
 };
 use serde::Deserialize;
 use settings::Settings;
@@ -39,7 +43,6 @@ use registrar::{ForDeployed, ForDismissed, SearchActionsRegistrar, WithResults};
 
 const MIN_INPUT_WIDTH_REMS: f32 = 10.;
 const MAX_INPUT_WIDTH_REMS: f32 = 30.;
-const MAX_BUFFER_SEARCH_HISTORY_SIZE: usize = 50;
 
 #[derive(PartialEq, Clone, Deserialize)]
 pub struct Deploy {
@@ -70,8 +73,39 @@ pub enum Event {
 }
 
 pub fn init(cx: &mut AppContext) {
-    cx.observe_new_views(|workspace: &mut Workspace, _| BufferSearchBar::register(workspace))
-        .detach();
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        BufferSearchBar::register(workspace);
+        persist_search_history(workspace, cx);
+    })
+    .detach();
+}
+
+/// Restores the buffer search history saved for the workspace into its project, where the
+/// search bars of all of its panes share it, and saves the history whenever it changes.
+fn persist_search_history(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let search_history = workspace.project().read(cx).buffer_search_history().clone();
+    if let Some(workspace_id) = workspace.database_id() {
+        let entries = load_search_history(SearchHistoryKind::Buffer, workspace_id, cx);
+        let search_history = search_history.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let entries = entries.await;
+            search_history.update(&mut cx, |search_history, _| search_history.restore(entries))
+        })
+        .detach_and_log_err(cx);
+    }
+
+    // The workspace is only assigned an id once it's serialized, so it's looked up on save.
+    cx.observe(&search_history, |workspace, search_history, cx| {
+        if let Some(workspace_id) = workspace.database_id() {
+            save_search_history(
+                SearchHistoryKind::Buffer,
+                workspace_id,
+                search_history.read(cx).entries(),
+                cx,
+            );
+        }
+    })
+    .detach();
 }
 
 pub struct BufferSearchBar {
@@ -89,7 +123,7 @@ pub struct BufferSearchBar {
     default_options: SearchOptions,
     query_contains_error: bool,
     dismissed: bool,
-    search_history: SearchHistory,
+    search_history: Model<SearchHistory>,
     search_history_cursor: SearchHistoryCursor,
     replace_enabled: bool,
     selection_search_enabled: bool,
@@ -327,6 +361,12 @@ impl Render for BufferSearchBar {
                             &self.replacement_editor,
                             cx.theme().colors().text,
                             cx,
+                        ))
+                        .child(self.render_search_option_button(
+                            SearchOptions::PRESERVE_CASE,
+                            cx.listener(|this, _, cx| {
+                                this.toggle_preserve_case(&TogglePreserveCase, cx)
+                            }),
                         )),
                 )
                 .child(
@@ -369,6 +409,7 @@ impl Render for BufferSearchBar {
                     .when(in_replace, |this| {
                         this.on_action(cx.listener(Self::replace_next))
                             .on_action(cx.listener(Self::replace_all))
+                            .on_action(cx.listener(Self::toggle_preserve_case))
                     })
             })
             .when(self.supported_options().case, |this| {
@@ -519,10 +560,7 @@ impl BufferSearchBar {
             pending_search: None,
             query_contains_error: false,
             dismissed: true,
-            search_history: SearchHistory::new(
-                Some(MAX_BUFFER_SEARCH_HISTORY_SIZE),
-                project::search_history::QueryInsertionBehavior::ReplacePreviousIfContains,
-            ),
+            search_history: cx.new_model(|_| Project::new_buffer_search_history()),
             search_history_cursor: Default::default(),
             active_search: None,
             replace_enabled: false,
@@ -533,6 +571,12 @@ impl BufferSearchBar {
         }
     }
 
+    /// Replaces the bar's own search history with a shared one, such as the project's.
+    pub fn set_search_history(&mut self, search_history: Model<SearchHistory>) {
+        self.search_history = search_history;
+        self.search_history_cursor.reset();
+    }
+
     pub fn is_dismissed(&self) -> bool {
         self.dismissed
     }
@@ -885,6 +929,10 @@ impl BufferSearchBar {
         self.toggle_search_option(SearchOptions::REGEX, cx)
     }
 
+    fn toggle_preserve_case(&mut self, _: &TogglePreserveCase, cx: &mut ViewContext<Self>) {
+        self.toggle_search_option(SearchOptions::PRESERVE_CASE, cx)
+    }
+
     fn clear_active_searchable_item_matches(&mut self, cx: &mut WindowContext) {
         if let Some(active_searchable_item) = self.active_searchable_item.as_ref() {
             self.active_match_index = None;
@@ -937,7 +985,11 @@ impl BufferSearchBar {
                         Default::default(),
                         Default::default(),
                     ) {
-                        Ok(query) => query.with_replacement(self.replacement(cx)),
+                        Ok(query) => query
+                            .with_replacement(self.replacement(cx))
+                            .with_preserve_case(
+                                self.search_options.contains(SearchOptions::PRESERVE_CASE),
+                            ),
                         Err(_) => {
                             self.query_contains_error = true;
                             self.clear_active_searchable_item_matches(cx);
@@ -954,7 +1006,11 @@ impl BufferSearchBar {
                         Default::default(),
                         Default::default(),
                     ) {
-                        Ok(query) => query.with_replacement(self.replacement(cx)),
+                        Ok(query) => query
+                            .with_replacement(self.replacement(cx))
+                            .with_preserve_case(
+                                self.search_options.contains(SearchOptions::PRESERVE_CASE),
+                            ),
                         Err(_) => {
                             self.query_contains_error = true;
                             self.clear_active_searchable_item_matches(cx);
@@ -965,7 +1021,7 @@ impl BufferSearchBar {
                 }
                 .into();
                 self.active_search = Some(query.clone());
-                let query_text = query.as_str().to_string();
+                let history_entry = SearchHistoryEntry::from(query.as_ref());
 
                 let matches = active_searchable_item.find_matches(query, cx);
 
@@ -981,8 +1037,10 @@ impl BufferSearchBar {
                                 .insert(active_searchable_item.downgrade(), matches);
 
                             this.update_match_index(cx);
-                            this.search_history
-                                .add(&mut this.search_history_cursor, query_text);
+                            this.search_history.update(cx, |search_history, cx| {
+                                search_history.add(&mut this.search_history_cursor, history_entry);
+                                cx.notify();
+                            });
                             if !this.dismissed {
                                 let matches = this
                                     .searchable_items_with_matches
@@ -1044,12 +1102,12 @@ impl BufferSearchBar {
     }
 
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
-        if let Some(new_query) = self
-            .search_history
-            .next(&mut self.search_history_cursor)
-            .map(str::to_string)
-        {
-            drop(self.search(&new_query, Some(self.search_options), cx));
+        if self.search_history.update(cx, |search_history, _| {
+            search_history
+                .next(&mut self.search_history_cursor)
+                .is_some()
+        }) {
+            self.search_history_entry(cx);
         } else {
             self.search_history_cursor.reset();
             drop(self.search("", Some(self.search_options), cx));
@@ -1057,26 +1115,35 @@ impl BufferSearchBar {
     }
 
     fn previous_history_query(&mut self, _: &PreviousHistoryQuery, cx: &mut ViewContext<Self>) {
-        if self.query(cx).is_empty() {
-            if let Some(new_query) = self
-                .search_history
-                .current(&mut self.search_history_cursor)
-                .map(str::to_string)
-            {
-                drop(self.search(&new_query, Some(self.search_options), cx));
-                return;
-            }
+        if self.query(cx).is_empty() && self.search_history_entry(cx) {
+            return;
         }
 
-        if let Some(new_query) = self
-            .search_history
-            .previous(&mut self.search_history_cursor)
-            .map(str::to_string)
-        {
-            drop(self.search(&new_query, Some(self.search_options), cx));
+        if self.search_history.update(cx, |search_history, _| {
+            search_history
+                .previous(&mut self.search_history_cursor)
+                .is_some()
+        }) {
+            self.search_history_entry(cx);
         }
     }
 
+    /// Searches for the selected history entry again, with the options it was searched with.
+    fn search_history_entry(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        let Some(entry) = self
+            .search_history
+            .read(cx)
+            .current_entry(&self.search_history_cursor)
+            .cloned()
+        else {
+            return false;
+        };
+        let options = SearchOptions::from_history_entry(&entry)
+            | (self.search_options & SearchOptions::PRESERVE_CASE);
+        drop(self.search(&entry.query, Some(options), cx));
+        true
+    }
+
     fn toggle_replace(&mut self, _: &ToggleReplace, cx: &mut ViewContext<Self>) {
         if let Some(_) = &self.active_searchable_item {
             self.replace_enabled = !self.replace_enabled;
@@ -1146,7 +1213,7 @@ mod tests {
     use std::ops::Range;
 
     use super::*;
-    use editor::{display_map::DisplayRow, DisplayPoint, Editor, MultiBuffer};
+    use editor::{display_map::DisplayRow, DisplayPoint, Editor, MultiBuffer, SearchWithinRange};
     use gpui::{Context, Hsla, TestAppContext, VisualTestContext};
     use language::{Buffer, Point};
     use project::Project;
@@ -1796,13 +1863,14 @@ mod tests {
             assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
         });
 
-        // Further previous items should go over the history in reverse order.
+        // Further previous items should go over the history in reverse order, restoring the
+        // options each query was searched with.
         search_bar.update(cx, |search_bar, cx| {
             search_bar.previous_history_query(&PreviousHistoryQuery, cx);
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "b");
-            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
+            assert_eq!(search_bar.search_options, SearchOptions::NONE);
         });
 
         // Previous items should never go behind the first history item.
@@ -1811,14 +1879,14 @@ mod tests {
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "a");
-            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
+            assert_eq!(search_bar.search_options, SearchOptions::NONE);
         });
         search_bar.update(cx, |search_bar, cx| {
             search_bar.previous_history_query(&PreviousHistoryQuery, cx);
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "a");
-            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
+            assert_eq!(search_bar.search_options, SearchOptions::NONE);
         });
 
         // Next items should go over the history in the original order.
@@ -1827,7 +1895,7 @@ mod tests {
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "b");
-            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
+            assert_eq!(search_bar.search_options, SearchOptions::NONE);
         });
 
         search_bar
@@ -1845,7 +1913,7 @@ mod tests {
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "c");
-            assert_eq!(search_bar.search_options, SearchOptions::NONE);
+            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
         });
         search_bar.update(cx, |search_bar, cx| {
             search_bar.previous_history_query(&PreviousHistoryQuery, cx);
@@ -1859,7 +1927,7 @@ mod tests {
         });
         search_bar.update(cx, |search_bar, cx| {
             assert_eq!(search_bar.query(cx), "c");
-            assert_eq!(search_bar.search_options, SearchOptions::NONE);
+            assert_eq!(search_bar.search_options, SearchOptions::CASE_SENSITIVE);
         });
         search_bar.update(cx, |search_bar, cx| {
             search_bar.next_history_query(&NextHistoryQuery, cx);
@@ -2154,6 +2222,72 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_replace_all_in_selection(cx: &mut TestAppContext) {
+        init_globals(cx);
+        let buffer = cx.new_model(|cx| {
+            Buffer::local(
+                r#"
+                aaa bbb aaa ccc
+                aaa bbb aaa ccc
+                aaa bbb aaa ccc
+                "#
+                .unindent(),
+                cx,
+            )
+        });
+        let cx = cx.add_empty_window();
+        let editor = cx.new_view(|cx| Editor::for_buffer(buffer.clone(), None, cx));
+
+        let search_bar = cx.new_view(|cx| {
+            let mut search_bar = BufferSearchBar::new(cx);
+            search_bar.set_active_pane_item(Some(&editor), cx);
+            search_bar.show(cx);
+            search_bar
+        });
+
+        editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |s| {
+                s.select_ranges(vec![Point::new(1, 0)..Point::new(1, 7)])
+            })
+        });
+        search_bar.update(cx, |search_bar, cx| {
+            let deploy = Deploy {
+                focus: true,
+                replace_enabled: true,
+                selection_search_enabled: true,
+            };
+            search_bar.deploy(&deploy, cx);
+        });
+        cx.run_until_parked();
+
+        search_bar
+            .update(cx, |search_bar, cx| search_bar.search("aaa", None, cx))
+            .await
+            .unwrap();
+        search_bar.update(cx, |search_bar, cx| {
+            search_bar.replacement_editor.update(cx, |editor, cx| {
+                editor.set_text("zzz", cx);
+            });
+            search_bar.replace_all(&ReplaceAll, cx);
+        });
+
+        editor.update(cx, |editor, cx| {
+            assert_eq!(
+                editor.text(cx),
+                r#"
+                aaa bbb aaa ccc
+                zzz bbb aaa ccc
+                aaa bbb aaa ccc
+                "#
+                .unindent(),
+                "Only the matches in the selection should be replaced"
+            );
+            // The searched ranges stay highlighted while the search bar is open.
+            assert!(editor.has_background_highlights::<SearchWithinRange>());
+        });
+    }
+
     #[gpui::test]
     async fn test_find_matches_in_selections_multiple_excerpts_buffer_multiple_selections(
         cx: &mut TestAppContext,
@@ -0,0 +1,52 @@
+use db::kvp::KEY_VALUE_STORE;
+use gpui::{AppContext, Task};
+use project::search_history::SearchHistoryEntry;
+use util::ResultExt;
+use workspace::WorkspaceId;
+
+/// The search a saved history belongs to.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SearchHistoryKind {
+    Buffer,
+    Project,
+}
+
+fn search_history_key(kind: SearchHistoryKind, workspace_id: WorkspaceId) -> String {
+    let kind = match kind {
+        SearchHistoryKind::Buffer => "buffer",
+        SearchHistoryKind::Project => "project",
+    };
+    let workspace_id: i64 = workspace_id.into();
+    format!("{kind}_search_history-{workspace_id}")
+}
+
+pub(crate) fn load_search_history(
+    kind: SearchHistoryKind,
+    workspace_id: WorkspaceId,
+    cx: &AppContext,
+) -> Task<Vec<SearchHistoryEntry>> {
+    let key = search_history_key(kind, workspace_id);
+    cx.background_executor().spawn(async move {
+        KEY_VALUE_STORE
+            .read_kvp(&key)
+            .log_err()
+            .flatten()
+            .and_then(|history| serde_json::from_str(&history).log_err())
+            .unwrap_or_default()
+    })
+}
+
+pub(crate) fn save_search_history(
+    kind: SearchHistoryKind,
+    workspace_id: WorkspaceId,
+    entries: &[SearchHistoryEntry],
+    cx: &AppContext,
+) {
+    let key = search_history_key(kind, workspace_id);
+    let Some(history) = serde_json::to_string(entries).log_err() else {
+        return;
+    };
+    cx.background_executor()
+        .spawn(async move { KEY_VALUE_STORE.write_kvp(key, history).await })
+        .detach_and_log_err(cx);
+}
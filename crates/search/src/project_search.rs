@@ -1,7 +1,9 @@
 use crate::{
+    persistence::{load_search_history, save_search_history, SearchHistoryKind},
     FocusSearch, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
-    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored, ToggleRegex,
-    ToggleReplace, ToggleWholeWord,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
+    ToggleOpenBuffersOnly, TogglePreserveCase, ToggleRegex, ToggleReplace, ToggleStructural,
+    ToggleWholeWord,
 };
 use collections::{HashMap, HashSet};
 use editor::{
@@ -9,7 +11,7 @@ use editor::{
     items::active_match_index,
     scroll::{Autoscroll, Axis},
     Anchor, Editor, EditorElement, EditorEvent, EditorSettings, EditorStyle, MultiBuffer,
-    MAX_TAB_TITLE_LEN,
+    ReplacementPreview, MAX_TAB_TITLE_LEN,
 };
 use gpui::{
     actions, div, Action, AnyElement, AnyView, AppContext, Context as _, EntityId, EventEmitter,
@@ -18,7 +20,11 @@ use gpui::{
     TextStyle, UpdateGlobal, View, ViewContext, VisualContext, WeakModel, WindowContext,
 };
 use menu::Confirm;
-use project::{search::SearchQuery, search_history::SearchHistoryCursor, Project, ProjectPath};
+use project::{
+    search::SearchQuery,
+    search_history::{SearchHistoryCursor, SearchHistoryEntry},
+    Project, ProjectPath,
+};
 use settings::Settings;
 use smol::stream::StreamExt;
 use std::{
@@ -45,7 +51,13 @@ const MAX_INPUT_WIDTH_REMS: f32 = 30.;
 
 actions!(
     project_search,
-    [SearchInNew, ToggleFocus, NextField, ToggleFilters]
+    [
+        SearchInNew,
+        ToggleFocus,
+        NextField,
+        ToggleFilters,
+        ToggleMatchExclusion
+    ]
 );
 
 #[derive(Default)]
@@ -55,7 +67,8 @@ impl Global for ActiveSettings {}
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
-    cx.observe_new_views(|workspace: &mut Workspace, _cx| {
+    cx.observe_new_views(|workspace: &mut Workspace, cx| {
+        restore_search_history(workspace, cx);
         register_workspace_action(workspace, move |search_bar, _: &FocusSearch, cx| {
             search_bar.focus_search(cx);
         });
@@ -71,9 +84,19 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ToggleRegex, cx| {
             search_bar.toggle_search_option(SearchOptions::REGEX, cx);
         });
+        register_workspace_action(workspace, move |search_bar, _: &ToggleStructural, cx| {
+            search_bar.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+        });
+        register_workspace_action(workspace, move |search_bar, _: &TogglePreserveCase, cx| {
+            search_bar.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+        });
         register_workspace_action(workspace, move |search_bar, action: &ToggleReplace, cx| {
             search_bar.toggle_replace(action, cx)
         });
+        register_workspace_action(
+            workspace,
+            move |search_bar, _: &ToggleMatchExclusion, cx| search_bar.toggle_match_exclusion(cx),
+        );
         register_workspace_action(
             workspace,
             move |search_bar, action: &SelectPrevMatch, cx| {
@@ -113,6 +136,22 @@ pub fn init(cx: &mut AppContext) {
     .detach();
 }
 
+/// Restores the project search history saved for the workspace.
+fn restore_search_history(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    let Some(workspace_id) = workspace.database_id() else {
+        return;
+    };
+    let project = workspace.project().downgrade();
+    let entries = load_search_history(SearchHistoryKind::Project, workspace_id, cx);
+    cx.spawn(|_, mut cx| async move {
+        let entries = entries.await;
+        project.update(&mut cx, |project, _| {
+            project.search_history_mut().restore(entries)
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
 struct ProjectSearch {
     project: Model<Project>,
     excerpts: Model<MultiBuffer>,
@@ -147,6 +186,10 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    /// The indices of the matches that are left as is when replacing all matches.
+    excluded_matches: HashSet<usize>,
+    /// The workspace the search history is saved for.
+    workspace_id: Option<WorkspaceId>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -199,9 +242,10 @@ impl ProjectSearch {
 
     fn search(&mut self, query: SearchQuery, cx: &mut ModelContext<Self>) {
         let search = self.project.update(cx, |project, cx| {
-            project
-                .search_history_mut()
-                .add(&mut self.search_history_cursor, query.as_str().to_string());
+            project.search_history_mut().add(
+                &mut self.search_history_cursor,
+                SearchHistoryEntry::from(&query),
+            );
             project.search(query.clone(), cx)
         });
         self.last_search_query_text = Some(query.as_str().to_string());
@@ -264,6 +308,19 @@ impl ProjectSearch {
     }
 }
 
+/// The key the results' scroll position is remembered under in the workspace, so that
+/// running the same search again restores it.
+fn results_scroll_position_key(query: &SearchQuery) -> SharedString {
+    format!(
+        "project_search:{}:{:?}:{:?}:{}",
+        SearchOptions::from_query(query).bits(),
+        query.files_to_include().sources(),
+        query.files_to_exclude().sources(),
+        query.as_str(),
+    )
+    .into()
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ViewEvent {
     UpdateTab,
@@ -460,6 +517,7 @@ impl Item for ProjectSearchView {
     }
 
     fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.workspace_id = workspace.database_id();
         self.results_editor
             .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
     }
@@ -537,16 +595,23 @@ impl ProjectSearchView {
         let Some(active_index) = self.active_match_index else {
             return;
         };
+        if self.excluded_matches.contains(&active_index) {
+            self.select_match(Direction::Next, cx);
+            return;
+        }
 
         let query = self.model.read(cx).active_query.clone();
         if let Some(query) = query {
-            let query = query.with_replacement(self.replacement(cx));
+            let query = query
+                .with_replacement(self.replacement(cx))
+                .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE));
 
             // TODO: Do we need the clone here?
             let mat = self.model.read(cx).match_ranges[active_index].clone();
             self.results_editor.update(cx, |editor, cx| {
                 editor.replace(&mat, &query, cx);
             });
+            self.refresh_replacement_previews(cx);
             self.select_match(Direction::Next, cx)
         }
     }
@@ -561,7 +626,10 @@ impl ProjectSearchView {
         let Some(query) = self.model.read(cx).active_query.as_ref() else {
             return;
         };
-        let query = query.clone().with_replacement(self.replacement(cx));
+        let query = query
+            .clone()
+            .with_replacement(self.replacement(cx))
+            .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE));
 
         let match_ranges = self
             .model
@@ -570,13 +638,68 @@ impl ProjectSearchView {
             return;
         }
 
+        // Every buffer gets its own undo entry, as the replacements are made in one transaction.
+        let mut included_matches = match_ranges
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.excluded_matches.contains(index))
+            .map(|(_, range)| range);
         self.results_editor.update(cx, |editor, cx| {
-            editor.replace_all(&mut match_ranges.iter(), &query, cx);
+            editor.replace_all(&mut included_matches, &query, cx);
         });
 
         self.model.update(cx, |model, _cx| {
             model.match_ranges = match_ranges;
         });
+        self.refresh_replacement_previews(cx);
+    }
+
+    /// Toggles whether the active match is left as is when replacing all matches.
+    fn toggle_match_exclusion(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(active_index) = self.active_match_index else {
+            return;
+        };
+        if !self.excluded_matches.remove(&active_index) {
+            self.excluded_matches.insert(active_index);
+        }
+        self.refresh_replacement_previews(cx);
+        cx.notify();
+    }
+
+    /// Shows the replacement of every match that isn't excluded while replacing is enabled.
+    fn refresh_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        let model = self.model.read(cx);
+        let previews = match &model.active_query {
+            Some(query) if self.replace_enabled => {
+                let query = query
+                    .clone()
+                    .with_replacement(self.replacement(cx))
+                    .with_preserve_case(self.search_options.contains(SearchOptions::PRESERVE_CASE));
+                let buffer = self.results_editor.read(cx).buffer().read(cx).snapshot(cx);
+                model
+                    .match_ranges
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !self.excluded_matches.contains(index))
+                    .filter_map(|(_, range)| {
+                        let text = buffer.text_for_range(range.clone()).collect::<String>();
+                        // Matches that were already replaced don't match the query anymore.
+                        if !query.detect(text.as_bytes()).unwrap_or(false) {
+                            return None;
+                        }
+                        let new_text = query.replacement_for(&text)?.into_owned();
+                        Some(ReplacementPreview {
+                            range: range.clone(),
+                            new_text,
+                        })
+                    })
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_replacement_previews(previews, cx);
+        });
     }
 
     fn new(
@@ -586,12 +709,15 @@ impl ProjectSearchView {
     ) -> Self {
         let project;
         let excerpts;
+        let mut scroll_position_key = None;
         let mut replacement_text = None;
         let mut query_text = String::new();
+        let mut included_files_text = String::new();
+        let mut excluded_files_text = String::new();
         let mut subscriptions = Vec::new();
 
         // Read in settings if available
-        let (mut options, filters_enabled) = if let Some(settings) = settings {
+        let (mut options, mut filters_enabled) = if let Some(settings) = settings {
             (settings.search_options, settings.filters_enabled)
         } else {
             (SearchOptions::NONE, false)
@@ -605,6 +731,12 @@ impl ProjectSearchView {
                 query_text = active_query.as_str().to_string();
                 replacement_text = active_query.replacement().map(ToOwned::to_owned);
                 options = SearchOptions::from_query(active_query);
+                scroll_position_key = Some(results_scroll_position_key(active_query));
+                // Keep the path filters the search was run with.
+                included_files_text = active_query.files_to_include().sources().join(", ");
+                excluded_files_text = active_query.files_to_exclude().sources().join(", ");
+                filters_enabled |=
+                    !included_files_text.is_empty() || !excluded_files_text.is_empty();
             }
         }
         subscriptions.push(cx.observe(&model, |this, _, cx| this.model_changed(cx)));
@@ -629,9 +761,18 @@ impl ProjectSearchView {
             }
             editor
         });
+        subscriptions.push(cx.subscribe(
+            &replacement_editor,
+            |this, _, event: &EditorEvent, cx| {
+                if matches!(event, EditorEvent::BufferEdited) {
+                    this.refresh_replacement_previews(cx);
+                }
+            },
+        ));
         let results_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_multibuffer(excerpts, Some(project.clone()), true, cx);
             editor.set_searchable(false);
+            editor.set_scroll_position_key(scroll_position_key, cx);
             editor
         });
         subscriptions.push(cx.observe(&results_editor, |_, _, cx| cx.emit(ViewEvent::UpdateTab)));
@@ -641,6 +782,9 @@ impl ProjectSearchView {
                 if matches!(event, editor::EditorEvent::SelectionsChanged { .. }) {
                     this.update_match_index(cx);
                 }
+                if matches!(event, EditorEvent::BufferEdited) && this.replace_enabled {
+                    this.refresh_replacement_previews(cx);
+                }
                 // Reraise editor events for workspace item activation purposes
                 cx.emit(ViewEvent::EditorEvent(event.clone()));
             }),
@@ -649,6 +793,7 @@ impl ProjectSearchView {
         let included_files_editor = cx.new_view(|cx| {
             let mut editor = Editor::single_line(cx);
             editor.set_placeholder_text("Include: crates/**/*.toml", cx);
+            editor.set_text(included_files_text, cx);
 
             editor
         });
@@ -662,6 +807,7 @@ impl ProjectSearchView {
         let excluded_files_editor = cx.new_view(|cx| {
             let mut editor = Editor::single_line(cx);
             editor.set_placeholder_text("Exclude: vendor/*, *.lock", cx);
+            editor.set_text(excluded_files_text, cx);
 
             editor
         });
@@ -698,6 +844,8 @@ impl ProjectSearchView {
             excluded_files_editor,
             filters_enabled,
             replace_enabled: false,
+            excluded_matches: HashSet::default(),
+            workspace_id: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
@@ -823,6 +971,7 @@ impl ProjectSearchView {
 
         search.update(cx, |search, cx| {
             search.replace_enabled = action.replace_enabled;
+            search.refresh_replacement_previews(cx);
             if let Some(query) = query {
                 search.set_query(&query, cx);
             }
@@ -832,7 +981,19 @@ impl ProjectSearchView {
 
     fn search(&mut self, cx: &mut ViewContext<Self>) {
         if let Some(query) = self.build_search_query(cx) {
+            // Stop remembering the scroll position while the results are replaced.
+            self.results_editor
+                .update(cx, |editor, cx| editor.set_scroll_position_key(None, cx));
             self.model.update(cx, |model, cx| model.search(query, cx));
+            if let Some(workspace_id) = self.workspace_id {
+                let project = self.model.read(cx).project.read(cx);
+                save_search_history(
+                    SearchHistoryKind::Project,
+                    workspace_id,
+                    project.search_history().entries(),
+                    cx,
+                );
+            }
         }
     }
 
@@ -875,7 +1036,47 @@ impl ProjectSearchView {
                 }
             };
 
-        let query = if self.search_options.contains(SearchOptions::REGEX) {
+        let query = if self.search_options.contains(SearchOptions::STRUCTURAL) {
+            let languages = self.model.read(cx).project.read(cx).languages().to_vec();
+            let query = SearchQuery::structural(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            )
+            .ok()
+            .filter(|query| {
+                // Only the grammars of the loaded languages are known, so a query can't be
+                // reported as invalid until one of them is loaded.
+                let mut grammars = languages
+                    .iter()
+                    .filter_map(|language| language.grammar())
+                    .map(|grammar| grammar.as_ref())
+                    .peekable();
+                grammars.peek().is_none()
+                    || query
+                        .structural_query()
+                        .map_or(true, |query| query.is_valid_in_any(grammars))
+            });
+            match query {
+                Some(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                None => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            }
+        } else if self.search_options.contains(SearchOptions::REGEX) {
             match SearchQuery::regex(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -934,7 +1135,12 @@ impl ProjectSearchView {
         if query.as_ref().is_some_and(|query| query.is_empty()) {
             return None;
         }
-        query
+        query.map(|query| {
+            query.with_open_buffers_only(
+                self.search_options
+                    .contains(SearchOptions::OPEN_BUFFERS_ONLY),
+            )
+        })
     }
 
     fn parse_path_matches(text: &str) -> anyhow::Result<PathMatcher> {
@@ -988,6 +1194,15 @@ impl ProjectSearchView {
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
     }
 
+    /// Fills in a query from the search history, along with the options it was searched with.
+    fn set_query_from_history(&mut self, entry: &SearchHistoryEntry, cx: &mut ViewContext<Self>) {
+        self.set_query(&entry.query, cx);
+        self.search_options = SearchOptions::from_history_entry(entry)
+            | (self.search_options
+                & (SearchOptions::PRESERVE_CASE | SearchOptions::OPEN_BUFFERS_ONLY));
+        cx.notify();
+    }
+
     fn focus_results_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             let cursor = query_editor.selections.newest_anchor().head();
@@ -1001,11 +1216,21 @@ impl ProjectSearchView {
         let match_ranges = self.model.read(cx).match_ranges.clone();
         if match_ranges.is_empty() {
             self.active_match_index = None;
+            self.excluded_matches.clear();
         } else {
             self.active_match_index = Some(0);
             self.update_match_index(cx);
             let prev_search_id = mem::replace(&mut self.search_id, self.model.read(cx).search_id);
             let is_new_search = self.search_id != prev_search_id;
+            if is_new_search {
+                self.excluded_matches.clear();
+            }
+            let scroll_position_key = self
+                .model
+                .read(cx)
+                .active_query
+                .as_ref()
+                .map(results_scroll_position_key);
             self.results_editor.update(cx, |editor, cx| {
                 if is_new_search {
                     let range_to_select = match_ranges
@@ -1015,6 +1240,8 @@ impl ProjectSearchView {
                         s.select_ranges(range_to_select)
                     });
                     editor.scroll(Point::default(), Some(Axis::Vertical), cx);
+                    // Reopen the results of a search that was run before where they were left.
+                    editor.set_scroll_position_key(scroll_position_key, cx);
                 }
                 editor.highlight_background::<Self>(
                     &match_ranges,
@@ -1026,6 +1253,7 @@ impl ProjectSearchView {
                 self.focus_results_editor(cx);
             }
         }
+        self.refresh_replacement_previews(cx);
 
         cx.emit(ViewEvent::UpdateTab);
         cx.notify();
@@ -1195,7 +1423,10 @@ impl ProjectSearchBar {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
                 search_view.toggle_search_option(option, cx);
-                if search_view.model.read(cx).active_query.is_some() {
+                // Preserving case only affects the replacements, the matches stay the same.
+                if option == SearchOptions::PRESERVE_CASE {
+                    search_view.refresh_replacement_previews(cx);
+                } else if search_view.model.read(cx).active_query.is_some() {
                     search_view.search(cx);
                 }
             });
@@ -1211,6 +1442,7 @@ impl ProjectSearchBar {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
                 this.replace_enabled = !this.replace_enabled;
+                this.refresh_replacement_previews(cx);
                 let editor_to_focus = if this.replace_enabled {
                     this.replacement_editor.focus_handle(cx)
                 } else {
@@ -1222,6 +1454,14 @@ impl ProjectSearchBar {
         }
     }
 
+    fn toggle_match_exclusion(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                search_view.toggle_match_exclusion(cx);
+            });
+        }
+    }
+
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) -> bool {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
@@ -1262,20 +1502,23 @@ impl ProjectSearchBar {
     fn next_history_query(&mut self, _: &NextHistoryQuery, cx: &mut ViewContext<Self>) {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
-                let new_query = search_view.model.update(cx, |model, cx| {
-                    if let Some(new_query) = model.project.update(cx, |project, _| {
-                        project
-                            .search_history_mut()
-                            .next(&mut model.search_history_cursor)
-                            .map(str::to_string)
-                    }) {
-                        new_query
-                    } else {
+                let entry = search_view.model.update(cx, |model, cx| {
+                    let entry = model.project.update(cx, |project, _| {
+                        let search_history = project.search_history_mut();
+                        search_history.next(&mut model.search_history_cursor)?;
+                        search_history
+                            .current_entry(&model.search_history_cursor)
+                            .cloned()
+                    });
+                    if entry.is_none() {
                         model.search_history_cursor.reset();
-                        String::new()
                     }
+                    entry
                 });
-                search_view.set_query(&new_query, cx);
+                match entry {
+                    Some(entry) => search_view.set_query_from_history(&entry, cx),
+                    None => search_view.set_query("", cx),
+                }
             });
         }
     }
@@ -1284,29 +1527,29 @@ impl ProjectSearchBar {
         if let Some(search_view) = self.active_project_search.as_ref() {
             search_view.update(cx, |search_view, cx| {
                 if search_view.query_editor.read(cx).text(cx).is_empty() {
-                    if let Some(new_query) = search_view
-                        .model
-                        .read(cx)
+                    let model = search_view.model.read(cx);
+                    if let Some(entry) = model
                         .project
                         .read(cx)
                         .search_history()
-                        .current(&search_view.model.read(cx).search_history_cursor)
-                        .map(str::to_string)
+                        .current_entry(&model.search_history_cursor)
+                        .cloned()
                     {
-                        search_view.set_query(&new_query, cx);
+                        search_view.set_query_from_history(&entry, cx);
                         return;
                     }
                 }
 
-                if let Some(new_query) = search_view.model.update(cx, |model, cx| {
+                if let Some(entry) = search_view.model.update(cx, |model, cx| {
                     model.project.update(cx, |project, _| {
-                        project
-                            .search_history_mut()
-                            .previous(&mut model.search_history_cursor)
-                            .map(str::to_string)
+                        let search_history = project.search_history_mut();
+                        search_history.previous(&mut model.search_history_cursor)?;
+                        search_history
+                            .current_entry(&model.search_history_cursor)
+                            .cloned()
                     })
                 }) {
-                    search_view.set_query(&new_query, cx);
+                    search_view.set_query_from_history(&entry, cx);
                 }
             });
         }
@@ -1398,6 +1641,12 @@ impl Render for ProjectSearchBar {
                         cx.listener(|this, _, cx| {
                             this.toggle_search_option(SearchOptions::REGEX, cx);
                         }),
+                    ))
+                    .child(SearchOptions::STRUCTURAL.as_button(
+                        self.is_option_enabled(SearchOptions::STRUCTURAL, cx),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::STRUCTURAL, cx);
+                        }),
                     )),
             );
 
@@ -1512,7 +1761,13 @@ impl Render for ProjectSearchBar {
                 .border_1()
                 .border_color(cx.theme().colors().border)
                 .rounded_lg()
-                .child(self.render_text_input(&search.replacement_editor, cx));
+                .child(self.render_text_input(&search.replacement_editor, cx))
+                .child(SearchOptions::PRESERVE_CASE.as_button(
+                    self.is_option_enabled(SearchOptions::PRESERVE_CASE, cx),
+                    cx.listener(|this, _, cx| {
+                        this.toggle_search_option(SearchOptions::PRESERVE_CASE, cx);
+                    }),
+                ));
             let replace_actions = h_flex().when(search.replace_enabled, |this| {
                 this.child(
                     IconButton::new("project-search-replace-next", IconName::ReplaceNext)
@@ -1525,6 +1780,17 @@ impl Render for ProjectSearchBar {
                         }))
                         .tooltip(|cx| Tooltip::for_action("Replace next match", &ReplaceNext, cx)),
                 )
+                .child(
+                    IconButton::new("project-search-toggle-match-exclusion", IconName::XCircle)
+                        .on_click(cx.listener(|this, _, cx| this.toggle_match_exclusion(cx)))
+                        .tooltip(|cx| {
+                            Tooltip::for_action(
+                                "Exclude or include the current match",
+                                &ToggleMatchExclusion,
+                                cx,
+                            )
+                        }),
+                )
                 .child(
                     IconButton::new("project-search-replace-all", IconName::ReplaceAll)
                         .on_click(cx.listener(|this, _, cx| {
@@ -1586,6 +1852,16 @@ impl Render for ProjectSearchBar {
                         }),
                     ),
                 )
+                .child(
+                    SearchOptions::OPEN_BUFFERS_ONLY.as_button(
+                        search
+                            .search_options
+                            .contains(SearchOptions::OPEN_BUFFERS_ONLY),
+                        cx.listener(|this, _, cx| {
+                            this.toggle_search_option(SearchOptions::OPEN_BUFFERS_ONLY, cx);
+                        }),
+                    ),
+                )
         });
         let mut key_context = KeyContext::default();
         key_context.add("ProjectSearchBar");
@@ -1635,6 +1911,9 @@ impl Render for ProjectSearchBar {
                 this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, cx| {
                     this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
                 }))
+                .on_action(cx.listener(|this, _: &ToggleOpenBuffersOnly, cx| {
+                    this.toggle_search_option(SearchOptions::OPEN_BUFFERS_ONLY, cx);
+                }))
             })
             .on_action(cx.listener(Self::select_next_match))
             .on_action(cx.listener(Self::select_prev_match))
@@ -1855,6 +2134,105 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_replace_all_skips_excluded_matches(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                "three.rs": "const THREE: usize = one::ONE + two::TWO;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        perform_search(search_view, "TWO", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.replace_enabled = true;
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("2", cx));
+            })
+            .unwrap();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.active_match_index, Some(0));
+                search_view.toggle_match_exclusion(cx);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst THREE: usize = one::ONE + two::TWO2;\n\n\n\n\nconst TWO2: usize = one::ONE + one::ONE;\n"
+                );
+
+                search_view.replace_all(&ReplaceAll, cx);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .update(cx, |editor, cx| editor.display_text(cx)),
+                    "\n\n\nconst THREE: usize = one::ONE + two::2;\n\n\n\n\nconst 2: usize = one::ONE + one::ONE;\n"
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_path_filters_kept_with_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "src": {
+                    "one.rs": "const ONE: usize = 1;",
+                    "generated": {
+                        "two.rs": "const TWO: usize = one::ONE + one::ONE;",
+                    },
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view
+                    .included_files_editor
+                    .update(cx, |editor, cx| editor.set_text("src/**/*.rs", cx));
+                search_view
+                    .excluded_files_editor
+                    .update(cx, |editor, cx| editor.set_text("**/generated/**", cx));
+            })
+            .unwrap();
+        perform_search(search_view, "const", cx);
+
+        // A view opened for the same search, like when splitting it, starts with its filters.
+        let split_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+        split_view
+            .update(cx, |split_view, cx| {
+                assert!(split_view.filters_enabled);
+                assert_eq!(
+                    split_view.included_files_editor.read(cx).text(cx),
+                    "src/**/*.rs"
+                );
+                assert_eq!(
+                    split_view.excluded_files_editor.read(cx).text(cx),
+                    "**/generated/**"
+                );
+            })
+            .unwrap();
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
@@ -3260,6 +3638,56 @@ pub mod tests {
             .expect("unable to update search view");
     }
 
+    #[gpui::test]
+    async fn test_restore_search_results_scroll_position(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "1.txt": "\n\n\n\n\n A B \n\n\n\n\n",
+                "2.txt": "\n\n\n\n\n A B \n\n\n\n\n",
+                "3.txt": "\n\n\n\n\n A B \n\n\n\n\n",
+                "4.txt": "\n\n\n\n\n A B \n\n\n\n\n",
+                "5.txt": "\n\n\n\n\n A B \n\n\n\n\n",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        perform_search(search_view, "A", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.results_editor.update(cx, |results_editor, cx| {
+                    results_editor.scroll(Point::new(0., 10.), Some(Axis::Vertical), cx);
+                });
+            })
+            .unwrap();
+
+        // A different search starts at the top...
+        perform_search(search_view, "B", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.results_editor.update(cx, |results_editor, cx| {
+                    assert_eq!(results_editor.scroll_position(cx), Point::default());
+                });
+            })
+            .unwrap();
+
+        // ...while running the first search again restores where its results were left.
+        perform_search(search_view, "A", cx);
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.results_editor.update(cx, |results_editor, cx| {
+                    assert_eq!(results_editor.scroll_position(cx), Point::new(0., 10.));
+                });
+            })
+            .unwrap();
+    }
+
     fn init_test(cx: &mut TestAppContext) {
         cx.update(|cx| {
             let settings = SettingsStore::test(cx);
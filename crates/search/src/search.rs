@@ -2,6 +2,7 @@ use bitflags::bitflags;
 pub use buffer_search::BufferSearchBar;
 use gpui::{actions, Action, AppContext, IntoElement};
 use project::search::SearchQuery;
+use project::search_history::SearchHistoryEntry;
 pub use project_search::ProjectSearchView;
 use ui::{prelude::*, Tooltip};
 use ui::{ButtonStyle, IconButton};
@@ -9,6 +10,7 @@ use workspace::notifications::NotificationId;
 use workspace::{Toast, Workspace};
 
 pub mod buffer_search;
+pub(crate) mod persistence;
 pub mod project_search;
 pub(crate) mod search_bar;
 
@@ -26,6 +28,9 @@ actions!(
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
         ToggleRegex,
+        TogglePreserveCase,
+        ToggleOpenBuffersOnly,
+        ToggleStructural,
         ToggleReplace,
         ToggleSelection,
         SelectNextMatch,
@@ -46,6 +51,9 @@ bitflags! {
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
         const REGEX = 0b1000;
+        const PRESERVE_CASE = 0b10000;
+        const OPEN_BUFFERS_ONLY = 0b100000;
+        const STRUCTURAL = 0b1000000;
     }
 }
 
@@ -56,6 +64,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => "Match case sensitively",
             SearchOptions::INCLUDE_IGNORED => "Also search files ignored by configuration",
             SearchOptions::REGEX => "Use regular expressions",
+            SearchOptions::PRESERVE_CASE => "Preserve case when replacing",
+            SearchOptions::OPEN_BUFFERS_ONLY => "Search only open buffers",
+            SearchOptions::STRUCTURAL => "Match syntax with tree-sitter patterns or code templates",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -66,6 +77,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::Sliders,
             SearchOptions::REGEX => ui::IconName::Regex,
+            SearchOptions::PRESERVE_CASE => ui::IconName::Font,
+            SearchOptions::OPEN_BUFFERS_ONLY => ui::IconName::File,
+            SearchOptions::STRUCTURAL => ui::IconName::Code,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -76,6 +90,9 @@ impl SearchOptions {
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
             SearchOptions::REGEX => Box::new(ToggleRegex),
+            SearchOptions::PRESERVE_CASE => Box::new(TogglePreserveCase),
+            SearchOptions::OPEN_BUFFERS_ONLY => Box::new(ToggleOpenBuffersOnly),
+            SearchOptions::STRUCTURAL => Box::new(ToggleStructural),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -90,6 +107,19 @@ impl SearchOptions {
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
         options.set(SearchOptions::REGEX, query.is_regex());
+        options.set(SearchOptions::PRESERVE_CASE, query.preserve_case());
+        options.set(SearchOptions::OPEN_BUFFERS_ONLY, query.open_buffers_only());
+        options.set(SearchOptions::STRUCTURAL, query.is_structural());
+        options
+    }
+
+    pub fn from_history_entry(entry: &SearchHistoryEntry) -> SearchOptions {
+        let mut options = SearchOptions::NONE;
+        options.set(SearchOptions::WHOLE_WORD, entry.whole_word);
+        options.set(SearchOptions::CASE_SENSITIVE, entry.case_sensitive);
+        options.set(SearchOptions::INCLUDE_IGNORED, entry.include_ignored);
+        options.set(SearchOptions::REGEX, entry.regex);
+        options.set(SearchOptions::STRUCTURAL, entry.structural);
         options
     }
 
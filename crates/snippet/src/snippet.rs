@@ -1,36 +1,60 @@
 use anyhow::{anyhow, Context, Result};
 use smallvec::SmallVec;
-use std::{collections::BTreeMap, ops::Range};
+use std::{collections::BTreeMap, mem, ops::Range};
 
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct Snippet {
     pub text: String,
     pub tabstops: Vec<TabStop>,
+    /// The choices of each tabstop in `tabstops`, for tabstops written as `${1|one,two|}`.
+    pub choices: Vec<Option<Vec<String>>>,
 }
 
 type TabStop = SmallVec<[Range<isize>; 2]>;
 
 impl Snippet {
+    /// Parses a snippet written in the LSP snippet syntax.
+    ///
+    /// Variables such as `$TM_FILENAME` are not resolved: they expand to their default value, if
+    /// any, and their transformations are ignored.
     pub fn parse(source: &str) -> Result<Self> {
         let mut text = String::with_capacity(source.len());
         let mut tabstops = BTreeMap::new();
-        parse_snippet(source, false, &mut text, &mut tabstops)
-            .context("failed to parse snippet")?;
+        let mut choices_by_index = BTreeMap::new();
+        parse_snippet(
+            source,
+            false,
+            &mut text,
+            &mut tabstops,
+            &mut choices_by_index,
+        )
+        .context("failed to parse snippet")?;
 
         let len = text.len() as isize;
         let final_tabstop = tabstops.remove(&0);
+        let final_choices = choices_by_index.remove(&0);
+        let mut choices = tabstops
+            .keys()
+            .map(|index| choices_by_index.remove(index))
+            .collect::<Vec<_>>();
         let mut tabstops = tabstops.into_values().collect::<Vec<_>>();
 
         if let Some(final_tabstop) = final_tabstop {
             tabstops.push(final_tabstop);
+            choices.push(final_choices);
         } else {
             let end_tabstop = [len..len].into_iter().collect();
             if !tabstops.last().map_or(false, |t| *t == end_tabstop) {
                 tabstops.push(end_tabstop);
+                choices.push(None);
             }
         }
 
-        Ok(Snippet { text, tabstops })
+        Ok(Snippet {
+            text,
+            tabstops,
+            choices,
+        })
     }
 }
 
@@ -39,12 +63,13 @@ fn parse_snippet<'a>(
     nested: bool,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    choices: &mut BTreeMap<usize, Vec<String>>,
 ) -> Result<&'a str> {
     loop {
         match source.chars().next() {
             None => return Ok(""),
             Some('$') => {
-                source = parse_tabstop(&source[1..], text, tabstops)?;
+                source = parse_tabstop(&source[1..], text, tabstops, choices)?;
             }
             Some('\\') => {
                 // As specified in the LSP spec (`Grammar` section),
@@ -85,16 +110,27 @@ fn parse_tabstop<'a>(
     mut source: &'a str,
     text: &mut String,
     tabstops: &mut BTreeMap<usize, TabStop>,
+    choices: &mut BTreeMap<usize, Vec<String>>,
 ) -> Result<&'a str> {
     let tabstop_start = text.len();
     let tabstop_index;
     if source.starts_with('{') {
+        if let Some(rest) = parse_variable_name(&source[1..]) {
+            return parse_variable(rest, text, tabstops, choices);
+        }
+
         let (index, rest) = parse_int(&source[1..])?;
         tabstop_index = index;
         source = rest;
 
         if source.starts_with(':') {
-            source = parse_snippet(&source[1..], true, text, tabstops)?;
+            source = parse_snippet(&source[1..], true, text, tabstops, choices)?;
+        } else if source.starts_with('|') {
+            let (tabstop_choices, rest) = parse_choices(&source[1..])?;
+            // The first choice is inserted until another one is picked.
+            text.push_str(&tabstop_choices[0]);
+            choices.insert(tabstop_index, tabstop_choices);
+            source = rest;
         }
 
         if source.starts_with('}') {
@@ -102,10 +138,16 @@ fn parse_tabstop<'a>(
         } else {
             return Err(anyhow!("expected a closing brace"));
         }
-    } else {
+    } else if let Some(rest) = parse_variable_name(source) {
+        return Ok(rest);
+    } else if source.starts_with(|c: char| c.is_ascii_digit()) {
         let (index, rest) = parse_int(source)?;
         tabstop_index = index;
         source = rest;
+    } else {
+        // A `$` that starts neither a tabstop nor a variable is inserted as is.
+        text.push('$');
+        return Ok(source);
     }
 
     tabstops
@@ -115,6 +157,78 @@ fn parse_tabstop<'a>(
     Ok(source)
 }
 
+fn parse_variable_name(source: &str) -> Option<&str> {
+    if !source.starts_with(|c: char| c == '_' || c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let len = source
+        .find(|c: char| c != '_' && !c.is_ascii_alphanumeric())
+        .unwrap_or(source.len());
+    Some(&source[len..])
+}
+
+fn parse_variable<'a>(
+    source: &'a str,
+    text: &mut String,
+    tabstops: &mut BTreeMap<usize, TabStop>,
+    choices: &mut BTreeMap<usize, Vec<String>>,
+) -> Result<&'a str> {
+    let source = if let Some(default) = source.strip_prefix(':') {
+        parse_snippet(default, true, text, tabstops, choices)?
+    } else if let Some(transform) = source.strip_prefix('/') {
+        skip_transform(transform)?
+    } else {
+        source
+    };
+
+    source
+        .strip_prefix('}')
+        .ok_or_else(|| anyhow!("expected a closing brace"))
+}
+
+/// Skips the `regex/format/options` of a variable transformation, up to its closing brace.
+fn skip_transform(source: &str) -> Result<&str> {
+    let mut depth = 0;
+    let mut chars = source.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '{' => depth += 1,
+            '}' if depth == 0 => return Ok(&source[ix..]),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    Err(anyhow!("expected a closing brace"))
+}
+
+fn parse_choices(source: &str) -> Result<(Vec<String>, &str)> {
+    let mut choices = Vec::new();
+    let mut choice = String::new();
+    let mut chars = source.char_indices();
+    loop {
+        match chars.next() {
+            None => return Err(anyhow!("expected a closing `|`")),
+            Some((_, '\\')) => match chars.next() {
+                Some((_, c @ ('$' | '}' | '\\' | ',' | '|'))) => choice.push(c),
+                Some((_, c)) => {
+                    choice.push('\\');
+                    choice.push(c);
+                }
+                None => return Err(anyhow!("expected a closing `|`")),
+            },
+            Some((_, ',')) => choices.push(mem::take(&mut choice)),
+            Some((ix, '|')) => {
+                choices.push(choice);
+                return Ok((choices, &source[ix + 1..]));
+            }
+            Some((_, c)) => choice.push(c),
+        }
+    }
+}
+
 fn parse_int(source: &str) -> Result<(usize, &str)> {
     let len = source
         .find(|c: char| !c.is_ascii_digit())
@@ -198,6 +312,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_snippet_with_choices() {
+        let snippet = Snippet::parse("let ${1|a,b\\,c|} = ${2:d};").unwrap();
+        assert_eq!(snippet.text, "let a = d;");
+        assert_eq!(tabstops(&snippet), &[vec![4..5], vec![8..9], vec![10..10]]);
+        assert_eq!(
+            snippet.choices,
+            &[Some(vec!["a".to_string(), "b,c".to_string()]), None, None]
+        );
+
+        // Mirrored tabstops and the final tabstop can offer choices too
+        let snippet = Snippet::parse("${1|x,y|}${1}${0|z|}").unwrap();
+        assert_eq!(snippet.text, "xz");
+        assert_eq!(tabstops(&snippet), &[vec![0..1, 1..1], vec![1..2]]);
+        assert_eq!(
+            snippet.choices,
+            &[
+                Some(vec!["x".to_string(), "y".to_string()]),
+                Some(vec!["z".to_string()])
+            ]
+        );
+
+        assert!(Snippet::parse("${1|a,b}").is_err());
+    }
+
     #[test]
     fn test_snippet_parsing_with_escaped_chars() {
         let snippet = Snippet::parse("\"\\$schema\": $1").unwrap();
@@ -220,6 +359,33 @@ mod tests {
         assert_eq!(tabstops(&snippet), &[vec![4..4], vec![7..7]]);
     }
 
+    #[test]
+    fn test_snippet_with_variables() {
+        let snippet = Snippet::parse("$TM_FILENAME-${CLIPBOARD}-$1").unwrap();
+        assert_eq!(snippet.text, "--");
+        assert_eq!(tabstops(&snippet), &[vec![2..2]]);
+
+        // Variables expand to their default, which may contain tabstops
+        let snippet = Snippet::parse("fn ${TM_SELECTED_TEXT:${1:name}}() {$0}").unwrap();
+        assert_eq!(snippet.text, "fn name() {}");
+        assert_eq!(tabstops(&snippet), &[vec![3..7], vec![11..11]]);
+
+        // Transformations are ignored
+        let snippet =
+            Snippet::parse("class ${TM_FILENAME/(.*)\\..+$/${1:/capitalize}/g} {$0}").unwrap();
+        assert_eq!(snippet.text, "class  {}");
+        assert_eq!(tabstops(&snippet), &[vec![8..8]]);
+
+        assert!(Snippet::parse("${TM_FILENAME").is_err());
+    }
+
+    #[test]
+    fn test_snippet_with_unescaped_dollar() {
+        let snippet = Snippet::parse("cost: $ ${1:5}$").unwrap();
+        assert_eq!(snippet.text, "cost: $ 5$");
+        assert_eq!(tabstops(&snippet), &[vec![8..9], vec![10..10]]);
+    }
+
     fn tabstops(snippet: &Snippet) -> Vec<Vec<Range<isize>>> {
         snippet.tabstops.iter().map(|t| t.to_vec()).collect()
     }
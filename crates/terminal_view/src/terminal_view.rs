@@ -1214,6 +1214,7 @@ impl SearchableItem for TerminalView {
                 .unwrap()),
             ),
             SearchQuery::Regex { .. } => regex_search_for_query(&query),
+            SearchQuery::Structural { .. } => None,
         };
 
         if let Some(s) = searcher {
@@ -20,6 +20,9 @@ pub fn init(cx: &mut AppContext) {
                 if !enabled {
                     Vim::unhook_vim_settings(editor, cx);
                 }
+            } else if enabled && editor.use_modal_editing() {
+                // The editor resets its scroll margins whenever settings change.
+                Vim::sync_scroll_margins(editor, cx);
             }
         })
         .detach();
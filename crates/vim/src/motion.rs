@@ -1693,10 +1693,9 @@ fn window_top(
         .anchor
         .to_display_point(map);
 
-    if first_visible_line.row() != DisplayRow(0)
-        && text_layout_details.vertical_scroll_margin as usize > times
-    {
-        times = text_layout_details.vertical_scroll_margin.ceil() as usize;
+    let vertical_scroll_margin = capped_vertical_scroll_margin(text_layout_details);
+    if first_visible_line.row() != DisplayRow(0) && vertical_scroll_margin as usize > times {
+        times = vertical_scroll_margin.ceil() as usize;
     }
 
     if let Some(visible_rows) = text_layout_details.visible_rows {
@@ -1756,10 +1755,9 @@ fn window_bottom(
             .to_display_point(map);
         let bottom_row = first_visible_line.row().0
             + (visible_rows + text_layout_details.scroll_anchor.offset.y - 1.).floor() as u32;
-        if bottom_row < map.max_point().row().0
-            && text_layout_details.vertical_scroll_margin as usize > times
-        {
-            times = text_layout_details.vertical_scroll_margin.ceil() as usize;
+        let vertical_scroll_margin = capped_vertical_scroll_margin(text_layout_details);
+        if bottom_row < map.max_point().row().0 && vertical_scroll_margin as usize > times {
+            times = vertical_scroll_margin.ceil() as usize;
         }
         let bottom_row_capped = bottom_row.min(map.max_point().row().0);
         let new_row = if bottom_row_capped.saturating_sub(times as u32) < first_visible_line.row().0
@@ -1776,6 +1774,17 @@ fn window_bottom(
     }
 }
 
+/// The vertical scroll margin, limited to half of the visible rows so that a
+/// `scrolloff` of 999 lands on the middle of the viewport.
+fn capped_vertical_scroll_margin(text_layout_details: &TextLayoutDetails) -> f32 {
+    match text_layout_details.visible_rows {
+        Some(visible_rows) => text_layout_details
+            .vertical_scroll_margin
+            .min(((visible_rows - 1.) / 2.).floor().max(0.)),
+        None => text_layout_details.vertical_scroll_margin,
+    }
+}
+
 pub fn coerce_punctuation(kind: CharKind, treat_punctuation_as_word: bool) -> CharKind {
     if treat_punctuation_as_word && kind == CharKind::Punctuation {
         CharKind::Word
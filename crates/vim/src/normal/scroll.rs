@@ -2,11 +2,10 @@ use crate::Vim;
 use editor::{
     display_map::{DisplayRow, ToDisplayPoint},
     scroll::ScrollAmount,
-    DisplayPoint, Editor, EditorSettings,
+    DisplayPoint, Editor,
 };
 use gpui::{actions, ViewContext};
 use language::Bias;
-use settings::Settings;
 use workspace::Workspace;
 
 actions!(
@@ -85,7 +84,9 @@ fn scroll_editor(
     };
 
     let top_anchor = editor.scroll_manager.anchor().anchor;
-    let vertical_scroll_margin = EditorSettings::get_global(cx).vertical_scroll_margin;
+    // A margin of half the viewport or more keeps the cursor centered.
+    let vertical_scroll_margin =
+        (editor.vertical_scroll_margin() as f32).min(((visible_line_count - 1.) / 2.).max(0.));
 
     editor.change_selections(None, cx, |s| {
         s.move_with(|map, selection| {
@@ -135,10 +136,12 @@ mod test {
     use crate::{
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
+        VimSettings,
     };
     use gpui::{point, px, size, Context};
     use indoc::indoc;
     use language::Point;
+    use settings::SettingsStore;
 
     #[gpui::test]
     async fn test_scroll(cx: &mut gpui::TestAppContext) {
@@ -229,6 +232,66 @@ mod test {
             )
         });
     }
+    #[gpui::test]
+    async fn test_scrolloff(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        let (line_height, visible_line_count) = cx.editor(|editor, cx| {
+            (
+                editor
+                    .style()
+                    .unwrap()
+                    .text
+                    .line_height_in_pixels(cx.rem_size()),
+                editor.visible_line_count().unwrap(),
+            )
+        });
+        let window = cx.window;
+        let margin = cx
+            .update_window(window, |_, cx| {
+                cx.viewport_size().height - line_height * visible_line_count
+            })
+            .unwrap();
+        cx.simulate_window_resize(cx.window, size(px(1000.), margin + 9. * line_height));
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.scrolloff = Some(999);
+                s.sidescrolloff = Some(2);
+            });
+        });
+        cx.update_editor(|editor, _| {
+            assert_eq!(editor.vertical_scroll_margin(), 999);
+            assert_eq!(editor.horizontal_scroll_margin(), 2);
+        });
+
+        let text = (0..30)
+            .map(|row| format!("line {row}\n"))
+            .collect::<String>();
+        cx.set_state(&format!("ˇ{text}"), Mode::Normal);
+        // A scrolloff of 999 keeps the cursor in the middle of the viewport.
+        cx.simulate_keystrokes("1 0 j");
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.snapshot(cx).scroll_position(), point(0., 6.))
+        });
+        cx.simulate_keystrokes("3 k");
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.snapshot(cx).scroll_position(), point(0., 3.))
+        });
+
+        // Without scrolloff, the editor's own scroll margins apply again.
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.update_user_settings::<VimSettings>(cx, |s| {
+                s.scrolloff = None;
+                s.sidescrolloff = None;
+            });
+        });
+        cx.update_editor(|editor, _| {
+            assert_eq!(editor.vertical_scroll_margin(), 3);
+            assert_eq!(editor.horizontal_scroll_margin(), 3);
+        });
+    }
+
     #[gpui::test]
     async fn test_ctrl_d_u(cx: &mut gpui::TestAppContext) {
         let mut cx = NeovimBackedTestContext::new(cx).await;
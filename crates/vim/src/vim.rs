@@ -23,7 +23,7 @@ use collections::HashMap;
 use command_palette_hooks::{CommandPaletteFilter, CommandPaletteInterceptor};
 use editor::{
     movement::{self, FindRange},
-    Anchor, Bias, Editor, EditorEvent, EditorMode, ToPoint,
+    Anchor, Bias, Editor, EditorEvent, EditorMode, EditorSettings, ToPoint,
 };
 use gpui::{
     actions, impl_actions, Action, AppContext, EntityId, FocusableView, Global, KeystrokeEvent,
@@ -559,13 +559,13 @@ impl Vim {
                 match lower {
                     '_' | ':' | '.' | '%' | '#' | '=' | '/' => {}
                     '+' => {
-                        cx.write_to_clipboard(content.into());
+                        Editor::write_to_clipboard(content.into(), cx);
                     }
                     '*' => {
                         #[cfg(target_os = "linux")]
                         cx.write_to_primary(content.into());
                         #[cfg(not(target_os = "linux"))]
-                        cx.write_to_clipboard(content.into());
+                        Editor::write_to_clipboard(content.into(), cx);
                     }
                     '"' => {
                         self.workspace_state.registers.insert('0', content.clone());
@@ -582,7 +582,7 @@ impl Vim {
                 || setting == UseSystemClipboard::OnYank && is_yank
             {
                 self.workspace_state.last_yank.replace(content.text.clone());
-                cx.write_to_clipboard(content.clone().into());
+                Editor::write_to_clipboard(content.clone().into(), cx);
             } else {
                 self.workspace_state.last_yank = cx
                     .read_from_clipboard()
@@ -1012,6 +1012,7 @@ impl Vim {
             editor.set_input_enabled(state.editor_input_enabled());
             editor.set_autoindent(state.should_autoindent());
             editor.selections.line_mode = matches!(state.mode, Mode::VisualLine);
+            Vim::sync_scroll_margins(editor, cx);
             if editor.is_focused(cx) || editor.mouse_menu_is_focused(cx) {
                 editor.set_keymap_context_layer::<Self>(state.keymap_context_layer(), cx);
                 // disable vim mode if a sub-editor (inline assist, rename, etc.) is focused
@@ -1029,9 +1030,34 @@ impl Vim {
             editor.set_input_enabled(true);
             editor.set_autoindent(true);
             editor.selections.line_mode = false;
+            let editor_settings = EditorSettings::get_global(cx);
+            let vertical_scroll_margin = editor_settings.vertical_scroll_margin as usize;
+            let horizontal_scroll_margin = editor_settings.horizontal_scroll_margin as usize;
+            editor.set_vertical_scroll_margin(vertical_scroll_margin, cx);
+            editor.set_horizontal_scroll_margin(horizontal_scroll_margin, cx);
+            editor.set_center_on_large_vertical_scroll_margin(false);
         }
         editor.remove_keymap_context_layer::<Self>(cx)
     }
+
+    /// Applies the `scrolloff` and `sidescrolloff` settings, falling back to the
+    /// editor's scroll margins when they aren't set.
+    fn sync_scroll_margins(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        let vim_settings = VimSettings::get_global(cx);
+        let editor_settings = EditorSettings::get_global(cx);
+        let vertical_scroll_margin = vim_settings
+            .scrolloff
+            .map_or(editor_settings.vertical_scroll_margin, |lines| lines as f32);
+        let horizontal_scroll_margin = vim_settings
+            .sidescrolloff
+            .map_or(editor_settings.horizontal_scroll_margin, |columns| {
+                columns as f32
+            });
+        let center_on_large_margin = vim_settings.scrolloff.is_some();
+        editor.set_vertical_scroll_margin(vertical_scroll_margin as usize, cx);
+        editor.set_horizontal_scroll_margin(horizontal_scroll_margin as usize, cx);
+        editor.set_center_on_large_vertical_scroll_margin(center_on_large_margin);
+    }
 }
 
 impl Settings for VimModeSetting {
@@ -1064,6 +1090,8 @@ struct VimSettings {
     pub use_multiline_find: bool,
     pub use_smartcase_find: bool,
     pub custom_digraphs: HashMap<String, Arc<str>>,
+    pub scrolloff: Option<u32>,
+    pub sidescrolloff: Option<u32>,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -1072,6 +1100,8 @@ struct VimSettingsContent {
     pub use_multiline_find: Option<bool>,
     pub use_smartcase_find: Option<bool>,
     pub custom_digraphs: Option<HashMap<String, Arc<str>>>,
+    pub scrolloff: Option<u32>,
+    pub sidescrolloff: Option<u32>,
 }
 
 impl Settings for VimSettings {
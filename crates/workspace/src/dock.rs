@@ -1,12 +1,14 @@
 use crate::persistence::model::DockData;
 use crate::{status_bar::StatusItemView, Workspace};
 use crate::{DraggedDock, Event, Pane};
+use anyhow::Context as _;
 use client::proto;
+use db::kvp::KEY_VALUE_STORE;
 use gpui::{
-    deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, Entity, EntityId,
-    EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton, MouseDownEvent,
-    MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled, Subscription, View,
-    ViewContext, VisualContext, WeakView, WindowContext,
+    deferred, div, px, Action, AnchorCorner, AnyView, AppContext, Axis, BackgroundExecutor, Entity,
+    EntityId, EventEmitter, FocusHandle, FocusableView, IntoElement, KeyContext, MouseButton,
+    MouseDownEvent, MouseUpEvent, ParentElement, Render, SharedString, StyleRefinement, Styled,
+    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -14,6 +16,7 @@ use settings::SettingsStore;
 use std::sync::Arc;
 use ui::{h_flex, ContextMenu, IconButton, Tooltip};
 use ui::{prelude::*, right_click_menu};
+use util::ResultExt;
 
 const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
 
@@ -203,6 +206,126 @@ impl DockPosition {
     }
 }
 
+/// The settings of a panel that can be docked at any position, for panels that have no other
+/// settings. Each panel has its own defaults, in the default settings file.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct DockPanelSettings {
+    pub button: bool,
+    pub default_width: Pixels,
+    pub default_height: Pixels,
+    pub dock: DockPosition,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct DockPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Default width of the panel in pixels, when docked at the left or right.
+    pub default_width: Option<f32>,
+    /// Default height of the panel in pixels, when docked at the bottom.
+    ///
+    /// Default: 240
+    pub default_height: Option<f32>,
+    /// Where to dock the panel.
+    pub dock: Option<DockPosition>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDockPanel {
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    active: Option<bool>,
+}
+
+/// The size a panel was resized to and whether it is open, which the panel keeps in the
+/// key-value store under its own key.
+pub struct DockPanelState {
+    key: &'static str,
+    width: Option<Pixels>,
+    height: Option<Pixels>,
+    active: bool,
+    pending_serialization: Task<Option<()>>,
+}
+
+impl DockPanelState {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            width: None,
+            height: None,
+            active: false,
+            pending_serialization: Task::ready(None),
+        }
+    }
+
+    pub async fn load(key: &'static str, executor: BackgroundExecutor) -> Self {
+        let serialized_panel = executor
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(key) })
+            .await
+            .with_context(|| format!("loading {key}"))
+            .log_err()
+            .flatten()
+            .map(|panel| serde_json::from_str::<SerializedDockPanel>(&panel))
+            .transpose()
+            .log_err()
+            .flatten();
+
+        let mut state = Self::new(key);
+        if let Some(serialized_panel) = serialized_panel {
+            state.width = serialized_panel.width.map(|px| px.round());
+            state.height = serialized_panel.height.map(|px| px.round());
+            state.active = serialized_panel.active.unwrap_or(false);
+        }
+        state
+    }
+
+    pub fn active(&self) -> bool {
+        self.active
+    }
+
+    pub fn set_active(&mut self, active: bool, cx: &AppContext) {
+        self.active = active;
+        self.serialize(cx);
+    }
+
+    pub fn size(&self, position: DockPosition, settings: &DockPanelSettings) -> Pixels {
+        match position {
+            DockPosition::Left | DockPosition::Right => {
+                self.width.unwrap_or(settings.default_width)
+            }
+            DockPosition::Bottom => self.height.unwrap_or(settings.default_height),
+        }
+    }
+
+    pub fn set_size(&mut self, position: DockPosition, size: Option<Pixels>, cx: &AppContext) {
+        match position {
+            DockPosition::Left | DockPosition::Right => self.width = size,
+            DockPosition::Bottom => self.height = size,
+        }
+        self.serialize(cx);
+    }
+
+    fn serialize(&mut self, cx: &AppContext) {
+        let key = self.key;
+        let serialized_panel = SerializedDockPanel {
+            width: self.width,
+            height: self.height,
+            active: Some(self.active),
+        };
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(key.into(), serde_json::to_string(&serialized_panel)?)
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+}
+
 struct PanelEntry {
     panel: Arc<dyn PanelHandle>,
     _subscriptions: [Subscription; 3],
@@ -4310,6 +4310,11 @@ impl Workspace {
         workspace
     }
 
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn set_database_id(&mut self, database_id: WorkspaceId) {
+        self.database_id = Some(database_id);
+    }
+
     pub fn register_action<A: Action>(
         &mut self,
         callback: impl Fn(&mut Self, &A, &mut ViewContext<Self>) + 'static,
@@ -229,11 +229,16 @@ fn init_ui(app_state: Arc<AppState>, cx: &mut AppContext) -> Result<()> {
     go_to_line::init(cx);
     file_finder::init(cx);
     tab_switcher::init(cx);
+    clipboard_history::init(cx);
+    keyboard_macros::init(cx);
     dev_server_projects::init(app_state.client.clone(), cx);
     outline::init(cx);
     project_symbols::init(cx);
     project_panel::init(Assets, cx);
     outline_panel::init(Assets, cx);
+    documentation_panel::init(cx);
+    references_panel::init(cx);
+    call_hierarchy_panel::init(cx);
     tasks_ui::init(cx);
     channel::init(&app_state.client.clone(), app_state.user_store.clone(), cx);
     search::init(cx);
@@ -19,11 +19,14 @@ pub use open_listener::*;
 
 use anyhow::Context as _;
 use assets::Assets;
+use call_hierarchy_panel::CallHierarchyPanel;
+use documentation_panel::DocumentationPanel;
 use futures::{channel::mpsc, select_biased, StreamExt};
 use outline_panel::OutlinePanel;
 use project::TaskSourceKind;
 use project_panel::ProjectPanel;
 use quick_action_bar::QuickActionBar;
+use references_panel::ReferencesPanel;
 use release_channel::{AppCommitSha, ReleaseChannel};
 use rope::Rope;
 use search::project_search::ProjectSearchBar;
@@ -243,6 +246,11 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
 
             let project_panel = ProjectPanel::load(workspace_handle.clone(), cx.clone());
             let outline_panel = OutlinePanel::load(workspace_handle.clone(), cx.clone());
+            let documentation_panel =
+                DocumentationPanel::load(workspace_handle.clone(), cx.clone());
+            let references_panel = ReferencesPanel::load(workspace_handle.clone(), cx.clone());
+            let call_hierarchy_panel =
+                CallHierarchyPanel::load(workspace_handle.clone(), cx.clone());
             let terminal_panel = TerminalPanel::load(workspace_handle.clone(), cx.clone());
             let channels_panel =
                 collab_ui::collab_panel::CollabPanel::load(workspace_handle.clone(), cx.clone());
@@ -256,6 +264,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             let (
                 project_panel,
                 outline_panel,
+                documentation_panel,
+                references_panel,
+                call_hierarchy_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -264,6 +275,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             ) = futures::try_join!(
                 project_panel,
                 outline_panel,
+                documentation_panel,
+                references_panel,
+                call_hierarchy_panel,
                 terminal_panel,
                 assistant_panel,
                 channels_panel,
@@ -275,6 +289,9 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(assistant_panel, cx);
                 workspace.add_panel(project_panel, cx);
                 workspace.add_panel(outline_panel, cx);
+                workspace.add_panel(documentation_panel, cx);
+                workspace.add_panel(references_panel, cx);
+                workspace.add_panel(call_hierarchy_panel, cx);
                 workspace.add_panel(terminal_panel, cx);
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
@@ -537,13 +554,18 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
 }
 
 fn initialize_pane(workspace: &mut Workspace, pane: &View<Pane>, cx: &mut ViewContext<Workspace>) {
+    let buffer_search_history = workspace.project().read(cx).buffer_search_history().clone();
     pane.update(cx, |pane, cx| {
         pane.toolbar().update(cx, |toolbar, cx| {
             let multibuffer_hint = cx.new_view(|_| MultibufferHint::new());
             toolbar.add_item(multibuffer_hint, cx);
             let breadcrumbs = cx.new_view(|_| Breadcrumbs::new());
             toolbar.add_item(breadcrumbs, cx);
-            let buffer_search_bar = cx.new_view(search::BufferSearchBar::new);
+            let buffer_search_bar = cx.new_view(|cx| {
+                let mut search_bar = search::BufferSearchBar::new(cx);
+                search_bar.set_search_history(buffer_search_history);
+                search_bar
+            });
             toolbar.add_item(buffer_search_bar.clone(), cx);
 
             let quick_action_bar =
@@ -3461,6 +3483,9 @@ mod tests {
             collab_ui::init(&app_state, cx);
             project_panel::init((), cx);
             outline_panel::init((), cx);
+            documentation_panel::init(cx);
+            references_panel::init(cx);
+            call_hierarchy_panel::init(cx);
             terminal_view::init(cx);
             copilot::copilot_chat::init(
                 app_state.fs.clone(),
@@ -124,6 +124,9 @@ pub fn app_menus() -> Vec<Menu> {
                 MenuItem::separator(),
                 MenuItem::action("Project Panel", project_panel::ToggleFocus),
                 MenuItem::action("Outline Panel", outline_panel::ToggleFocus),
+                MenuItem::action("Documentation Panel", documentation_panel::ToggleFocus),
+                MenuItem::action("References Panel", references_panel::ToggleFocus),
+                MenuItem::action("Call Hierarchy Panel", call_hierarchy_panel::ToggleFocus),
                 MenuItem::action("Collab Panel", collab_panel::ToggleFocus),
                 MenuItem::action("Terminal Panel", terminal_panel::ToggleFocus),
                 MenuItem::separator(),